@@ -1,9 +1,9 @@
-use std::{collections::HashMap, ffi::c_void, slice};
+use std::{collections::HashMap, slice};
 
-use enum_map::{enum_map, Enum, EnumMap};
-use gl::types::{GLboolean, GLenum, GLint, GLsizei, GLsizeiptr, GLuint, GLvoid};
+use enum_map::{enum_map, EnumMap};
+use gl::types::{GLboolean, GLenum, GLsizei, GLsizeiptr, GLuint, GLvoid};
 
-use crate::{debug, error, warning, GlVersion};
+use crate::{debug, error, version::gl_enum, vertex_array::VertexArrayManager, warning, GlVersion};
 
 pub mod gl_functions;
 
@@ -14,59 +14,15 @@ pub struct BufferManager {
 	buffer_index: GLuint,
 	active_buffers: HashMap<GLuint, Option<Buffer>>,
 	deleted_buffers: Vec<GLuint>,
+	/// Buffers deleted while still attached to a vertex array that was not bound,
+	/// kept alive until the last attachment is removed
+	orphaned_buffers: HashMap<GLuint, Option<Buffer>>,
+	/// `ELEMENT_ARRAY_BUFFER` is vertex array state, its slot here is unused
 	bound_buffers: EnumMap<BufferBinding, GLuint>,
 }
 
-macro_rules! gl_enum {
-	($ename:ident { $($name:ident($(gl: $gl_major:literal . $gl_minor:literal)? $(, es: $es_major:literal . $es_minor:literal)?);)* }) => {
-		#[derive(Copy, Clone, Enum)]
-		#[allow(non_camel_case_types)]
-		pub enum $ename {
-			$($name,)*
-		}
-
-		impl $ename {
-			pub fn from_gl(gl: GLenum) -> Option<Self> {
-				match gl {
-					$(gl::$name => Some(Self::$name),)*
-					_ => None,
-				}
-			}
-
-			pub fn check_version(&self, version: &GlVersion) {
-				match self {
-					$(Self::$name if !$crate::version::at_least!(version, $(gl: $gl_major . $gl_minor)? $(, es: $es_major . $es_minor)?) => {
-						error!("{}", concat!(
-							"GL_",
-							stringify!($name),
-							" requires",
-							$(concat!(" OpenGL ", $gl_major, ".", $gl_minor),)?
-							$(concat!(" or OpenGL ES ", $es_major, ".", $es_minor),)?
-						));
-					},)*
-					_ => {},
-				}
-			}
-
-			pub fn to_gl(&self) -> GLenum {
-				match self {
-					$(Self::$name => gl::$name,)*
-				}
-			}
-		}
-
-		impl std::fmt::Display for $ename {
-			fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-				match self {
-					$(Self::$name => write!(f, concat!("GL_", stringify!($name))),)*
-				}
-			}
-		}
-	}
-}
-
 macro_rules! buffer_binding {
-	($($name:ident($(gl: $gl_major:literal . $gl_minor:literal)? $(, es: $es_major:literal . $es_minor:literal)?);)*) => {
+	($($name:ident($(gl: $gl_major:literal . $gl_minor:literal)? $(,)? $(es: $es_major:literal . $es_minor:literal)?);)*) => {
 		gl_enum! {
 			BufferBinding {
 				$($name($(gl: $gl_major . $gl_minor)? $(, es: $es_major . $es_minor)?);)*
@@ -80,12 +36,10 @@ macro_rules! buffer_binding {
 				}
 			}
 
-			fn check_bound(gl_version: &GlVersion, target: GLenum, map: &EnumMap<Self, GLuint>) -> Option<GLuint> {
-				match target {
-					$(::paste::paste!(gl::[<$name _BINDING>]) => {
-						Self::$name.check_version(gl_version);
-						Some(map[Self::$name])
-					},)*
+			/// Target whose binding is queried by `pname`
+			pub(crate) fn from_binding(pname: GLenum) -> Option<Self> {
+				match pname {
+					$(::paste::paste!($crate::version::enums::[<$name _BINDING>]) => Some(Self::$name),)*
 					_ => None,
 				}
 			}
@@ -108,6 +62,7 @@ buffer_binding! {
 	DRAW_INDIRECT_BUFFER(gl: 4 . 0, es: 3 . 1);
 	QUERY_BUFFER(gl: 4 . 4);
 	SHADER_STORAGE_BUFFER(gl: 4 . 3, es: 3 . 1);
+	PARAMETER_BUFFER(gl: 4 . 6);
 }
 
 gl_enum! {
@@ -129,17 +84,43 @@ pub struct Buffer {
 	memory: Vec<u8>,
 }
 
+impl Buffer {
+	pub fn usage(&self) -> BufferUsage {
+		self.usage
+	}
+
+	pub fn memory(&self) -> &[u8] {
+		&self.memory
+	}
+
+	pub(crate) fn memory_mut(&mut self) -> &mut [u8] {
+		&mut self.memory
+	}
+}
+
+impl Default for BufferManager {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
 impl BufferManager {
 	pub fn new() -> Self {
 		Self {
 			buffer_index: 1,
 			active_buffers: HashMap::new(),
 			deleted_buffers: Vec::new(),
+			orphaned_buffers: HashMap::new(),
 			bound_buffers: BufferBinding::empty_map(),
 		}
 	}
 
-	pub fn gen_buffers(&mut self, error: &mut GLenum, buffer_count: GLsizei, buffers: *mut GLuint) {
+	pub(crate) fn gen_buffers(
+		&mut self,
+		error: &mut GLenum,
+		buffer_count: GLsizei,
+		buffers: *mut GLuint,
+	) {
 		if buffer_count < 0 {
 			*error = gl::INVALID_VALUE;
 			error!("glGenBuffers called with invalid buffer count {}", buffer_count);
@@ -157,8 +138,9 @@ impl BufferManager {
 		}
 	}
 
-	pub fn free_buffers(
+	pub(crate) fn free_buffers(
 		&mut self,
+		vertex_arrays: &mut VertexArrayManager,
 		error: &mut GLenum,
 		buffer_count: GLsizei,
 		buffers: *mut GLuint,
@@ -172,30 +154,38 @@ impl BufferManager {
 			for buffer_id in buffers.iter() {
 				if self.deleted_buffers.contains(buffer_id) {
 					warning!("double freed buffer {}", buffer_id);
-				} else if self.active_buffers.remove(buffer_id).is_none() {
-					warning!("attempted to free unallocated buffer {}", buffer_id);
-				} else {
-					// the above branch conditional will remove the buffer from `self.active_buffers`
+				} else if let Some(buffer) = self.active_buffers.remove(buffer_id) {
 					self.deleted_buffers.push(*buffer_id);
 					for (_, binding) in self.bound_buffers.iter_mut() {
 						if *binding == *buffer_id {
 							*binding = 0;
-							break
 						}
 					}
+
+					// only the bound vertex array loses its attachments,
+					// other vertex arrays keep the storage alive
+					vertex_arrays.detach_buffer(*buffer_id);
+					if vertex_arrays.references_buffer(*buffer_id) {
+						debug!("buffer {} is still attached to a vertex array", buffer_id);
+						self.orphaned_buffers.insert(*buffer_id, buffer);
+					}
+
 					debug!("freed {} buffer(s) {:?}", buffers.len(), buffers);
+				} else {
+					warning!("attempted to free unallocated buffer {}", buffer_id);
 				}
 			}
 		}
 	}
 
-	pub fn is_buffer(&mut self, buffer: GLuint) -> GLboolean {
+	pub fn is_buffer(&self, buffer: GLuint) -> GLboolean {
 		self.active_buffers.contains_key(&buffer) as u8
 	}
 
 	pub fn bind_buffer(
 		&mut self,
 		gl_version: &GlVersion,
+		vertex_arrays: &mut VertexArrayManager,
 		error: &mut GLenum,
 		target: GLenum,
 		buffer_id: GLuint,
@@ -213,10 +203,10 @@ impl BufferManager {
 		};
 
 		if buffer_id == 0 {
-			self.bound_buffers[target] = 0;
+			*self.binding_mut(vertex_arrays, target) = 0;
 			debug!("unbound buffer target {}", target);
 		} else if self.active_buffers.contains_key(&buffer_id) {
-			self.bound_buffers[target] = buffer_id;
+			*self.binding_mut(vertex_arrays, target) = buffer_id;
 			debug!("bound buffer {} to {}", buffer_id, target);
 		} else {
 			*error = gl::INVALID_VALUE;
@@ -258,7 +248,7 @@ impl BufferManager {
 		// safe to convert size to usize due to the above check
 		let (memory, memstr) = {
 			// FIXME: allocation can panic
-			if data == std::ptr::null() {
+			if data.is_null() {
 				(vec![0u8; size as usize], format!("new {size} byte array"))
 			} else {
 				let slice = unsafe { slice::from_raw_parts(data as *const u8, size as usize) };
@@ -274,9 +264,11 @@ impl BufferManager {
 		debug!("allocated buffer {} with {} as {}", buffer_id, memstr, usage);
 	}
 
+	#[allow(clippy::too_many_arguments)]
 	pub fn buffer_data_target(
 		&mut self,
 		gl_version: &GlVersion,
+		vertex_arrays: &VertexArrayManager,
 		error: &mut GLenum,
 		target: GLenum,
 		size: GLsizeiptr,
@@ -295,7 +287,7 @@ impl BufferManager {
 			},
 		};
 
-		let buffer_id = match self.bound_buffers[target] {
+		let buffer_id = match self.bound(vertex_arrays, target) {
 			0 => {
 				*error = gl::INVALID_OPERATION;
 				error!("attempted to allocate a buffer for unbound target {}", target);
@@ -325,8 +317,49 @@ impl BufferManager {
 		self.buffer_data(gl_version, error, buffer_id, size, data, usage);
 	}
 
-	pub fn get_int(&self, gl_version: &GlVersion, pname: GLenum) -> Option<GLint> {
-		BufferBinding::check_bound(gl_version, pname, &self.bound_buffers).map(|i| i as i32)
+	/// Buffer bound to `target`, resolving `ELEMENT_ARRAY_BUFFER` through the bound vertex array
+	pub fn bound(&self, vertex_arrays: &VertexArrayManager, target: BufferBinding) -> GLuint {
+		match target {
+			BufferBinding::ELEMENT_ARRAY_BUFFER => vertex_arrays.bound().element_array_buffer(),
+			target => self.bound_buffers[target],
+		}
+	}
+
+	fn binding_mut<'a>(
+		&'a mut self,
+		vertex_arrays: &'a mut VertexArrayManager,
+		target: BufferBinding,
+	) -> &'a mut GLuint {
+		match target {
+			BufferBinding::ELEMENT_ARRAY_BUFFER => vertex_arrays.element_array_buffer_mut(),
+			target => &mut self.bound_buffers[target],
+		}
+	}
+
+	/// Storage of a live buffer, or of a deleted buffer still attached to a vertex array
+	pub fn buffer(&self, buffer_id: GLuint) -> Option<&Buffer> {
+		self.active_buffers
+			.get(&buffer_id)
+			.or_else(|| self.orphaned_buffers.get(&buffer_id))
+			.and_then(|buffer| buffer.as_ref())
+	}
+
+	pub(crate) fn buffer_mut(&mut self, buffer_id: GLuint) -> Option<&mut Buffer> {
+		self.active_buffers
+			.get_mut(&buffer_id)
+			.or_else(|| self.orphaned_buffers.get_mut(&buffer_id))
+			.and_then(|buffer| buffer.as_mut())
+	}
+
+	/// Release storage of deleted buffers no vertex array is attached to anymore
+	pub fn collect_orphans(&mut self, vertex_arrays: &VertexArrayManager) {
+		self.orphaned_buffers.retain(|buffer_id, _| {
+			let referenced = vertex_arrays.references_buffer(*buffer_id);
+			if !referenced {
+				debug!("released storage of deleted buffer {}", buffer_id);
+			}
+			referenced
+		});
 	}
 
 	pub fn finalize(self) {
@@ -14,9 +14,9 @@ gl_functions! {
 	fn glDeleteBuffers(buffer_count: GLsizei, buffers: *mut GLuint);
 	require gl 2 . 1;
 	require es 2 . 0;
-	take [error, buffer_manager]
+	take [error, buffer_manager, vertex_array_manager]
 	{
-		buffer_manager.free_buffers(error, buffer_count, buffers);
+		buffer_manager.free_buffers(vertex_array_manager, error, buffer_count, buffers);
 	}
 
 	fn glIsBuffer(buffer: GLuint) -> GLboolean;
@@ -30,18 +30,20 @@ gl_functions! {
 	fn glBindBuffer(target: GLenum, buffer: GLuint);
 	require gl 2 . 1;
 	require es 2 . 0;
-	take [gl_version, error, buffer_manager]
+	take [gl_version, error, buffer_manager, vertex_array_manager]
 	{
-		buffer_manager.bind_buffer(gl_version, error, target, buffer);
+		buffer_manager.bind_buffer(gl_version, vertex_array_manager, error, target, buffer);
+		buffer_manager.collect_orphans(vertex_array_manager);
 	}
 
 	fn glBufferData(target: GLenum, size: GLsizeiptr, data: *const GLvoid, usage: GLenum);
 	require gl 2 . 1;
 	require es 2 . 0;
-	take [gl_version, error, buffer_manager]
+	take [gl_version, error, buffer_manager, vertex_array_manager]
 	{
 		buffer_manager.buffer_data_target(
 			gl_version,
+			vertex_array_manager,
 			error,
 			target,
 			size,
@@ -12,7 +12,7 @@ fn create_destroy() {
 	test_harness(GlVersion::from_version(VersionType::GL, 2, 1), || unsafe {
 		let mut buffer = 0;
 		gl::GenBuffers(1, &mut buffer);
-		gl::DeleteBuffers(1, &mut buffer);
+		gl::DeleteBuffers(1, &buffer);
 	})
 }
 
@@ -31,8 +31,8 @@ fn double_free() {
 	test_harness(GlVersion::from_version(VersionType::GL, 2, 1), || unsafe {
 		let mut buffer = 0;
 		gl::GenBuffers(1, &mut buffer);
-		gl::DeleteBuffers(1, &mut buffer);
-		gl::DeleteBuffers(1, &mut buffer);
+		gl::DeleteBuffers(1, &buffer);
+		gl::DeleteBuffers(1, &buffer);
 	})
 }
 
@@ -68,7 +68,7 @@ fn is_buffer() {
 		assert_eq!(gl::IsBuffer(buffer), gl::FALSE);
 		gl::GenBuffers(1, &mut buffer);
 		assert_eq!(gl::IsBuffer(buffer), gl::TRUE);
-		gl::DeleteBuffers(1, &mut buffer);
+		gl::DeleteBuffers(1, &buffer);
 		assert_eq!(gl::IsBuffer(buffer), gl::FALSE);
 	})
 }
@@ -87,7 +87,7 @@ fn bind_buffer() {
 		gl::BindBuffer(gl::ARRAY_BUFFER, 0);
 		gl::GetIntegerv(gl::ARRAY_BUFFER_BINDING, &mut get_val);
 		assert_eq!(get_val, 0);
-		gl::DeleteBuffers(1, &mut buffer);
+		gl::DeleteBuffers(1, &buffer);
 	})
 }
 
@@ -126,7 +126,7 @@ fn buffer_data() {
 		);
 		gl::BufferData(gl::ARRAY_BUFFER, 42, std::ptr::null(), gl::STATIC_DRAW);
 
-		gl::DeleteBuffers(1, &mut buffer);
+		gl::DeleteBuffers(1, &buffer);
 	});
 }
 
@@ -154,7 +154,7 @@ fn buffer_data_invalid_target() {
 		gl::GenBuffers(1, &mut buffer);
 		gl::BindBuffer(gl::ARRAY_BUFFER, buffer);
 		gl::BufferData(gl::TEXTURE, 0, std::ptr::null(), gl::STATIC_DRAW);
-		gl::DeleteBuffers(1, &mut buffer);
+		gl::DeleteBuffers(1, &buffer);
 	})
 }
 
@@ -166,7 +166,7 @@ fn buffer_data_invalid_usage() {
 		gl::GenBuffers(1, &mut buffer);
 		gl::BindBuffer(gl::ARRAY_BUFFER, buffer);
 		gl::BufferData(gl::ARRAY_BUFFER, 0, std::ptr::null(), gl::TEXTURE);
-		gl::DeleteBuffers(1, &mut buffer);
+		gl::DeleteBuffers(1, &buffer);
 	})
 }
 
@@ -177,7 +177,7 @@ fn use_deleted_binding() {
 		let mut buffer = 0;
 		gl::GenBuffers(1, &mut buffer);
 		gl::BindBuffer(gl::ARRAY_BUFFER, buffer);
-		gl::DeleteBuffers(1, &mut buffer);
+		gl::DeleteBuffers(1, &buffer);
 
 		gl::BufferData(gl::ARRAY_BUFFER, 0, std::ptr::null(), gl::STATIC_DRAW);
 	})
@@ -218,7 +218,7 @@ fn gl_errors() {
 			gl::BufferData(gl::ARRAY_BUFFER, -1, std::ptr::null(), gl::STATIC_DRAW);
 			assert_eq!(gl::GetError(), gl::INVALID_VALUE);
 
-			gl::DeleteBuffers(1, &mut buffer);
+			gl::DeleteBuffers(1, &buffer);
 		},
 	)
 }
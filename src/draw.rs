@@ -0,0 +1,872 @@
+use std::{
+	collections::BTreeMap,
+	mem,
+	ops::{Range, RangeInclusive},
+	slice,
+};
+
+use gl::types::{GLenum, GLint, GLsizei, GLuint, GLvoid};
+
+use crate::{
+	buffer::BufferManager,
+	debug,
+	error,
+	state::{RenderState, StateManager},
+	version::{ext, gl_enum, VersionType},
+	vertex_array::{AttribKind, AttribValue, VertexArrayManager},
+	warning,
+	GlVersion,
+	MockContextData,
+	MockContextRef,
+};
+
+pub mod gl_functions;
+pub mod indirect;
+
+#[cfg(test)]
+mod test;
+
+gl_enum! {
+	PrimitiveMode {
+		POINTS(gl: 2 . 1, es: 2 . 0);
+		LINE_STRIP(gl: 2 . 1, es: 2 . 0);
+		LINE_LOOP(gl: 2 . 1, es: 2 . 0);
+		LINES(gl: 2 . 1, es: 2 . 0);
+		TRIANGLE_STRIP(gl: 2 . 1, es: 2 . 0);
+		TRIANGLE_FAN(gl: 2 . 1, es: 2 . 0);
+		TRIANGLES(gl: 2 . 1, es: 2 . 0);
+		LINES_ADJACENCY(gl: 3 . 2, es: 3 . 2);
+		LINE_STRIP_ADJACENCY(gl: 3 . 2, es: 3 . 2);
+		TRIANGLES_ADJACENCY(gl: 3 . 2, es: 3 . 2);
+		TRIANGLE_STRIP_ADJACENCY(gl: 3 . 2, es: 3 . 2);
+		PATCHES(gl: 4 . 0, es: 3 . 2);
+	}
+}
+
+gl_enum! {
+	IndexType {
+		UNSIGNED_BYTE(gl: 2 . 1, es: 2 . 0);
+		UNSIGNED_SHORT(gl: 2 . 1, es: 2 . 0);
+		UNSIGNED_INT(gl: 2 . 1, es: 3 . 0);
+	}
+}
+
+impl IndexType {
+	pub fn size(&self) -> usize {
+		match self {
+			Self::UNSIGNED_BYTE => 1,
+			Self::UNSIGNED_SHORT => 2,
+			Self::UNSIGNED_INT => 4,
+		}
+	}
+
+	fn decode(&self, bytes: &[u8]) -> Vec<u32> {
+		match self {
+			Self::UNSIGNED_BYTE => bytes.iter().map(|i| *i as u32).collect(),
+			Self::UNSIGNED_SHORT =>
+				bytes.chunks_exact(2).map(|i| u16::from_ne_bytes([i[0], i[1]]) as u32).collect(),
+			Self::UNSIGNED_INT => bytes
+				.chunks_exact(4)
+				.map(|i| u32::from_ne_bytes([i[0], i[1], i[2], i[3]]))
+				.collect(),
+		}
+	}
+}
+
+#[derive(Default)]
+pub struct DrawManager {
+	draw_calls: Vec<DrawCall>,
+	indirect_commands: Vec<indirect::IndirectCommand>,
+	/// Work group counts of compute dispatches
+	dispatches: Vec<[GLuint; 3]>,
+	/// Number of `draw_calls` already passed to the rasterizer
+	rasterized: usize,
+	/// Draw calls and vertices recorded over the context's lifetime, for frame statistics
+	totals: (usize, usize),
+}
+
+/// Instances drawn by a draw call
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Instances {
+	pub count: GLsizei,
+	pub base_instance: GLuint,
+}
+
+impl Instances {
+	/// The single instance drawn by non-instanced draw calls
+	pub const SINGLE: Self = Self {
+		count: 1,
+		base_instance: 0,
+	};
+
+	pub fn new(count: GLsizei, base_instance: GLuint) -> Self {
+		Self {
+			count,
+			base_instance,
+		}
+	}
+}
+
+/// A validated draw call with its vertex data decoded
+#[derive(Clone, Debug, PartialEq)]
+pub struct DrawCall {
+	pub mode: PrimitiveMode,
+	/// Index type of indexed draws
+	pub index_type: Option<IndexType>,
+	/// Vertex ids in the order they are fetched, either `first..first + count`
+	/// or the indices with the base vertex added
+	pub vertices: Vec<u32>,
+	/// Offsets into `vertices` at which primitive restart began a new primitive
+	pub restarts: Vec<usize>,
+	pub instances: Instances,
+	pub vertex_array: GLuint,
+	/// Decoded values of every enabled per-vertex attribute, one per entry of `vertices`
+	///
+	/// Values fetched out of bounds are recorded as zero.
+	pub attributes: BTreeMap<GLuint, Vec<AttribValue>>,
+	/// Decoded values of every enabled attribute with a non-zero divisor, one per instance
+	pub instanced_attributes: BTreeMap<GLuint, Vec<AttribValue>>,
+	/// Fixed-function state the draw was made with
+	pub state: RenderState,
+}
+
+impl DrawCall {
+	pub fn count(&self) -> usize {
+		self.vertices.len()
+	}
+
+	/// Ranges of `vertices` making up separate primitives, split at primitive restarts
+	pub fn strips(&self) -> Vec<Range<usize>> {
+		strips(&self.restarts, self.vertices.len())
+	}
+
+	pub fn attribute(&self, index: GLuint) -> Option<&[AttribValue]> {
+		self.attributes.get(&index).map(|values| values.as_slice())
+	}
+
+	pub fn instanced_attribute(&self, index: GLuint) -> Option<&[AttribValue]> {
+		self.instanced_attributes.get(&index).map(|values| values.as_slice())
+	}
+}
+
+impl MockContextRef {
+	/// Draw calls recorded since the context was created or they were last taken
+	pub fn draw_calls(&self) -> Vec<DrawCall> {
+		crate::context().draw_manager.draw_calls.clone()
+	}
+
+	/// Take the recorded draw calls, clearing the log
+	pub fn take_draw_calls(&self) -> Vec<DrawCall> {
+		let draw_manager = &mut crate::context().draw_manager;
+		draw_manager.rasterized = 0;
+		mem::take(&mut draw_manager.draw_calls)
+	}
+}
+
+impl MockContextData {
+	/// Record draw calls with `record` if the draw framebuffer is complete, then rasterize them
+	/// into it
+	pub(crate) fn draw(&mut self, func: &str, record: impl FnOnce(&mut Self)) {
+		let complete = self.framebuffer_manager.check_draw_complete(
+			&self.gl_version,
+			&self.renderbuffer_manager,
+			&mut self.error,
+			func,
+		);
+		if !complete {
+			return
+		}
+
+		record(self);
+
+		let Self {
+			draw_manager,
+			framebuffer_manager,
+			renderbuffer_manager,
+			rasterizer,
+			..
+		} = self;
+		framebuffer_manager.draw(renderbuffer_manager, |framebuffer| {
+			rasterizer.rasterize(draw_manager.unrasterized(), framebuffer)
+		});
+	}
+}
+
+/// Validation shared by every draw call, returns the primitive mode if the draw can proceed
+fn check_draw(
+	gl_version: &GlVersion,
+	vertex_arrays: &VertexArrayManager,
+	error: &mut GLenum,
+	func: &str,
+	mode: GLenum,
+	count: GLsizei,
+) -> Option<PrimitiveMode> {
+	let mode = match PrimitiveMode::from_gl(mode) {
+		None => {
+			*error = gl::INVALID_ENUM;
+			error!("{} called with invalid primitive mode {}", func, mode);
+			return None
+		},
+		Some(mode) => {
+			mode.check_version(gl_version);
+			mode
+		},
+	};
+
+	if count < 0 {
+		*error = gl::INVALID_VALUE;
+		error!("{} called with negative count {}", func, count);
+		return None
+	}
+
+	if gl_version.is_core() && vertex_arrays.bound_id() == 0 {
+		*error = gl::INVALID_OPERATION;
+		error!("{} called without a bound vertex array in a core profile context", func);
+		return None
+	}
+
+	Some(mode)
+}
+
+/// Split `0..len` at each of `restarts`, dropping empty ranges
+fn strips(restarts: &[usize], len: usize) -> Vec<Range<usize>> {
+	let mut bounds = vec![0];
+	bounds.extend(restarts);
+	bounds.push(len);
+	bounds
+		.windows(2)
+		.map(|w| w[0]..w[1])
+		.filter(|strip| !strip.is_empty())
+		.collect()
+}
+
+/// Validate an index type, `GL_UNSIGNED_INT` needs `OES_element_index_uint` on ES 2.0
+fn check_index_type(
+	gl_version: &GlVersion,
+	error: &mut GLenum,
+	func: &str,
+	ty: GLenum,
+) -> Option<IndexType> {
+	match IndexType::from_gl(ty) {
+		Some(IndexType::UNSIGNED_INT)
+			if matches!(gl_version.ty, VersionType::ES)
+				&& !gl_version.has_extension(&ext::OES_element_index_uint) =>
+		{
+			*error = gl::INVALID_ENUM;
+			error!(
+				"{} called with GL_UNSIGNED_INT indices, which require {}",
+				func,
+				ext::OES_element_index_uint.provided_str
+			);
+			None
+		},
+		Some(ty) => Some(ty),
+		None => {
+			*error = gl::INVALID_ENUM;
+			error!("{} called with invalid index type {}", func, ty);
+			None
+		},
+	}
+}
+
+/// Warn about vertex counts which leave vertices unused by `mode`
+fn check_primitive_count(func: &str, mode: PrimitiveMode, count: usize) {
+	let (minimum, multiple) = match mode {
+		PrimitiveMode::POINTS | PrimitiveMode::PATCHES => (1, 1),
+		PrimitiveMode::LINES => (2, 2),
+		PrimitiveMode::LINE_STRIP | PrimitiveMode::LINE_LOOP => (2, 1),
+		PrimitiveMode::TRIANGLES => (3, 3),
+		PrimitiveMode::TRIANGLE_STRIP | PrimitiveMode::TRIANGLE_FAN => (3, 1),
+		PrimitiveMode::LINES_ADJACENCY => (4, 4),
+		PrimitiveMode::LINE_STRIP_ADJACENCY => (4, 1),
+		PrimitiveMode::TRIANGLES_ADJACENCY => (6, 6),
+		PrimitiveMode::TRIANGLE_STRIP_ADJACENCY => (6, 2),
+	};
+
+	if count < minimum || !count.is_multiple_of(multiple) {
+		warning!("{} draws {} vertices as {}, leaving some unused", func, count, mode);
+	}
+}
+
+/// Check instance parameters, returns false if the draw cannot proceed
+fn check_instances(error: &mut GLenum, func: &str, instances: Instances) -> bool {
+	if instances.count < 0 {
+		*error = gl::INVALID_VALUE;
+		error!("{} called with negative instance count {}", func, instances.count);
+		return false
+	}
+
+	true
+}
+
+/// Elements fetched for attribute `index` of the bound vertex array
+///
+/// Attributes with a divisor fetch one element per `divisor` instances starting at the base
+/// instance, others fetch one element per vertex. `instances.count` must be positive.
+fn fetched_elements(
+	vertex_arrays: &VertexArrayManager,
+	index: usize,
+	vertices: &RangeInclusive<u64>,
+	instances: Instances,
+) -> RangeInclusive<u64> {
+	match vertex_arrays.bound().attribute_binding(index).divisor {
+		0 => vertices.clone(),
+		divisor => {
+			let base = instances.base_instance as u64;
+			base..=base + (instances.count as u64 - 1) / divisor as u64
+		},
+	}
+}
+
+/// Check every enabled attribute can be fetched for all of `vertices` and `instances`
+///
+/// Out of bounds fetches are not GL errors, they are undefined behavior
+/// and commonly hang the GPU, so only the mock's error handling is triggered.
+fn check_vertex_fetch(
+	buffers: &BufferManager,
+	vertex_arrays: &VertexArrayManager,
+	func: &str,
+	vertices: RangeInclusive<u64>,
+	instances: Instances,
+) {
+	let array = vertex_arrays.bound();
+
+	for (index, attrib) in array.attributes().iter().enumerate() {
+		if !attrib.enabled {
+			continue
+		}
+
+		let binding = array.attribute_binding(index);
+		if binding.buffer == 0 {
+			if vertex_arrays.bound_id() == 0 {
+				debug!(
+					"{} reads vertex attribute {} from a client side array, which cannot be bounds checked",
+					func, index
+				);
+			} else {
+				error!(
+					"{} reads vertex attribute {} of vertex array {} which has no buffer",
+					func,
+					index,
+					vertex_arrays.bound_id()
+				);
+			}
+			continue
+		}
+
+		let Some(buffer) = buffers.buffer(binding.buffer) else {
+			error!(
+				"{} reads vertex attribute {} from buffer {} which has no storage",
+				func, index, binding.buffer
+			);
+			continue
+		};
+
+		let elements = fetched_elements(vertex_arrays, index, &vertices, instances);
+		// `None` if the fetch reaches past the largest offset
+		let end = (binding.offset as u64)
+			.checked_add(attrib.relative_offset as u64 + attrib.element_size() as u64)
+			.and_then(|start| {
+				start.checked_add(elements.end().checked_mul(binding.stride as u64)?)
+			});
+
+		if end.is_none_or(|end| end > buffer.memory().len() as u64) {
+			error!(
+				"{} reads vertex attribute {} for {} {}..={} from offset {} with stride {} of buffer {}, which is only {} bytes long",
+				func,
+				index,
+				if binding.divisor == 0 { "vertices" } else { "instances" },
+				elements.start(),
+				elements.end(),
+				binding.offset,
+				binding.stride,
+				binding.buffer,
+				buffer.memory().len()
+			);
+		}
+	}
+}
+
+/// Decode element `element` of attribute `index` from the bound vertex array
+///
+/// # Safety
+/// Client side arrays must be valid for the read
+unsafe fn fetch_attribute(
+	buffers: &BufferManager,
+	vertex_arrays: &VertexArrayManager,
+	index: usize,
+	element: u32,
+) -> AttribValue {
+	let array = vertex_arrays.bound();
+	let attrib = &array.attributes()[index];
+	let binding = array.attribute_binding(index);
+
+	let len = attrib.element_size();
+	// `None` if the element lies past the largest offset
+	let range = binding
+		.offset
+		.checked_add(attrib.relative_offset as usize)
+		.and_then(|start| start.checked_add((element as usize).checked_mul(binding.stride)?))
+		.and_then(|start| Some(start..start.checked_add(len)?));
+
+	let bytes = match binding.buffer {
+		0 if vertex_arrays.bound_id() == 0 && binding.offset != 0 =>
+			range.map(|range| slice::from_raw_parts(range.start as *const u8, len)),
+		0 => None,
+		buffer => buffers
+			.buffer(buffer)
+			.zip(range)
+			.and_then(|(buffer, range)| buffer.memory().get(range)),
+	};
+
+	match (bytes, attrib.kind) {
+		(Some(bytes), _) => attrib.decode(bytes),
+		(None, AttribKind::Float) => AttribValue::Float([0.0; 4]),
+		(None, AttribKind::Integer) => AttribValue::Int([0; 4]),
+		(None, AttribKind::Double) => AttribValue::Double([0.0; 4]),
+	}
+}
+
+/// Read `count` indices from the bound element array buffer, or client memory
+#[allow(clippy::too_many_arguments)]
+unsafe fn fetch_indices(
+	gl_version: &GlVersion,
+	buffers: &BufferManager,
+	vertex_arrays: &VertexArrayManager,
+	error: &mut GLenum,
+	func: &str,
+	count: usize,
+	ty: IndexType,
+	indices: *const GLvoid,
+) -> Option<Vec<u32>> {
+	let len = count * ty.size();
+	let element_buffer = vertex_arrays.bound().element_array_buffer();
+
+	if element_buffer == 0 {
+		if gl_version.is_core() {
+			*error = gl::INVALID_OPERATION;
+			error!("{} called without an element array buffer in a core profile context", func);
+			None
+		} else if indices.is_null() {
+			error!("{} called without an element array buffer or client side indices", func);
+			None
+		} else {
+			debug!("{} reads indices from client memory at {:?}", func, indices);
+			Some(ty.decode(slice::from_raw_parts(indices as *const u8, len)))
+		}
+	} else {
+		let Some(buffer) = buffers.buffer(element_buffer) else {
+			error!("{} reads indices from buffer {} which has no storage", func, element_buffer);
+			return None
+		};
+
+		let offset = indices as usize;
+		if !offset.is_multiple_of(ty.size()) {
+			warning!("{} reads {} indices from misaligned offset {}", func, ty, offset);
+		}
+
+		match offset.checked_add(len).and_then(|end| buffer.memory().get(offset..end)) {
+			Some(bytes) => Some(ty.decode(bytes)),
+			None => {
+				error!(
+					"{} reads {} indices from offset {} of element array buffer {}, which is only {} bytes long",
+					func,
+					count,
+					offset,
+					element_buffer,
+					buffer.memory().len()
+				);
+				None
+			},
+		}
+	}
+}
+
+impl DrawManager {
+	/// Draw calls and vertices of every instance recorded over the context's lifetime
+	pub fn totals(&self) -> (usize, usize) {
+		self.totals
+	}
+
+	/// Draw calls recorded since the last call, to be rasterized
+	pub fn unrasterized(&mut self) -> &[DrawCall] {
+		let start = mem::replace(&mut self.rasterized, self.draw_calls.len());
+		&self.draw_calls[start..]
+	}
+
+	/// # Safety
+	/// Client side arrays must be valid for every vertex drawn
+	#[allow(clippy::too_many_arguments)]
+	pub unsafe fn draw_arrays(
+		&mut self,
+		gl_version: &GlVersion,
+		buffers: &BufferManager,
+		vertex_arrays: &VertexArrayManager,
+		state: &StateManager,
+		error: &mut GLenum,
+		func: &str,
+		mode: GLenum,
+		first: GLint,
+		count: GLsizei,
+		instances: Instances,
+	) {
+		let Some(mode) = check_draw(gl_version, vertex_arrays, error, func, mode, count) else {
+			return
+		};
+
+		if !check_instances(error, func, instances) {
+			return
+		}
+
+		if first < 0 {
+			*error = gl::INVALID_VALUE;
+			error!("{} called with negative first vertex {}", func, first);
+			return
+		}
+
+		if count == 0 || instances.count == 0 {
+			debug!("{} draws nothing", func);
+			return
+		}
+
+		check_primitive_count(func, mode, count as usize);
+
+		let vertices = first as u64..=first as u64 + count as u64 - 1;
+		check_vertex_fetch(buffers, vertex_arrays, func, vertices, instances);
+
+		let vertices = (first as u32..first as u32 + count as u32).collect();
+		self.record(buffers, vertex_arrays, state, mode, None, vertices, Vec::new(), instances);
+		debug!(
+			"drew {} vertices from {} as {}, {} instances",
+			count, first, mode, instances.count
+		);
+	}
+
+	/// # Safety
+	/// `indices` must be valid for `count` reads if no element array buffer is bound,
+	/// client side arrays must be valid for every vertex drawn
+	#[allow(clippy::too_many_arguments)]
+	pub unsafe fn draw_elements(
+		&mut self,
+		gl_version: &GlVersion,
+		buffers: &BufferManager,
+		vertex_arrays: &VertexArrayManager,
+		state: &StateManager,
+		error: &mut GLenum,
+		func: &str,
+		mode: GLenum,
+		count: GLsizei,
+		ty: GLenum,
+		indices: *const GLvoid,
+		base_vertex: GLint,
+		instances: Instances,
+	) {
+		self.elements(
+			gl_version,
+			buffers,
+			vertex_arrays,
+			state,
+			error,
+			func,
+			mode,
+			None,
+			count,
+			ty,
+			indices,
+			base_vertex,
+			instances,
+		);
+	}
+
+	/// # Safety
+	/// See `draw_elements`
+	#[allow(clippy::too_many_arguments)]
+	pub unsafe fn draw_range_elements(
+		&mut self,
+		gl_version: &GlVersion,
+		buffers: &BufferManager,
+		vertex_arrays: &VertexArrayManager,
+		state: &StateManager,
+		error: &mut GLenum,
+		func: &str,
+		mode: GLenum,
+		start: GLuint,
+		end: GLuint,
+		count: GLsizei,
+		ty: GLenum,
+		indices: *const GLvoid,
+		base_vertex: GLint,
+	) {
+		if end < start {
+			*error = gl::INVALID_VALUE;
+			error!("{} called with end {} before start {}", func, end, start);
+			return
+		}
+
+		self.elements(
+			gl_version,
+			buffers,
+			vertex_arrays,
+			state,
+			error,
+			func,
+			mode,
+			Some(start..=end),
+			count,
+			ty,
+			indices,
+			base_vertex,
+			Instances::SINGLE,
+		);
+	}
+
+	/// Draw `drawcount` ranges of vertices, as if by calling `glDrawArrays` for each
+	///
+	/// # Safety
+	/// `first` and `count` must be valid for `drawcount` reads,
+	/// client side arrays must be valid for every vertex drawn
+	#[allow(clippy::too_many_arguments)]
+	pub unsafe fn multi_draw_arrays(
+		&mut self,
+		gl_version: &GlVersion,
+		buffers: &BufferManager,
+		vertex_arrays: &VertexArrayManager,
+		state: &StateManager,
+		error: &mut GLenum,
+		func: &str,
+		mode: GLenum,
+		first: *const GLint,
+		count: *const GLsizei,
+		drawcount: GLsizei,
+	) {
+		if drawcount < 0 {
+			*error = gl::INVALID_VALUE;
+			error!("{} called with negative draw count {}", func, drawcount);
+			return
+		}
+
+		for i in 0..drawcount as usize {
+			self.draw_arrays(
+				gl_version,
+				buffers,
+				vertex_arrays,
+				state,
+				error,
+				func,
+				mode,
+				*first.add(i),
+				*count.add(i),
+				Instances::SINGLE,
+			);
+		}
+	}
+
+	/// Draw `drawcount` lists of indices, as if by calling `glDrawElementsBaseVertex` for each
+	///
+	/// # Safety
+	/// `count`, `indices` and `base_vertex`, if not null, must be valid for `drawcount` reads,
+	/// each of `indices` must be valid as for `draw_elements`
+	#[allow(clippy::too_many_arguments)]
+	pub unsafe fn multi_draw_elements(
+		&mut self,
+		gl_version: &GlVersion,
+		buffers: &BufferManager,
+		vertex_arrays: &VertexArrayManager,
+		state: &StateManager,
+		error: &mut GLenum,
+		func: &str,
+		mode: GLenum,
+		count: *const GLsizei,
+		ty: GLenum,
+		indices: *const *const GLvoid,
+		drawcount: GLsizei,
+		base_vertex: *const GLint,
+	) {
+		if drawcount < 0 {
+			*error = gl::INVALID_VALUE;
+			error!("{} called with negative draw count {}", func, drawcount);
+			return
+		}
+
+		for i in 0..drawcount as usize {
+			self.elements(
+				gl_version,
+				buffers,
+				vertex_arrays,
+				state,
+				error,
+				func,
+				mode,
+				None,
+				*count.add(i),
+				ty,
+				*indices.add(i),
+				if base_vertex.is_null() {
+					0
+				} else {
+					*base_vertex.add(i)
+				},
+				Instances::SINGLE,
+			);
+		}
+	}
+
+	/// Shared implementation of the indexed draws
+	///
+	/// # Safety
+	/// See `draw_elements`
+	#[allow(clippy::too_many_arguments)]
+	unsafe fn elements(
+		&mut self,
+		gl_version: &GlVersion,
+		buffers: &BufferManager,
+		vertex_arrays: &VertexArrayManager,
+		state: &StateManager,
+		error: &mut GLenum,
+		func: &str,
+		mode: GLenum,
+		range: Option<RangeInclusive<GLuint>>,
+		count: GLsizei,
+		ty: GLenum,
+		indices: *const GLvoid,
+		base_vertex: GLint,
+		instances: Instances,
+	) {
+		let Some(mode) = check_draw(gl_version, vertex_arrays, error, func, mode, count) else {
+			return
+		};
+
+		let Some(ty) = check_index_type(gl_version, error, func, ty) else {
+			return
+		};
+
+		if !check_instances(error, func, instances) {
+			return
+		}
+
+		if count == 0 || instances.count == 0 {
+			debug!("{} draws nothing", func);
+			return
+		}
+
+		let Some(indices) = fetch_indices(
+			gl_version,
+			buffers,
+			vertex_arrays,
+			error,
+			func,
+			count as usize,
+			ty,
+			indices,
+		) else {
+			return
+		};
+
+		let restart = state.restart_index(ty);
+		let mut vertices = Vec::with_capacity(indices.len());
+		let mut restarts = Vec::new();
+
+		for index in indices {
+			if Some(index) == restart {
+				restarts.push(vertices.len());
+				continue
+			}
+
+			// indices outside the range are undefined behavior, not an error
+			if let Some(range) = &range {
+				if !range.contains(&index) {
+					error!(
+						"{} reads index {} outside of the declared range {}..={}",
+						func,
+						index,
+						range.start(),
+						range.end()
+					);
+				}
+			}
+
+			let Ok(vertex) = u32::try_from(index as i64 + base_vertex as i64) else {
+				error!(
+					"{} adds base vertex {} to index {}, leaving the valid range",
+					func, base_vertex, index
+				);
+				return
+			};
+			vertices.push(vertex);
+		}
+
+		if vertices.is_empty() {
+			debug!("{} draws only primitive restart indices", func);
+			return
+		}
+
+		for strip in strips(&restarts, vertices.len()) {
+			check_primitive_count(func, mode, strip.len());
+		}
+
+		let min = *vertices.iter().min().unwrap() as u64;
+		let max = *vertices.iter().max().unwrap() as u64;
+		check_vertex_fetch(buffers, vertex_arrays, func, min..=max, instances);
+
+		self.record(buffers, vertex_arrays, state, mode, Some(ty), vertices, restarts, instances);
+		debug!("drew {} {} indices as {}, {} instances", count, ty, mode, instances.count);
+	}
+
+	/// # Safety
+	/// Client side arrays must be valid for every vertex in `vertices` and instance in `instances`
+	#[allow(clippy::too_many_arguments)]
+	unsafe fn record(
+		&mut self,
+		buffers: &BufferManager,
+		vertex_arrays: &VertexArrayManager,
+		state: &StateManager,
+		mode: PrimitiveMode,
+		index_type: Option<IndexType>,
+		vertices: Vec<u32>,
+		restarts: Vec<usize>,
+		instances: Instances,
+	) {
+		let array = vertex_arrays.bound();
+		let mut attributes = BTreeMap::new();
+		let mut instanced_attributes = BTreeMap::new();
+
+		for (index, attrib) in array.attributes().iter().enumerate() {
+			if !attrib.enabled {
+				continue
+			}
+
+			match array.attribute_binding(index).divisor {
+				0 => {
+					let values = vertices
+						.iter()
+						.map(|vertex| fetch_attribute(buffers, vertex_arrays, index, *vertex))
+						.collect();
+					attributes.insert(index as GLuint, values);
+				},
+				divisor => {
+					let values = (0..instances.count as u32)
+						.map(|instance| {
+							let element = instances.base_instance + instance / divisor;
+							fetch_attribute(buffers, vertex_arrays, index, element)
+						})
+						.collect();
+					instanced_attributes.insert(index as GLuint, values);
+				},
+			}
+		}
+
+		self.totals.0 += 1;
+		self.totals.1 += vertices.len() * instances.count as usize;
+		self.draw_calls.push(DrawCall {
+			mode,
+			index_type,
+			vertices,
+			restarts,
+			instances,
+			vertex_array: vertex_arrays.bound_id(),
+			attributes,
+			instanced_attributes,
+			state: state.render().clone(),
+		});
+	}
+}
@@ -0,0 +1,599 @@
+use gl::types::{GLenum, GLint, GLintptr, GLsizei, GLuint, GLvoid};
+
+use crate::{
+	draw::{indirect::DrawCount, Instances},
+	function_mapping::gl_functions,
+};
+
+gl_functions! {
+	fn glDrawArrays(mode: GLenum, first: GLint, count: GLsizei);
+	require gl 2 . 1;
+	require es 2 . 0;
+	take context
+	{
+		context.draw("glDrawArrays", |context| {
+			context.draw_manager.draw_arrays(
+				&context.gl_version,
+				&context.buffer_manager,
+				&context.vertex_array_manager,
+				&context.state_manager,
+				&mut context.error,
+				"glDrawArrays",
+				mode,
+				first,
+				count,
+				Instances::SINGLE,
+			);
+		});
+	}
+
+	fn glDrawArraysInstanced(mode: GLenum, first: GLint, count: GLsizei, instance_count: GLsizei);
+	require gl 3 . 1;
+	require es 3 . 0;
+	require ext ARB_draw_instanced | EXT_draw_instanced | ANGLE_instanced_arrays;
+	take context
+	{
+		context.draw("glDrawArraysInstanced", |context| {
+			context.draw_manager.draw_arrays(
+				&context.gl_version,
+				&context.buffer_manager,
+				&context.vertex_array_manager,
+				&context.state_manager,
+				&mut context.error,
+				"glDrawArraysInstanced",
+				mode,
+				first,
+				count,
+				Instances::new(instance_count, 0),
+			);
+		});
+	}
+
+	fn glDrawArraysInstancedBaseInstance(
+		mode: GLenum,
+		first: GLint,
+		count: GLsizei,
+		instance_count: GLsizei,
+		base_instance: GLuint,
+	);
+	require gl 4 . 2;
+	require ext ARB_base_instance | EXT_base_instance;
+	take context
+	{
+		context.draw("glDrawArraysInstancedBaseInstance", |context| {
+			context.draw_manager.draw_arrays(
+				&context.gl_version,
+				&context.buffer_manager,
+				&context.vertex_array_manager,
+				&context.state_manager,
+				&mut context.error,
+				"glDrawArraysInstancedBaseInstance",
+				mode,
+				first,
+				count,
+				Instances::new(instance_count, base_instance),
+			);
+		});
+	}
+
+	fn glMultiDrawArrays(
+		mode: GLenum,
+		first: *const GLint,
+		count: *const GLsizei,
+		drawcount: GLsizei,
+	);
+	require gl 2 . 1;
+	require ext EXT_multi_draw_arrays;
+	take context
+	{
+		context.draw("glMultiDrawArrays", |context| {
+			context.draw_manager.multi_draw_arrays(
+				&context.gl_version,
+				&context.buffer_manager,
+				&context.vertex_array_manager,
+				&context.state_manager,
+				&mut context.error,
+				"glMultiDrawArrays",
+				mode,
+				first,
+				count,
+				drawcount,
+			);
+		});
+	}
+
+	fn glDrawElements(mode: GLenum, count: GLsizei, ty: GLenum, indices: *const GLvoid);
+	require gl 2 . 1;
+	require es 2 . 0;
+	take context
+	{
+		context.draw("glDrawElements", |context| {
+			context.draw_manager.draw_elements(
+				&context.gl_version,
+				&context.buffer_manager,
+				&context.vertex_array_manager,
+				&context.state_manager,
+				&mut context.error,
+				"glDrawElements",
+				mode,
+				count,
+				ty,
+				indices,
+				0,
+				Instances::SINGLE,
+			);
+		});
+	}
+
+	fn glDrawElementsBaseVertex(
+		mode: GLenum,
+		count: GLsizei,
+		ty: GLenum,
+		indices: *const GLvoid,
+		base_vertex: GLint,
+	);
+	require gl 3 . 2;
+	require es 3 . 2;
+	require ext
+		ARB_draw_elements_base_vertex
+		| OES_draw_elements_base_vertex
+		| EXT_draw_elements_base_vertex;
+	take context
+	{
+		context.draw("glDrawElementsBaseVertex", |context| {
+			context.draw_manager.draw_elements(
+				&context.gl_version,
+				&context.buffer_manager,
+				&context.vertex_array_manager,
+				&context.state_manager,
+				&mut context.error,
+				"glDrawElementsBaseVertex",
+				mode,
+				count,
+				ty,
+				indices,
+				base_vertex,
+				Instances::SINGLE,
+			);
+		});
+	}
+
+	fn glDrawElementsInstanced(
+		mode: GLenum,
+		count: GLsizei,
+		ty: GLenum,
+		indices: *const GLvoid,
+		instance_count: GLsizei,
+	);
+	require gl 3 . 1;
+	require es 3 . 0;
+	require ext ARB_draw_instanced | EXT_draw_instanced | ANGLE_instanced_arrays;
+	take context
+	{
+		context.draw("glDrawElementsInstanced", |context| {
+			context.draw_manager.draw_elements(
+				&context.gl_version,
+				&context.buffer_manager,
+				&context.vertex_array_manager,
+				&context.state_manager,
+				&mut context.error,
+				"glDrawElementsInstanced",
+				mode,
+				count,
+				ty,
+				indices,
+				0,
+				Instances::new(instance_count, 0),
+			);
+		});
+	}
+
+	fn glDrawElementsInstancedBaseVertex(
+		mode: GLenum,
+		count: GLsizei,
+		ty: GLenum,
+		indices: *const GLvoid,
+		instance_count: GLsizei,
+		base_vertex: GLint,
+	);
+	require gl 3 . 2;
+	require es 3 . 2;
+	require ext
+		ARB_draw_elements_base_vertex
+		| OES_draw_elements_base_vertex
+		| EXT_draw_elements_base_vertex;
+	take context
+	{
+		context.draw("glDrawElementsInstancedBaseVertex", |context| {
+			context.draw_manager.draw_elements(
+				&context.gl_version,
+				&context.buffer_manager,
+				&context.vertex_array_manager,
+				&context.state_manager,
+				&mut context.error,
+				"glDrawElementsInstancedBaseVertex",
+				mode,
+				count,
+				ty,
+				indices,
+				base_vertex,
+				Instances::new(instance_count, 0),
+			);
+		});
+	}
+
+	fn glDrawElementsInstancedBaseInstance(
+		mode: GLenum,
+		count: GLsizei,
+		ty: GLenum,
+		indices: *const GLvoid,
+		instance_count: GLsizei,
+		base_instance: GLuint,
+	);
+	require gl 4 . 2;
+	require ext ARB_base_instance | EXT_base_instance;
+	take context
+	{
+		context.draw("glDrawElementsInstancedBaseInstance", |context| {
+			context.draw_manager.draw_elements(
+				&context.gl_version,
+				&context.buffer_manager,
+				&context.vertex_array_manager,
+				&context.state_manager,
+				&mut context.error,
+				"glDrawElementsInstancedBaseInstance",
+				mode,
+				count,
+				ty,
+				indices,
+				0,
+				Instances::new(instance_count, base_instance),
+			);
+		});
+	}
+
+	fn glDrawElementsInstancedBaseVertexBaseInstance(
+		mode: GLenum,
+		count: GLsizei,
+		ty: GLenum,
+		indices: *const GLvoid,
+		instance_count: GLsizei,
+		base_vertex: GLint,
+		base_instance: GLuint,
+	);
+	require gl 4 . 2;
+	require ext ARB_base_instance | EXT_base_instance;
+	take context
+	{
+		context.draw("glDrawElementsInstancedBaseVertexBaseInstance", |context| {
+			context.draw_manager.draw_elements(
+				&context.gl_version,
+				&context.buffer_manager,
+				&context.vertex_array_manager,
+				&context.state_manager,
+				&mut context.error,
+				"glDrawElementsInstancedBaseVertexBaseInstance",
+				mode,
+				count,
+				ty,
+				indices,
+				base_vertex,
+				Instances::new(instance_count, base_instance),
+			);
+		});
+	}
+
+	fn glDrawRangeElements(
+		mode: GLenum,
+		start: GLuint,
+		end: GLuint,
+		count: GLsizei,
+		ty: GLenum,
+		indices: *const GLvoid,
+	);
+	require gl 2 . 1;
+	require es 3 . 0;
+	take context
+	{
+		context.draw("glDrawRangeElements", |context| {
+			context.draw_manager.draw_range_elements(
+				&context.gl_version,
+				&context.buffer_manager,
+				&context.vertex_array_manager,
+				&context.state_manager,
+				&mut context.error,
+				"glDrawRangeElements",
+				mode,
+				start,
+				end,
+				count,
+				ty,
+				indices,
+				0,
+			);
+		});
+	}
+
+	fn glDrawRangeElementsBaseVertex(
+		mode: GLenum,
+		start: GLuint,
+		end: GLuint,
+		count: GLsizei,
+		ty: GLenum,
+		indices: *const GLvoid,
+		base_vertex: GLint,
+	);
+	require gl 3 . 2;
+	require es 3 . 2;
+	require ext
+		ARB_draw_elements_base_vertex
+		| OES_draw_elements_base_vertex
+		| EXT_draw_elements_base_vertex;
+	take context
+	{
+		context.draw("glDrawRangeElementsBaseVertex", |context| {
+			context.draw_manager.draw_range_elements(
+				&context.gl_version,
+				&context.buffer_manager,
+				&context.vertex_array_manager,
+				&context.state_manager,
+				&mut context.error,
+				"glDrawRangeElementsBaseVertex",
+				mode,
+				start,
+				end,
+				count,
+				ty,
+				indices,
+				base_vertex,
+			);
+		});
+	}
+
+	fn glMultiDrawElements(
+		mode: GLenum,
+		count: *const GLsizei,
+		ty: GLenum,
+		indices: *const *const GLvoid,
+		drawcount: GLsizei,
+	);
+	require gl 2 . 1;
+	require ext EXT_multi_draw_arrays;
+	take context
+	{
+		context.draw("glMultiDrawElements", |context| {
+			context.draw_manager.multi_draw_elements(
+				&context.gl_version,
+				&context.buffer_manager,
+				&context.vertex_array_manager,
+				&context.state_manager,
+				&mut context.error,
+				"glMultiDrawElements",
+				mode,
+				count,
+				ty,
+				indices,
+				drawcount,
+				std::ptr::null(),
+			);
+		});
+	}
+
+	fn glMultiDrawElementsBaseVertex(
+		mode: GLenum,
+		count: *const GLsizei,
+		ty: GLenum,
+		indices: *const *const GLvoid,
+		drawcount: GLsizei,
+		base_vertex: *const GLint,
+	);
+	require gl 3 . 2;
+	require ext
+		ARB_draw_elements_base_vertex
+		| OES_draw_elements_base_vertex
+		| EXT_draw_elements_base_vertex;
+	take context
+	{
+		context.draw("glMultiDrawElementsBaseVertex", |context| {
+			context.draw_manager.multi_draw_elements(
+				&context.gl_version,
+				&context.buffer_manager,
+				&context.vertex_array_manager,
+				&context.state_manager,
+				&mut context.error,
+				"glMultiDrawElementsBaseVertex",
+				mode,
+				count,
+				ty,
+				indices,
+				drawcount,
+				base_vertex,
+			);
+		});
+	}
+
+	fn glDrawArraysIndirect(mode: GLenum, indirect: *const GLvoid);
+	require gl 4 . 0;
+	require es 3 . 1;
+	require ext ARB_draw_indirect;
+	take context
+	{
+		context.draw("glDrawArraysIndirect", |context| {
+			context.draw_manager.draw_arrays_indirect(
+				&context.gl_version,
+				&context.buffer_manager,
+				&context.vertex_array_manager,
+				&context.state_manager,
+				&mut context.error,
+				"glDrawArraysIndirect",
+				mode,
+				indirect as usize,
+				DrawCount::Fixed(1),
+				0,
+			);
+		});
+	}
+
+	fn glMultiDrawArraysIndirect(
+		mode: GLenum,
+		indirect: *const GLvoid,
+		drawcount: GLsizei,
+		stride: GLsizei,
+	);
+	require gl 4 . 3;
+	require ext ARB_multi_draw_indirect | EXT_multi_draw_indirect;
+	take context
+	{
+		context.draw("glMultiDrawArraysIndirect", |context| {
+			context.draw_manager.draw_arrays_indirect(
+				&context.gl_version,
+				&context.buffer_manager,
+				&context.vertex_array_manager,
+				&context.state_manager,
+				&mut context.error,
+				"glMultiDrawArraysIndirect",
+				mode,
+				indirect as usize,
+				DrawCount::Fixed(drawcount),
+				stride,
+			);
+		});
+	}
+
+	fn glMultiDrawArraysIndirectCount(
+		mode: GLenum,
+		indirect: *const GLvoid,
+		drawcount: GLintptr,
+		max_drawcount: GLsizei,
+		stride: GLsizei,
+	);
+	require gl 4 . 6;
+	require ext ARB_indirect_parameters;
+	take context
+	{
+		context.draw("glMultiDrawArraysIndirectCount", |context| {
+			context.draw_manager.draw_arrays_indirect(
+				&context.gl_version,
+				&context.buffer_manager,
+				&context.vertex_array_manager,
+				&context.state_manager,
+				&mut context.error,
+				"glMultiDrawArraysIndirectCount",
+				mode,
+				indirect as usize,
+				DrawCount::Parameter { offset: drawcount, max: max_drawcount },
+				stride,
+			);
+		});
+	}
+
+	fn glDrawElementsIndirect(mode: GLenum, ty: GLenum, indirect: *const GLvoid);
+	require gl 4 . 0;
+	require es 3 . 1;
+	require ext ARB_draw_indirect;
+	take context
+	{
+		context.draw("glDrawElementsIndirect", |context| {
+			context.draw_manager.draw_elements_indirect(
+				&context.gl_version,
+				&context.buffer_manager,
+				&context.vertex_array_manager,
+				&context.state_manager,
+				&mut context.error,
+				"glDrawElementsIndirect",
+				mode,
+				ty,
+				indirect as usize,
+				DrawCount::Fixed(1),
+				0,
+			);
+		});
+	}
+
+	fn glMultiDrawElementsIndirect(
+		mode: GLenum,
+		ty: GLenum,
+		indirect: *const GLvoid,
+		drawcount: GLsizei,
+		stride: GLsizei,
+	);
+	require gl 4 . 3;
+	require ext ARB_multi_draw_indirect | EXT_multi_draw_indirect;
+	take context
+	{
+		context.draw("glMultiDrawElementsIndirect", |context| {
+			context.draw_manager.draw_elements_indirect(
+				&context.gl_version,
+				&context.buffer_manager,
+				&context.vertex_array_manager,
+				&context.state_manager,
+				&mut context.error,
+				"glMultiDrawElementsIndirect",
+				mode,
+				ty,
+				indirect as usize,
+				DrawCount::Fixed(drawcount),
+				stride,
+			);
+		});
+	}
+
+	fn glMultiDrawElementsIndirectCount(
+		mode: GLenum,
+		ty: GLenum,
+		indirect: *const GLvoid,
+		drawcount: GLintptr,
+		max_drawcount: GLsizei,
+		stride: GLsizei,
+	);
+	require gl 4 . 6;
+	require ext ARB_indirect_parameters;
+	take context
+	{
+		context.draw("glMultiDrawElementsIndirectCount", |context| {
+			context.draw_manager.draw_elements_indirect(
+				&context.gl_version,
+				&context.buffer_manager,
+				&context.vertex_array_manager,
+				&context.state_manager,
+				&mut context.error,
+				"glMultiDrawElementsIndirectCount",
+				mode,
+				ty,
+				indirect as usize,
+				DrawCount::Parameter { offset: drawcount, max: max_drawcount },
+				stride,
+			);
+		});
+	}
+
+	fn glDispatchCompute(num_groups_x: GLuint, num_groups_y: GLuint, num_groups_z: GLuint);
+	require gl 4 . 3;
+	require es 3 . 1;
+	require ext ARB_compute_shader;
+	take [limits, error, draw_manager]
+	{
+		draw_manager.dispatch_compute(
+			limits,
+			error,
+			"glDispatchCompute",
+			[num_groups_x, num_groups_y, num_groups_z],
+		);
+	}
+
+	fn glDispatchComputeIndirect(indirect: GLintptr);
+	require gl 4 . 3;
+	require es 3 . 1;
+	require ext ARB_compute_shader;
+	take [limits, error, buffer_manager, vertex_array_manager, draw_manager]
+	{
+		draw_manager.dispatch_compute_indirect(
+			limits,
+			buffer_manager,
+			vertex_array_manager,
+			error,
+			"glDispatchComputeIndirect",
+			indirect,
+		);
+	}
+}
@@ -0,0 +1,516 @@
+use std::mem;
+
+use gl::types::{GLenum, GLint, GLintptr, GLsizei, GLuint};
+
+use crate::{
+	buffer::{BufferBinding, BufferManager},
+	debug,
+	draw::{check_draw, check_index_type, DrawManager, Instances},
+	error,
+	limits::Limits,
+	state::StateManager,
+	version::{ext, VersionType},
+	vertex_array::VertexArrayManager,
+	GlVersion,
+	MockContextRef,
+};
+
+/// Command read by `glDrawArraysIndirect`, laid out as in buffer memory
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct DrawArraysIndirectCommand {
+	pub count: GLuint,
+	pub instance_count: GLuint,
+	pub first: GLuint,
+	pub base_instance: GLuint,
+}
+
+/// Command read by `glDrawElementsIndirect`, laid out as in buffer memory
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct DrawElementsIndirectCommand {
+	pub count: GLuint,
+	pub instance_count: GLuint,
+	pub first_index: GLuint,
+	pub base_vertex: GLint,
+	pub base_instance: GLuint,
+}
+
+/// Command read by `glDispatchComputeIndirect`, laid out as in buffer memory
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct DispatchIndirectCommand {
+	pub num_groups_x: GLuint,
+	pub num_groups_y: GLuint,
+	pub num_groups_z: GLuint,
+}
+
+/// A command decoded from an indirect buffer, before validation
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum IndirectCommand {
+	DrawArrays(DrawArraysIndirectCommand),
+	DrawElements(DrawElementsIndirectCommand),
+	Dispatch(DispatchIndirectCommand),
+}
+
+/// Number of commands read by an indirect multi draw
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DrawCount {
+	Fixed(GLsizei),
+	/// Read from `offset` into the `GL_PARAMETER_BUFFER`, clamped to `max`
+	Parameter {
+		offset: GLintptr,
+		max: GLsizei,
+	},
+}
+
+/// Commands made only of 32 bit words, decodable from buffer memory
+trait Command: Sized {
+	fn from_words(words: &[u32]) -> Self;
+}
+
+impl Command for DrawArraysIndirectCommand {
+	fn from_words(words: &[u32]) -> Self {
+		Self {
+			count: words[0],
+			instance_count: words[1],
+			first: words[2],
+			base_instance: words[3],
+		}
+	}
+}
+
+impl Command for DrawElementsIndirectCommand {
+	fn from_words(words: &[u32]) -> Self {
+		Self {
+			count: words[0],
+			instance_count: words[1],
+			first_index: words[2],
+			base_vertex: words[3] as GLint,
+			base_instance: words[4],
+		}
+	}
+}
+
+impl Command for DispatchIndirectCommand {
+	fn from_words(words: &[u32]) -> Self {
+		Self {
+			num_groups_x: words[0],
+			num_groups_y: words[1],
+			num_groups_z: words[2],
+		}
+	}
+}
+
+fn decode<T: Command>(bytes: &[u8]) -> T {
+	let words: Vec<_> = bytes
+		.chunks_exact(4)
+		.map(|i| u32::from_ne_bytes([i[0], i[1], i[2], i[3]]))
+		.collect();
+	T::from_words(&words)
+}
+
+/// Clamp a command's unsigned field to the signed parameter of the direct draw
+fn signed(value: GLuint) -> GLsizei {
+	GLsizei::try_from(value).unwrap_or(GLsizei::MAX)
+}
+
+impl MockContextRef {
+	/// Indirect commands decoded since the context was created or they were last taken
+	///
+	/// Every command read is recorded, including ones that draw nothing.
+	pub fn indirect_commands(&self) -> Vec<IndirectCommand> {
+		crate::context().draw_manager.indirect_commands.clone()
+	}
+
+	/// Take the decoded indirect commands, clearing the log
+	pub fn take_indirect_commands(&self) -> Vec<IndirectCommand> {
+		mem::take(&mut crate::context().draw_manager.indirect_commands)
+	}
+
+	/// Work group counts of compute dispatches since the context was created or they were last taken
+	pub fn dispatches(&self) -> Vec<[GLuint; 3]> {
+		crate::context().draw_manager.dispatches.clone()
+	}
+
+	/// Take the recorded compute dispatches, clearing the log
+	pub fn take_dispatches(&self) -> Vec<[GLuint; 3]> {
+		mem::take(&mut crate::context().draw_manager.dispatches)
+	}
+}
+
+/// Read `len` bytes at `offset` into the buffer bound to `target`
+fn read_bound<'a>(
+	buffers: &'a BufferManager,
+	vertex_arrays: &VertexArrayManager,
+	error: &mut GLenum,
+	func: &str,
+	target: BufferBinding,
+	offset: usize,
+	len: usize,
+) -> Option<&'a [u8]> {
+	let id = buffers.bound(vertex_arrays, target);
+	if id == 0 {
+		*error = gl::INVALID_OPERATION;
+		error!("{} called without a buffer bound to {}", func, target);
+		return None
+	}
+
+	let memory = buffers
+		.buffer(id)
+		.zip(offset.checked_add(len))
+		.and_then(|(buffer, end)| buffer.memory().get(offset..end));
+	if memory.is_none() {
+		*error = gl::INVALID_OPERATION;
+		error!(
+			"{} reads {} bytes from offset {} of {} buffer {}, which is only {} bytes long",
+			func,
+			len,
+			offset,
+			target,
+			id,
+			buffers.buffer(id).map_or(0, |buffer| buffer.memory().len())
+		);
+	}
+	memory
+}
+
+/// Check an offset into an indirect buffer is a non-negative multiple of 4
+fn check_offset(error: &mut GLenum, func: &str, offset: GLintptr) -> bool {
+	if offset < 0 || offset % 4 != 0 {
+		*error = gl::INVALID_VALUE;
+		error!(
+			"{} called with offset {}, which is not a non-negative multiple of 4",
+			func, offset
+		);
+		return false
+	}
+
+	true
+}
+
+/// Validation shared by indirect draws, which cannot read from client memory
+fn check_indirect_draw(
+	gl_version: &GlVersion,
+	vertex_arrays: &VertexArrayManager,
+	error: &mut GLenum,
+	func: &str,
+) -> bool {
+	if vertex_arrays.bound_id() == 0 {
+		if matches!(gl_version.ty, VersionType::ES) {
+			*error = gl::INVALID_OPERATION;
+			error!("{} called without a bound vertex array", func);
+			return false
+		}
+
+		let array = vertex_arrays.bound();
+		let client_array = (0..array.attributes().len()).find(|index| {
+			array.attributes()[*index].enabled && array.attribute_binding(*index).buffer == 0
+		});
+		if let Some(index) = client_array {
+			*error = gl::INVALID_OPERATION;
+			error!(
+				"{} called with vertex attribute {} sourced from a client side array",
+				func, index
+			);
+			return false
+		}
+	}
+
+	true
+}
+
+/// Decode the commands of an indirect draw from the `GL_DRAW_INDIRECT_BUFFER`
+fn read_commands<T: Command>(
+	buffers: &BufferManager,
+	vertex_arrays: &VertexArrayManager,
+	error: &mut GLenum,
+	func: &str,
+	indirect: usize,
+	drawcount: DrawCount,
+	stride: GLsizei,
+) -> Option<Vec<T>> {
+	let size = mem::size_of::<T>();
+
+	if stride < 0 || stride % 4 != 0 {
+		*error = gl::INVALID_VALUE;
+		error!(
+			"{} called with stride {}, which is not a non-negative multiple of 4",
+			func, stride
+		);
+		return None
+	}
+	let stride = if stride == 0 { size } else { stride as usize };
+
+	let count = match drawcount {
+		DrawCount::Fixed(count) | DrawCount::Parameter { max: count, .. } if count < 0 => {
+			*error = gl::INVALID_VALUE;
+			error!("{} called with negative draw count {}", func, count);
+			return None
+		},
+		DrawCount::Fixed(count) => count as usize,
+		DrawCount::Parameter { offset, max } => {
+			if !check_offset(error, func, offset) {
+				return None
+			}
+
+			let bytes = read_bound(
+				buffers,
+				vertex_arrays,
+				error,
+				func,
+				BufferBinding::PARAMETER_BUFFER,
+				offset as usize,
+				4,
+			)?;
+			let count = u32::from_ne_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+			debug!("{} read draw count {} from the parameter buffer", func, count);
+			count.min(max as u32) as usize
+		},
+	};
+
+	if !check_offset(error, func, indirect as GLintptr) {
+		return None
+	}
+
+	let len = match count {
+		0 => 0,
+		count => (count - 1) * stride + size,
+	};
+	let memory = read_bound(
+		buffers,
+		vertex_arrays,
+		error,
+		func,
+		BufferBinding::DRAW_INDIRECT_BUFFER,
+		indirect,
+		len,
+	)?;
+
+	Some((0..count).map(|i| decode(&memory[i * stride..i * stride + size])).collect())
+}
+
+/// Base instances are reserved and must be zero without `ARB_base_instance`
+fn check_base_instance(gl_version: &GlVersion, func: &str, base_instance: GLuint) {
+	if base_instance != 0
+		&& !gl_version.has_extension(&ext::ARB_base_instance)
+		&& !gl_version.has_extension(&ext::EXT_base_instance)
+	{
+		error!(
+			"{} read base instance {} from an indirect command, which must be zero without {}",
+			func,
+			base_instance,
+			ext::ARB_base_instance.provided_str
+		);
+	}
+}
+
+/// True if a dimension of `num_groups` is above `GL_MAX_COMPUTE_WORK_GROUP_COUNT`
+fn exceeds_work_group_count(limits: &Limits, num_groups: [GLuint; 3]) -> bool {
+	num_groups
+		.iter()
+		.zip(limits.max_compute_work_group_count)
+		.any(|(count, max)| *count > max)
+}
+
+impl DrawManager {
+	/// Draw the `GL_DRAW_INDIRECT_BUFFER` commands at `indirect`, as if by calling
+	/// `glDrawArraysInstancedBaseInstance` for each
+	///
+	/// `stride` is ignored by the single draw, pass 0.
+	#[allow(clippy::too_many_arguments)]
+	pub fn draw_arrays_indirect(
+		&mut self,
+		gl_version: &GlVersion,
+		buffers: &BufferManager,
+		vertex_arrays: &VertexArrayManager,
+		state: &StateManager,
+		error: &mut GLenum,
+		func: &str,
+		mode: GLenum,
+		indirect: usize,
+		drawcount: DrawCount,
+		stride: GLsizei,
+	) {
+		if check_draw(gl_version, vertex_arrays, error, func, mode, 0).is_none()
+			|| !check_indirect_draw(gl_version, vertex_arrays, error, func)
+		{
+			return
+		}
+
+		let Some(commands) = read_commands::<DrawArraysIndirectCommand>(
+			buffers,
+			vertex_arrays,
+			error,
+			func,
+			indirect,
+			drawcount,
+			stride,
+		) else {
+			return
+		};
+
+		for command in commands {
+			self.indirect_commands.push(IndirectCommand::DrawArrays(command));
+			check_base_instance(gl_version, func, command.base_instance);
+
+			// SAFETY: client side arrays were rejected by `check_indirect_draw`
+			unsafe {
+				self.draw_arrays(
+					gl_version,
+					buffers,
+					vertex_arrays,
+					state,
+					error,
+					func,
+					mode,
+					signed(command.first),
+					signed(command.count),
+					Instances::new(signed(command.instance_count), command.base_instance),
+				);
+			}
+		}
+	}
+
+	/// Draw the `GL_DRAW_INDIRECT_BUFFER` commands at `indirect`, as if by calling
+	/// `glDrawElementsInstancedBaseVertexBaseInstance` for each
+	///
+	/// `stride` is ignored by the single draw, pass 0.
+	#[allow(clippy::too_many_arguments)]
+	pub fn draw_elements_indirect(
+		&mut self,
+		gl_version: &GlVersion,
+		buffers: &BufferManager,
+		vertex_arrays: &VertexArrayManager,
+		state: &StateManager,
+		error: &mut GLenum,
+		func: &str,
+		mode: GLenum,
+		ty: GLenum,
+		indirect: usize,
+		drawcount: DrawCount,
+		stride: GLsizei,
+	) {
+		if check_draw(gl_version, vertex_arrays, error, func, mode, 0).is_none()
+			|| !check_indirect_draw(gl_version, vertex_arrays, error, func)
+		{
+			return
+		}
+
+		let Some(index_type) = check_index_type(gl_version, error, func, ty) else {
+			return
+		};
+
+		if vertex_arrays.bound().element_array_buffer() == 0 {
+			*error = gl::INVALID_OPERATION;
+			error!("{} called without an element array buffer", func);
+			return
+		}
+
+		let Some(commands) = read_commands::<DrawElementsIndirectCommand>(
+			buffers,
+			vertex_arrays,
+			error,
+			func,
+			indirect,
+			drawcount,
+			stride,
+		) else {
+			return
+		};
+
+		for command in commands {
+			self.indirect_commands.push(IndirectCommand::DrawElements(command));
+			check_base_instance(gl_version, func, command.base_instance);
+
+			let offset = command.first_index as usize * index_type.size();
+
+			// SAFETY: indices are read from the element array buffer checked above,
+			// client side arrays were rejected by `check_indirect_draw`
+			unsafe {
+				self.draw_elements(
+					gl_version,
+					buffers,
+					vertex_arrays,
+					state,
+					error,
+					func,
+					mode,
+					signed(command.count),
+					ty,
+					offset as *const _,
+					command.base_vertex,
+					Instances::new(signed(command.instance_count), command.base_instance),
+				);
+			}
+		}
+	}
+
+	pub fn dispatch_compute(
+		&mut self,
+		limits: &Limits,
+		error: &mut GLenum,
+		func: &str,
+		num_groups: [GLuint; 3],
+	) {
+		if exceeds_work_group_count(limits, num_groups) {
+			*error = gl::INVALID_VALUE;
+			error!(
+				"{} called with {:?} work groups, more than GL_MAX_COMPUTE_WORK_GROUP_COUNT {:?}",
+				func, num_groups, limits.max_compute_work_group_count
+			);
+			return
+		}
+
+		self.dispatches.push(num_groups);
+		debug!("dispatched {:?} work groups", num_groups);
+	}
+
+	/// Dispatch the `GL_DISPATCH_INDIRECT_BUFFER` command at `indirect`
+	pub fn dispatch_compute_indirect(
+		&mut self,
+		limits: &Limits,
+		buffers: &BufferManager,
+		vertex_arrays: &VertexArrayManager,
+		error: &mut GLenum,
+		func: &str,
+		indirect: GLintptr,
+	) {
+		if !check_offset(error, func, indirect) {
+			return
+		}
+
+		let Some(memory) = read_bound(
+			buffers,
+			vertex_arrays,
+			error,
+			func,
+			BufferBinding::DISPATCH_INDIRECT_BUFFER,
+			indirect as usize,
+			mem::size_of::<DispatchIndirectCommand>(),
+		) else {
+			return
+		};
+
+		let command: DispatchIndirectCommand = decode(memory);
+		self.indirect_commands.push(IndirectCommand::Dispatch(command));
+
+		let num_groups = [
+			command.num_groups_x,
+			command.num_groups_y,
+			command.num_groups_z,
+		];
+
+		// too many work groups is undefined behavior when read from a buffer, not an error
+		if exceeds_work_group_count(limits, num_groups) {
+			error!(
+				"{} read {:?} work groups, more than GL_MAX_COMPUTE_WORK_GROUP_COUNT {:?}",
+				func, num_groups, limits.max_compute_work_group_count
+			);
+			return
+		}
+
+		self.dispatches.push(num_groups);
+		debug!("dispatched {:?} work groups", num_groups);
+	}
+}
@@ -0,0 +1,661 @@
+use std::ffi::c_void;
+
+use gl::types::{GLenum, GLintptr, GLsizei, GLsizeiptr, GLuint};
+
+use crate::{
+	draw::{
+		indirect::{
+			DispatchIndirectCommand,
+			DrawArraysIndirectCommand,
+			DrawElementsIndirectCommand,
+			IndirectCommand,
+		},
+		IndexType,
+		Instances,
+		PrimitiveMode,
+	},
+	test::{gl33_core, gl46_core, test_harness, test_harness_context, test_harness_handling},
+	version::{ext::OES_element_index_uint, Profile, VersionType},
+	vertex_array::AttribValue,
+	GlVersion,
+};
+
+/// Create a buffer filled with `data` bound to `target`
+unsafe fn buffer_with<T>(target: gl::types::GLenum, data: &[T]) -> GLuint {
+	let mut buffer = 0;
+	gl::GenBuffers(1, &mut buffer);
+	gl::BindBuffer(target, buffer);
+	gl::BufferData(
+		target,
+		std::mem::size_of_val(data) as GLsizeiptr,
+		data.as_ptr() as *const c_void,
+		gl::STATIC_DRAW,
+	);
+	buffer
+}
+
+/// Run `f` with a vertex array holding 3 vec3 positions in attribute 0
+fn with_triangle(version: GlVersion, f: impl FnOnce()) {
+	test_harness(version, || unsafe {
+		let mut array = 0;
+		gl::GenVertexArrays(1, &mut array);
+		gl::BindVertexArray(array);
+
+		let buffer = buffer_with(gl::ARRAY_BUFFER, &[0.0f32; 9]);
+		gl::VertexAttribPointer(0, 3, gl::FLOAT, gl::FALSE, 0, std::ptr::null());
+		gl::EnableVertexAttribArray(0);
+
+		f();
+
+		gl::DeleteBuffers(1, &buffer);
+		gl::DeleteVertexArrays(1, &array);
+	})
+}
+
+#[test]
+fn draw_arrays() {
+	with_triangle(gl33_core(), || unsafe {
+		gl::DrawArrays(gl::TRIANGLES, 0, 3);
+		gl::DrawArrays(gl::POINTS, 2, 1);
+		gl::DrawArrays(gl::POINTS, 0, 0);
+	})
+}
+
+#[test]
+#[should_panic]
+fn draw_arrays_out_of_bounds() {
+	with_triangle(gl33_core(), || unsafe {
+		gl::DrawArrays(gl::TRIANGLES, 1, 3);
+	})
+}
+
+#[test]
+#[should_panic]
+fn draw_arrays_without_storage() {
+	test_harness(gl33_core(), || unsafe {
+		let mut array = 0;
+		let mut buffer = 0;
+		gl::GenVertexArrays(1, &mut array);
+		gl::GenBuffers(1, &mut buffer);
+		gl::BindVertexArray(array);
+		gl::BindBuffer(gl::ARRAY_BUFFER, buffer);
+		gl::VertexAttribPointer(0, 3, gl::FLOAT, gl::FALSE, 0, std::ptr::null());
+		gl::EnableVertexAttribArray(0);
+		gl::DrawArrays(gl::TRIANGLES, 0, 3);
+	})
+}
+
+#[test]
+fn draw_elements() {
+	with_triangle(gl33_core(), || unsafe {
+		let indices = buffer_with(gl::ELEMENT_ARRAY_BUFFER, &[0u16, 1, 2, 2, 1, 0]);
+		gl::DrawElements(gl::TRIANGLES, 6, gl::UNSIGNED_SHORT, std::ptr::null());
+		gl::DrawElements(gl::TRIANGLES, 3, gl::UNSIGNED_SHORT, 6 as *const c_void);
+		gl::DeleteBuffers(1, &indices);
+	})
+}
+
+#[test]
+#[should_panic]
+fn draw_elements_index_out_of_range() {
+	with_triangle(gl33_core(), || unsafe {
+		let indices = buffer_with(gl::ELEMENT_ARRAY_BUFFER, &[0u8, 1, 3]);
+		gl::DrawElements(gl::TRIANGLES, 3, gl::UNSIGNED_BYTE, std::ptr::null());
+		gl::DeleteBuffers(1, &indices);
+	})
+}
+
+#[test]
+#[should_panic]
+fn draw_elements_past_element_buffer() {
+	with_triangle(gl33_core(), || unsafe {
+		let indices = buffer_with(gl::ELEMENT_ARRAY_BUFFER, &[0u32, 1, 2]);
+		gl::DrawElements(gl::TRIANGLES, 3, gl::UNSIGNED_INT, 4 as *const c_void);
+		gl::DeleteBuffers(1, &indices);
+	})
+}
+
+#[test]
+fn overflowing_offsets() {
+	test_harness_handling(gl33_core(), crate::ErrorHandling::DoNotPanic, || unsafe {
+		let mut array = 0;
+		gl::GenVertexArrays(1, &mut array);
+		gl::BindVertexArray(array);
+
+		// offsets past the end of the buffer are logged, but no GL error
+		let buffer = buffer_with(gl::ARRAY_BUFFER, &[0.0f32; 9]);
+		let offset = (usize::MAX - 3) as *const c_void;
+		gl::VertexAttribPointer(0, 3, gl::FLOAT, gl::FALSE, 12, offset);
+		gl::EnableVertexAttribArray(0);
+		gl::DrawArrays(gl::TRIANGLES, 0, 3);
+		assert_eq!(gl::GetError(), gl::NO_ERROR);
+
+		gl::VertexAttribPointer(0, 3, gl::FLOAT, gl::FALSE, 0, std::ptr::null());
+		let indices = buffer_with(gl::ELEMENT_ARRAY_BUFFER, &[0u16, 1, 2]);
+		let offset = (usize::MAX - 1) as *const c_void;
+		gl::DrawElements(gl::TRIANGLES, 3, gl::UNSIGNED_SHORT, offset);
+		assert_eq!(gl::GetError(), gl::NO_ERROR);
+
+		gl::DeleteBuffers(1, &indices);
+		gl::DeleteBuffers(1, &buffer);
+		gl::DeleteVertexArrays(1, &array);
+	})
+}
+
+#[test]
+fn client_side_arrays() {
+	test_harness(GlVersion::from_version(VersionType::GL, 2, 1), || unsafe {
+		let positions = [0.0f32; 6];
+		let indices = [0u8, 1, 2];
+		gl::VertexAttribPointer(0, 2, gl::FLOAT, gl::FALSE, 0, positions.as_ptr() as *const _);
+		gl::EnableVertexAttribArray(0);
+		gl::DrawElements(gl::TRIANGLES, 3, gl::UNSIGNED_BYTE, indices.as_ptr() as *const _);
+	})
+}
+
+#[test]
+fn gl_errors() {
+	test_harness_handling(gl33_core(), crate::ErrorHandling::DoNotPanic, || unsafe {
+		gl::DrawArrays(gl::TRIANGLES, 0, 3);
+		assert_eq!(gl::GetError(), gl::INVALID_OPERATION);
+
+		let mut array = 0;
+		gl::GenVertexArrays(1, &mut array);
+		gl::BindVertexArray(array);
+
+		gl::DrawArrays(gl::TEXTURE, 0, 3);
+		assert_eq!(gl::GetError(), gl::INVALID_ENUM);
+
+		gl::DrawArrays(gl::TRIANGLES, 0, -3);
+		assert_eq!(gl::GetError(), gl::INVALID_VALUE);
+
+		gl::DrawArrays(gl::TRIANGLES, -1, 3);
+		assert_eq!(gl::GetError(), gl::INVALID_VALUE);
+
+		gl::DrawElements(gl::TRIANGLES, 3, gl::FLOAT, std::ptr::null());
+		assert_eq!(gl::GetError(), gl::INVALID_ENUM);
+
+		// client side indices
+		let indices = [0u8, 1, 2];
+		gl::DrawElements(gl::TRIANGLES, 3, gl::UNSIGNED_BYTE, indices.as_ptr() as *const _);
+		assert_eq!(gl::GetError(), gl::INVALID_OPERATION);
+
+		gl::DeleteVertexArrays(1, &array);
+	})
+}
+
+#[test]
+fn record_draw_arrays() {
+	test_harness_context(gl33_core(), |ctx| unsafe {
+		let mut array = 0;
+		gl::GenVertexArrays(1, &mut array);
+		gl::BindVertexArray(array);
+
+		// interleaved vec2 position and normalized rgba8 color
+		#[repr(C)]
+		struct Vertex([f32; 2], [u8; 4]);
+		let vertices = [
+			Vertex([0.0, 0.0], [255, 0, 0, 255]),
+			Vertex([1.0, 0.0], [0, 255, 0, 255]),
+			Vertex([0.0, 1.0], [0, 0, 255, 0]),
+		];
+		let buffer = buffer_with(gl::ARRAY_BUFFER, &vertices);
+		gl::VertexAttribPointer(0, 2, gl::FLOAT, gl::FALSE, 12, std::ptr::null());
+		gl::VertexAttribPointer(1, 4, gl::UNSIGNED_BYTE, gl::TRUE, 12, 8 as *const _);
+		gl::EnableVertexAttribArray(0);
+		gl::EnableVertexAttribArray(1);
+
+		gl::DrawArrays(gl::TRIANGLES, 0, 3);
+		gl::DrawArrays(gl::POINTS, 1, 2);
+
+		let draws = ctx.take_draw_calls();
+		assert_eq!(draws.len(), 2);
+		assert_eq!(draws[0].mode, PrimitiveMode::TRIANGLES);
+		assert_eq!(draws[0].count(), 3);
+		assert_eq!(draws[0].vertex_array, array);
+		assert_eq!(draws[0].attribute(0).unwrap(), [
+			AttribValue::Float([0.0, 0.0, 0.0, 1.0]),
+			AttribValue::Float([1.0, 0.0, 0.0, 1.0]),
+			AttribValue::Float([0.0, 1.0, 0.0, 1.0]),
+		]);
+		assert_eq!(draws[0].attribute(1).unwrap()[2], AttribValue::Float([0.0, 0.0, 1.0, 0.0]));
+		assert_eq!(draws[1].vertices, [1, 2]);
+		assert_eq!(draws[1].attribute(0).unwrap()[0], AttribValue::Float([1.0, 0.0, 0.0, 1.0]));
+		assert!(draws[1].attribute(2).is_none());
+		assert!(ctx.draw_calls().is_empty());
+
+		gl::DeleteBuffers(1, &buffer);
+		gl::DeleteVertexArrays(1, &array);
+	})
+}
+
+#[test]
+fn record_draw_elements() {
+	test_harness_context(gl33_core(), |ctx| unsafe {
+		let mut array = 0;
+		gl::GenVertexArrays(1, &mut array);
+		gl::BindVertexArray(array);
+
+		let buffer = buffer_with(gl::ARRAY_BUFFER, &[10i16, 20, 30, 40]);
+		let indices = buffer_with(gl::ELEMENT_ARRAY_BUFFER, &[3u8, 0, 2]);
+		gl::VertexAttribIPointer(2, 1, gl::SHORT, 0, std::ptr::null());
+		gl::EnableVertexAttribArray(2);
+
+		gl::DrawElements(gl::LINE_STRIP, 3, gl::UNSIGNED_BYTE, std::ptr::null());
+
+		let draws = ctx.draw_calls();
+		assert_eq!(draws[0].index_type, Some(IndexType::UNSIGNED_BYTE));
+		assert_eq!(draws[0].vertices, [3, 0, 2]);
+		assert_eq!(draws[0].attribute(2).unwrap(), [
+			AttribValue::Int([40, 0, 0, 1]),
+			AttribValue::Int([10, 0, 0, 1]),
+			AttribValue::Int([30, 0, 0, 1])
+		]);
+
+		gl::DeleteBuffers(1, &indices);
+		gl::DeleteBuffers(1, &buffer);
+		gl::DeleteVertexArrays(1, &array);
+	})
+}
+
+/// Run `f` with the triangle and 2 instances of a float offset in attribute 1
+fn with_instanced_triangle(f: impl FnOnce(&crate::MockContextRef)) {
+	let version = GlVersion::from_version(VersionType::GL, 4, 2).with_profile(Profile::Core);
+	test_harness_context(version, |ctx| unsafe {
+		let mut array = 0;
+		gl::GenVertexArrays(1, &mut array);
+		gl::BindVertexArray(array);
+
+		let positions = buffer_with(gl::ARRAY_BUFFER, &[0.0f32; 9]);
+		gl::VertexAttribPointer(0, 3, gl::FLOAT, gl::FALSE, 0, std::ptr::null());
+		gl::EnableVertexAttribArray(0);
+
+		let offsets = buffer_with(gl::ARRAY_BUFFER, &[1.0f32, 2.0]);
+		gl::VertexAttribPointer(1, 1, gl::FLOAT, gl::FALSE, 0, std::ptr::null());
+		gl::VertexAttribDivisor(1, 1);
+		gl::EnableVertexAttribArray(1);
+
+		f(ctx);
+
+		gl::DeleteBuffers(1, &offsets);
+		gl::DeleteBuffers(1, &positions);
+		gl::DeleteVertexArrays(1, &array);
+	})
+}
+
+#[test]
+fn draw_instanced() {
+	with_instanced_triangle(|ctx| unsafe {
+		gl::DrawArraysInstanced(gl::TRIANGLES, 0, 3, 2);
+		gl::DrawArraysInstancedBaseInstance(gl::TRIANGLES, 0, 3, 1, 1);
+		gl::VertexAttribDivisor(1, 2);
+		gl::DrawArraysInstanced(gl::TRIANGLES, 0, 3, 4);
+
+		let draws = ctx.take_draw_calls();
+		assert_eq!(draws[0].instances, Instances::new(2, 0));
+		assert_eq!(draws[0].attribute(0).unwrap().len(), 3);
+		assert!(draws[0].attribute(1).is_none());
+		assert_eq!(draws[0].instanced_attribute(1).unwrap(), [
+			AttribValue::Float([1.0, 0.0, 0.0, 1.0]),
+			AttribValue::Float([2.0, 0.0, 0.0, 1.0])
+		]);
+		assert_eq!(draws[1].instanced_attribute(1).unwrap(), [AttribValue::Float([
+			2.0, 0.0, 0.0, 1.0
+		])]);
+		assert_eq!(
+			draws[2].instanced_attribute(1).unwrap().to_vec(),
+			[1.0, 1.0, 2.0, 2.0].map(|x| AttribValue::Float([x, 0.0, 0.0, 1.0]))
+		);
+	})
+}
+
+#[test]
+#[should_panic]
+fn draw_instanced_out_of_bounds() {
+	with_instanced_triangle(|_| unsafe {
+		gl::DrawArraysInstancedBaseInstance(gl::TRIANGLES, 0, 3, 2, 1);
+	})
+}
+
+#[test]
+fn draw_elements_base_vertex() {
+	with_instanced_triangle(|ctx| unsafe {
+		let indices = buffer_with(gl::ELEMENT_ARRAY_BUFFER, &[1u16, 0, 2, 0]);
+		gl::DrawElementsBaseVertex(gl::TRIANGLES, 3, gl::UNSIGNED_SHORT, 2 as *const c_void, 0);
+		gl::DrawRangeElementsBaseVertex(
+			gl::LINES,
+			0,
+			1,
+			2,
+			gl::UNSIGNED_SHORT,
+			std::ptr::null(),
+			1,
+		);
+		gl::DrawElementsInstancedBaseVertexBaseInstance(
+			gl::POINTS,
+			1,
+			gl::UNSIGNED_SHORT,
+			std::ptr::null(),
+			1,
+			1,
+			1,
+		);
+
+		let draws = ctx.take_draw_calls();
+		assert_eq!(draws[0].vertices, [0, 2, 0]);
+		assert_eq!(draws[1].vertices, [2, 1]);
+		assert_eq!(draws[2].vertices, [2]);
+		assert_eq!(draws[2].instances, Instances::new(1, 1));
+		assert_eq!(draws[2].instanced_attribute(1).unwrap(), [AttribValue::Float([
+			2.0, 0.0, 0.0, 1.0
+		])]);
+
+		gl::DeleteBuffers(1, &indices);
+	})
+}
+
+#[test]
+#[should_panic]
+fn draw_range_elements_outside_range() {
+	with_triangle(gl33_core(), || unsafe {
+		let indices = buffer_with(gl::ELEMENT_ARRAY_BUFFER, &[0u8, 1, 2]);
+		gl::DrawRangeElements(gl::TRIANGLES, 0, 1, 3, gl::UNSIGNED_BYTE, std::ptr::null());
+		gl::DeleteBuffers(1, &indices);
+	})
+}
+
+#[test]
+fn multi_draw() {
+	with_instanced_triangle(|ctx| unsafe {
+		gl::DisableVertexAttribArray(1);
+
+		gl::MultiDrawArrays(gl::POINTS, [0, 2].as_ptr(), [2, 1].as_ptr(), 2);
+
+		let indices = buffer_with(gl::ELEMENT_ARRAY_BUFFER, &[0u8, 1, 2, 1]);
+		let offsets = [std::ptr::null(), 3 as *const c_void];
+		gl::MultiDrawElementsBaseVertex(
+			gl::POINTS,
+			[3, 1].as_ptr(),
+			gl::UNSIGNED_BYTE,
+			offsets.as_ptr(),
+			2,
+			[0, 1].as_ptr(),
+		);
+
+		let vertices: Vec<_> =
+			ctx.take_draw_calls().into_iter().map(|draw| draw.vertices).collect();
+		assert_eq!(vertices, [vec![0, 1], vec![2], vec![0, 1, 2], vec![2]]);
+
+		gl::DeleteBuffers(1, &indices);
+	})
+}
+
+#[test]
+#[should_panic]
+fn draw_instanced_missing_extension() {
+	with_triangle(GlVersion::from_version(VersionType::GL, 3, 0), || unsafe {
+		gl::DrawArraysInstanced(gl::TRIANGLES, 0, 3, 2);
+	})
+}
+
+#[test]
+fn instanced_gl_errors() {
+	test_harness_handling(gl33_core(), crate::ErrorHandling::DoNotPanic, || unsafe {
+		let mut array = 0;
+		gl::GenVertexArrays(1, &mut array);
+		gl::BindVertexArray(array);
+
+		gl::DrawArraysInstanced(gl::TRIANGLES, 0, 3, -1);
+		assert_eq!(gl::GetError(), gl::INVALID_VALUE);
+
+		gl::DrawRangeElements(gl::TRIANGLES, 2, 1, 3, gl::UNSIGNED_BYTE, std::ptr::null());
+		assert_eq!(gl::GetError(), gl::INVALID_VALUE);
+
+		gl::MultiDrawArrays(gl::TRIANGLES, std::ptr::null(), std::ptr::null(), -1);
+		assert_eq!(gl::GetError(), gl::INVALID_VALUE);
+
+		gl::DeleteVertexArrays(1, &array);
+	})
+}
+
+#[test]
+fn draw_indirect() {
+	test_harness_context(gl46_core(), |ctx| unsafe {
+		let mut array = 0;
+		gl::GenVertexArrays(1, &mut array);
+		gl::BindVertexArray(array);
+		let positions = buffer_with(gl::ARRAY_BUFFER, &[0.0f32; 12]);
+		gl::VertexAttribPointer(0, 3, gl::FLOAT, gl::FALSE, 0, std::ptr::null());
+		gl::EnableVertexAttribArray(0);
+
+		let culled = DrawArraysIndirectCommand {
+			count: 3,
+			..Default::default()
+		};
+		let visible = DrawArraysIndirectCommand {
+			count: 3,
+			instance_count: 2,
+			first: 1,
+			base_instance: 0,
+		};
+		let commands = buffer_with(gl::DRAW_INDIRECT_BUFFER, &[culled, visible]);
+
+		gl::DrawArraysIndirect(gl::TRIANGLES, 16 as *const c_void);
+		gl::MultiDrawArraysIndirect(gl::TRIANGLES, std::ptr::null(), 2, 0);
+
+		assert_eq!(
+			ctx.take_indirect_commands(),
+			[visible, culled, visible].map(IndirectCommand::DrawArrays)
+		);
+		let draws = ctx.take_draw_calls();
+		assert_eq!(draws.len(), 2);
+		assert_eq!(draws[1].vertices, [1, 2, 3]);
+		assert_eq!(draws[1].instances, Instances::new(2, 0));
+
+		let elements = DrawElementsIndirectCommand {
+			count: 2,
+			instance_count: 1,
+			first_index: 1,
+			base_vertex: 1,
+			base_instance: 0,
+		};
+		// padded to a stride of 24 bytes
+		#[repr(C)]
+		struct Padded(DrawElementsIndirectCommand, u32);
+		let data = [Padded(elements, 0), Padded(elements, 0)];
+		let indirect = buffer_with(gl::DRAW_INDIRECT_BUFFER, &data);
+		let indices = buffer_with(gl::ELEMENT_ARRAY_BUFFER, &[0u16, 2, 1]);
+		gl::MultiDrawElementsIndirect(gl::LINES, gl::UNSIGNED_SHORT, std::ptr::null(), 2, 24);
+
+		assert_eq!(ctx.take_indirect_commands(), [IndirectCommand::DrawElements(elements); 2]);
+		let draws = ctx.take_draw_calls();
+		assert_eq!(draws.len(), 2);
+		assert_eq!(draws[0].vertices, [3, 2]);
+
+		gl::DeleteBuffers(1, &indices);
+		gl::DeleteBuffers(1, &indirect);
+		gl::DeleteBuffers(1, &commands);
+		gl::DeleteBuffers(1, &positions);
+		gl::DeleteVertexArrays(1, &array);
+	})
+}
+
+#[test]
+fn draw_indirect_count() {
+	test_harness_context(gl46_core(), |ctx| unsafe {
+		let draw: unsafe extern "system" fn(GLenum, *const c_void, GLintptr, GLsizei, GLsizei) =
+			std::mem::transmute(ctx.get_proc_address("glMultiDrawArraysIndirectCount"));
+
+		let mut array = 0;
+		gl::GenVertexArrays(1, &mut array);
+		gl::BindVertexArray(array);
+
+		let command = DrawArraysIndirectCommand {
+			count: 1,
+			instance_count: 1,
+			..Default::default()
+		};
+		let commands = buffer_with(gl::DRAW_INDIRECT_BUFFER, &[command; 4]);
+		let parameters = buffer_with(crate::version::enums::PARAMETER_BUFFER, &[0u32, 3]);
+
+		draw(gl::POINTS, std::ptr::null(), 4, 2, 0);
+		assert_eq!(ctx.take_indirect_commands().len(), 2);
+		draw(gl::POINTS, std::ptr::null(), 4, 8, 0);
+		assert_eq!(ctx.take_indirect_commands().len(), 3);
+		draw(gl::POINTS, std::ptr::null(), 0, 8, 0);
+		assert!(ctx.take_indirect_commands().is_empty());
+
+		gl::DeleteBuffers(1, &parameters);
+		gl::DeleteBuffers(1, &commands);
+		gl::DeleteVertexArrays(1, &array);
+	})
+}
+
+#[test]
+fn dispatch_indirect() {
+	test_harness_context(gl46_core(), |ctx| unsafe {
+		let command = DispatchIndirectCommand {
+			num_groups_x: 4,
+			num_groups_y: 2,
+			num_groups_z: 1,
+		};
+		let buffer = buffer_with(gl::DISPATCH_INDIRECT_BUFFER, &[command]);
+
+		gl::DispatchCompute(1, 1, 1);
+		gl::DispatchComputeIndirect(0);
+
+		assert_eq!(ctx.take_dispatches(), [[1, 1, 1], [4, 2, 1]]);
+		assert_eq!(ctx.take_indirect_commands(), [IndirectCommand::Dispatch(command)]);
+
+		gl::DeleteBuffers(1, &buffer);
+	})
+}
+
+#[test]
+#[should_panic]
+fn dispatch_indirect_too_many_groups() {
+	test_harness(gl46_core(), || unsafe {
+		let command = DispatchIndirectCommand {
+			num_groups_x: 1 << 20,
+			num_groups_y: 1,
+			num_groups_z: 1,
+		};
+		let buffer = buffer_with(gl::DISPATCH_INDIRECT_BUFFER, &[command]);
+		gl::DispatchComputeIndirect(0);
+		gl::DeleteBuffers(1, &buffer);
+	})
+}
+
+#[test]
+fn indirect_gl_errors() {
+	test_harness_handling(gl46_core(), crate::ErrorHandling::DoNotPanic, || unsafe {
+		let mut array = 0;
+		gl::GenVertexArrays(1, &mut array);
+		gl::BindVertexArray(array);
+
+		gl::DrawArraysIndirect(gl::TRIANGLES, std::ptr::null());
+		assert_eq!(gl::GetError(), gl::INVALID_OPERATION);
+
+		let buffer = buffer_with(gl::DRAW_INDIRECT_BUFFER, &[DrawArraysIndirectCommand::default()]);
+		gl::DrawArraysIndirect(gl::TRIANGLES, 2 as *const c_void);
+		assert_eq!(gl::GetError(), gl::INVALID_VALUE);
+
+		gl::DrawArraysIndirect(gl::TRIANGLES, 4 as *const c_void);
+		assert_eq!(gl::GetError(), gl::INVALID_OPERATION);
+		gl::DrawArraysIndirect(gl::TRIANGLES, (isize::MAX - 3) as *const c_void);
+		assert_eq!(gl::GetError(), gl::INVALID_OPERATION);
+
+		gl::MultiDrawArraysIndirect(gl::TRIANGLES, std::ptr::null(), 1, 6);
+		assert_eq!(gl::GetError(), gl::INVALID_VALUE);
+
+		gl::MultiDrawArraysIndirect(gl::TRIANGLES, std::ptr::null(), 2, 0);
+		assert_eq!(gl::GetError(), gl::INVALID_OPERATION);
+
+		// no element array buffer
+		gl::DrawElementsIndirect(gl::TRIANGLES, gl::UNSIGNED_INT, std::ptr::null());
+		assert_eq!(gl::GetError(), gl::INVALID_OPERATION);
+
+		gl::DispatchCompute(1 << 20, 1, 1);
+		assert_eq!(gl::GetError(), gl::INVALID_VALUE);
+
+		gl::DispatchComputeIndirect(0);
+		assert_eq!(gl::GetError(), gl::INVALID_OPERATION);
+		let dispatch = buffer_with(gl::DISPATCH_INDIRECT_BUFFER, &[0u32; 3]);
+		gl::DispatchComputeIndirect(GLintptr::MAX - 3);
+		assert_eq!(gl::GetError(), gl::INVALID_OPERATION);
+
+		gl::DeleteBuffers(1, &dispatch);
+		gl::DeleteBuffers(1, &buffer);
+		gl::DeleteVertexArrays(1, &array);
+	});
+
+	let es = GlVersion::from_version(VersionType::ES, 3, 1);
+	test_harness_handling(es, crate::ErrorHandling::DoNotPanic, || unsafe {
+		let buffer = buffer_with(gl::DRAW_INDIRECT_BUFFER, &[DrawArraysIndirectCommand::default()]);
+		gl::DrawArraysIndirect(gl::TRIANGLES, std::ptr::null());
+		assert_eq!(gl::GetError(), gl::INVALID_OPERATION);
+		gl::DeleteBuffers(1, &buffer);
+	})
+}
+
+#[test]
+fn primitive_restart() {
+	let version = GlVersion::from_version(VersionType::GL, 4, 3).with_profile(Profile::Core);
+	test_harness_context(version, |ctx| unsafe {
+		let mut array = 0;
+		gl::GenVertexArrays(1, &mut array);
+		gl::BindVertexArray(array);
+		let positions = buffer_with(gl::ARRAY_BUFFER, &[0.0f32; 8]);
+		gl::VertexAttribPointer(0, 2, gl::FLOAT, gl::FALSE, 0, std::ptr::null());
+		gl::EnableVertexAttribArray(0);
+
+		// restart indices would be far out of bounds if fetched
+		let indices =
+			buffer_with(gl::ELEMENT_ARRAY_BUFFER, &[0u16, 1, 2, 0xffff, 1, 2, 3, 100, 3, 2, 1]);
+
+		gl::Enable(gl::PRIMITIVE_RESTART_FIXED_INDEX);
+		gl::DrawElements(gl::TRIANGLE_STRIP, 7, gl::UNSIGNED_SHORT, std::ptr::null());
+
+		gl::Disable(gl::PRIMITIVE_RESTART_FIXED_INDEX);
+		gl::Enable(gl::PRIMITIVE_RESTART);
+		gl::PrimitiveRestartIndex(100);
+		gl::DrawElements(gl::TRIANGLE_STRIP, 7, gl::UNSIGNED_SHORT, 8 as *const c_void);
+
+		let draws = ctx.take_draw_calls();
+		assert_eq!(draws[0].vertices, [0, 1, 2, 1, 2, 3]);
+		assert_eq!(draws[0].restarts, [3]);
+		assert_eq!(draws[0].strips(), [0..3, 3..6]);
+		assert_eq!(draws[1].vertices, [1, 2, 3, 3, 2, 1]);
+		assert_eq!(draws[1].strips(), [0..3, 3..6]);
+
+		gl::DeleteBuffers(1, &indices);
+		gl::DeleteBuffers(1, &positions);
+		gl::DeleteVertexArrays(1, &array);
+	})
+}
+
+#[test]
+#[should_panic]
+fn incomplete_primitive() {
+	test_harness(GlVersion::from_version(VersionType::GL, 2, 1), || unsafe {
+		let positions = [0.0f32; 10];
+		gl::VertexAttribPointer(0, 2, gl::FLOAT, gl::FALSE, 0, positions.as_ptr() as *const _);
+		gl::EnableVertexAttribArray(0);
+		gl::DrawArrays(gl::TRIANGLES, 0, 5);
+	})
+}
+
+#[test]
+fn element_index_uint() {
+	let indices = [0u32, 1, 2];
+	let es = GlVersion::from_version(VersionType::ES, 2, 0);
+	test_harness_handling(es, crate::ErrorHandling::DoNotPanic, || unsafe {
+		gl::DrawElements(gl::POINTS, 3, gl::UNSIGNED_INT, indices.as_ptr() as *const _);
+		assert_eq!(gl::GetError(), gl::INVALID_ENUM);
+	});
+
+	let es = GlVersion::new(VersionType::ES, 2, 0, &[&OES_element_index_uint]);
+	test_harness(es, || unsafe {
+		gl::DrawElements(gl::POINTS, 3, gl::UNSIGNED_INT, indices.as_ptr() as *const _);
+	})
+}
@@ -0,0 +1,190 @@
+use crate::{
+	version::{at_least, ext, gl_enum, VersionType},
+	GlVersion,
+};
+
+#[cfg(test)]
+mod test;
+
+gl_enum! {
+	InternalFormat {
+		R8(gl: 3 . 0, es: 3 . 0);
+		RG8(gl: 3 . 0, es: 3 . 0);
+		RGB8(gl: 2 . 1, es: 3 . 0);
+		RGBA8(gl: 2 . 1, es: 3 . 0);
+		RGBA4(gl: 2 . 1, es: 2 . 0);
+		RGB5_A1(gl: 2 . 1, es: 2 . 0);
+		RGB565(gl: 4 . 1, es: 2 . 0);
+		RGB10_A2(gl: 2 . 1, es: 3 . 0);
+		SRGB8_ALPHA8(gl: 2 . 1, es: 3 . 0);
+		R16F(gl: 3 . 0, es: 3 . 0);
+		RG16F(gl: 3 . 0, es: 3 . 0);
+		RGBA16F(gl: 3 . 0, es: 3 . 0);
+		R32F(gl: 3 . 0, es: 3 . 0);
+		RG32F(gl: 3 . 0, es: 3 . 0);
+		RGBA32F(gl: 3 . 0, es: 3 . 0);
+		R11F_G11F_B10F(gl: 3 . 0, es: 3 . 0);
+		R8I(gl: 3 . 0, es: 3 . 0);
+		R8UI(gl: 3 . 0, es: 3 . 0);
+		R32I(gl: 3 . 0, es: 3 . 0);
+		R32UI(gl: 3 . 0, es: 3 . 0);
+		RGBA8I(gl: 3 . 0, es: 3 . 0);
+		RGBA8UI(gl: 3 . 0, es: 3 . 0);
+		RGBA32I(gl: 3 . 0, es: 3 . 0);
+		RGBA32UI(gl: 3 . 0, es: 3 . 0);
+		DEPTH_COMPONENT16(gl: 2 . 1, es: 2 . 0);
+		DEPTH_COMPONENT24(gl: 2 . 1, es: 3 . 0);
+		DEPTH_COMPONENT32F(gl: 3 . 0, es: 3 . 0);
+		DEPTH24_STENCIL8(gl: 3 . 0, es: 3 . 0);
+		DEPTH32F_STENCIL8(gl: 3 . 0, es: 3 . 0);
+		STENCIL_INDEX8(gl: 3 . 0, es: 2 . 0);
+	}
+}
+
+/// How the color components of a format are stored
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ComponentType {
+	UnsignedNormalized,
+	Float,
+	Int,
+	UnsignedInt,
+}
+
+impl InternalFormat {
+	/// Type of the color components, `None` for depth and stencil formats
+	pub fn component_type(&self) -> Option<ComponentType> {
+		use InternalFormat::*;
+
+		match self {
+			R8 | RG8 | RGB8 | RGBA8 | RGBA4 | RGB5_A1 | RGB565 | RGB10_A2 | SRGB8_ALPHA8 =>
+				Some(ComponentType::UnsignedNormalized),
+			R16F | RG16F | RGBA16F | R32F | RG32F | RGBA32F | R11F_G11F_B10F =>
+				Some(ComponentType::Float),
+			R8I | R32I | RGBA8I | RGBA32I => Some(ComponentType::Int),
+			R8UI | R32UI | RGBA8UI | RGBA32UI => Some(ComponentType::UnsignedInt),
+			DEPTH_COMPONENT16 | DEPTH_COMPONENT24 | DEPTH_COMPONENT32F | DEPTH24_STENCIL8
+			| DEPTH32F_STENCIL8 | STENCIL_INDEX8 => None,
+		}
+	}
+
+	pub fn is_color(&self) -> bool {
+		self.component_type().is_some()
+	}
+
+	/// Bits of the red, green, blue and alpha components
+	pub fn color_bits(&self) -> [u8; 4] {
+		use InternalFormat::*;
+
+		match self {
+			R8 | R8I | R8UI => [8, 0, 0, 0],
+			RG8 => [8, 8, 0, 0],
+			RGB8 => [8, 8, 8, 0],
+			RGBA8 | SRGB8_ALPHA8 | RGBA8I | RGBA8UI => [8; 4],
+			RGBA4 => [4; 4],
+			RGB5_A1 => [5, 5, 5, 1],
+			RGB565 => [5, 6, 5, 0],
+			RGB10_A2 => [10, 10, 10, 2],
+			R16F => [16, 0, 0, 0],
+			RG16F => [16, 16, 0, 0],
+			RGBA16F => [16; 4],
+			R32F | R32I | R32UI => [32, 0, 0, 0],
+			RG32F => [32, 32, 0, 0],
+			RGBA32F | RGBA32I | RGBA32UI => [32; 4],
+			R11F_G11F_B10F => [11, 11, 10, 0],
+			DEPTH_COMPONENT16 | DEPTH_COMPONENT24 | DEPTH_COMPONENT32F | DEPTH24_STENCIL8
+			| DEPTH32F_STENCIL8 | STENCIL_INDEX8 => [0; 4],
+		}
+	}
+
+	pub fn depth_bits(&self) -> u8 {
+		match self {
+			Self::DEPTH_COMPONENT16 => 16,
+			Self::DEPTH_COMPONENT24 | Self::DEPTH24_STENCIL8 => 24,
+			Self::DEPTH_COMPONENT32F | Self::DEPTH32F_STENCIL8 => 32,
+			_ => 0,
+		}
+	}
+
+	pub fn stencil_bits(&self) -> u8 {
+		match self {
+			Self::DEPTH24_STENCIL8 | Self::DEPTH32F_STENCIL8 | Self::STENCIL_INDEX8 => 8,
+			_ => 0,
+		}
+	}
+
+	/// True if the format can be rendered to as a color attachment
+	///
+	/// OpenGL ES only renders to float formats with `EXT_color_buffer_float`,
+	/// or `EXT_color_buffer_half_float` for the 16 bit ones.
+	pub fn color_renderable(&self, gl_version: &GlVersion) -> bool {
+		match (gl_version.ty, self.component_type()) {
+			(_, None) => false,
+			(VersionType::ES, Some(ComponentType::Float)) =>
+				gl_version.has_extension(&ext::EXT_color_buffer_float)
+					|| (matches!(self, Self::R16F | Self::RG16F | Self::RGBA16F)
+						&& gl_version.has_extension(&ext::EXT_color_buffer_half_float)),
+			_ => true,
+		}
+	}
+
+	/// True if the context accepts the format for renderbuffer storage
+	pub fn renderbuffer_supported(&self, gl_version: &GlVersion) -> bool {
+		use InternalFormat::*;
+
+		match gl_version.ty {
+			VersionType::GL => match self {
+				RGB565 =>
+					at_least!(gl_version, gl: 4 . 1)
+						|| gl_version.has_extension(&ext::ARB_ES2_compatibility),
+				RGB8 | RGBA8 | RGBA4 | RGB5_A1 | RGB10_A2 | SRGB8_ALPHA8 | DEPTH_COMPONENT16
+				| DEPTH_COMPONENT24 => true,
+				DEPTH24_STENCIL8 | STENCIL_INDEX8 =>
+					at_least!(gl_version, gl: 3 . 0)
+						|| gl_version.has_extension(&ext::ARB_framebuffer_object),
+				_ => at_least!(gl_version, gl: 3 . 0),
+			},
+			VersionType::ES => match self {
+				RGBA4 | RGB5_A1 | RGB565 | DEPTH_COMPONENT16 | STENCIL_INDEX8 => true,
+				RGB8 | RGBA8 =>
+					at_least!(gl_version, es: 3 . 0)
+						|| gl_version.has_extension(&ext::OES_rgb8_rgba8),
+				DEPTH_COMPONENT24 =>
+					at_least!(gl_version, es: 3 . 0) || gl_version.has_extension(&ext::OES_depth24),
+				DEPTH24_STENCIL8 =>
+					at_least!(gl_version, es: 3 . 0)
+						|| gl_version.has_extension(&ext::OES_packed_depth_stencil),
+				R16F | RG16F | RGBA16F | R32F | RG32F | RGBA32F | R11F_G11F_B10F =>
+					self.color_renderable(gl_version),
+				_ => at_least!(gl_version, es: 3 . 0),
+			},
+		}
+	}
+
+	/// Round `color` to what the format stores
+	///
+	/// Components the format lacks read back as (0, 0, 0, 1).
+	pub fn quantize(&self, color: [f32; 4]) -> [f32; 4] {
+		let bits = self.color_bits();
+		let ty = self.component_type();
+
+		[0, 1, 2, 3].map(|i| {
+			let (c, bits) = (color[i] as f64, bits[i] as i32);
+			if bits == 0 {
+				return if i == 3 { 1.0 } else { 0.0 }
+			}
+
+			(match ty {
+				Some(ComponentType::UnsignedNormalized) => {
+					let max = 2f64.powi(bits) - 1.0;
+					(c.clamp(0.0, 1.0) * max).round() / max
+				},
+				Some(ComponentType::Float) if *self == Self::R11F_G11F_B10F => c.max(0.0),
+				Some(ComponentType::Float) => c,
+				Some(ComponentType::Int) =>
+					c.round().clamp(-(2f64.powi(bits - 1)), 2f64.powi(bits - 1) - 1.0),
+				Some(ComponentType::UnsignedInt) => c.round().clamp(0.0, 2f64.powi(bits) - 1.0),
+				None => c,
+			}) as f32
+		})
+	}
+}
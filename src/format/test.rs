@@ -0,0 +1,48 @@
+use crate::{
+	format::InternalFormat,
+	version::{ext, VersionType},
+	GlVersion,
+};
+
+#[test]
+fn quantize() {
+	assert_eq!(InternalFormat::RGBA8.quantize([2.0, -1.0, 0.0, 1.0]), [1.0, 0.0, 0.0, 1.0]);
+	assert_eq!(InternalFormat::RGB5_A1.quantize([0.5, 0.5, 0.5, 0.4]), [
+		16.0 / 31.0,
+		16.0 / 31.0,
+		16.0 / 31.0,
+		0.0
+	]);
+	assert_eq!(InternalFormat::RG8.quantize([1.0; 4]), [1.0, 1.0, 0.0, 1.0]);
+	assert_eq!(InternalFormat::R32F.quantize([-2.5, 1.0, 1.0, 0.0]), [-2.5, 0.0, 0.0, 1.0]);
+	assert_eq!(InternalFormat::R11F_G11F_B10F.quantize([-1.0, 2.0, 0.5, 0.0]), [
+		0.0, 2.0, 0.5, 1.0
+	]);
+	assert_eq!(InternalFormat::RGBA8I.quantize([200.0, -200.0, 1.4, -1.6]), [
+		127.0, -128.0, 1.0, -2.0
+	]);
+	assert_eq!(InternalFormat::R8UI.quantize([300.0, 0.0, 0.0, 0.0]), [255.0, 0.0, 0.0, 1.0]);
+}
+
+#[test]
+fn renderability() {
+	let es30 = GlVersion::from_version(VersionType::ES, 3, 0);
+	let es32 = GlVersion::from_version(VersionType::ES, 3, 2);
+	let half_float = GlVersion::new(VersionType::ES, 3, 0, &[&ext::EXT_color_buffer_half_float]);
+	let gl30 = GlVersion::from_version(VersionType::GL, 3, 0);
+
+	assert!(!InternalFormat::RGBA16F.color_renderable(&es30));
+	assert!(InternalFormat::RGBA16F.color_renderable(&half_float));
+	assert!(!InternalFormat::RGBA32F.color_renderable(&half_float));
+	assert!(InternalFormat::RGBA32F.color_renderable(&es32));
+	assert!(InternalFormat::RGBA32F.color_renderable(&gl30));
+	assert!(!InternalFormat::DEPTH_COMPONENT16.color_renderable(&gl30));
+
+	assert!(!InternalFormat::RGB565.renderbuffer_supported(&gl30));
+	assert!(InternalFormat::RGB565.renderbuffer_supported(&es30));
+	assert!(!InternalFormat::R8.renderbuffer_supported(&GlVersion::from_version(
+		VersionType::ES,
+		2,
+		0
+	)));
+}
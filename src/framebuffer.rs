@@ -0,0 +1,869 @@
+use std::{
+	collections::{BTreeMap, HashMap},
+	fmt,
+	slice,
+};
+
+use gl::types::{GLboolean, GLenum, GLsizei, GLuint};
+
+use crate::{
+	debug,
+	error,
+	format::InternalFormat,
+	limits::Limits,
+	renderbuffer::{Renderbuffer, RenderbufferManager},
+	surface::Surface,
+	version::{at_least, enums, ext, gl_enum, VersionType},
+	warning,
+	GlVersion,
+	MockContextRef,
+};
+
+mod blit;
+mod clear;
+pub mod gl_functions;
+mod read;
+
+pub use clear::ClearValue;
+pub use read::implementation_read_format;
+
+#[cfg(test)]
+mod test;
+
+/// Color, depth and stencil storage of a framebuffer
+///
+/// Pixels are stored bottom row first, as OpenGL addresses them.
+/// Planes can be missing, like the color plane of a depth renderbuffer,
+/// in which case they read as `None` and ignore writes.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Framebuffer {
+	width: usize,
+	height: usize,
+	/// Format colors are rounded to, `None` only clamps them to `[0, 1]`
+	color_format: Option<InternalFormat>,
+	color: Option<Vec<[f32; 4]>>,
+	depth: Option<Vec<f32>>,
+	stencil: Option<Vec<u8>>,
+}
+
+impl Framebuffer {
+	/// Framebuffer cleared to transparent black, depth 1 and stencil 0
+	pub fn new(width: usize, height: usize) -> Self {
+		Self {
+			width,
+			height,
+			color_format: None,
+			color: Some(vec![[0.0; 4]; width * height]),
+			depth: Some(vec![1.0; width * height]),
+			stencil: Some(vec![0; width * height]),
+		}
+	}
+
+	/// Storage of the default framebuffer described by `surface`
+	fn for_surface(surface: &Surface) -> Self {
+		let (width, height) = (surface.width.max(0) as usize, surface.height.max(0) as usize);
+		let color_format = surface.color_format;
+
+		Self {
+			width,
+			height,
+			color_format,
+			color: Some(vec![
+				color_format.map_or([0.0; 4], |f| f.quantize([0.0; 4]));
+				width * height
+			]),
+			depth: (surface.depth_bits > 0).then(|| vec![1.0; width * height]),
+			stencil: (surface.stencil_bits > 0).then(|| vec![0; width * height]),
+		}
+	}
+
+	/// Storage of a single image of `format`, with only the planes it has
+	pub fn with_format(width: usize, height: usize, format: InternalFormat) -> Self {
+		Self {
+			width,
+			height,
+			color_format: format.is_color().then_some(format),
+			color: format.is_color().then(|| vec![format.quantize([0.0; 4]); width * height]),
+			depth: (format.depth_bits() > 0).then(|| vec![1.0; width * height]),
+			stencil: (format.stencil_bits() > 0).then(|| vec![0; width * height]),
+		}
+	}
+
+	pub fn width(&self) -> usize {
+		self.width
+	}
+
+	pub fn height(&self) -> usize {
+		self.height
+	}
+
+	pub fn color_format(&self) -> Option<InternalFormat> {
+		self.color_format
+	}
+
+	fn index(&self, x: usize, y: usize) -> Option<usize> {
+		(x < self.width && y < self.height).then(|| y * self.width + x)
+	}
+
+	pub fn color(&self, x: usize, y: usize) -> Option<[f32; 4]> {
+		Some(self.color.as_ref()?[self.index(x, y)?])
+	}
+
+	/// Store a color, rounded to the color format or clamped to `[0, 1]` without one
+	pub fn set_color(&mut self, x: usize, y: usize, color: [f32; 4]) {
+		let color = match self.color_format {
+			Some(format) => format.quantize(color),
+			None => color.map(|c| c.clamp(0.0, 1.0)),
+		};
+		if let (Some(i), Some(plane)) = (self.index(x, y), self.color.as_mut()) {
+			plane[i] = color;
+		}
+	}
+
+	/// Copy holding only the plane of `attachment`, `None` if it is missing
+	pub fn plane(&self, attachment: Attachment) -> Option<Self> {
+		let mut plane = Self {
+			color: None,
+			depth: None,
+			stencil: None,
+			..*self
+		};
+		match attachment {
+			Attachment::Color(0) => plane.color = Some(self.color.clone()?),
+			Attachment::Color(_) => return None,
+			Attachment::Depth => plane.depth = Some(self.depth.clone()?),
+			Attachment::Stencil => plane.stencil = Some(self.stencil.clone()?),
+		}
+
+		Some(plane)
+	}
+
+	pub fn depth(&self, x: usize, y: usize) -> Option<f32> {
+		Some(self.depth.as_ref()?[self.index(x, y)?])
+	}
+
+	pub fn set_depth(&mut self, x: usize, y: usize, depth: f32) {
+		if let (Some(i), Some(plane)) = (self.index(x, y), self.depth.as_mut()) {
+			plane[i] = depth.clamp(0.0, 1.0);
+		}
+	}
+
+	pub fn stencil(&self, x: usize, y: usize) -> Option<u8> {
+		Some(self.stencil.as_ref()?[self.index(x, y)?])
+	}
+
+	pub fn set_stencil(&mut self, x: usize, y: usize, stencil: u8) {
+		if let (Some(i), Some(plane)) = (self.index(x, y), self.stencil.as_mut()) {
+			plane[i] = stencil;
+		}
+	}
+}
+
+/// Bottom left `width` x `height` corner of a plane `stride` pixels wide
+fn crop<T: Copy>(plane: &[T], stride: usize, width: usize, height: usize) -> Vec<T> {
+	(0..height)
+		.flat_map(|y| plane[y * stride..y * stride + width].iter().copied())
+		.collect()
+}
+
+/// Write back a plane cropped with `crop`
+fn uncrop<T: Copy>(plane: &mut [T], stride: usize, cropped: &[T], width: usize) {
+	if width == 0 {
+		return
+	}
+
+	for (y, row) in cropped.chunks(width).enumerate() {
+		plane[y * stride..y * stride + width].copy_from_slice(row);
+	}
+}
+
+gl_enum! {
+	FramebufferTarget {
+		FRAMEBUFFER(gl: 3 . 0, es: 2 . 0);
+		DRAW_FRAMEBUFFER(gl: 3 . 0, es: 3 . 0);
+		READ_FRAMEBUFFER(gl: 3 . 0, es: 3 . 0);
+	}
+}
+
+/// Attachment point of a framebuffer object
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Attachment {
+	Color(u32),
+	Depth,
+	Stencil,
+}
+
+impl Attachment {
+	/// Attachment points named by `attachment`, both depth and stencil for
+	/// `GL_DEPTH_STENCIL_ATTACHMENT`
+	fn from_gl(
+		gl_version: &GlVersion,
+		limits: &Limits,
+		error: &mut GLenum,
+		func: &str,
+		attachment: GLenum,
+	) -> Option<Vec<Self>> {
+		let max_color = limits.max_color_attachments;
+
+		match attachment {
+			gl::DEPTH_ATTACHMENT => Some(vec![Self::Depth]),
+			gl::STENCIL_ATTACHMENT => Some(vec![Self::Stencil]),
+			gl::DEPTH_STENCIL_ATTACHMENT => {
+				if !at_least!(gl_version, gl: 3 . 0, es: 3 . 0)
+					&& !gl_version.has_extension(&ext::ARB_framebuffer_object)
+				{
+					error!("GL_DEPTH_STENCIL_ATTACHMENT requires OpenGL 3.0 or OpenGL ES 3.0");
+				}
+				Some(vec![Self::Depth, Self::Stencil])
+			},
+			gl::COLOR_ATTACHMENT0..=gl::COLOR_ATTACHMENT31 => {
+				let index = attachment - gl::COLOR_ATTACHMENT0;
+				if index < max_color {
+					Some(vec![Self::Color(index)])
+				} else {
+					*error = gl::INVALID_OPERATION;
+					error!(
+						"{} called with GL_COLOR_ATTACHMENT{}, but GL_MAX_COLOR_ATTACHMENTS is {}",
+						func, index, max_color
+					);
+					None
+				}
+			},
+			_ => {
+				*error = gl::INVALID_ENUM;
+				error!("{} called with invalid attachment {}", func, attachment);
+				None
+			},
+		}
+	}
+}
+
+impl fmt::Display for Attachment {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::Color(i) => write!(f, "GL_COLOR_ATTACHMENT{i}"),
+			Self::Depth => write!(f, "GL_DEPTH_ATTACHMENT"),
+			Self::Stencil => write!(f, "GL_STENCIL_ATTACHMENT"),
+		}
+	}
+}
+
+/// Parse a framebuffer target, which only need version checks without `ARB_framebuffer_object`
+fn framebuffer_target(
+	gl_version: &GlVersion,
+	error: &mut GLenum,
+	func: &str,
+	target: GLenum,
+) -> Option<FramebufferTarget> {
+	match FramebufferTarget::from_gl(target) {
+		None => {
+			*error = gl::INVALID_ENUM;
+			error!("{} called with invalid target {}", func, target);
+			None
+		},
+		Some(target) => {
+			if !gl_version.has_extension(&ext::ARB_framebuffer_object) {
+				target.check_version(gl_version);
+			}
+			Some(target)
+		},
+	}
+}
+
+/// Framebuffer object, attachments are renderbuffer names
+#[derive(Clone, Debug, Default)]
+pub struct FramebufferObject {
+	attachments: BTreeMap<Attachment, GLuint>,
+}
+
+impl FramebufferObject {
+	pub fn attachment(&self, attachment: Attachment) -> Option<GLuint> {
+		self.attachments.get(&attachment).copied()
+	}
+}
+
+pub struct FramebufferManager {
+	surface: Surface,
+	/// Back buffer of double buffered surfaces
+	default_framebuffer: Framebuffer,
+	/// Contents presented by the last swap, unused by single buffered surfaces
+	front_buffer: Framebuffer,
+	/// Clears over the context's lifetime, for frame statistics
+	clears: usize,
+	framebuffer_index: GLuint,
+	/// Generated names, which only become objects once bound
+	active_framebuffers: HashMap<GLuint, Option<FramebufferObject>>,
+	deleted_framebuffers: Vec<GLuint>,
+	draw_framebuffer: GLuint,
+	read_framebuffer: GLuint,
+}
+
+impl Default for FramebufferManager {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl FramebufferManager {
+	pub fn new() -> Self {
+		Self::with_surface(Surface::default())
+	}
+
+	pub fn with_surface(surface: Surface) -> Self {
+		Self {
+			default_framebuffer: Framebuffer::for_surface(&surface),
+			front_buffer: Framebuffer::for_surface(&surface),
+			surface,
+			clears: 0,
+			framebuffer_index: 1,
+			active_framebuffers: HashMap::new(),
+			deleted_framebuffers: Vec::new(),
+			draw_framebuffer: 0,
+			read_framebuffer: 0,
+		}
+	}
+
+	pub fn default_framebuffer(&self) -> &Framebuffer {
+		&self.default_framebuffer
+	}
+
+	pub fn surface(&self) -> &Surface {
+		&self.surface
+	}
+
+	/// Buffer shown on screen, the default framebuffer itself when single buffered
+	pub fn front_buffer(&self) -> &Framebuffer {
+		match self.surface.double_buffered {
+			true => &self.front_buffer,
+			false => &self.default_framebuffer,
+		}
+	}
+
+	/// Present the back buffer, keeping its contents
+	pub fn swap_buffers(&mut self) {
+		if self.surface.double_buffered {
+			self.front_buffer.clone_from(&self.default_framebuffer);
+		}
+	}
+
+	/// Number of clears over the context's lifetime
+	pub fn clears(&self) -> usize {
+		self.clears
+	}
+
+	/// # Safety
+	/// `framebuffers` must be valid for `count` writes
+	pub unsafe fn gen_framebuffers(
+		&mut self,
+		error: &mut GLenum,
+		count: GLsizei,
+		framebuffers: *mut GLuint,
+	) {
+		if count < 0 {
+			*error = gl::INVALID_VALUE;
+			error!("glGenFramebuffers called with invalid framebuffer count {}", count);
+		} else {
+			let framebuffers = slice::from_raw_parts_mut(framebuffers, count as usize);
+
+			for framebuffer_id in framebuffers.iter_mut() {
+				*framebuffer_id = self.framebuffer_index;
+				self.framebuffer_index += 1;
+
+				self.active_framebuffers.insert(*framebuffer_id, None);
+			}
+
+			debug!("created {} framebuffer(s) {:?}", framebuffers.len(), framebuffers);
+		}
+	}
+
+	/// # Safety
+	/// `framebuffers` must be valid for `count` reads
+	pub unsafe fn free_framebuffers(
+		&mut self,
+		renderbuffers: &mut RenderbufferManager,
+		error: &mut GLenum,
+		count: GLsizei,
+		framebuffers: *const GLuint,
+	) {
+		if count < 0 {
+			*error = gl::INVALID_VALUE;
+			error!("glDeleteFramebuffers called with invalid framebuffer count {}", count);
+			return
+		}
+
+		let framebuffers = slice::from_raw_parts(framebuffers, count as usize);
+
+		for framebuffer_id in framebuffers.iter() {
+			if *framebuffer_id == 0 {
+				continue
+			}
+
+			if self.deleted_framebuffers.contains(framebuffer_id) {
+				warning!("double freed framebuffer {}", framebuffer_id);
+			} else if self.active_framebuffers.remove(framebuffer_id).is_some() {
+				self.deleted_framebuffers.push(*framebuffer_id);
+
+				// deleting a bound framebuffer reverts its bindings to the default framebuffer
+				if self.draw_framebuffer == *framebuffer_id {
+					self.draw_framebuffer = 0;
+				}
+				if self.read_framebuffer == *framebuffer_id {
+					self.read_framebuffer = 0;
+				}
+
+				debug!("freed framebuffer {}", framebuffer_id);
+			} else {
+				warning!("attempted to free unallocated framebuffer {}", framebuffer_id);
+			}
+		}
+
+		renderbuffers.collect_orphans(self);
+	}
+
+	pub fn is_framebuffer(&self, framebuffer: GLuint) -> GLboolean {
+		matches!(self.active_framebuffers.get(&framebuffer), Some(Some(_))) as u8
+	}
+
+	pub fn bind_framebuffer(
+		&mut self,
+		gl_version: &GlVersion,
+		error: &mut GLenum,
+		target: GLenum,
+		framebuffer_id: GLuint,
+	) {
+		let Some(target) = framebuffer_target(gl_version, error, "glBindFramebuffer", target)
+		else {
+			return
+		};
+
+		if framebuffer_id != 0 {
+			match self.active_framebuffers.get_mut(&framebuffer_id) {
+				Some(framebuffer) => {
+					framebuffer.get_or_insert_with(FramebufferObject::default);
+				},
+				None => {
+					*error = gl::INVALID_OPERATION;
+					if self.deleted_framebuffers.contains(&framebuffer_id) {
+						error!("attempted to bind framebuffer that has already been freed");
+					} else {
+						error!("attempted to bind an unallocated framebuffer");
+					}
+					return
+				},
+			}
+		}
+
+		match target {
+			FramebufferTarget::FRAMEBUFFER => {
+				self.draw_framebuffer = framebuffer_id;
+				self.read_framebuffer = framebuffer_id;
+			},
+			FramebufferTarget::DRAW_FRAMEBUFFER => self.draw_framebuffer = framebuffer_id,
+			FramebufferTarget::READ_FRAMEBUFFER => self.read_framebuffer = framebuffer_id,
+		}
+		debug!("bound framebuffer {} to {}", framebuffer_id, target);
+	}
+
+	/// Framebuffer bound to `target`, `GL_FRAMEBUFFER` meaning the draw framebuffer
+	pub fn bound(&self, target: FramebufferTarget) -> GLuint {
+		match target {
+			FramebufferTarget::FRAMEBUFFER | FramebufferTarget::DRAW_FRAMEBUFFER =>
+				self.draw_framebuffer,
+			FramebufferTarget::READ_FRAMEBUFFER => self.read_framebuffer,
+		}
+	}
+
+	pub fn framebuffer(&self, framebuffer_id: GLuint) -> Option<&FramebufferObject> {
+		self.active_framebuffers.get(&framebuffer_id)?.as_ref()
+	}
+
+	/// Framebuffer object bound to `target`, raising `GL_INVALID_OPERATION` for the default framebuffer
+	fn bound_object_mut(
+		&mut self,
+		error: &mut GLenum,
+		func: &str,
+		target: FramebufferTarget,
+	) -> Option<&mut FramebufferObject> {
+		match self.bound(target) {
+			0 => {
+				*error = gl::INVALID_OPERATION;
+				error!("{} called with the default framebuffer bound to {}", func, target);
+				None
+			},
+			// bound framebuffers are objects
+			id => self.active_framebuffers.get_mut(&id).unwrap().as_mut(),
+		}
+	}
+
+	#[allow(clippy::too_many_arguments)]
+	pub fn framebuffer_renderbuffer(
+		&mut self,
+		gl_version: &GlVersion,
+		limits: &Limits,
+		renderbuffers: &mut RenderbufferManager,
+		error: &mut GLenum,
+		target: GLenum,
+		attachment: GLenum,
+		renderbuffer_target: GLenum,
+		renderbuffer: GLuint,
+	) {
+		let func = "glFramebufferRenderbuffer";
+		let Some(target) = framebuffer_target(gl_version, error, func, target) else {
+			return
+		};
+		let Some(attachments) = Attachment::from_gl(gl_version, limits, error, func, attachment)
+		else {
+			return
+		};
+
+		if renderbuffer_target != gl::RENDERBUFFER {
+			*error = gl::INVALID_ENUM;
+			error!("{} called with invalid renderbuffer target {}", func, renderbuffer_target);
+			return
+		}
+
+		if renderbuffer != 0 && renderbuffers.is_renderbuffer(renderbuffer) == gl::FALSE {
+			*error = gl::INVALID_OPERATION;
+			error!("{} called with {}, which is not a renderbuffer object", func, renderbuffer);
+			return
+		}
+
+		let Some(framebuffer) = self.bound_object_mut(error, func, target) else {
+			return
+		};
+
+		for attachment in attachments {
+			if renderbuffer == 0 {
+				framebuffer.attachments.remove(&attachment);
+				debug!("detached {} of the framebuffer bound to {}", attachment, target);
+			} else {
+				framebuffer.attachments.insert(attachment, renderbuffer);
+				debug!(
+					"attached renderbuffer {} to {} of the framebuffer bound to {}",
+					renderbuffer, attachment, target
+				);
+			}
+		}
+
+		renderbuffers.collect_orphans(self);
+	}
+
+	/// Texture attachment, which can only detach as there are no texture objects
+	#[allow(clippy::too_many_arguments)]
+	pub fn framebuffer_texture(
+		&mut self,
+		gl_version: &GlVersion,
+		limits: &Limits,
+		error: &mut GLenum,
+		func: &str,
+		target: GLenum,
+		attachment: GLenum,
+		texture: GLuint,
+	) {
+		let Some(target) = framebuffer_target(gl_version, error, func, target) else {
+			return
+		};
+		let Some(attachments) = Attachment::from_gl(gl_version, limits, error, func, attachment)
+		else {
+			return
+		};
+
+		if texture != 0 {
+			*error = gl::INVALID_OPERATION;
+			error!("{} called with texture {}, mock-gl does not support textures", func, texture);
+			return
+		}
+
+		let Some(framebuffer) = self.bound_object_mut(error, func, target) else {
+			return
+		};
+
+		for attachment in attachments {
+			framebuffer.attachments.remove(&attachment);
+			debug!("detached {} of the framebuffer bound to {}", attachment, target);
+		}
+	}
+
+	/// Completeness status of a framebuffer, `GL_FRAMEBUFFER_COMPLETE` for the default one
+	pub fn status(
+		&self,
+		gl_version: &GlVersion,
+		renderbuffers: &RenderbufferManager,
+		framebuffer_id: GLuint,
+	) -> GLenum {
+		let Some(framebuffer) = self.framebuffer(framebuffer_id) else {
+			return gl::FRAMEBUFFER_COMPLETE
+		};
+
+		let size = |renderbuffer: &Renderbuffer| {
+			(renderbuffer.image().width(), renderbuffer.image().height())
+		};
+
+		if framebuffer.attachments.is_empty() {
+			debug!("framebuffer {} has no attachments", framebuffer_id);
+			return gl::FRAMEBUFFER_INCOMPLETE_MISSING_ATTACHMENT
+		}
+
+		let mut images = Vec::new();
+		for (attachment, renderbuffer_id) in &framebuffer.attachments {
+			let renderbuffer = renderbuffers.renderbuffer(*renderbuffer_id);
+			let Some((renderbuffer, format)) = renderbuffer
+				.and_then(|renderbuffer| Some((renderbuffer, renderbuffer.format()?)))
+				.filter(|(renderbuffer, _)| size(renderbuffer) != (0, 0))
+			else {
+				debug!("{} of framebuffer {} has no storage", attachment, framebuffer_id);
+				return gl::FRAMEBUFFER_INCOMPLETE_ATTACHMENT
+			};
+
+			let attachable = match attachment {
+				Attachment::Color(_) => format.color_renderable(gl_version),
+				Attachment::Depth => format.depth_bits() > 0,
+				Attachment::Stencil => format.stencil_bits() > 0,
+			};
+			if !attachable {
+				debug!("{} of framebuffer {} can not be {}", format, framebuffer_id, attachment);
+				return gl::FRAMEBUFFER_INCOMPLETE_ATTACHMENT
+			}
+
+			images.push(renderbuffer);
+		}
+
+		if matches!(gl_version.ty, VersionType::ES)
+			&& !at_least!(gl_version, es: 3 . 0)
+			&& images.iter().any(|renderbuffer| size(renderbuffer) != size(images[0]))
+		{
+			debug!("attachments of framebuffer {} differ in size", framebuffer_id);
+			return enums::FRAMEBUFFER_INCOMPLETE_DIMENSIONS
+		}
+
+		if images.iter().any(|renderbuffer| renderbuffer.samples() != images[0].samples()) {
+			debug!("attachments of framebuffer {} differ in sample count", framebuffer_id);
+			return gl::FRAMEBUFFER_INCOMPLETE_MULTISAMPLE
+		}
+
+		let depth = framebuffer.attachment(Attachment::Depth);
+		let stencil = framebuffer.attachment(Attachment::Stencil);
+		if matches!(gl_version.ty, VersionType::ES)
+			&& depth.is_some()
+			&& stencil.is_some()
+			&& depth != stencil
+		{
+			debug!(
+				"framebuffer {} has separate depth and stencil images, which OpenGL ES does not support",
+				framebuffer_id
+			);
+			return gl::FRAMEBUFFER_UNSUPPORTED
+		}
+
+		gl::FRAMEBUFFER_COMPLETE
+	}
+
+	pub fn check_status(
+		&self,
+		gl_version: &GlVersion,
+		renderbuffers: &RenderbufferManager,
+		error: &mut GLenum,
+		target: GLenum,
+	) -> GLenum {
+		match framebuffer_target(gl_version, error, "glCheckFramebufferStatus", target) {
+			Some(target) => self.status(gl_version, renderbuffers, self.bound(target)),
+			None => 0,
+		}
+	}
+
+	/// Raise `GL_INVALID_FRAMEBUFFER_OPERATION` if the draw framebuffer is incomplete
+	pub fn check_draw_complete(
+		&self,
+		gl_version: &GlVersion,
+		renderbuffers: &RenderbufferManager,
+		error: &mut GLenum,
+		func: &str,
+	) -> bool {
+		let status = self.status(gl_version, renderbuffers, self.draw_framebuffer);
+		if status != gl::FRAMEBUFFER_COMPLETE {
+			*error = gl::INVALID_FRAMEBUFFER_OPERATION;
+			error!(
+				"{} called with incomplete draw framebuffer {}, its status is {:#x}",
+				func, self.draw_framebuffer, status
+			);
+		}
+
+		status == gl::FRAMEBUFFER_COMPLETE
+	}
+
+	/// Attachments of a framebuffer object gathered into one `Framebuffer`,
+	/// cropped to their common size
+	///
+	/// Only `GL_COLOR_ATTACHMENT0` is used, as draw and read buffers are not modelled.
+	fn gather(renderbuffers: &RenderbufferManager, framebuffer: &FramebufferObject) -> Framebuffer {
+		let image = |attachment| {
+			Some(renderbuffers.renderbuffer(framebuffer.attachment(attachment)?)?.image())
+		};
+		let (color, depth, stencil) = (
+			image(Attachment::Color(0)),
+			image(Attachment::Depth),
+			image(Attachment::Stencil),
+		);
+
+		let images = [color, depth, stencil].into_iter().flatten().collect::<Vec<_>>();
+		let width = images.iter().map(|image| image.width).min().unwrap_or(0);
+		let height = images.iter().map(|image| image.height).min().unwrap_or(0);
+
+		Framebuffer {
+			width,
+			height,
+			color_format: color.and_then(|image| image.color_format),
+			color: color
+				.and_then(|image| Some(crop(image.color.as_ref()?, image.width, width, height))),
+			depth: depth
+				.and_then(|image| Some(crop(image.depth.as_ref()?, image.width, width, height))),
+			stencil: stencil
+				.and_then(|image| Some(crop(image.stencil.as_ref()?, image.width, width, height))),
+		}
+	}
+
+	/// Write back a `Framebuffer` gathered from `framebuffer`
+	fn scatter(
+		renderbuffers: &mut RenderbufferManager,
+		framebuffer: &FramebufferObject,
+		gathered: &Framebuffer,
+	) {
+		let width = gathered.width;
+		for (attachment, id) in &framebuffer.attachments {
+			let Some(image) = renderbuffers.renderbuffer_mut(*id).map(Renderbuffer::image_mut)
+			else {
+				continue
+			};
+			let stride = image.width;
+			match attachment {
+				Attachment::Color(0) => {
+					if let (Some(plane), Some(cropped)) = (&mut image.color, &gathered.color) {
+						uncrop(plane, stride, cropped, width);
+					}
+				},
+				Attachment::Color(_) => {},
+				Attachment::Depth => {
+					if let (Some(plane), Some(cropped)) = (&mut image.depth, &gathered.depth) {
+						uncrop(plane, stride, cropped, width);
+					}
+				},
+				Attachment::Stencil => {
+					if let (Some(plane), Some(cropped)) = (&mut image.stencil, &gathered.stencil) {
+						uncrop(plane, stride, cropped, width);
+					}
+				},
+			}
+		}
+	}
+
+	/// Run `f` on the storage of the draw framebuffer
+	///
+	/// The attachments of framebuffer objects are gathered into one `Framebuffer`
+	/// and written back afterwards.
+	pub fn draw<R>(
+		&mut self,
+		renderbuffers: &mut RenderbufferManager,
+		f: impl FnOnce(&mut Framebuffer) -> R,
+	) -> R {
+		let Some(framebuffer) = self.framebuffer(self.draw_framebuffer) else {
+			return f(&mut self.default_framebuffer)
+		};
+
+		let mut gathered = Self::gather(renderbuffers, framebuffer);
+		let result = f(&mut gathered);
+		Self::scatter(renderbuffers, framebuffer, &gathered);
+
+		result
+	}
+
+	/// Copy of the read framebuffer's storage
+	pub fn read_image(&self, renderbuffers: &RenderbufferManager) -> Framebuffer {
+		match self.framebuffer(self.read_framebuffer) {
+			Some(framebuffer) => Self::gather(renderbuffers, framebuffer),
+			None => self.default_framebuffer.clone(),
+		}
+	}
+
+	/// Sample count of a framebuffer, 0 for single sampled ones
+	pub fn samples(&self, renderbuffers: &RenderbufferManager, framebuffer_id: GLuint) -> GLsizei {
+		if framebuffer_id == 0 {
+			return self.surface.samples
+		}
+
+		self.framebuffer(framebuffer_id)
+			.into_iter()
+			.flat_map(|framebuffer| framebuffer.attachments.values())
+			.filter_map(|id| renderbuffers.renderbuffer(*id))
+			.map(Renderbuffer::samples)
+			.max()
+			.unwrap_or(0)
+	}
+
+	/// Internal format of a framebuffer attachment, unknown for the default framebuffer
+	pub fn attachment_format(
+		&self,
+		renderbuffers: &RenderbufferManager,
+		framebuffer_id: GLuint,
+		attachment: Attachment,
+	) -> Option<InternalFormat> {
+		let framebuffer = self.framebuffer(framebuffer_id)?;
+		renderbuffers.renderbuffer(framebuffer.attachment(attachment)?)?.format()
+	}
+
+	/// Remove a deleted renderbuffer from the bound framebuffers,
+	/// other framebuffers keep it attached
+	pub fn detach_renderbuffer(&mut self, renderbuffer_id: GLuint) {
+		for framebuffer_id in [self.draw_framebuffer, self.read_framebuffer] {
+			if let Some(Some(framebuffer)) = self.active_framebuffers.get_mut(&framebuffer_id) {
+				framebuffer.attachments.retain(|_, id| *id != renderbuffer_id);
+			}
+		}
+	}
+
+	pub fn references_renderbuffer(&self, renderbuffer_id: GLuint) -> bool {
+		self.active_framebuffers
+			.values()
+			.flatten()
+			.any(|framebuffer| framebuffer.attachments.values().any(|id| *id == renderbuffer_id))
+	}
+
+	pub fn finalize(self) {
+		if !self.active_framebuffers.is_empty() {
+			error!(
+				"mock-gl context was dropped with dangling framebuffers {:?}",
+				self.active_framebuffers.keys().collect::<Vec<_>>()
+			);
+		}
+	}
+}
+
+impl MockContextRef {
+	/// Resize the default framebuffer, as if its window was resized
+	///
+	/// Contents of both the front and back buffer are discarded, and `GL_MAX_VIEWPORT_DIMS` is
+	/// raised to cover the new size. Like a window resize this leaves the viewport alone, except
+	/// for the first resize of an initially empty surface, which initializes the viewport and
+	/// scissor box as making the context current would.
+	pub fn resize(&self, width: GLsizei, height: GLsizei) {
+		let mut context = crate::context();
+		let context = &mut *context;
+
+		let framebuffers = &mut context.framebuffer_manager;
+		if framebuffers.surface.width == 0 && framebuffers.surface.height == 0 {
+			context.state_manager.init_surface_size(width, height);
+		}
+
+		context.limits.cover_surface(width, height);
+
+		framebuffers.surface.width = width;
+		framebuffers.surface.height = height;
+		framebuffers.default_framebuffer = Framebuffer::for_surface(&framebuffers.surface);
+		framebuffers.front_buffer = Framebuffer::for_surface(&framebuffers.surface);
+		debug!("resized the default framebuffer to {}x{}", width, height);
+	}
+
+	/// Copy of the default framebuffer's contents
+	pub fn default_framebuffer(&self) -> Framebuffer {
+		crate::context().framebuffer_manager.default_framebuffer.clone()
+	}
+}
@@ -0,0 +1,216 @@
+use gl::types::{GLbitfield, GLenum, GLint};
+
+use crate::{
+	error,
+	format::{ComponentType, InternalFormat},
+	framebuffer::{Attachment, Framebuffer, FramebufferManager},
+	renderbuffer::RenderbufferManager,
+	state::{Capability, Rect, StateManager},
+	version::VersionType,
+	GlVersion,
+};
+
+/// Color formats can only be blitted between the same kind of component
+fn blit_class(format: Option<InternalFormat>) -> Option<ComponentType> {
+	match format.and_then(|format| format.component_type()) {
+		Some(ComponentType::Int) => Some(ComponentType::Int),
+		Some(ComponentType::UnsignedInt) => Some(ComponentType::UnsignedInt),
+		_ => None,
+	}
+}
+
+/// Source coordinate sampled for the center of destination pixel `x`,
+/// mapping `[dst0, dst1)` onto `[src0, src1)` which mirrors when they run in opposite directions
+fn source_coordinate(x: usize, src0: GLint, src1: GLint, dst0: GLint, dst1: GLint) -> f32 {
+	let t = (x as f32 + 0.5 - dst0 as f32) / (dst1 - dst0) as f32;
+	src0 as f32 + t * (src1 - src0) as f32
+}
+
+fn nearest(source: &Framebuffer, x: f32, y: f32) -> Option<(usize, usize)> {
+	(x >= 0.0 && y >= 0.0)
+		.then(|| (x.floor() as usize, y.floor() as usize))
+		.filter(|(x, y)| *x < source.width() && *y < source.height())
+}
+
+/// Bilinear filtering, clamped to the edges of the source
+fn linear(source: &Framebuffer, x: f32, y: f32) -> Option<[f32; 4]> {
+	nearest(source, x, y)?;
+
+	let (x, y) = (x - 0.5, y - 0.5);
+	let clamp = |v: f32, size: usize| (v.floor().max(0.0) as usize).min(size - 1);
+	let (x0, y0) = (clamp(x, source.width()), clamp(y, source.height()));
+	let (x1, y1) = (clamp(x + 1.0, source.width()), clamp(y + 1.0, source.height()));
+	let (fx, fy) = (x - x.floor(), y - y.floor());
+
+	let [c00, c10, c01, c11] = [(x0, y0), (x1, y0), (x0, y1), (x1, y1)]
+		.map(|(x, y)| source.color(x, y).unwrap_or([0.0; 4]));
+	Some([0, 1, 2, 3].map(|i| {
+		let bottom = c00[i] + (c10[i] - c00[i]) * fx;
+		let top = c01[i] + (c11[i] - c01[i]) * fx;
+		bottom + (top - bottom) * fy
+	}))
+}
+
+impl FramebufferManager {
+	/// Copy a rectangle of the read framebuffer into the draw framebuffer
+	///
+	/// Multisample sources are resolved by the copy, and the scissor test
+	/// applies to the destination.
+	#[allow(clippy::too_many_arguments)]
+	pub fn blit_framebuffer(
+		&mut self,
+		gl_version: &GlVersion,
+		renderbuffers: &mut RenderbufferManager,
+		state: &StateManager,
+		error: &mut GLenum,
+		src: [GLint; 4],
+		dst: [GLint; 4],
+		mask: GLbitfield,
+		filter: GLenum,
+	) {
+		let buffer_bits = gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT | gl::STENCIL_BUFFER_BIT;
+		if mask & !buffer_bits != 0 {
+			*error = gl::INVALID_VALUE;
+			error!("glBlitFramebuffer called with invalid mask {:#x}", mask);
+			return
+		}
+
+		if filter != gl::NEAREST && filter != gl::LINEAR {
+			*error = gl::INVALID_ENUM;
+			error!("glBlitFramebuffer called with invalid filter {}", filter);
+			return
+		}
+
+		let (color, depth, stencil) = (
+			mask & gl::COLOR_BUFFER_BIT != 0,
+			mask & gl::DEPTH_BUFFER_BIT != 0,
+			mask & gl::STENCIL_BUFFER_BIT != 0,
+		);
+		if filter == gl::LINEAR && (depth || stencil) {
+			*error = gl::INVALID_OPERATION;
+			error!("glBlitFramebuffer can only blit depth and stencil with GL_NEAREST");
+			return
+		}
+
+		let (read, draw) = (self.read_framebuffer, self.draw_framebuffer);
+		for (framebuffer, name) in [(read, "read"), (draw, "draw")] {
+			let status = self.status(gl_version, renderbuffers, framebuffer);
+			if status != gl::FRAMEBUFFER_COMPLETE {
+				*error = gl::INVALID_FRAMEBUFFER_OPERATION;
+				error!(
+					"glBlitFramebuffer called with incomplete {} framebuffer {}, its status is {:#x}",
+					name, framebuffer, status
+				);
+				return
+			}
+		}
+
+		let is_es = matches!(gl_version.ty, VersionType::ES);
+		if is_es && read == draw {
+			*error = gl::INVALID_OPERATION;
+			error!("glBlitFramebuffer can not blit framebuffer {} onto itself on OpenGL ES", read);
+			return
+		}
+
+		if self.samples(renderbuffers, draw) > 0 {
+			*error = gl::INVALID_OPERATION;
+			error!("glBlitFramebuffer can not blit into multisample framebuffer {}", draw);
+			return
+		}
+
+		let read_color = self.attachment_format(renderbuffers, read, Attachment::Color(0));
+		let draw_color = self.attachment_format(renderbuffers, draw, Attachment::Color(0));
+		if self.samples(renderbuffers, read) > 0 {
+			// OpenGL ES resolves without moving or scaling, and without format conversions
+			let size = |r: [GLint; 4]| ((r[2] - r[0]).abs(), (r[3] - r[1]).abs());
+			if (is_es && src != dst) || size(src) != size(dst) {
+				*error = gl::INVALID_OPERATION;
+				error!("glBlitFramebuffer can not scale multisample framebuffer {}", read);
+				return
+			}
+
+			if is_es && color && read_color != draw_color {
+				*error = gl::INVALID_OPERATION;
+				error!("glBlitFramebuffer can not convert formats while resolving on OpenGL ES");
+				return
+			}
+		}
+
+		if color {
+			if blit_class(read_color) != blit_class(draw_color) {
+				*error = gl::INVALID_OPERATION;
+				error!(
+					"glBlitFramebuffer can not blit color between {} and {}",
+					read_color.map_or("the default framebuffer".into(), |f| f.to_string()),
+					draw_color.map_or("the default framebuffer".into(), |f| f.to_string())
+				);
+				return
+			}
+
+			if filter == gl::LINEAR && blit_class(read_color).is_some() {
+				*error = gl::INVALID_OPERATION;
+				error!("glBlitFramebuffer can not filter integer formats with GL_LINEAR");
+				return
+			}
+		}
+
+		for (enabled, attachment) in [(depth, Attachment::Depth), (stencil, Attachment::Stencil)] {
+			let read_format = self.attachment_format(renderbuffers, read, attachment);
+			let draw_format = self.attachment_format(renderbuffers, draw, attachment);
+			if let (true, Some(read_format), Some(draw_format)) =
+				(enabled, read_format, draw_format)
+			{
+				if read_format != draw_format {
+					*error = gl::INVALID_OPERATION;
+					error!(
+						"glBlitFramebuffer can not blit {} between {} and {}",
+						attachment, read_format, draw_format
+					);
+					return
+				}
+			}
+		}
+
+		let source = self.read_image(renderbuffers);
+		let scissor: Option<Rect> =
+			state.enabled(Capability::SCISSOR_TEST).then(|| state.scissor());
+
+		self.draw(renderbuffers, |target| {
+			let (x_min, x_max) = (dst[0].min(dst[2]).max(0), dst[0].max(dst[2]));
+			let (y_min, y_max) = (dst[1].min(dst[3]).max(0), dst[1].max(dst[3]));
+			let x_max = (x_max.max(0) as usize).min(target.width());
+			let y_max = (y_max.max(0) as usize).min(target.height());
+
+			for y in y_min as usize..y_max {
+				for x in x_min as usize..x_max {
+					if scissor.is_some_and(|scissor| !scissor.contains(x as i32, y as i32)) {
+						continue
+					}
+
+					let sx = source_coordinate(x, src[0], src[2], dst[0], dst[2]);
+					let sy = source_coordinate(y, src[1], src[3], dst[1], dst[3]);
+					let Some((nx, ny)) = nearest(&source, sx, sy) else {
+						// reads outside of the source leave the destination undefined
+						continue
+					};
+
+					if color {
+						let value = match filter {
+							gl::LINEAR => linear(&source, sx, sy),
+							_ => source.color(nx, ny),
+						};
+						if let Some(value) = value {
+							target.set_color(x, y, value);
+						}
+					}
+					if let Some(value) = source.depth(nx, ny).filter(|_| depth) {
+						target.set_depth(x, y, value);
+					}
+					if let Some(value) = source.stencil(nx, ny).filter(|_| stencil) {
+						target.set_stencil(x, y, value);
+					}
+				}
+			}
+		});
+	}
+}
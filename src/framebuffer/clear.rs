@@ -0,0 +1,222 @@
+use std::ops::Range;
+
+use gl::types::{GLbitfield, GLenum, GLfloat, GLint, GLuint};
+
+use crate::{
+	error,
+	format::ComponentType,
+	framebuffer::{Attachment, Framebuffer, FramebufferManager},
+	limits::Limits,
+	renderbuffer::RenderbufferManager,
+	state::{Capability, StateManager},
+	warning,
+	GlVersion,
+};
+
+/// Value passed to one of the `glClearBuffer*` functions
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ClearValue {
+	Float([GLfloat; 4]),
+	Int([GLint; 4]),
+	UnsignedInt([GLuint; 4]),
+	Depth(GLfloat),
+	Stencil(GLint),
+	DepthStencil(GLfloat, GLint),
+}
+
+impl ClearValue {
+	/// Value of `glClearBufferfv`, `None` for buffers it can not clear
+	///
+	/// # Safety
+	/// `value` must be valid for 4 reads when clearing color and 1 otherwise
+	pub unsafe fn float(buffer: GLenum, value: *const GLfloat) -> Option<Self> {
+		match buffer {
+			gl::COLOR => Some(Self::Float((value as *const [GLfloat; 4]).read())),
+			gl::DEPTH => Some(Self::Depth(*value)),
+			_ => None,
+		}
+	}
+
+	/// Value of `glClearBufferiv`, `None` for buffers it can not clear
+	///
+	/// # Safety
+	/// `value` must be valid for 4 reads when clearing color and 1 otherwise
+	pub unsafe fn int(buffer: GLenum, value: *const GLint) -> Option<Self> {
+		match buffer {
+			gl::COLOR => Some(Self::Int((value as *const [GLint; 4]).read())),
+			gl::STENCIL => Some(Self::Stencil(*value)),
+			_ => None,
+		}
+	}
+
+	/// Value of `glClearBufferuiv`, `None` for buffers it can not clear
+	///
+	/// # Safety
+	/// `value` must be valid for 4 reads
+	pub unsafe fn unsigned_int(buffer: GLenum, value: *const GLuint) -> Option<Self> {
+		match buffer {
+			gl::COLOR => Some(Self::UnsignedInt((value as *const [GLuint; 4]).read())),
+			_ => None,
+		}
+	}
+
+	/// Value of `glClearBufferfi`, `None` for buffers it can not clear
+	pub fn depth_stencil(buffer: GLenum, depth: GLfloat, stencil: GLint) -> Option<Self> {
+		(buffer == gl::DEPTH_STENCIL).then_some(Self::DepthStencil(depth, stencil))
+	}
+
+	/// Component type a color value is meant for
+	fn component_type(&self) -> Option<ComponentType> {
+		match self {
+			Self::Float(_) => Some(ComponentType::Float),
+			Self::Int(_) => Some(ComponentType::Int),
+			Self::UnsignedInt(_) => Some(ComponentType::UnsignedInt),
+			_ => None,
+		}
+	}
+}
+
+/// Pixels a clear writes to, which is the whole framebuffer unless scissored
+fn clear_region(state: &StateManager, target: &Framebuffer) -> (Range<usize>, Range<usize>) {
+	let (width, height) = (target.width as GLint, target.height as GLint);
+	if !state.enabled(Capability::SCISSOR_TEST) {
+		return (0..target.width, 0..target.height)
+	}
+
+	let scissor = state.scissor();
+	let clamp = |v: GLint, max: GLint| v.clamp(0, max) as usize;
+	(
+		clamp(scissor.x, width)..clamp(scissor.x.saturating_add(scissor.width), width),
+		clamp(scissor.y, height)..clamp(scissor.y.saturating_add(scissor.height), height),
+	)
+}
+
+/// Clear the given planes of `target`, honoring the write masks and the scissor test
+fn clear_image(
+	target: &mut Framebuffer,
+	state: &StateManager,
+	color: Option<[f32; 4]>,
+	depth: Option<f32>,
+	stencil: Option<GLint>,
+) {
+	let (xs, ys) = clear_region(state, target);
+	let render = state.render();
+	let color_mask = render.draw_buffers[0].color_mask;
+	let depth = depth.filter(|_| render.depth_mask);
+	let stencil_mask = render.stencil[0].writemask as u8;
+
+	for y in ys {
+		for x in xs.clone() {
+			if let (Some(color), Some(old)) = (color, target.color(x, y)) {
+				let color = [0, 1, 2, 3].map(|i| if color_mask[i] { color[i] } else { old[i] });
+				target.set_color(x, y, color);
+			}
+			if let Some(depth) = depth {
+				target.set_depth(x, y, depth);
+			}
+			if let (Some(stencil), Some(old)) = (stencil, target.stencil(x, y)) {
+				target.set_stencil(x, y, (old & !stencil_mask) | (stencil as u8 & stencil_mask));
+			}
+		}
+	}
+}
+
+impl FramebufferManager {
+	/// Clear buffers of the draw framebuffer to the clear values of `state`
+	pub fn clear(
+		&mut self,
+		gl_version: &GlVersion,
+		renderbuffers: &mut RenderbufferManager,
+		state: &StateManager,
+		error: &mut GLenum,
+		mask: GLbitfield,
+	) {
+		let buffer_bits = gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT | gl::STENCIL_BUFFER_BIT;
+		if mask & !buffer_bits != 0 {
+			*error = gl::INVALID_VALUE;
+			error!("glClear called with invalid mask {:#x}", mask);
+			return
+		}
+
+		if !self.check_draw_complete(gl_version, renderbuffers, error, "glClear") {
+			return
+		}
+		self.clears += 1;
+
+		let color = (mask & gl::COLOR_BUFFER_BIT != 0).then(|| state.clear_color());
+		let depth = (mask & gl::DEPTH_BUFFER_BIT != 0).then(|| state.clear_depth());
+		let stencil = (mask & gl::STENCIL_BUFFER_BIT != 0).then(|| state.clear_stencil());
+
+		self.draw(renderbuffers, |target| clear_image(target, state, color, depth, stencil));
+	}
+
+	/// Clear one buffer of the draw framebuffer to `value`,
+	/// which is `None` if `buffer` can't be cleared by `func`
+	#[allow(clippy::too_many_arguments)]
+	pub fn clear_buffer(
+		&mut self,
+		gl_version: &GlVersion,
+		limits: &Limits,
+		renderbuffers: &mut RenderbufferManager,
+		state: &StateManager,
+		error: &mut GLenum,
+		func: &str,
+		buffer: GLenum,
+		drawbuffer: GLint,
+		value: Option<ClearValue>,
+	) {
+		let Some(value) = value else {
+			*error = gl::INVALID_ENUM;
+			error!("{} called with invalid buffer {}", func, buffer);
+			return
+		};
+
+		let max_drawbuffer = match value.component_type() {
+			Some(_) => limits.max_draw_buffers as GLint,
+			None => 1,
+		};
+		if !(0..max_drawbuffer).contains(&drawbuffer) {
+			*error = gl::INVALID_VALUE;
+			error!("{} called with invalid draw buffer {} for buffer {}", func, drawbuffer, buffer);
+			return
+		}
+
+		if !self.check_draw_complete(gl_version, renderbuffers, error, func) {
+			return
+		}
+		self.clears += 1;
+
+		let (color, depth, stencil) = match value {
+			ClearValue::Float(color) => (Some(color), None, None),
+			ClearValue::Int(color) => (Some(color.map(|c| c as f32)), None, None),
+			ClearValue::UnsignedInt(color) => (Some(color.map(|c| c as f32)), None, None),
+			ClearValue::Depth(depth) => (None, Some(depth), None),
+			ClearValue::Stencil(stencil) => (None, None, Some(stencil)),
+			ClearValue::DepthStencil(depth, stencil) => (None, Some(depth), Some(stencil)),
+		};
+
+		if let Some(ty) = value.component_type() {
+			// only GL_COLOR_ATTACHMENT0 is drawn to, as draw buffers are not modelled
+			if drawbuffer != 0 {
+				return
+			}
+
+			let format =
+				self.attachment_format(renderbuffers, self.draw_framebuffer, Attachment::Color(0));
+			let buffer_ty = match format.and_then(|format| format.component_type()) {
+				Some(ComponentType::UnsignedNormalized) | None => ComponentType::Float,
+				Some(ty) => ty,
+			};
+			if ty != buffer_ty {
+				warning!(
+					"{} called on a color buffer of {}, which leaves it undefined",
+					func,
+					format.map_or("the default framebuffer".into(), |format| format.to_string())
+				);
+				return
+			}
+		}
+
+		self.draw(renderbuffers, |target| clear_image(target, state, color, depth, stencil));
+	}
+}
@@ -0,0 +1,324 @@
+use gl::types::{GLbitfield, GLboolean, GLenum, GLfloat, GLint, GLsizei, GLuint, GLvoid};
+
+use crate::{framebuffer::ClearValue, function_mapping::gl_functions, state::Rect};
+
+gl_functions! {
+	fn glGenFramebuffers(count: GLsizei, framebuffers: *mut GLuint);
+	require gl 3 . 0;
+	require es 2 . 0;
+	require ext ARB_framebuffer_object;
+	take [error, framebuffer_manager]
+	{
+		framebuffer_manager.gen_framebuffers(error, count, framebuffers);
+	}
+
+	fn glDeleteFramebuffers(count: GLsizei, framebuffers: *const GLuint);
+	require gl 3 . 0;
+	require es 2 . 0;
+	require ext ARB_framebuffer_object;
+	take [error, framebuffer_manager, renderbuffer_manager]
+	{
+		framebuffer_manager.free_framebuffers(renderbuffer_manager, error, count, framebuffers);
+	}
+
+	fn glIsFramebuffer(framebuffer: GLuint) -> GLboolean;
+	require gl 3 . 0;
+	require es 2 . 0;
+	require ext ARB_framebuffer_object;
+	take [framebuffer_manager]
+	{
+		framebuffer_manager.is_framebuffer(framebuffer)
+	}
+
+	fn glBindFramebuffer(target: GLenum, framebuffer: GLuint);
+	require gl 3 . 0;
+	require es 2 . 0;
+	require ext ARB_framebuffer_object;
+	take [gl_version, error, framebuffer_manager]
+	{
+		framebuffer_manager.bind_framebuffer(gl_version, error, target, framebuffer);
+	}
+
+	fn glFramebufferRenderbuffer(
+		target: GLenum,
+		attachment: GLenum,
+		renderbuffer_target: GLenum,
+		renderbuffer: GLuint,
+	);
+	require gl 3 . 0;
+	require es 2 . 0;
+	require ext ARB_framebuffer_object;
+	take [gl_version, limits, error, framebuffer_manager, renderbuffer_manager]
+	{
+		framebuffer_manager.framebuffer_renderbuffer(
+			gl_version,
+			limits,
+			renderbuffer_manager,
+			error,
+			target,
+			attachment,
+			renderbuffer_target,
+			renderbuffer,
+		);
+	}
+
+	fn glFramebufferTexture(target: GLenum, attachment: GLenum, texture: GLuint, level: GLint);
+	require gl 3 . 2;
+	require es 3 . 2;
+	take [gl_version, limits, error, framebuffer_manager]
+	{
+		framebuffer_manager.framebuffer_texture(
+			gl_version,
+			limits,
+			error,
+			"glFramebufferTexture",
+			target,
+			attachment,
+			texture,
+		);
+	}
+
+	fn glFramebufferTexture1D(
+		target: GLenum,
+		attachment: GLenum,
+		texture_target: GLenum,
+		texture: GLuint,
+		level: GLint,
+	);
+	require gl 3 . 0;
+	require ext ARB_framebuffer_object;
+	take [gl_version, limits, error, framebuffer_manager]
+	{
+		framebuffer_manager.framebuffer_texture(
+			gl_version,
+			limits,
+			error,
+			"glFramebufferTexture1D",
+			target,
+			attachment,
+			texture,
+		);
+	}
+
+	fn glFramebufferTexture2D(
+		target: GLenum,
+		attachment: GLenum,
+		texture_target: GLenum,
+		texture: GLuint,
+		level: GLint,
+	);
+	require gl 3 . 0;
+	require es 2 . 0;
+	require ext ARB_framebuffer_object;
+	take [gl_version, limits, error, framebuffer_manager]
+	{
+		framebuffer_manager.framebuffer_texture(
+			gl_version,
+			limits,
+			error,
+			"glFramebufferTexture2D",
+			target,
+			attachment,
+			texture,
+		);
+	}
+
+	fn glFramebufferTexture3D(
+		target: GLenum,
+		attachment: GLenum,
+		texture_target: GLenum,
+		texture: GLuint,
+		level: GLint,
+		layer: GLint,
+	);
+	require gl 3 . 0;
+	require ext ARB_framebuffer_object;
+	take [gl_version, limits, error, framebuffer_manager]
+	{
+		framebuffer_manager.framebuffer_texture(
+			gl_version,
+			limits,
+			error,
+			"glFramebufferTexture3D",
+			target,
+			attachment,
+			texture,
+		);
+	}
+
+	fn glFramebufferTextureLayer(
+		target: GLenum,
+		attachment: GLenum,
+		texture: GLuint,
+		level: GLint,
+		layer: GLint,
+	);
+	require gl 3 . 0;
+	require es 3 . 0;
+	require ext ARB_framebuffer_object;
+	take [gl_version, limits, error, framebuffer_manager]
+	{
+		framebuffer_manager.framebuffer_texture(
+			gl_version,
+			limits,
+			error,
+			"glFramebufferTextureLayer",
+			target,
+			attachment,
+			texture,
+		);
+	}
+
+	fn glCheckFramebufferStatus(target: GLenum) -> GLenum;
+	require gl 3 . 0;
+	require es 2 . 0;
+	require ext ARB_framebuffer_object;
+	take [gl_version, error, framebuffer_manager, renderbuffer_manager]
+	{
+		framebuffer_manager.check_status(gl_version, renderbuffer_manager, error, target)
+	}
+
+	fn glBlitFramebuffer(
+		src_x0: GLint,
+		src_y0: GLint,
+		src_x1: GLint,
+		src_y1: GLint,
+		dst_x0: GLint,
+		dst_y0: GLint,
+		dst_x1: GLint,
+		dst_y1: GLint,
+		mask: GLbitfield,
+		filter: GLenum,
+	);
+	require gl 3 . 0;
+	require es 3 . 0;
+	require ext ARB_framebuffer_object;
+	take [gl_version, error, framebuffer_manager, renderbuffer_manager, state_manager]
+	{
+		framebuffer_manager.blit_framebuffer(
+			gl_version,
+			renderbuffer_manager,
+			state_manager,
+			error,
+			[src_x0, src_y0, src_x1, src_y1],
+			[dst_x0, dst_y0, dst_x1, dst_y1],
+			mask,
+			filter,
+		);
+	}
+
+	fn glClear(mask: GLbitfield);
+	require gl 2 . 1;
+	require es 2 . 0;
+	take [gl_version, error, framebuffer_manager, renderbuffer_manager, state_manager]
+	{
+		framebuffer_manager.clear(gl_version, renderbuffer_manager, state_manager, error, mask);
+	}
+
+	fn glClearBufferiv(buffer: GLenum, drawbuffer: GLint, value: *const GLint);
+	require gl 3 . 0;
+	require es 3 . 0;
+	take [gl_version, limits, error, framebuffer_manager, renderbuffer_manager, state_manager]
+	{
+		framebuffer_manager.clear_buffer(
+			gl_version,
+			limits,
+			renderbuffer_manager,
+			state_manager,
+			error,
+			"glClearBufferiv",
+			buffer,
+			drawbuffer,
+			ClearValue::int(buffer, value),
+		);
+	}
+
+	fn glClearBufferuiv(buffer: GLenum, drawbuffer: GLint, value: *const GLuint);
+	require gl 3 . 0;
+	require es 3 . 0;
+	take [gl_version, limits, error, framebuffer_manager, renderbuffer_manager, state_manager]
+	{
+		framebuffer_manager.clear_buffer(
+			gl_version,
+			limits,
+			renderbuffer_manager,
+			state_manager,
+			error,
+			"glClearBufferuiv",
+			buffer,
+			drawbuffer,
+			ClearValue::unsigned_int(buffer, value),
+		);
+	}
+
+	fn glClearBufferfv(buffer: GLenum, drawbuffer: GLint, value: *const GLfloat);
+	require gl 3 . 0;
+	require es 3 . 0;
+	take [gl_version, limits, error, framebuffer_manager, renderbuffer_manager, state_manager]
+	{
+		framebuffer_manager.clear_buffer(
+			gl_version,
+			limits,
+			renderbuffer_manager,
+			state_manager,
+			error,
+			"glClearBufferfv",
+			buffer,
+			drawbuffer,
+			ClearValue::float(buffer, value),
+		);
+	}
+
+	fn glClearBufferfi(buffer: GLenum, drawbuffer: GLint, depth: GLfloat, stencil: GLint);
+	require gl 3 . 0;
+	require es 3 . 0;
+	take [gl_version, limits, error, framebuffer_manager, renderbuffer_manager, state_manager]
+	{
+		framebuffer_manager.clear_buffer(
+			gl_version,
+			limits,
+			renderbuffer_manager,
+			state_manager,
+			error,
+			"glClearBufferfi",
+			buffer,
+			drawbuffer,
+			ClearValue::depth_stencil(buffer, depth, stencil),
+		);
+	}
+
+	fn glReadPixels(
+		x: GLint,
+		y: GLint,
+		width: GLsizei,
+		height: GLsizei,
+		format: GLenum,
+		ty: GLenum,
+		pixels: *mut GLvoid,
+	);
+	require gl 2 . 1;
+	require es 2 . 0;
+	take [
+		gl_version,
+		error,
+		framebuffer_manager,
+		renderbuffer_manager,
+		buffer_manager,
+		vertex_array_manager,
+		state_manager,
+	]
+	{
+		framebuffer_manager.read_pixels(
+			gl_version,
+			renderbuffer_manager,
+			buffer_manager,
+			vertex_array_manager,
+			state_manager,
+			error,
+			Rect::new(x, y, width, height),
+			format,
+			ty,
+			pixels,
+		);
+	}
+}
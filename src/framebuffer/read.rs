@@ -0,0 +1,269 @@
+use std::slice;
+
+use gl::types::{GLenum, GLvoid};
+
+use crate::{
+	buffer::{BufferBinding, BufferManager},
+	error,
+	format::{ComponentType, InternalFormat},
+	framebuffer::{Framebuffer, FramebufferManager},
+	pixel::{self, PixelFormat, PixelType},
+	renderbuffer::RenderbufferManager,
+	state::{Rect, StateManager},
+	version::VersionType,
+	vertex_array::VertexArrayManager,
+	GlVersion,
+};
+
+/// Format and type reported as `GL_IMPLEMENTATION_COLOR_READ_FORMAT` and `_TYPE`
+/// for a read buffer of `format`, the default framebuffer's being unknown
+///
+/// These match the layout of the format, as most drivers report.
+pub fn implementation_read_format(format: Option<InternalFormat>) -> (PixelFormat, PixelType) {
+	use InternalFormat::*;
+	use PixelFormat::{RED, RED_INTEGER, RG, RGB, RGBA, RGBA_INTEGER};
+	use PixelType::*;
+
+	match format {
+		Some(R8) => (RED, UNSIGNED_BYTE),
+		Some(RG8) => (RG, UNSIGNED_BYTE),
+		Some(RGB8) => (RGB, UNSIGNED_BYTE),
+		Some(RGBA4) => (RGBA, UNSIGNED_SHORT_4_4_4_4),
+		Some(RGB5_A1) => (RGBA, UNSIGNED_SHORT_5_5_5_1),
+		Some(RGB565) => (RGB, UNSIGNED_SHORT_5_6_5),
+		Some(RGB10_A2) => (RGBA, UNSIGNED_INT_2_10_10_10_REV),
+		Some(R16F) => (RED, HALF_FLOAT),
+		Some(RG16F) => (RG, HALF_FLOAT),
+		Some(RGBA16F) => (RGBA, HALF_FLOAT),
+		Some(R32F) => (RED, FLOAT),
+		Some(RG32F) => (RG, FLOAT),
+		Some(RGBA32F) => (RGBA, FLOAT),
+		Some(R11F_G11F_B10F) => (RGB, FLOAT),
+		Some(R8I) => (RED_INTEGER, BYTE),
+		Some(R8UI) => (RED_INTEGER, UNSIGNED_BYTE),
+		Some(R32I) => (RED_INTEGER, INT),
+		Some(R32UI) => (RED_INTEGER, UNSIGNED_INT),
+		Some(RGBA8I) => (RGBA_INTEGER, BYTE),
+		Some(RGBA8UI) => (RGBA_INTEGER, UNSIGNED_BYTE),
+		Some(RGBA32I) => (RGBA_INTEGER, INT),
+		Some(RGBA32UI) => (RGBA_INTEGER, UNSIGNED_INT),
+		_ => (RGBA, UNSIGNED_BYTE),
+	}
+}
+
+/// True if OpenGL ES can read a color buffer of `read_format` as `format` and `ty`
+///
+/// Only one pair per component type is always supported, besides the implementation's pair.
+fn es_read_supported(
+	read_format: Option<InternalFormat>,
+	format: PixelFormat,
+	ty: PixelType,
+) -> bool {
+	let component_type = read_format
+		.and_then(|format| format.component_type())
+		.unwrap_or(ComponentType::UnsignedNormalized);
+
+	let supported = match component_type {
+		ComponentType::UnsignedNormalized =>
+			(format, ty) == (PixelFormat::RGBA, PixelType::UNSIGNED_BYTE)
+				|| (read_format == Some(InternalFormat::RGB10_A2)
+					&& (format, ty) == (PixelFormat::RGBA, PixelType::UNSIGNED_INT_2_10_10_10_REV)),
+		ComponentType::Float => (format, ty) == (PixelFormat::RGBA, PixelType::FLOAT),
+		ComponentType::Int => (format, ty) == (PixelFormat::RGBA_INTEGER, PixelType::INT),
+		ComponentType::UnsignedInt =>
+			(format, ty) == (PixelFormat::RGBA_INTEGER, PixelType::UNSIGNED_INT),
+	};
+
+	supported || (format, ty) == implementation_read_format(read_format)
+}
+
+/// True if a packed type holds the components of `format`
+fn packed_type_matches(format: PixelFormat, ty: PixelType) -> bool {
+	use PixelFormat::*;
+
+	match ty {
+		PixelType::UNSIGNED_SHORT_5_6_5 => matches!(format, RGB | BGR),
+		PixelType::UNSIGNED_SHORT_4_4_4_4 | PixelType::UNSIGNED_SHORT_5_5_5_1 =>
+			matches!(format, RGBA | BGRA),
+		PixelType::UNSIGNED_INT_2_10_10_10_REV =>
+			matches!(format, RGBA | BGRA | RGBA_INTEGER | BGRA_INTEGER),
+		PixelType::UNSIGNED_INT_24_8 | PixelType::FLOAT_32_UNSIGNED_INT_24_8_REV =>
+			format == DEPTH_STENCIL,
+		_ => format != DEPTH_STENCIL,
+	}
+}
+
+impl FramebufferManager {
+	/// Check that `image` of the read framebuffer can be read as `format` and `ty`
+	fn check_read_format(
+		gl_version: &GlVersion,
+		error: &mut GLenum,
+		image: &Framebuffer,
+		format: PixelFormat,
+		ty: PixelType,
+	) -> bool {
+		let read_format = image.color_format();
+
+		let readable = if matches!(gl_version.ty, VersionType::ES) {
+			es_read_supported(read_format, format, ty)
+		} else if !packed_type_matches(format, ty) || (format.is_integer() && ty.is_float()) {
+			false
+		} else {
+			match format {
+				PixelFormat::DEPTH_COMPONENT => image.depth.is_some(),
+				PixelFormat::STENCIL_INDEX => image.stencil.is_some(),
+				PixelFormat::DEPTH_STENCIL => image.depth.is_some() && image.stencil.is_some(),
+				_ => {
+					let integer_buffer = matches!(
+						read_format.and_then(|format| format.component_type()),
+						Some(ComponentType::Int | ComponentType::UnsignedInt)
+					);
+					image.color.is_some() && format.is_integer() == integer_buffer
+				},
+			}
+		};
+
+		if !readable {
+			*error = gl::INVALID_OPERATION;
+			error!(
+				"glReadPixels can not read {} as {} and {}",
+				read_format.map_or("the default framebuffer".into(), |format| format.to_string()),
+				format,
+				ty
+			);
+		}
+
+		readable
+	}
+
+	/// Read a rectangle of the read framebuffer into client memory,
+	/// or into the buffer bound to `GL_PIXEL_PACK_BUFFER` at offset `pixels`
+	///
+	/// Pixels outside of the framebuffer are left untouched.
+	///
+	/// # Safety
+	/// Without a pixel pack buffer, `pixels` must be valid for writes of the
+	/// image as laid out by the pack pixel storage modes
+	#[allow(clippy::too_many_arguments)]
+	pub unsafe fn read_pixels(
+		&self,
+		gl_version: &GlVersion,
+		renderbuffers: &RenderbufferManager,
+		buffers: &mut BufferManager,
+		vertex_arrays: &VertexArrayManager,
+		state: &StateManager,
+		error: &mut GLenum,
+		rect: Rect,
+		format: GLenum,
+		ty: GLenum,
+		pixels: *mut GLvoid,
+	) {
+		if rect.width < 0 || rect.height < 0 {
+			*error = gl::INVALID_VALUE;
+			error!("glReadPixels called with negative size {}x{}", rect.width, rect.height);
+			return
+		}
+
+		let Some(format) = PixelFormat::from_gl(format) else {
+			*error = gl::INVALID_ENUM;
+			error!("glReadPixels called with invalid format {}", format);
+			return
+		};
+		let Some(ty) = PixelType::from_gl(ty) else {
+			*error = gl::INVALID_ENUM;
+			error!("glReadPixels called with invalid type {}", ty);
+			return
+		};
+		format.check_version(gl_version);
+		ty.check_version(gl_version);
+
+		let status = self.status(gl_version, renderbuffers, self.read_framebuffer);
+		if status != gl::FRAMEBUFFER_COMPLETE {
+			*error = gl::INVALID_FRAMEBUFFER_OPERATION;
+			error!(
+				"glReadPixels called with incomplete read framebuffer {}, its status is {:#x}",
+				self.read_framebuffer, status
+			);
+			return
+		}
+
+		if self.samples(renderbuffers, self.read_framebuffer) > 0 {
+			*error = gl::INVALID_OPERATION;
+			error!("glReadPixels can not read multisample framebuffer {}", self.read_framebuffer);
+			return
+		}
+
+		let image = self.read_image(renderbuffers);
+		if !Self::check_read_format(gl_version, error, &image, format, ty) {
+			return
+		}
+
+		let layout = state.pack().layout(format, ty, rect.width as usize, rect.height as usize);
+		let pack_buffer = buffers.bound(vertex_arrays, BufferBinding::PIXEL_PACK_BUFFER);
+		let memory = if pack_buffer != 0 {
+			let offset = pixels as usize;
+			if !offset.is_multiple_of(ty.size()) {
+				*error = gl::INVALID_OPERATION;
+				error!(
+					"glReadPixels called with offset {}, which is not aligned to {}",
+					offset, ty
+				);
+				return
+			}
+
+			let memory = match buffers.buffer_mut(pack_buffer) {
+				Some(buffer) => buffer.memory_mut(),
+				None => &mut [],
+			};
+			if offset.checked_add(layout.len).is_none_or(|end| end > memory.len()) {
+				*error = gl::INVALID_OPERATION;
+				error!(
+					"glReadPixels would write {} bytes at offset {} of pixel pack buffer {} holding {} bytes",
+					layout.len,
+					offset,
+					pack_buffer,
+					memory.len()
+				);
+				return
+			}
+
+			&mut memory[offset..offset + layout.len]
+		} else if pixels.is_null() {
+			if layout.len > 0 {
+				error!("glReadPixels called with a null pointer");
+			}
+			return
+		} else {
+			slice::from_raw_parts_mut(pixels as *mut u8, layout.len)
+		};
+
+		let mut encoded = Vec::with_capacity(layout.group_size);
+		for j in 0..rect.height as usize {
+			for i in 0..rect.width as usize {
+				let (x, y) = (rect.x as i64 + i as i64, rect.y as i64 + j as i64);
+				if x < 0 || y < 0 {
+					continue
+				}
+				let (x, y) = (x as usize, y as usize);
+
+				let value = match format {
+					PixelFormat::DEPTH_COMPONENT =>
+						image.depth(x, y).map(|depth| [depth, 0.0, 0.0, 0.0]),
+					PixelFormat::STENCIL_INDEX =>
+						image.stencil(x, y).map(|stencil| [stencil as f32, 0.0, 0.0, 0.0]),
+					PixelFormat::DEPTH_STENCIL => image
+						.depth(x, y)
+						.zip(image.stencil(x, y))
+						.map(|(depth, stencil)| [depth, stencil as f32, 0.0, 0.0]),
+					_ => image.color(x, y),
+				};
+				let Some(value) = value else { continue };
+
+				encoded.clear();
+				pixel::encode(format, ty, value, &mut encoded);
+				let start = layout.offset + j * layout.stride + i * layout.group_size;
+				memory[start..start + encoded.len()].copy_from_slice(&encoded);
+			}
+		}
+	}
+}
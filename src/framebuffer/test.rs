@@ -0,0 +1,685 @@
+use std::ffi::c_void;
+
+use gl::types::{GLenum, GLsizei, GLsizeiptr, GLuint};
+
+use crate::{
+	state::Rect,
+	test::{gl33_core, test_harness, test_harness_context, test_harness_handling},
+	version::{enums, ext, Profile, VersionType},
+	ErrorHandling,
+	GlVersion,
+};
+
+#[test]
+fn resize() {
+	let version = GlVersion::from_version(VersionType::GL, 3, 3).with_profile(Profile::Core);
+	test_harness_context(version, |ctx| unsafe {
+		assert_eq!(ctx.default_framebuffer().width(), 0);
+
+		ctx.resize(4, 2);
+		let framebuffer = ctx.default_framebuffer();
+		assert_eq!((framebuffer.width(), framebuffer.height()), (4, 2));
+		assert_eq!(framebuffer.color(3, 1), Some([0.0; 4]));
+		assert_eq!(framebuffer.depth(3, 1), Some(1.0));
+		assert_eq!(framebuffer.color(4, 1), None);
+
+		// the first resize initializes the viewport, later ones leave it alone
+		assert_eq!(crate::context().state_manager.viewport().rect(), Rect::new(0, 0, 4, 2));
+
+		gl::Viewport(1, 1, 2, 1);
+		ctx.resize(8, 8);
+		assert_eq!(crate::context().state_manager.viewport().rect(), Rect::new(1, 1, 2, 1));
+	})
+}
+
+#[test]
+fn resize_beyond_viewport_minimum() {
+	// OpenGL ES 2.0 only requires 64 pixel viewports, a window can be larger
+	test_harness_context(GlVersion::from_version(VersionType::ES, 2, 0), |ctx| unsafe {
+		ctx.resize(800, 600);
+		gl::Viewport(0, 0, 800, 600);
+
+		let mut viewport = [0; 4];
+		gl::GetIntegerv(gl::VIEWPORT, viewport.as_mut_ptr());
+		assert_eq!(viewport, [0, 0, 800, 600]);
+		assert_eq!(ctx.limits().max_viewport_dims, [800, 600]);
+	})
+}
+
+/// Bound renderbuffer with storage of `format`
+unsafe fn renderbuffer(format: GLenum, width: GLsizei, height: GLsizei) -> GLuint {
+	let mut renderbuffer = 0;
+	gl::GenRenderbuffers(1, &mut renderbuffer);
+	gl::BindRenderbuffer(gl::RENDERBUFFER, renderbuffer);
+	gl::RenderbufferStorage(gl::RENDERBUFFER, format, width, height);
+	renderbuffer
+}
+
+#[test]
+fn bindings() {
+	test_harness(gl33_core(), || unsafe {
+		let mut framebuffers = [0; 2];
+		gl::GenFramebuffers(2, framebuffers.as_mut_ptr());
+		assert_eq!(gl::IsFramebuffer(framebuffers[0]), gl::FALSE);
+
+		gl::BindFramebuffer(gl::FRAMEBUFFER, framebuffers[0]);
+		assert_eq!(gl::IsFramebuffer(framebuffers[0]), gl::TRUE);
+		gl::BindFramebuffer(gl::READ_FRAMEBUFFER, framebuffers[1]);
+
+		let (mut draw, mut read) = (0, 0);
+		gl::GetIntegerv(gl::DRAW_FRAMEBUFFER_BINDING, &mut draw);
+		gl::GetIntegerv(gl::READ_FRAMEBUFFER_BINDING, &mut read);
+		assert_eq!((draw as GLuint, read as GLuint), (framebuffers[0], framebuffers[1]));
+
+		// deleting a bound framebuffer binds the default framebuffer
+		gl::DeleteFramebuffers(1, &framebuffers[1]);
+		gl::GetIntegerv(gl::READ_FRAMEBUFFER_BINDING, &mut read);
+		assert_eq!(read, 0);
+
+		gl::DeleteFramebuffers(1, &framebuffers[0]);
+		gl::GetIntegerv(gl::FRAMEBUFFER_BINDING, &mut draw);
+		assert_eq!(draw, 0);
+	})
+}
+
+#[test]
+fn completeness() {
+	test_harness(gl33_core(), || unsafe {
+		assert_eq!(gl::CheckFramebufferStatus(gl::FRAMEBUFFER), gl::FRAMEBUFFER_COMPLETE);
+
+		let mut framebuffer = 0;
+		gl::GenFramebuffers(1, &mut framebuffer);
+		gl::BindFramebuffer(gl::FRAMEBUFFER, framebuffer);
+		assert_eq!(
+			gl::CheckFramebufferStatus(gl::FRAMEBUFFER),
+			gl::FRAMEBUFFER_INCOMPLETE_MISSING_ATTACHMENT
+		);
+
+		let mut empty = 0;
+		gl::GenRenderbuffers(1, &mut empty);
+		gl::BindRenderbuffer(gl::RENDERBUFFER, empty);
+		gl::FramebufferRenderbuffer(
+			gl::FRAMEBUFFER,
+			gl::COLOR_ATTACHMENT0,
+			gl::RENDERBUFFER,
+			empty,
+		);
+		assert_eq!(
+			gl::CheckFramebufferStatus(gl::FRAMEBUFFER),
+			gl::FRAMEBUFFER_INCOMPLETE_ATTACHMENT
+		);
+
+		// a depth format can't be a color attachment
+		let depth = renderbuffer(gl::DEPTH_COMPONENT24, 4, 4);
+		gl::FramebufferRenderbuffer(
+			gl::FRAMEBUFFER,
+			gl::COLOR_ATTACHMENT0,
+			gl::RENDERBUFFER,
+			depth,
+		);
+		assert_eq!(
+			gl::CheckFramebufferStatus(gl::FRAMEBUFFER),
+			gl::FRAMEBUFFER_INCOMPLETE_ATTACHMENT
+		);
+
+		let color = renderbuffer(gl::RGBA8, 4, 4);
+		gl::FramebufferRenderbuffer(
+			gl::FRAMEBUFFER,
+			gl::COLOR_ATTACHMENT0,
+			gl::RENDERBUFFER,
+			color,
+		);
+		gl::FramebufferRenderbuffer(gl::FRAMEBUFFER, gl::DEPTH_ATTACHMENT, gl::RENDERBUFFER, depth);
+		assert_eq!(gl::CheckFramebufferStatus(gl::FRAMEBUFFER), gl::FRAMEBUFFER_COMPLETE);
+
+		// sizes may differ outside of OpenGL ES 2.0
+		let small = renderbuffer(gl::RGBA8, 2, 2);
+		gl::FramebufferRenderbuffer(
+			gl::FRAMEBUFFER,
+			gl::COLOR_ATTACHMENT1,
+			gl::RENDERBUFFER,
+			small,
+		);
+		assert_eq!(gl::CheckFramebufferStatus(gl::FRAMEBUFFER), gl::FRAMEBUFFER_COMPLETE);
+
+		// the read framebuffer is still the default one
+		assert_eq!(gl::CheckFramebufferStatus(gl::READ_FRAMEBUFFER), gl::FRAMEBUFFER_COMPLETE);
+		gl::BindFramebuffer(gl::READ_FRAMEBUFFER, 0);
+
+		gl::DeleteRenderbuffers(1, &empty);
+		gl::DeleteRenderbuffers(1, &depth);
+		gl::DeleteRenderbuffers(1, &color);
+		gl::DeleteRenderbuffers(1, &small);
+		gl::DeleteFramebuffers(1, &framebuffer);
+	})
+}
+
+#[test]
+fn es2_completeness() {
+	let version = GlVersion::from_version(VersionType::ES, 2, 0);
+	test_harness(version, || unsafe {
+		let mut framebuffer = 0;
+		gl::GenFramebuffers(1, &mut framebuffer);
+		gl::BindFramebuffer(gl::FRAMEBUFFER, framebuffer);
+
+		let color = renderbuffer(gl::RGBA4, 4, 4);
+		let depth = renderbuffer(gl::DEPTH_COMPONENT16, 2, 2);
+		gl::FramebufferRenderbuffer(
+			gl::FRAMEBUFFER,
+			gl::COLOR_ATTACHMENT0,
+			gl::RENDERBUFFER,
+			color,
+		);
+		gl::FramebufferRenderbuffer(gl::FRAMEBUFFER, gl::DEPTH_ATTACHMENT, gl::RENDERBUFFER, depth);
+		assert_eq!(
+			gl::CheckFramebufferStatus(gl::FRAMEBUFFER),
+			enums::FRAMEBUFFER_INCOMPLETE_DIMENSIONS
+		);
+
+		gl::BindRenderbuffer(gl::RENDERBUFFER, depth);
+		gl::RenderbufferStorage(gl::RENDERBUFFER, gl::DEPTH_COMPONENT16, 4, 4);
+		assert_eq!(gl::CheckFramebufferStatus(gl::FRAMEBUFFER), gl::FRAMEBUFFER_COMPLETE);
+
+		// OpenGL ES needs depth and stencil in the same image
+		let stencil = renderbuffer(gl::STENCIL_INDEX8, 4, 4);
+		gl::FramebufferRenderbuffer(
+			gl::FRAMEBUFFER,
+			gl::STENCIL_ATTACHMENT,
+			gl::RENDERBUFFER,
+			stencil,
+		);
+		assert_eq!(gl::CheckFramebufferStatus(gl::FRAMEBUFFER), gl::FRAMEBUFFER_UNSUPPORTED);
+
+		gl::DeleteRenderbuffers(1, &color);
+		gl::DeleteRenderbuffers(1, &depth);
+		gl::DeleteRenderbuffers(1, &stencil);
+		gl::DeleteFramebuffers(1, &framebuffer);
+	})
+}
+
+#[test]
+fn gl_errors() {
+	test_harness_handling(gl33_core(), ErrorHandling::DoNotPanic, || unsafe {
+		gl::BindFramebuffer(gl::TEXTURE_2D, 0);
+		assert_eq!(gl::GetError(), gl::INVALID_ENUM);
+
+		gl::BindFramebuffer(gl::FRAMEBUFFER, 7);
+		assert_eq!(gl::GetError(), gl::INVALID_OPERATION);
+
+		// attachments of the default framebuffer can't change
+		gl::FramebufferRenderbuffer(gl::FRAMEBUFFER, gl::COLOR_ATTACHMENT0, gl::RENDERBUFFER, 0);
+		assert_eq!(gl::GetError(), gl::INVALID_OPERATION);
+
+		let mut framebuffer = 0;
+		gl::GenFramebuffers(1, &mut framebuffer);
+		gl::BindFramebuffer(gl::DRAW_FRAMEBUFFER, framebuffer);
+
+		gl::FramebufferRenderbuffer(
+			gl::FRAMEBUFFER,
+			gl::COLOR_ATTACHMENT0 + 8,
+			gl::RENDERBUFFER,
+			0,
+		);
+		assert_eq!(gl::GetError(), gl::INVALID_OPERATION);
+
+		gl::FramebufferRenderbuffer(gl::FRAMEBUFFER, gl::TEXTURE_2D, gl::RENDERBUFFER, 0);
+		assert_eq!(gl::GetError(), gl::INVALID_ENUM);
+
+		let mut renderbuffer = 0;
+		gl::GenRenderbuffers(1, &mut renderbuffer);
+		gl::FramebufferRenderbuffer(
+			gl::FRAMEBUFFER,
+			gl::COLOR_ATTACHMENT0,
+			gl::RENDERBUFFER,
+			renderbuffer,
+		);
+		assert_eq!(gl::GetError(), gl::INVALID_OPERATION);
+
+		// there are no textures to attach, only detaching works
+		gl::FramebufferTexture2D(gl::FRAMEBUFFER, gl::COLOR_ATTACHMENT0, gl::TEXTURE_2D, 1, 0);
+		assert_eq!(gl::GetError(), gl::INVALID_OPERATION);
+		gl::FramebufferTexture2D(gl::FRAMEBUFFER, gl::COLOR_ATTACHMENT0, gl::TEXTURE_2D, 0, 0);
+		assert_eq!(gl::GetError(), gl::NO_ERROR);
+
+		assert_eq!(gl::CheckFramebufferStatus(gl::RENDERBUFFER), 0);
+		assert_eq!(gl::GetError(), gl::INVALID_ENUM);
+
+		// drawing to an incomplete framebuffer
+		let mut array = 0;
+		gl::GenVertexArrays(1, &mut array);
+		gl::BindVertexArray(array);
+		gl::DrawArrays(gl::TRIANGLES, 0, 3);
+		assert_eq!(gl::GetError(), gl::INVALID_FRAMEBUFFER_OPERATION);
+
+		gl::DeleteVertexArrays(1, &array);
+		gl::DeleteRenderbuffers(1, &renderbuffer);
+		gl::DeleteFramebuffers(1, &framebuffer);
+	})
+}
+
+#[test]
+fn draw_to_renderbuffers() {
+	test_harness_context(gl33_core(), |ctx| unsafe {
+		ctx.resize(4, 4);
+
+		let positions: [[f32; 3]; 3] = [[-1.0, -1.0, 0.5], [3.0, -1.0, 0.5], [-1.0, 3.0, 0.5]];
+		let mut array = 0;
+		let mut buffer = 0;
+		gl::GenVertexArrays(1, &mut array);
+		gl::BindVertexArray(array);
+		gl::GenBuffers(1, &mut buffer);
+		gl::BindBuffer(gl::ARRAY_BUFFER, buffer);
+		gl::BufferData(
+			gl::ARRAY_BUFFER,
+			std::mem::size_of_val(&positions) as GLsizeiptr,
+			positions.as_ptr() as *const c_void,
+			gl::STATIC_DRAW,
+		);
+		gl::VertexAttribPointer(0, 3, gl::FLOAT, gl::FALSE, 0, std::ptr::null());
+		gl::EnableVertexAttribArray(0);
+
+		let mut framebuffer = 0;
+		gl::GenFramebuffers(1, &mut framebuffer);
+		gl::BindFramebuffer(gl::FRAMEBUFFER, framebuffer);
+		let color = renderbuffer(gl::RGBA4, 4, 4);
+		let depth = renderbuffer(gl::DEPTH24_STENCIL8, 8, 8);
+		gl::FramebufferRenderbuffer(
+			gl::FRAMEBUFFER,
+			gl::COLOR_ATTACHMENT0,
+			gl::RENDERBUFFER,
+			color,
+		);
+		gl::FramebufferRenderbuffer(
+			gl::FRAMEBUFFER,
+			gl::DEPTH_STENCIL_ATTACHMENT,
+			gl::RENDERBUFFER,
+			depth,
+		);
+
+		gl::Enable(gl::DEPTH_TEST);
+		ctx.set_fragment_color([0.5, 0.0, 1.0, 1.0]);
+		gl::DrawArrays(gl::TRIANGLES, 0, 3);
+
+		{
+			let context = crate::context();
+			let renderbuffers = &context.renderbuffer_manager;
+
+			// colors are rounded to the 4 bits of GL_RGBA4
+			let image = renderbuffers.renderbuffer(color).unwrap().image();
+			assert_eq!(image.color(3, 3), Some([8.0 / 15.0, 0.0, 1.0, 1.0]));
+			assert_eq!(image.depth(3, 3), None);
+
+			// only the common size of all attachments is drawn
+			let image = renderbuffers.renderbuffer(depth).unwrap().image();
+			assert_eq!(image.depth(3, 3), Some(0.75));
+			assert_eq!(image.depth(4, 4), Some(1.0));
+			assert_eq!(image.color(3, 3), None);
+		}
+		assert_eq!(ctx.default_framebuffer().color(0, 0), Some([0.0; 4]));
+
+		// the renderbuffer stays alive while attached to an unbound framebuffer
+		gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+		gl::DeleteRenderbuffers(1, &color);
+		assert!(crate::context().renderbuffer_manager.renderbuffer(color).is_some());
+		gl::DeleteFramebuffers(1, &framebuffer);
+		assert!(crate::context().renderbuffer_manager.renderbuffer(color).is_none());
+
+		gl::DeleteRenderbuffers(1, &depth);
+		gl::DeleteBuffers(1, &buffer);
+		gl::DeleteVertexArrays(1, &array);
+	})
+}
+
+/// Framebuffer with a single color attachment of `format`
+unsafe fn color_framebuffer(format: GLenum, samples: GLsizei, size: GLsizei) -> (GLuint, GLuint) {
+	let mut framebuffer = 0;
+	let mut color = 0;
+	gl::GenFramebuffers(1, &mut framebuffer);
+	gl::BindFramebuffer(gl::FRAMEBUFFER, framebuffer);
+	gl::GenRenderbuffers(1, &mut color);
+	gl::BindRenderbuffer(gl::RENDERBUFFER, color);
+	gl::RenderbufferStorageMultisample(gl::RENDERBUFFER, samples, format, size, size);
+	gl::FramebufferRenderbuffer(gl::FRAMEBUFFER, gl::COLOR_ATTACHMENT0, gl::RENDERBUFFER, color);
+	(framebuffer, color)
+}
+
+fn renderbuffer_color(renderbuffer: GLuint, x: usize, y: usize) -> Option<[f32; 4]> {
+	let context = crate::context();
+	context.renderbuffer_manager.renderbuffer(renderbuffer)?.image().color(x, y)
+}
+
+#[test]
+fn blit() {
+	test_harness_context(gl33_core(), |ctx| unsafe {
+		let (multisample, multisample_color) = color_framebuffer(gl::RGBA8, 4, 2);
+		let (resolved, resolved_color) = color_framebuffer(gl::RGBA8, 0, 4);
+		assert_eq!(gl::CheckFramebufferStatus(gl::FRAMEBUFFER), gl::FRAMEBUFFER_COMPLETE);
+
+		{
+			let mut context = crate::context();
+			let image = context.renderbuffer_manager.renderbuffer_mut(multisample_color);
+			let image = image.unwrap().image_mut();
+			image.set_color(0, 0, [1.0, 0.0, 0.0, 1.0]);
+			image.set_color(1, 0, [0.0, 1.0, 0.0, 1.0]);
+		}
+
+		// resolve into the bottom left corner
+		gl::BindFramebuffer(gl::READ_FRAMEBUFFER, multisample);
+		gl::BlitFramebuffer(0, 0, 2, 2, 0, 0, 2, 2, gl::COLOR_BUFFER_BIT, gl::NEAREST);
+		assert_eq!(renderbuffer_color(resolved_color, 0, 0), Some([1.0, 0.0, 0.0, 1.0]));
+		assert_eq!(renderbuffer_color(resolved_color, 1, 0), Some([0.0, 1.0, 0.0, 1.0]));
+		assert_eq!(renderbuffer_color(resolved_color, 2, 0), Some([0.0; 4]));
+
+		// scale up and mirror horizontally into the default framebuffer
+		ctx.resize(4, 4);
+		gl::BindFramebuffer(gl::READ_FRAMEBUFFER, resolved);
+		gl::BindFramebuffer(gl::DRAW_FRAMEBUFFER, 0);
+		gl::BlitFramebuffer(0, 0, 2, 1, 4, 0, 0, 4, gl::COLOR_BUFFER_BIT, gl::NEAREST);
+		let framebuffer = ctx.default_framebuffer();
+		assert_eq!(framebuffer.color(0, 3), Some([0.0, 1.0, 0.0, 1.0]));
+		assert_eq!(framebuffer.color(3, 0), Some([1.0, 0.0, 0.0, 1.0]));
+
+		// linear filtering blends neighbouring pixels
+		gl::BlitFramebuffer(0, 0, 2, 1, 0, 0, 4, 1, gl::COLOR_BUFFER_BIT, gl::LINEAR);
+		let framebuffer = ctx.default_framebuffer();
+		assert_eq!(framebuffer.color(0, 0), Some([1.0, 0.0, 0.0, 1.0]));
+		assert_eq!(framebuffer.color(1, 0), Some([0.75, 0.25, 0.0, 1.0]));
+
+		// the scissor test limits the destination
+		gl::Enable(gl::SCISSOR_TEST);
+		gl::Scissor(0, 0, 1, 1);
+		gl::BlitFramebuffer(1, 0, 2, 1, 0, 0, 4, 4, gl::COLOR_BUFFER_BIT, gl::NEAREST);
+		let framebuffer = ctx.default_framebuffer();
+		assert_eq!(framebuffer.color(0, 0), Some([0.0, 1.0, 0.0, 1.0]));
+		assert_eq!(framebuffer.color(1, 0), Some([0.75, 0.25, 0.0, 1.0]));
+		gl::Disable(gl::SCISSOR_TEST);
+
+		gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+		gl::DeleteFramebuffers(1, &multisample);
+		gl::DeleteFramebuffers(1, &resolved);
+		gl::DeleteRenderbuffers(1, &multisample_color);
+		gl::DeleteRenderbuffers(1, &resolved_color);
+	})
+}
+
+#[test]
+fn blit_gl_errors() {
+	test_harness_handling(gl33_core(), ErrorHandling::DoNotPanic, || unsafe {
+		let (multisample, multisample_color) = color_framebuffer(gl::RGBA8, 2, 4);
+		let (integer, integer_color) = color_framebuffer(gl::RGBA8UI, 0, 4);
+		let (normalized, normalized_color) = color_framebuffer(gl::RGBA8, 0, 4);
+
+		gl::BlitFramebuffer(0, 0, 4, 4, 0, 0, 4, 4, 0x1, gl::NEAREST);
+		assert_eq!(gl::GetError(), gl::INVALID_VALUE);
+
+		gl::BlitFramebuffer(0, 0, 4, 4, 0, 0, 4, 4, gl::COLOR_BUFFER_BIT, gl::LINEAR_MIPMAP_LINEAR);
+		assert_eq!(gl::GetError(), gl::INVALID_ENUM);
+
+		gl::BlitFramebuffer(0, 0, 4, 4, 0, 0, 4, 4, gl::DEPTH_BUFFER_BIT, gl::LINEAR);
+		assert_eq!(gl::GetError(), gl::INVALID_OPERATION);
+
+		// multisample sources can't be scaled, or blitted into multisample framebuffers
+		gl::BindFramebuffer(gl::READ_FRAMEBUFFER, multisample);
+		gl::BlitFramebuffer(0, 0, 4, 4, 0, 0, 2, 2, gl::COLOR_BUFFER_BIT, gl::NEAREST);
+		assert_eq!(gl::GetError(), gl::INVALID_OPERATION);
+
+		gl::BindFramebuffer(gl::DRAW_FRAMEBUFFER, multisample);
+		gl::BlitFramebuffer(0, 0, 4, 4, 0, 0, 4, 4, gl::COLOR_BUFFER_BIT, gl::NEAREST);
+		assert_eq!(gl::GetError(), gl::INVALID_OPERATION);
+
+		// integer and normalized colors don't mix, and integers can't be filtered
+		gl::BindFramebuffer(gl::READ_FRAMEBUFFER, integer);
+		gl::BindFramebuffer(gl::DRAW_FRAMEBUFFER, normalized);
+		gl::BlitFramebuffer(0, 0, 4, 4, 0, 0, 4, 4, gl::COLOR_BUFFER_BIT, gl::NEAREST);
+		assert_eq!(gl::GetError(), gl::INVALID_OPERATION);
+
+		gl::BindFramebuffer(gl::DRAW_FRAMEBUFFER, integer);
+		gl::BlitFramebuffer(0, 0, 4, 4, 0, 0, 4, 4, gl::COLOR_BUFFER_BIT, gl::LINEAR);
+		assert_eq!(gl::GetError(), gl::INVALID_OPERATION);
+		gl::BlitFramebuffer(0, 0, 4, 4, 0, 0, 4, 4, gl::COLOR_BUFFER_BIT, gl::NEAREST);
+		assert_eq!(gl::GetError(), gl::NO_ERROR);
+
+		// incomplete framebuffers
+		let mut empty = 0;
+		gl::GenFramebuffers(1, &mut empty);
+		gl::BindFramebuffer(gl::DRAW_FRAMEBUFFER, empty);
+		gl::BlitFramebuffer(0, 0, 4, 4, 0, 0, 4, 4, gl::COLOR_BUFFER_BIT, gl::NEAREST);
+		assert_eq!(gl::GetError(), gl::INVALID_FRAMEBUFFER_OPERATION);
+
+		gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+		gl::DeleteFramebuffers(4, [multisample, integer, normalized, empty].as_ptr());
+		gl::DeleteRenderbuffers(3, [multisample_color, integer_color, normalized_color].as_ptr());
+	})
+}
+
+#[test]
+fn clear() {
+	test_harness_context(gl33_core(), |ctx| unsafe {
+		ctx.resize(4, 4);
+
+		gl::ClearColor(1.0, 0.5, 0.25, 1.0);
+		gl::ClearDepth(0.5);
+		gl::ClearStencil(0xff);
+		gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT | gl::STENCIL_BUFFER_BIT);
+		let framebuffer = ctx.default_framebuffer();
+		assert_eq!(framebuffer.color(3, 3), Some([1.0, 0.5, 0.25, 1.0]));
+		assert_eq!(framebuffer.depth(3, 3), Some(0.5));
+		assert_eq!(framebuffer.stencil(3, 3), Some(0xff));
+
+		// write masks keep masked components and bits
+		gl::ColorMask(gl::FALSE, gl::TRUE, gl::TRUE, gl::FALSE);
+		gl::DepthMask(gl::FALSE);
+		gl::StencilMask(0x0f);
+		gl::ClearColor(0.0, 0.0, 0.0, 0.0);
+		gl::ClearDepth(1.0);
+		gl::ClearStencil(0);
+		gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT | gl::STENCIL_BUFFER_BIT);
+		let framebuffer = ctx.default_framebuffer();
+		assert_eq!(framebuffer.color(0, 0), Some([1.0, 0.0, 0.0, 1.0]));
+		assert_eq!(framebuffer.depth(0, 0), Some(0.5));
+		assert_eq!(framebuffer.stencil(0, 0), Some(0xf0));
+
+		let mut writemask = 0;
+		gl::GetIntegerv(gl::STENCIL_WRITEMASK, &mut writemask);
+		assert_eq!(writemask, 0x0f);
+
+		// the scissor test limits clears
+		gl::ColorMask(gl::TRUE, gl::TRUE, gl::TRUE, gl::TRUE);
+		gl::Enable(gl::SCISSOR_TEST);
+		gl::Scissor(2, 2, 8, 8);
+		gl::ClearColor(0.0, 0.0, 1.0, 1.0);
+		gl::Clear(gl::COLOR_BUFFER_BIT);
+		let framebuffer = ctx.default_framebuffer();
+		assert_eq!(framebuffer.color(1, 1), Some([1.0, 0.0, 0.0, 1.0]));
+		assert_eq!(framebuffer.color(2, 2), Some([0.0, 0.0, 1.0, 1.0]));
+		gl::Disable(gl::SCISSOR_TEST);
+
+		// clears of framebuffer objects are rounded to the format
+		let (framebuffer, color) = color_framebuffer(gl::RGBA8UI, 0, 2);
+		gl::ClearBufferuiv(gl::COLOR, 0, [7, 300, 0, 1].as_ptr());
+		assert_eq!(renderbuffer_color(color, 1, 1), Some([7.0, 255.0, 0.0, 1.0]));
+
+		let depth = renderbuffer(gl::DEPTH24_STENCIL8, 2, 2);
+		gl::FramebufferRenderbuffer(
+			gl::FRAMEBUFFER,
+			gl::DEPTH_STENCIL_ATTACHMENT,
+			gl::RENDERBUFFER,
+			depth,
+		);
+		gl::DepthMask(gl::TRUE);
+		gl::StencilMask(0xff);
+		gl::ClearBufferfi(gl::DEPTH_STENCIL, 0, 0.25, 3);
+		{
+			let context = crate::context();
+			let image = context.renderbuffer_manager.renderbuffer(depth).unwrap().image();
+			assert_eq!((image.depth(0, 1), image.stencil(0, 1)), (Some(0.25), Some(3)));
+		}
+
+		gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+		gl::DeleteFramebuffers(1, &framebuffer);
+		gl::DeleteRenderbuffers(2, [color, depth].as_ptr());
+	})
+}
+
+#[test]
+fn clear_gl_errors() {
+	test_harness_handling(gl33_core(), ErrorHandling::DoNotPanic, || unsafe {
+		gl::Clear(0x1);
+		assert_eq!(gl::GetError(), gl::INVALID_VALUE);
+
+		let value = [0.0f32; 4];
+		gl::ClearBufferfv(gl::STENCIL, 0, value.as_ptr());
+		assert_eq!(gl::GetError(), gl::INVALID_ENUM);
+		gl::ClearBufferfv(gl::DEPTH, 1, value.as_ptr());
+		assert_eq!(gl::GetError(), gl::INVALID_VALUE);
+		gl::ClearBufferfv(gl::COLOR, 8, value.as_ptr());
+		assert_eq!(gl::GetError(), gl::INVALID_VALUE);
+		gl::ClearBufferfi(gl::DEPTH, 0, 1.0, 0);
+		assert_eq!(gl::GetError(), gl::INVALID_ENUM);
+
+		let mut empty = 0;
+		gl::GenFramebuffers(1, &mut empty);
+		gl::BindFramebuffer(gl::FRAMEBUFFER, empty);
+		gl::Clear(gl::COLOR_BUFFER_BIT);
+		assert_eq!(gl::GetError(), gl::INVALID_FRAMEBUFFER_OPERATION);
+
+		gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+		gl::DeleteFramebuffers(1, &empty);
+	})
+}
+
+#[test]
+fn read_pixels() {
+	// GL_IMPLEMENTATION_COLOR_READ_FORMAT is part of ARB_ES2_compatibility
+	let version = GlVersion::new(VersionType::GL, 3, 3, &[&ext::ARB_ES2_compatibility])
+		.with_profile(Profile::Core);
+	test_harness_context(version, |ctx| unsafe {
+		ctx.resize(3, 2);
+		gl::ClearColor(1.0, 0.5, 0.0, 1.0);
+		gl::Clear(gl::COLOR_BUFFER_BIT);
+
+		// rows are padded to the pack alignment, pixels outside the framebuffer are untouched
+		let mut pixels = [0xaau8; 24];
+		gl::ReadPixels(1, 0, 3, 2, gl::RGB, gl::UNSIGNED_BYTE, pixels.as_mut_ptr() as *mut c_void);
+		assert_eq!(pixels[..12], [255, 128, 0, 255, 128, 0, 0xaa, 0xaa, 0xaa, 0xaa, 0xaa, 0xaa]);
+		assert_eq!(pixels[12..15], [255, 128, 0]);
+
+		gl::PixelStorei(gl::PACK_ALIGNMENT, 1);
+		gl::PixelStorei(gl::PACK_SKIP_PIXELS, 1);
+		let mut pixels = [0u8; 8];
+		gl::ReadPixels(0, 0, 1, 1, gl::BGRA, gl::UNSIGNED_BYTE, pixels.as_mut_ptr() as *mut c_void);
+		assert_eq!(pixels, [0, 0, 0, 0, 0, 128, 255, 255]);
+		gl::PixelStorei(gl::PACK_SKIP_PIXELS, 0);
+
+		let mut depth = [0.0f32; 1];
+		gl::ReadPixels(
+			0,
+			0,
+			1,
+			1,
+			gl::DEPTH_COMPONENT,
+			gl::FLOAT,
+			depth.as_mut_ptr() as *mut c_void,
+		);
+		assert_eq!(depth, [1.0]);
+
+		// pixel pack buffers take an offset instead of a pointer
+		let mut buffer = 0;
+		gl::GenBuffers(1, &mut buffer);
+		gl::BindBuffer(gl::PIXEL_PACK_BUFFER, buffer);
+		gl::BufferData(gl::PIXEL_PACK_BUFFER, 8, std::ptr::null(), gl::STREAM_READ);
+		gl::ReadPixels(0, 1, 1, 1, gl::RGBA, gl::UNSIGNED_BYTE, 4 as *mut c_void);
+		{
+			let context = crate::context();
+			let memory = context.buffer_manager.buffer(buffer).unwrap().memory();
+			assert_eq!(memory, [0, 0, 0, 0, 255, 128, 0, 255]);
+		}
+		gl::BindBuffer(gl::PIXEL_PACK_BUFFER, 0);
+		gl::DeleteBuffers(1, &buffer);
+
+		// framebuffer objects read back their format's values
+		let (framebuffer, color) = color_framebuffer(gl::RGBA32F, 0, 1);
+		gl::ClearBufferfv(gl::COLOR, 0, [-1.0, 2.0, 0.5, 0.0].as_ptr());
+		let mut pixels = [0.0f32; 4];
+		gl::ReadPixels(0, 0, 1, 1, gl::RGBA, gl::FLOAT, pixels.as_mut_ptr() as *mut c_void);
+		assert_eq!(pixels, [-1.0, 2.0, 0.5, 0.0]);
+
+		let mut format = 0;
+		gl::GetIntegerv(gl::IMPLEMENTATION_COLOR_READ_FORMAT, &mut format);
+		assert_eq!(format as GLenum, gl::RGBA);
+
+		gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+		gl::DeleteFramebuffers(1, &framebuffer);
+		gl::DeleteRenderbuffers(1, &color);
+	})
+}
+
+#[test]
+fn read_pixels_gl_errors() {
+	test_harness_handling(gl33_core(), ErrorHandling::DoNotPanic, || unsafe {
+		let mut pixels = [0u32; 16];
+		let pixels = pixels.as_mut_ptr() as *mut c_void;
+
+		gl::ReadPixels(0, 0, -1, 1, gl::RGBA, gl::UNSIGNED_BYTE, pixels);
+		assert_eq!(gl::GetError(), gl::INVALID_VALUE);
+		gl::ReadPixels(0, 0, 1, 1, gl::RGBA8, gl::UNSIGNED_BYTE, pixels);
+		assert_eq!(gl::GetError(), gl::INVALID_ENUM);
+		gl::ReadPixels(0, 0, 1, 1, gl::RGBA, gl::UNSIGNED_SHORT_5_6_5, pixels);
+		assert_eq!(gl::GetError(), gl::INVALID_OPERATION);
+		gl::ReadPixels(0, 0, 1, 1, gl::RGBA_INTEGER, gl::INT, pixels);
+		assert_eq!(gl::GetError(), gl::INVALID_OPERATION);
+		gl::PixelStorei(gl::PACK_ALIGNMENT, 3);
+		assert_eq!(gl::GetError(), gl::INVALID_VALUE);
+
+		let (framebuffer, color) = color_framebuffer(gl::RGBA8, 0, 2);
+		gl::ReadPixels(0, 0, 1, 1, gl::DEPTH_COMPONENT, gl::FLOAT, pixels);
+		assert_eq!(gl::GetError(), gl::INVALID_OPERATION);
+
+		// pixel pack buffers must be large enough
+		let mut buffer = 0;
+		gl::GenBuffers(1, &mut buffer);
+		gl::BindBuffer(gl::PIXEL_PACK_BUFFER, buffer);
+		gl::BufferData(gl::PIXEL_PACK_BUFFER, 8, std::ptr::null(), gl::STREAM_READ);
+		gl::ReadPixels(0, 0, 2, 2, gl::RGBA, gl::UNSIGNED_BYTE, std::ptr::null_mut());
+		assert_eq!(gl::GetError(), gl::INVALID_OPERATION);
+		let offset = usize::MAX - 3;
+		gl::ReadPixels(0, 0, 1, 1, gl::RGBA, gl::UNSIGNED_BYTE, offset as *mut c_void);
+		assert_eq!(gl::GetError(), gl::INVALID_OPERATION);
+		gl::BindBuffer(gl::PIXEL_PACK_BUFFER, 0);
+		gl::DeleteBuffers(1, &buffer);
+
+		let (multisample, multisample_color) = color_framebuffer(gl::RGBA8, 4, 2);
+		gl::ReadPixels(0, 0, 1, 1, gl::RGBA, gl::UNSIGNED_BYTE, pixels);
+		assert_eq!(gl::GetError(), gl::INVALID_OPERATION);
+
+		gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+		gl::DeleteFramebuffers(2, [framebuffer, multisample].as_ptr());
+		gl::DeleteRenderbuffers(2, [color, multisample_color].as_ptr());
+	})
+}
+
+#[test]
+fn es_read_pixels() {
+	let es30 = GlVersion::from_version(VersionType::ES, 3, 0);
+	test_harness_handling(es30, ErrorHandling::DoNotPanic, || unsafe {
+		let mut pixels = [0u32; 4];
+		let pixels = pixels.as_mut_ptr() as *mut c_void;
+		let (framebuffer, color) = color_framebuffer(gl::RGB565, 0, 1);
+
+		// besides the implementation's pair, only RGBA and unsigned bytes read normalized buffers
+		gl::ReadPixels(0, 0, 1, 1, gl::RGBA, gl::UNSIGNED_BYTE, pixels);
+		gl::ReadPixels(0, 0, 1, 1, gl::RGB, gl::UNSIGNED_SHORT_5_6_5, pixels);
+		assert_eq!(gl::GetError(), gl::NO_ERROR);
+		gl::ReadPixels(0, 0, 1, 1, gl::RGB, gl::UNSIGNED_BYTE, pixels);
+		assert_eq!(gl::GetError(), gl::INVALID_OPERATION);
+
+		let (mut format, mut ty) = (0, 0);
+		gl::GetIntegerv(gl::IMPLEMENTATION_COLOR_READ_FORMAT, &mut format);
+		gl::GetIntegerv(gl::IMPLEMENTATION_COLOR_READ_TYPE, &mut ty);
+		assert_eq!((format as GLenum, ty as GLenum), (gl::RGB, gl::UNSIGNED_SHORT_5_6_5));
+
+		gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+		gl::DeleteFramebuffers(1, &framebuffer);
+		gl::DeleteRenderbuffers(1, &color);
+	})
+}
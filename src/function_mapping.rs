@@ -1,8 +1,17 @@
 use std::{ffi::c_void, ptr};
 
-use gl::types::{GLenum, GLint};
+use gl::types::GLenum;
 
-use crate::{buffer::gl_functions::*, MockContextRef};
+use crate::{
+	buffer::gl_functions::*,
+	draw::gl_functions::*,
+	framebuffer::gl_functions::*,
+	query::gl_functions::*,
+	renderbuffer::gl_functions::*,
+	state::gl_functions::*,
+	vertex_array::gl_functions::*,
+	MockContextRef,
+};
 
 macro_rules! mapping {
 	($($($name:literal)|* => $func:expr;)*) => {
@@ -25,13 +34,14 @@ macro_rules! gl_functions {
 		fn $name:ident($($param:ident: $ty:ty),*$(,)?) $(-> $return:ty)?;
 		$(require gl $gl_major:literal . $gl_minor:literal;)?
 		$(require es $es_major:literal . $es_minor:literal;)?
-		$(require ext $req:ident;)?
-		$(take [$($take:ident),*])?
+		$(require ext $($req:ident)|+;)?
+		$(take [$($take:ident),* $(,)?])?
+		$(take $context:ident)?
 		$block:block
 	)*} => {
 		$(
 			#[allow(non_snake_case)]
-			pub(crate) unsafe extern "system" fn $name ($($param: $ty),*) $(-> $return)? {
+			pub(crate) unsafe extern "system-unwind" fn $name ($($param: $ty),*) $(-> $return)? {
 				::log::trace!(
 					target: "mock-gl",
 					concat!(
@@ -54,12 +64,12 @@ macro_rules! gl_functions {
 
 				if !gl_version_met {
 					if false {
-					} $(else if context.gl_version.extensions.contains(&&$crate::version::ext::$req) {
+					} $(else if false $(|| context.gl_version.extensions.contains(&&$crate::version::ext::$req))+ {
 					} else if true {
 						$crate::error!(
 							"{} requires {}",
 							stringify!($name),
-							$crate::version::ext::$req.provided_str
+							[$($crate::version::ext::$req.provided_str),+].join(" or ")
 						);
 					})? else {
 						$crate::error!(
@@ -75,6 +85,7 @@ macro_rules! gl_functions {
 				}
 
 				let $crate::MockContextData { $($($take),*,)? .. } = context;
+				$(let $context = context;)?
 
 				$block
 			}
@@ -91,13 +102,181 @@ pub(crate) use gl_functions;
 
 mapping! {
 	"glGetError" => glGetError;
+	"glGetString" => glGetString;
+	"glGetStringi" => glGetStringi;
+	"glGetBooleanv" => glGetBooleanv;
 	"glGetIntegerv" => glGetIntegerv;
+	"glGetInteger64v" | "glGetInteger64vAPPLE" | "glGetInteger64vEXT" => glGetInteger64v;
+	"glGetFloatv" => glGetFloatv;
+	"glGetDoublev" => glGetDoublev;
+	"glGetIntegeri_v" | "glGetIntegerIndexedvEXT" => glGetIntegeri_v;
+	"glGetBooleani_v" | "glGetBooleanIndexedvEXT" => glGetBooleani_v;
+	"glGetInteger64i_v" => glGetInteger64i_v;
+	"glGetFloati_v" | "glGetFloati_vOES" | "glGetFloati_vNV" => glGetFloati_v;
+	"glGetDoublei_v" => glGetDoublei_v;
 	"glGenBuffers" | "glGenBuffersARB" => glGenBuffers;
 	"glDeleteBuffers" | "glDeleteBuffersARB" => glDeleteBuffers;
 	"glIsBuffer" | "glIsBufferARB" => glIsBuffer;
 	"glBindBuffer" | "glBindBufferARB" => glBindBuffer;
 	"glBufferData" | "glBufferDataARB" => glBufferData;
 	"glNamedBufferData" => glNamedBufferData;
+	"glGenVertexArrays" | "glGenVertexArraysOES" => glGenVertexArrays;
+	"glDeleteVertexArrays" | "glDeleteVertexArraysOES" => glDeleteVertexArrays;
+	"glIsVertexArray" | "glIsVertexArrayOES" => glIsVertexArray;
+	"glBindVertexArray" | "glBindVertexArrayOES" => glBindVertexArray;
+	"glVertexAttribPointer" | "glVertexAttribPointerARB" => glVertexAttribPointer;
+	"glEnableVertexAttribArray" | "glEnableVertexAttribArrayARB" => glEnableVertexAttribArray;
+	"glDisableVertexAttribArray" | "glDisableVertexAttribArrayARB" => glDisableVertexAttribArray;
+	"glVertexAttribIPointer" | "glVertexAttribIPointerEXT" => glVertexAttribIPointer;
+	"glVertexAttribLPointer" | "glVertexAttribLPointerEXT" => glVertexAttribLPointer;
+	"glVertexAttribDivisor"
+		| "glVertexAttribDivisorARB"
+		| "glVertexAttribDivisorEXT"
+		| "glVertexAttribDivisorANGLE" => glVertexAttribDivisor;
+	"glVertexAttribFormat" => glVertexAttribFormat;
+	"glVertexAttribIFormat" => glVertexAttribIFormat;
+	"glVertexAttribLFormat" => glVertexAttribLFormat;
+	"glVertexAttribBinding" => glVertexAttribBinding;
+	"glBindVertexBuffer" => glBindVertexBuffer;
+	"glBindVertexBuffers" => glBindVertexBuffers;
+	"glVertexBindingDivisor" => glVertexBindingDivisor;
+	"glCreateVertexArrays" => glCreateVertexArrays;
+	"glVertexArrayElementBuffer" => glVertexArrayElementBuffer;
+	"glVertexArrayAttribFormat" => glVertexArrayAttribFormat;
+	"glVertexArrayAttribIFormat" => glVertexArrayAttribIFormat;
+	"glVertexArrayAttribLFormat" => glVertexArrayAttribLFormat;
+	"glVertexArrayAttribBinding" => glVertexArrayAttribBinding;
+	"glVertexArrayVertexBuffer" => glVertexArrayVertexBuffer;
+	"glVertexArrayVertexBuffers" => glVertexArrayVertexBuffers;
+	"glVertexArrayBindingDivisor" => glVertexArrayBindingDivisor;
+	"glEnableVertexArrayAttrib" => glEnableVertexArrayAttrib;
+	"glDisableVertexArrayAttrib" => glDisableVertexArrayAttrib;
+	"glDrawArrays" | "glDrawArraysEXT" => glDrawArrays;
+	"glDrawArraysInstanced"
+		| "glDrawArraysInstancedARB"
+		| "glDrawArraysInstancedEXT"
+		| "glDrawArraysInstancedANGLE" => glDrawArraysInstanced;
+	"glDrawArraysInstancedBaseInstance" | "glDrawArraysInstancedBaseInstanceEXT" =>
+		glDrawArraysInstancedBaseInstance;
+	"glMultiDrawArrays" | "glMultiDrawArraysEXT" => glMultiDrawArrays;
+	"glDrawElements" => glDrawElements;
+	"glDrawElementsBaseVertex" | "glDrawElementsBaseVertexOES" | "glDrawElementsBaseVertexEXT" =>
+		glDrawElementsBaseVertex;
+	"glDrawElementsInstanced"
+		| "glDrawElementsInstancedARB"
+		| "glDrawElementsInstancedEXT"
+		| "glDrawElementsInstancedANGLE" => glDrawElementsInstanced;
+	"glDrawElementsInstancedBaseVertex"
+		| "glDrawElementsInstancedBaseVertexOES"
+		| "glDrawElementsInstancedBaseVertexEXT" => glDrawElementsInstancedBaseVertex;
+	"glDrawElementsInstancedBaseInstance" | "glDrawElementsInstancedBaseInstanceEXT" =>
+		glDrawElementsInstancedBaseInstance;
+	"glDrawElementsInstancedBaseVertexBaseInstance"
+		| "glDrawElementsInstancedBaseVertexBaseInstanceEXT" => glDrawElementsInstancedBaseVertexBaseInstance;
+	"glDrawRangeElements" | "glDrawRangeElementsEXT" => glDrawRangeElements;
+	"glDrawRangeElementsBaseVertex" | "glDrawRangeElementsBaseVertexOES" | "glDrawRangeElementsBaseVertexEXT" =>
+		glDrawRangeElementsBaseVertex;
+	"glMultiDrawElements" | "glMultiDrawElementsEXT" => glMultiDrawElements;
+	"glMultiDrawElementsBaseVertex" | "glMultiDrawElementsBaseVertexOES" | "glMultiDrawElementsBaseVertexEXT" =>
+		glMultiDrawElementsBaseVertex;
+	"glDrawArraysIndirect" => glDrawArraysIndirect;
+	"glDrawElementsIndirect" => glDrawElementsIndirect;
+	"glMultiDrawArraysIndirect" | "glMultiDrawArraysIndirectEXT" => glMultiDrawArraysIndirect;
+	"glMultiDrawElementsIndirect" | "glMultiDrawElementsIndirectEXT" => glMultiDrawElementsIndirect;
+	"glMultiDrawArraysIndirectCount" | "glMultiDrawArraysIndirectCountARB" => glMultiDrawArraysIndirectCount;
+	"glMultiDrawElementsIndirectCount" | "glMultiDrawElementsIndirectCountARB" =>
+		glMultiDrawElementsIndirectCount;
+	"glDispatchCompute" => glDispatchCompute;
+	"glDispatchComputeIndirect" => glDispatchComputeIndirect;
+	"glEnable" => glEnable;
+	"glDisable" => glDisable;
+	"glIsEnabled" => glIsEnabled;
+	"glEnablei" | "glEnableiEXT" | "glEnableiOES" => glEnablei;
+	"glDisablei" | "glDisableiEXT" | "glDisableiOES" => glDisablei;
+	"glIsEnabledi" | "glIsEnablediEXT" | "glIsEnablediOES" => glIsEnabledi;
+	"glPrimitiveRestartIndex" => glPrimitiveRestartIndex;
+	"glViewport" => glViewport;
+	"glScissor" => glScissor;
+	"glViewportIndexedf" | "glViewportIndexedfOES" | "glViewportIndexedfNV" => glViewportIndexedf;
+	"glViewportIndexedfv" | "glViewportIndexedfvOES" | "glViewportIndexedfvNV" => glViewportIndexedfv;
+	"glViewportArrayv" | "glViewportArrayvOES" | "glViewportArrayvNV" => glViewportArrayv;
+	"glScissorIndexed" | "glScissorIndexedOES" | "glScissorIndexedNV" => glScissorIndexed;
+	"glScissorIndexedv" | "glScissorIndexedvOES" | "glScissorIndexedvNV" => glScissorIndexedv;
+	"glScissorArrayv" | "glScissorArrayvOES" | "glScissorArrayvNV" => glScissorArrayv;
+	"glDepthRange" => glDepthRange;
+	"glDepthRangef" | "glDepthRangefOES" => glDepthRangef;
+	"glDepthRangeIndexed" => glDepthRangeIndexed;
+	"glDepthRangeIndexedfOES" | "glDepthRangeIndexedfNV" => glDepthRangeIndexedfOES;
+	"glDepthRangeArrayv" => glDepthRangeArrayv;
+	"glDepthRangeArrayfvOES" | "glDepthRangeArrayfvNV" => glDepthRangeArrayfvOES;
+	"glClipControl" | "glClipControlEXT" => glClipControl;
+	"glDepthFunc" => glDepthFunc;
+	"glBlendFunc" => glBlendFunc;
+	"glBlendFuncSeparate" => glBlendFuncSeparate;
+	"glBlendFunci" | "glBlendFunciARB" | "glBlendFunciEXT" | "glBlendFunciOES" => glBlendFunci;
+	"glBlendFuncSeparatei"
+		| "glBlendFuncSeparateiARB"
+		| "glBlendFuncSeparateiEXT"
+		| "glBlendFuncSeparateiOES" => glBlendFuncSeparatei;
+	"glBlendEquation" | "glBlendEquationEXT" => glBlendEquation;
+	"glBlendEquationSeparate" => glBlendEquationSeparate;
+	"glBlendEquationi"
+		| "glBlendEquationiARB"
+		| "glBlendEquationiEXT"
+		| "glBlendEquationiOES" => glBlendEquationi;
+	"glBlendEquationSeparatei"
+		| "glBlendEquationSeparateiARB"
+		| "glBlendEquationSeparateiEXT"
+		| "glBlendEquationSeparateiOES" => glBlendEquationSeparatei;
+	"glBlendColor" => glBlendColor;
+	"glStencilFunc" => glStencilFunc;
+	"glStencilFuncSeparate" => glStencilFuncSeparate;
+	"glStencilOp" => glStencilOp;
+	"glStencilOpSeparate" => glStencilOpSeparate;
+	"glStencilMaskSeparate" => glStencilMaskSeparate;
+	"glCullFace" => glCullFace;
+	"glFrontFace" => glFrontFace;
+	"glClearColor" => glClearColor;
+	"glClearDepth" => glClearDepth;
+	"glClearDepthf" | "glClearDepthfOES" => glClearDepthf;
+	"glClearStencil" => glClearStencil;
+	"glColorMask" => glColorMask;
+	"glColorMaski" | "glColorMaskiEXT" | "glColorMaskiOES" => glColorMaski;
+	"glDepthMask" => glDepthMask;
+	"glStencilMask" => glStencilMask;
+	"glPixelStorei" => glPixelStorei;
+	"glGenFramebuffers" | "glGenFramebuffersEXT" => glGenFramebuffers;
+	"glDeleteFramebuffers" | "glDeleteFramebuffersEXT" => glDeleteFramebuffers;
+	"glIsFramebuffer" | "glIsFramebufferEXT" => glIsFramebuffer;
+	"glBindFramebuffer" => glBindFramebuffer;
+	"glFramebufferRenderbuffer" | "glFramebufferRenderbufferEXT" => glFramebufferRenderbuffer;
+	"glFramebufferTexture" | "glFramebufferTextureARB" | "glFramebufferTextureEXT" | "glFramebufferTextureOES" =>
+		glFramebufferTexture;
+	"glFramebufferTexture1D" | "glFramebufferTexture1DEXT" => glFramebufferTexture1D;
+	"glFramebufferTexture2D" | "glFramebufferTexture2DEXT" => glFramebufferTexture2D;
+	"glFramebufferTexture3D" | "glFramebufferTexture3DEXT" | "glFramebufferTexture3DOES" => glFramebufferTexture3D;
+	"glFramebufferTextureLayer" | "glFramebufferTextureLayerARB" | "glFramebufferTextureLayerEXT" =>
+		glFramebufferTextureLayer;
+	"glCheckFramebufferStatus" | "glCheckFramebufferStatusEXT" => glCheckFramebufferStatus;
+	"glGenRenderbuffers" | "glGenRenderbuffersEXT" => glGenRenderbuffers;
+	"glDeleteRenderbuffers" | "glDeleteRenderbuffersEXT" => glDeleteRenderbuffers;
+	"glIsRenderbuffer" | "glIsRenderbufferEXT" => glIsRenderbuffer;
+	"glBindRenderbuffer" => glBindRenderbuffer;
+	"glRenderbufferStorage" | "glRenderbufferStorageEXT" => glRenderbufferStorage;
+	"glRenderbufferStorageMultisample"
+		| "glRenderbufferStorageMultisampleEXT"
+		| "glRenderbufferStorageMultisampleANGLE"
+		| "glRenderbufferStorageMultisampleNV" => glRenderbufferStorageMultisample;
+	"glGetRenderbufferParameteriv" | "glGetRenderbufferParameterivEXT" => glGetRenderbufferParameteriv;
+	"glGetInternalformativ" => glGetInternalformativ;
+	"glBlitFramebuffer" | "glBlitFramebufferEXT" | "glBlitFramebufferANGLE" | "glBlitFramebufferNV" =>
+		glBlitFramebuffer;
+	"glClear" => glClear;
+	"glClearBufferiv" => glClearBufferiv;
+	"glClearBufferuiv" => glClearBufferuiv;
+	"glClearBufferfv" => glClearBufferfv;
+	"glClearBufferfi" => glClearBufferfi;
+	"glReadPixels" => glReadPixels;
 }
 
 gl_functions! {
@@ -111,18 +290,4 @@ gl_functions! {
 
 		e
 	}
-
-	fn glGetIntegerv(pname: GLenum, params: *mut GLint);
-	require gl 2 . 1;
-	require es 2 . 0;
-	take [gl_version, error, buffer_manager]
-	{
-		let int = buffer_manager.get_int(gl_version, pname);
-		if let Some(int) = int {
-			*params = int;
-		} else {
-			*error = gl::INVALID_ENUM;
-			crate::error!("mock-gl does not support glGet target {}", pname);
-		}
-	}
 }
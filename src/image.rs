@@ -0,0 +1,323 @@
+use std::{
+	env,
+	fs,
+	io,
+	path::{Path, PathBuf},
+};
+
+use gl::types::{GLenum, GLuint};
+
+use crate::{
+	framebuffer::{Attachment, Framebuffer},
+	MockContextRef,
+};
+
+#[cfg(feature = "png")]
+mod png;
+
+#[cfg(test)]
+mod test;
+
+/// Environment variable which makes `Image::assert_matches` overwrite golden images
+pub const UPDATE_GOLDENS_VAR: &str = "MOCK_GL_UPDATE_GOLDENS";
+
+#[derive(Debug, thiserror::Error)]
+pub enum ImageError {
+	#[error(transparent)]
+	Io(#[from] io::Error),
+	#[error("invalid image file: {0}")]
+	Format(String),
+	#[error("unsupported image file extension of {0}")]
+	Extension(PathBuf),
+	#[error("{pixels} pixels don't make up a {width}x{height} image")]
+	PixelCount {
+		width: usize,
+		height: usize,
+		pixels: usize,
+	},
+}
+
+/// 8 bit RGBA image, rows stored top first as image files expect
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Image {
+	width: usize,
+	height: usize,
+	pixels: Vec<[u8; 4]>,
+}
+
+impl Image {
+	pub fn new(width: usize, height: usize, pixels: Vec<[u8; 4]>) -> Result<Self, ImageError> {
+		if width.checked_mul(height) != Some(pixels.len()) {
+			return Err(ImageError::PixelCount {
+				width,
+				height,
+				pixels: pixels.len(),
+			})
+		}
+
+		Ok(Self {
+			width,
+			height,
+			pixels,
+		})
+	}
+
+	/// Colors of `framebuffer`, flipped as its rows are stored bottom first
+	///
+	/// Components are clamped to `[0, 1]`. Without a color plane, depth is shown
+	/// as gray levels or else stencil values as they are.
+	pub fn from_framebuffer(framebuffer: &Framebuffer) -> Self {
+		let (width, height) = (framebuffer.width(), framebuffer.height());
+		let pixel = |x, y| {
+			if let Some(color) = framebuffer.color(x, y) {
+				color.map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8)
+			} else if let Some(depth) = framebuffer.depth(x, y) {
+				let gray = (depth * 255.0).round() as u8;
+				[gray, gray, gray, 255]
+			} else {
+				let stencil = framebuffer.stencil(x, y).unwrap_or(0);
+				[stencil, stencil, stencil, 255]
+			}
+		};
+
+		let pixels = (0..height).rev().flat_map(|y| (0..width).map(move |x| pixel(x, y))).collect();
+		Self {
+			width,
+			height,
+			pixels,
+		}
+	}
+
+	pub fn width(&self) -> usize {
+		self.width
+	}
+
+	pub fn height(&self) -> usize {
+		self.height
+	}
+
+	/// Pixel in column `x` of row `y`, counting rows from the top
+	pub fn pixel(&self, x: usize, y: usize) -> Option<[u8; 4]> {
+		(x < self.width && y < self.height).then(|| self.pixels[y * self.width + x])
+	}
+
+	pub fn pixels(&self) -> &[[u8; 4]] {
+		&self.pixels
+	}
+
+	/// Binary PPM of the image, which drops alpha
+	pub fn to_ppm(&self) -> Vec<u8> {
+		let mut ppm = format!("P6\n{} {}\n255\n", self.width, self.height).into_bytes();
+		ppm.extend(self.pixels.iter().flat_map(|pixel| &pixel[..3]));
+		ppm
+	}
+
+	/// Parse a binary PPM with 8 bit components, alpha is read as opaque
+	pub fn from_ppm(bytes: &[u8]) -> Result<Self, ImageError> {
+		let format = |message: &str| ImageError::Format(format!("PPM {message}"));
+
+		// the header is 4 whitespace separated fields, with comments running to the end of lines
+		let mut fields = Vec::new();
+		let mut i = 0;
+		while fields.len() < 4 {
+			match bytes.get(i) {
+				None => return Err(format("header is truncated")),
+				Some(b'#') =>
+					while bytes.get(i).is_some_and(|b| *b != b'\n') {
+						i += 1;
+					},
+				Some(b) if b.is_ascii_whitespace() => i += 1,
+				Some(_) => {
+					let start = i;
+					while bytes.get(i).is_some_and(|b| !b.is_ascii_whitespace()) {
+						i += 1;
+					}
+					fields.push(String::from_utf8_lossy(&bytes[start..i]).into_owned());
+				},
+			}
+		}
+		// a single whitespace character separates the header from the pixels
+		let data = &bytes[(i + 1).min(bytes.len())..];
+
+		if fields[0] != "P6" {
+			return Err(format("magic number is not P6"))
+		}
+		let [width, height, max] = [1, 2, 3].map(|i| fields[i].parse::<usize>().ok());
+		let (Some(width), Some(height), Some(255)) = (width, height, max) else {
+			return Err(format("header must have a size and 255 as the maximum value"))
+		};
+		let Some(len) = width.checked_mul(height).and_then(|count| count.checked_mul(3)) else {
+			return Err(format("size is too large"))
+		};
+		if data.len() < len {
+			return Err(format("pixel data is truncated"))
+		}
+
+		let pixels = data.chunks_exact(3).take(len / 3).map(|rgb| [rgb[0], rgb[1], rgb[2], 255]);
+		Self::new(width, height, pixels.collect())
+	}
+
+	/// Write the image as PPM, or as PNG if the `png` feature is enabled, by the extension of `path`
+	pub fn save(&self, path: impl AsRef<Path>) -> Result<(), ImageError> {
+		let path = path.as_ref();
+		let bytes = match path.extension().and_then(|ext| ext.to_str()) {
+			Some("ppm") => self.to_ppm(),
+			#[cfg(feature = "png")]
+			Some("png") => self.to_png(),
+			_ => return Err(ImageError::Extension(path.to_owned())),
+		};
+
+		Ok(fs::write(path, bytes)?)
+	}
+
+	/// Read an image written by `save`
+	pub fn load(path: impl AsRef<Path>) -> Result<Self, ImageError> {
+		let path = path.as_ref();
+		match path.extension().and_then(|ext| ext.to_str()) {
+			Some("ppm") => Self::from_ppm(&fs::read(path)?),
+			#[cfg(feature = "png")]
+			Some("png") => Self::from_png(&fs::read(path)?),
+			_ => Err(ImageError::Extension(path.to_owned())),
+		}
+	}
+
+	/// Pixels whose components differ by more than `tolerance`, and the largest difference
+	pub fn compare(&self, other: &Image, tolerance: u8) -> (usize, u8) {
+		self.pixels.iter().zip(&other.pixels).fold((0, 0), |(count, max), (a, b)| {
+			let difference = (0..4).map(|i| a[i].abs_diff(b[i])).max().unwrap_or(0);
+			((count + (difference > tolerance) as usize), max.max(difference))
+		})
+	}
+
+	/// Image highlighting pixels differing by more than `tolerance` in red,
+	/// with matching pixels dimmed to gray
+	///
+	/// Pixels `other` doesn't have count as differing.
+	pub fn diff(&self, other: &Image, tolerance: u8) -> Image {
+		let pixels = self.pixels.iter().enumerate().map(|(i, a)| match other.pixels.get(i) {
+			Some(b) if (0..4).all(|i| a[i].abs_diff(b[i]) <= tolerance) => {
+				let gray = ((a[0] as u16 + a[1] as u16 + a[2] as u16) / 6) as u8;
+				[gray, gray, gray, 255]
+			},
+			_ => [255, 0, 0, 255],
+		});
+
+		Image {
+			pixels: pixels.collect(),
+			..*self
+		}
+	}
+
+	/// Assert the image matches the golden image at `path`, allowing components
+	/// to differ by `tolerance`
+	///
+	/// Setting `MOCK_GL_UPDATE_GOLDENS` to anything but `0` writes the image as the new golden instead.
+	/// On failure the image and a diff are written next to the golden, as `<name>.actual.<ext>`
+	/// and `<name>.diff.<ext>`.
+	///
+	/// # Panics
+	/// If the images differ or the golden can't be read
+	pub fn assert_matches(&self, path: impl AsRef<Path>, tolerance: u8) {
+		let path = path.as_ref();
+		if env::var(UPDATE_GOLDENS_VAR).is_ok_and(|value| !value.is_empty() && value != "0") {
+			if let Err(e) = self.save(path) {
+				panic!("mock-gl failed to update golden image {}: {e}", path.display());
+			}
+			return
+		}
+
+		let sibling = |kind: &str| {
+			let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+			let ext = path.extension().unwrap_or_default().to_string_lossy();
+			path.with_file_name(format!("{stem}.{kind}.{ext}"))
+		};
+
+		// PPM has no alpha channel, so only colors are compared against it
+		let actual = match path.extension().and_then(|ext| ext.to_str()) {
+			Some("ppm") => Self {
+				pixels: self.pixels.iter().map(|[r, g, b, _]| [*r, *g, *b, 255]).collect(),
+				..self.clone()
+			},
+			_ => self.clone(),
+		};
+
+		let golden = match Self::load(path) {
+			Ok(golden) => golden,
+			Err(e) => {
+				let _ = actual.save(sibling("actual"));
+				panic!(
+					"mock-gl failed to read golden image {}: {e}, set {UPDATE_GOLDENS_VAR}=1 to create it",
+					path.display()
+				);
+			},
+		};
+
+		if (golden.width, golden.height) != (actual.width, actual.height) {
+			let _ = actual.save(sibling("actual"));
+			panic!(
+				"image is {}x{}, but golden image {} is {}x{}",
+				actual.width,
+				actual.height,
+				path.display(),
+				golden.width,
+				golden.height
+			);
+		}
+
+		let (count, max) = actual.compare(&golden, tolerance);
+		if count > 0 {
+			let _ = actual.save(sibling("actual"));
+			let _ = actual.diff(&golden, tolerance).save(sibling("diff"));
+			panic!(
+				"{count} pixels differ from golden image {} by more than {tolerance}, at most by {max}, see {}",
+				path.display(),
+				sibling("diff").display()
+			);
+		}
+	}
+}
+
+/// Attachment of the default framebuffer or a framebuffer object named by `attachment`,
+/// which also accepts the default framebuffer's `GL_BACK`, `GL_DEPTH` and `GL_STENCIL`
+fn image_attachment(attachment: GLenum) -> Option<Attachment> {
+	match attachment {
+		gl::BACK | gl::COLOR => Some(Attachment::Color(0)),
+		gl::DEPTH | gl::DEPTH_ATTACHMENT => Some(Attachment::Depth),
+		gl::STENCIL | gl::STENCIL_ATTACHMENT => Some(Attachment::Stencil),
+		gl::COLOR_ATTACHMENT0..=gl::COLOR_ATTACHMENT31 =>
+			Some(Attachment::Color(attachment - gl::COLOR_ATTACHMENT0)),
+		_ => None,
+	}
+}
+
+impl MockContextRef {
+	/// Contents of an attachment of a framebuffer as an RGBA image, `None` if there is no such image
+	///
+	/// Framebuffer 0 is the default framebuffer, where `GL_FRONT` is the image presented by the
+	/// last swap and `GL_BACK` or `GL_COLOR_ATTACHMENT0` is drawn to. Depth attachments are
+	/// shown as gray levels, see `Image::from_framebuffer`.
+	pub fn framebuffer_image(&self, framebuffer: GLuint, attachment: GLenum) -> Option<Image> {
+		let context = crate::context();
+		let framebuffers = &context.framebuffer_manager;
+
+		let (image, attachment) = match (framebuffer, attachment) {
+			(0, gl::FRONT) => (framebuffers.front_buffer(), Attachment::Color(0)),
+			(0, attachment) => (framebuffers.default_framebuffer(), image_attachment(attachment)?),
+			(framebuffer, attachment) => {
+				let attachment = image_attachment(attachment)?;
+				let renderbuffer = framebuffers.framebuffer(framebuffer)?.attachment(attachment)?;
+				(context.renderbuffer_manager.renderbuffer(renderbuffer)?.image(), attachment)
+			},
+		};
+
+		Some(Image::from_framebuffer(&image.plane(attachment)?))
+	}
+
+	/// Assert the color of the default framebuffer matches the golden image at `path`,
+	/// see `Image::assert_matches`
+	pub fn assert_image_matches(&self, path: impl AsRef<Path>, tolerance: u8) {
+		let image =
+			Image::from_framebuffer(crate::context().framebuffer_manager.default_framebuffer());
+		image.assert_matches(path, tolerance);
+	}
+}
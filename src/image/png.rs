@@ -0,0 +1,156 @@
+//! Uncompressed PNG, storing image data in deflate blocks without compression
+
+use crate::image::{Image, ImageError};
+
+const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+/// Largest payload of a stored deflate block
+const MAX_STORED_BLOCK: usize = 0xffff;
+
+fn crc32(bytes: &[u8]) -> u32 {
+	!bytes.iter().fold(!0u32, |crc, byte| {
+		(0..8).fold(crc ^ *byte as u32, |crc, _| {
+			if crc & 1 == 1 {
+				(crc >> 1) ^ 0xedb8_8320
+			} else {
+				crc >> 1
+			}
+		})
+	})
+}
+
+fn adler32(bytes: &[u8]) -> u32 {
+	let (a, b) = bytes.iter().fold((1u32, 0u32), |(a, b), byte| {
+		let a = (a + *byte as u32) % 65521;
+		(a, (b + a) % 65521)
+	});
+	(b << 16) | a
+}
+
+fn chunk(png: &mut Vec<u8>, ty: &[u8; 4], data: &[u8]) {
+	png.extend((data.len() as u32).to_be_bytes());
+	let start = png.len();
+	png.extend(ty);
+	png.extend(data);
+	let crc = crc32(&png[start..]);
+	png.extend(crc.to_be_bytes());
+}
+
+impl Image {
+	/// PNG of the image, 8 bit RGBA without compression
+	pub fn to_png(&self) -> Vec<u8> {
+		let mut png = SIGNATURE.to_vec();
+
+		let mut header = Vec::new();
+		header.extend((self.width as u32).to_be_bytes());
+		header.extend((self.height as u32).to_be_bytes());
+		// bit depth 8, truecolor with alpha, default compression and filtering, no interlacing
+		header.extend([8, 6, 0, 0, 0]);
+		chunk(&mut png, b"IHDR", &header);
+
+		// every scanline starts with filter type 0, leaving it unfiltered
+		let scanlines = self
+			.pixels
+			.chunks(self.width.max(1))
+			.flat_map(|row| [0].into_iter().chain(row.iter().flatten().copied()))
+			.collect::<Vec<_>>();
+
+		let mut zlib = vec![0x78, 0x01];
+		let blocks = scanlines.chunks(MAX_STORED_BLOCK).collect::<Vec<_>>();
+		for (i, block) in blocks.iter().enumerate() {
+			zlib.push((i + 1 == blocks.len()) as u8);
+			zlib.extend((block.len() as u16).to_le_bytes());
+			zlib.extend((!(block.len() as u16)).to_le_bytes());
+			zlib.extend(*block);
+		}
+		if blocks.is_empty() {
+			zlib.extend([1, 0, 0, 0xff, 0xff]);
+		}
+		zlib.extend(adler32(&scanlines).to_be_bytes());
+		chunk(&mut png, b"IDAT", &zlib);
+
+		chunk(&mut png, b"IEND", &[]);
+		png
+	}
+
+	/// Parse an uncompressed, unfiltered 8 bit RGB or RGBA PNG like those written by `to_png`
+	pub fn from_png(bytes: &[u8]) -> Result<Self, ImageError> {
+		let format = |message: &str| ImageError::Format(format!("PNG {message}"));
+
+		let Some(mut rest) = bytes.strip_prefix(&SIGNATURE) else {
+			return Err(format("signature is missing"))
+		};
+
+		let mut header = None;
+		let mut zlib = Vec::new();
+		while rest.len() >= 12 {
+			let len = u32::from_be_bytes(rest[..4].try_into().unwrap()) as usize;
+			let Some(data) = rest.get(8..8 + len) else {
+				return Err(format("chunk is truncated"))
+			};
+			match &rest[4..8] {
+				b"IHDR" => header = Some(data.to_vec()),
+				b"IDAT" => zlib.extend(data),
+				b"IEND" => break,
+				_ => {},
+			}
+			rest = rest.get(12 + len..).unwrap_or_default();
+		}
+
+		let Some(header) = header.filter(|header| header.len() == 13) else {
+			return Err(format("header is missing"))
+		};
+		let width = u32::from_be_bytes(header[0..4].try_into().unwrap()) as usize;
+		let height = u32::from_be_bytes(header[4..8].try_into().unwrap()) as usize;
+		let channels = match header[8..] {
+			[8, 6, 0, 0, 0] => 4,
+			[8, 2, 0, 0, 0] => 3,
+			_ => return Err(format("must be 8 bit RGB or RGBA without interlacing")),
+		};
+
+		// inflate stored blocks
+		let mut scanlines = Vec::new();
+		let mut data = zlib.get(2..).unwrap_or_default();
+		loop {
+			let Some(&[flags, l0, l1, _, _]) = data.get(..5) else {
+				return Err(format("image data is truncated"))
+			};
+			if flags & 0b110 != 0 {
+				return Err(format("image data must be uncompressed"))
+			}
+			let len = u16::from_le_bytes([l0, l1]) as usize;
+			let Some(block) = data.get(5..5 + len) else {
+				return Err(format("image data is truncated"))
+			};
+			scanlines.extend(block);
+			data = &data[5 + len..];
+
+			if flags & 1 == 1 {
+				break
+			}
+		}
+
+		let size = width
+			.checked_mul(channels)
+			.map(|row| row + 1)
+			.and_then(|stride| Some((stride, stride.checked_mul(height)?)));
+		let Some((stride, len)) = size else {
+			return Err(format("size is too large"))
+		};
+		if scanlines.len() < len {
+			return Err(format("image data is truncated"))
+		}
+
+		let mut pixels = Vec::with_capacity(width * height);
+		for row in scanlines.chunks_exact(stride).take(height) {
+			if row[0] != 0 {
+				return Err(format("scanlines must be unfiltered"))
+			}
+			pixels.extend(row[1..].chunks_exact(channels).map(|pixel| match pixel {
+				[r, g, b] => [*r, *g, *b, 255],
+				_ => [pixel[0], pixel[1], pixel[2], pixel[3]],
+			}));
+		}
+
+		Self::new(width, height, pixels)
+	}
+}
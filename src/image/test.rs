@@ -0,0 +1,139 @@
+use std::{env, fs, panic, path::PathBuf};
+
+use crate::{
+	image::{Image, ImageError, UPDATE_GOLDENS_VAR},
+	surface::Surface,
+	test::{gl33_core, test_harness_surface},
+};
+
+/// Empty directory for the files written by a test
+fn output_dir(name: &str) -> PathBuf {
+	let dir = env::temp_dir().join(format!("mock-gl-{}-{name}", std::process::id()));
+	let _ = fs::remove_dir_all(&dir);
+	fs::create_dir_all(&dir).unwrap();
+	dir
+}
+
+#[test]
+fn framebuffer_images() {
+	test_harness_surface(gl33_core(), Surface::new(2, 2), |ctx| unsafe {
+		gl::Enable(gl::SCISSOR_TEST);
+		gl::Scissor(0, 1, 1, 1);
+		gl::ClearColor(1.0, 0.0, 0.0, 0.5);
+		gl::ClearDepth(0.0);
+		gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
+		gl::Disable(gl::SCISSOR_TEST);
+
+		// the top row of the image is the top row of the framebuffer
+		let image = ctx.framebuffer_image(0, gl::BACK).unwrap();
+		assert_eq!(image.pixel(0, 0), Some([255, 0, 0, 128]));
+		assert_eq!(image.pixel(0, 1), Some([0, 0, 0, 0]));
+
+		let depth = ctx.framebuffer_image(0, gl::DEPTH).unwrap();
+		assert_eq!(depth.pixels(), [[0, 0, 0, 255], [255; 4], [255; 4], [255; 4]]);
+
+		assert_eq!(ctx.framebuffer_image(0, gl::FRONT).unwrap().pixel(0, 0), Some([0; 4]));
+		assert_eq!(ctx.framebuffer_image(0, gl::COLOR_ATTACHMENT1), None);
+
+		// framebuffer objects give their attachments' images
+		let mut framebuffer = 0;
+		let mut renderbuffer = 0;
+		gl::GenFramebuffers(1, &mut framebuffer);
+		gl::BindFramebuffer(gl::FRAMEBUFFER, framebuffer);
+		gl::GenRenderbuffers(1, &mut renderbuffer);
+		gl::BindRenderbuffer(gl::RENDERBUFFER, renderbuffer);
+		gl::RenderbufferStorage(gl::RENDERBUFFER, gl::RGBA8, 3, 1);
+		gl::FramebufferRenderbuffer(
+			gl::FRAMEBUFFER,
+			gl::COLOR_ATTACHMENT0,
+			gl::RENDERBUFFER,
+			renderbuffer,
+		);
+		gl::ClearColor(0.0, 0.0, 1.0, 1.0);
+		gl::Clear(gl::COLOR_BUFFER_BIT);
+
+		let image = ctx.framebuffer_image(framebuffer, gl::COLOR_ATTACHMENT0).unwrap();
+		assert_eq!((image.width(), image.height()), (3, 1));
+		assert_eq!(image.pixel(2, 0), Some([0, 0, 255, 255]));
+		assert_eq!(ctx.framebuffer_image(framebuffer, gl::DEPTH_ATTACHMENT), None);
+
+		gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+		gl::DeleteFramebuffers(1, &framebuffer);
+		gl::DeleteRenderbuffers(1, &renderbuffer);
+	})
+}
+
+#[test]
+fn ppm() {
+	let image = Image::new(2, 1, vec![[1, 2, 3, 4], [5, 6, 7, 8]]).unwrap();
+	let ppm = image.to_ppm();
+	assert_eq!(ppm, b"P6\n2 1\n255\n\x01\x02\x03\x05\x06\x07");
+
+	assert_eq!(
+		Image::from_ppm(&ppm).unwrap(),
+		Image::new(2, 1, vec![[1, 2, 3, 255], [5, 6, 7, 255]]).unwrap()
+	);
+	let commented = b"P6 # a comment\n2\t1 255\n\x01\x02\x03\x05\x06\x07";
+	assert_eq!(Image::from_ppm(commented).unwrap().pixel(1, 0), Some([5, 6, 7, 255]));
+
+	assert!(Image::from_ppm(b"P3\n1 1\n255\n0 0 0").is_err());
+	assert!(Image::from_ppm(b"P6\n2 2\n255\n\x00").is_err());
+	let huge = format!("P6\n{} 2\n255\n", usize::MAX / 4);
+	assert!(matches!(Image::from_ppm(huge.as_bytes()), Err(ImageError::Format(_))));
+}
+
+#[test]
+fn pixel_count() {
+	assert!(matches!(
+		Image::new(2, 2, vec![[0; 4]; 3]),
+		Err(ImageError::PixelCount { pixels: 3, .. })
+	));
+	assert!(Image::new(usize::MAX, 2, Vec::new()).is_err());
+}
+
+#[cfg(feature = "png")]
+#[test]
+fn png() {
+	let image = Image::new(2, 2, vec![[1, 2, 3, 4], [5, 6, 7, 8], [9, 10, 11, 12], [
+		13, 14, 15, 16,
+	]])
+	.unwrap();
+	let png = image.to_png();
+	assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+	assert_eq!(Image::from_png(&png).unwrap(), image);
+
+	// large images span several deflate blocks
+	let large = Image::new(200, 100, vec![[7; 4]; 200 * 100]).unwrap();
+	assert_eq!(Image::from_png(&large.to_png()).unwrap(), large);
+
+	assert!(Image::from_png(b"not a png").is_err());
+}
+
+#[test]
+fn golden_images() {
+	let dir = output_dir("goldens");
+	let golden = dir.join("golden.ppm");
+	let image = Image::new(2, 1, vec![[10, 20, 30, 0], [40, 50, 60, 0]]).unwrap();
+
+	// a missing golden fails, unless goldens are being updated
+	assert!(panic::catch_unwind(|| image.assert_matches(&golden, 0)).is_err());
+	assert!(dir.join("golden.actual.ppm").exists());
+
+	env::set_var(UPDATE_GOLDENS_VAR, "1");
+	image.assert_matches(&golden, 0);
+	env::remove_var(UPDATE_GOLDENS_VAR);
+	// alpha is not stored in PPM goldens
+	image.assert_matches(&golden, 0);
+
+	// components may differ by the tolerance
+	let close = Image::new(2, 1, vec![[12, 20, 30, 0], [40, 51, 60, 0]]).unwrap();
+	close.assert_matches(&golden, 2);
+	assert!(panic::catch_unwind(|| close.assert_matches(&golden, 1)).is_err());
+
+	let changed = Image::new(2, 1, vec![[10, 20, 30, 0], [40, 80, 60, 0]]).unwrap();
+	assert!(panic::catch_unwind(|| changed.assert_matches(&golden, 2)).is_err());
+	let diff = Image::load(dir.join("golden.diff.ppm")).unwrap();
+	assert_eq!(diff.pixels(), [[10, 10, 10, 255], [255, 0, 0, 255]]);
+
+	let _ = fs::remove_dir_all(dir);
+}
@@ -8,14 +8,30 @@ use std::{
 };
 
 pub mod buffer;
+pub mod draw;
+pub mod format;
+pub mod framebuffer;
 pub mod function_mapping;
+pub mod image;
+pub mod limits;
 pub mod log;
+pub mod pixel;
+pub mod preset;
+pub mod query;
+pub mod raster;
+pub mod renderbuffer;
+pub mod state;
+pub mod surface;
 pub mod version;
+pub mod vertex_array;
 
 #[cfg(test)]
 mod test;
 
 use gl::types::GLenum;
+use limits::Limits;
+use preset::Preset;
+use surface::Surface;
 use version::GlVersion;
 
 use self::log::*;
@@ -39,6 +55,28 @@ pub enum ErrorHandling {
 }
 
 pub fn new(version: GlVersion, error_handling: ErrorHandling) -> MockContextRef {
+	new_with_surface(version, error_handling, Surface::default())
+}
+
+/// Create a context whose default framebuffer is described by `surface`
+pub fn new_with_surface(
+	version: GlVersion,
+	error_handling: ErrorHandling,
+	surface: Surface,
+) -> MockContextRef {
+	let limits = Limits::new(&version);
+	new_with_limits(version, error_handling, surface, limits)
+}
+
+/// Create a context reporting and enforcing `limits` instead of the minimums of `version`
+///
+/// `GL_MAX_VIEWPORT_DIMS` is raised to the size of `surface` if it is smaller.
+pub fn new_with_limits(
+	version: GlVersion,
+	error_handling: ErrorHandling,
+	surface: Surface,
+	mut limits: Limits,
+) -> MockContextRef {
 	if INSTANCE.lock().unwrap_or_else(|p| p.into_inner()).is_some() {
 		panic!("Only once MockContext can exist at a time");
 	}
@@ -49,15 +87,57 @@ pub fn new(version: GlVersion, error_handling: ErrorHandling) -> MockContextRef
 		error_handling,
 	});
 
+	let mut state_manager = state::StateManager::new();
+	if surface.width != 0 || surface.height != 0 {
+		state_manager.init_surface_size(surface.width, surface.height);
+	}
+
+	let strings = query::Strings::new(&version);
+	limits.cover_surface(surface.width, surface.height);
+	limits.clamp_to_capacity();
+
 	*INSTANCE.lock().unwrap_or_else(|p| p.into_inner()) = Some(MockContextData {
 		gl_version: version,
 		error: gl::NO_ERROR,
 		buffer_manager: buffer::BufferManager::new(),
+		vertex_array_manager: vertex_array::VertexArrayManager::new(),
+		draw_manager: draw::DrawManager::default(),
+		state_manager,
+		framebuffer_manager: framebuffer::FramebufferManager::with_surface(surface),
+		renderbuffer_manager: renderbuffer::RenderbufferManager::new(),
+		rasterizer: raster::Rasterizer::new(),
+		frame_manager: surface::FrameManager::default(),
+		strings,
+		limits,
 	});
 
 	MockContextRef(PhantomData)
 }
 
+/// Create a context reporting the version, extensions, limits and strings of `preset`
+pub fn new_with_preset(
+	preset: Preset,
+	error_handling: ErrorHandling,
+	surface: Surface,
+) -> MockContextRef {
+	let context = new_with_limits(preset.version, error_handling, surface, preset.limits);
+	context.set_vendor(&preset.vendor);
+	context.set_renderer(&preset.renderer);
+
+	let strings = &mut crate::context().strings;
+	if let Some(version) = &preset.version_string {
+		strings.set_version(version);
+	}
+	if let Some(version) = &preset.shading_language_version {
+		strings.set_shading_language_version(version);
+	}
+	if let Some(extensions) = &preset.extensions {
+		strings.set_extensions(extensions);
+	}
+
+	context
+}
+
 /// Mock OpenGL context
 struct MockContextMetadata {
 	thread: ThreadId,
@@ -69,6 +149,15 @@ struct MockContextData {
 	gl_version: GlVersion,
 	error: GLenum,
 	buffer_manager: buffer::BufferManager,
+	vertex_array_manager: vertex_array::VertexArrayManager,
+	draw_manager: draw::DrawManager,
+	state_manager: state::StateManager,
+	framebuffer_manager: framebuffer::FramebufferManager,
+	renderbuffer_manager: renderbuffer::RenderbufferManager,
+	rasterizer: raster::Rasterizer,
+	frame_manager: surface::FrameManager,
+	strings: query::Strings,
+	limits: Limits,
 }
 
 pub struct MockContextRef(PhantomData<()>);
@@ -80,8 +169,20 @@ impl MockContextRef {
 			gl_version: _,
 			error: _,
 			buffer_manager,
+			vertex_array_manager,
+			draw_manager: _,
+			state_manager: _,
+			framebuffer_manager,
+			renderbuffer_manager,
+			rasterizer: _,
+			frame_manager: _,
+			strings: _,
+			limits: _,
 		} = INSTANCE.lock().unwrap_or_else(|p| p.into_inner()).take().unwrap();
 		buffer_manager.finalize();
+		vertex_array_manager.finalize();
+		framebuffer_manager.finalize();
+		renderbuffer_manager.finalize();
 
 		let should_panic = {
 			let m = meta();
@@ -0,0 +1,161 @@
+use gl::types::{GLint64, GLsizei, GLuint};
+
+use crate::{
+	debug,
+	state::{MAX_DRAW_BUFFERS, MAX_VIEWPORTS},
+	version::{at_least, ext, GlVersion, VersionType},
+	vertex_array::{MAX_VERTEX_ATTRIBS, MAX_VERTEX_ATTRIB_BINDINGS},
+	MockContextRef,
+};
+
+#[cfg(test)]
+mod test;
+
+/// Implementation dependent limits, reported by the `GL_MAX_*` queries and enforced by every
+/// entry point taking an index, size or count they bound
+///
+/// mock-gl has no textures or programs, so the texture, uniform and work group size limits are
+/// only reported.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Limits {
+	pub max_texture_size: GLsizei,
+	pub max_renderbuffer_size: GLsizei,
+	pub max_viewport_dims: [GLsizei; 2],
+	pub max_viewports: GLuint,
+	pub max_samples: GLsizei,
+	pub max_color_attachments: GLuint,
+	pub max_draw_buffers: GLuint,
+	pub max_vertex_attribs: GLuint,
+	pub max_vertex_attrib_bindings: GLuint,
+	pub max_vertex_attrib_relative_offset: GLuint,
+	pub max_vertex_attrib_stride: GLsizei,
+	pub max_vertex_uniform_vectors: GLuint,
+	pub max_fragment_uniform_vectors: GLuint,
+	pub max_texture_image_units: GLuint,
+	pub max_combined_texture_image_units: GLuint,
+	pub max_uniform_block_size: GLint64,
+	pub max_compute_work_group_count: [GLuint; 3],
+	pub max_compute_work_group_size: [GLuint; 3],
+	pub max_compute_work_group_invocations: GLuint,
+}
+
+impl Limits {
+	/// Smallest limits an implementation of `gl_version` may report
+	pub fn new(gl_version: &GlVersion) -> Self {
+		let es = matches!(gl_version.ty, VersionType::ES);
+		let es2 = es && !at_least!(gl_version, es: 3 . 0);
+
+		let max_texture_size = match gl_version.ty {
+			VersionType::GL if at_least!(gl_version, gl: 4 . 0) => 16384,
+			VersionType::GL if at_least!(gl_version, gl: 3 . 0) => 1024,
+			VersionType::ES if at_least!(gl_version, es: 3 . 0) => 2048,
+			_ => 64,
+		};
+
+		let max_combined_texture_image_units = match gl_version.ty {
+			VersionType::GL if at_least!(gl_version, gl: 4 . 3) => 96,
+			VersionType::GL if at_least!(gl_version, gl: 4 . 0) => 80,
+			VersionType::GL if at_least!(gl_version, gl: 3 . 2) => 48,
+			VersionType::GL if at_least!(gl_version, gl: 3 . 0) => 16,
+			VersionType::GL => 2,
+			VersionType::ES if at_least!(gl_version, es: 3 . 2) => 96,
+			VersionType::ES if at_least!(gl_version, es: 3 . 1) => 48,
+			VersionType::ES if at_least!(gl_version, es: 3 . 0) => 32,
+			VersionType::ES => 8,
+		};
+
+		let (max_color_attachments, max_draw_buffers) = match gl_version.ty {
+			VersionType::GL => (8, 8),
+			VersionType::ES if es2 => (1, 1),
+			VersionType::ES => (4, 4),
+		};
+
+		// vectors of four components, the OpenGL limits are on components
+		let (max_vertex_uniform_vectors, max_fragment_uniform_vectors) = match gl_version.ty {
+			VersionType::GL if at_least!(gl_version, gl: 3 . 0) => (256, 256),
+			VersionType::GL => (128, 16),
+			VersionType::ES if es2 => (128, 16),
+			VersionType::ES => (256, 224),
+		};
+
+		let viewport_array = gl_version.has_extension(&ext::ARB_viewport_array)
+			|| gl_version.has_extension(&ext::OES_viewport_array);
+
+		Self {
+			max_texture_size,
+			// OpenGL ES 2.0 only requires renderbuffers of a single pixel, which no driver reports
+			max_renderbuffer_size: max_texture_size,
+			max_viewport_dims: [max_texture_size; 2],
+			max_viewports: if viewport_array {
+				MAX_VIEWPORTS as GLuint
+			} else {
+				1
+			},
+			max_samples: 4,
+			max_color_attachments,
+			max_draw_buffers,
+			max_vertex_attribs: if es2 { 8 } else { 16 },
+			max_vertex_attrib_bindings: 16,
+			max_vertex_attrib_relative_offset: 2047,
+			max_vertex_attrib_stride: 2048,
+			max_vertex_uniform_vectors,
+			max_fragment_uniform_vectors,
+			max_texture_image_units: match gl_version.ty {
+				VersionType::GL if !at_least!(gl_version, gl: 3 . 0) => 2,
+				_ if es2 => 8,
+				_ => 16,
+			},
+			max_combined_texture_image_units,
+			max_uniform_block_size: 16384,
+			max_compute_work_group_count: [65535; 3],
+			max_compute_work_group_size: if es { [128, 128, 64] } else { [1024, 1024, 64] },
+			max_compute_work_group_invocations: if es { 128 } else { 1024 },
+		}
+	}
+
+	/// Range viewport origins are clamped to, `GL_VIEWPORT_BOUNDS_RANGE`
+	pub fn viewport_bounds(&self) -> [f32; 2] {
+		let bound = 2.0 * self.max_viewport_dims[0].max(self.max_viewport_dims[1]) as f32;
+		[-bound, bound - 1.0]
+	}
+
+	/// Raise `GL_MAX_VIEWPORT_DIMS` to a surface of `width` by `height`, which the viewport
+	/// must be able to cover
+	pub(crate) fn cover_surface(&mut self, width: GLsizei, height: GLsizei) {
+		self.max_viewport_dims[0] = self.max_viewport_dims[0].max(width);
+		self.max_viewport_dims[1] = self.max_viewport_dims[1].max(height);
+	}
+
+	/// Lower the limits sizing state arrays to what mock-gl can store
+	pub(crate) fn clamp_to_capacity(&mut self) {
+		let clamp = |name: &str, value: &mut GLuint, capacity: GLuint| {
+			if *value > capacity {
+				debug!("clamped {} from {} to {}", name, value, capacity);
+				*value = capacity;
+			}
+		};
+
+		clamp("GL_MAX_VIEWPORTS", &mut self.max_viewports, MAX_VIEWPORTS as GLuint);
+		clamp("GL_MAX_DRAW_BUFFERS", &mut self.max_draw_buffers, MAX_DRAW_BUFFERS as GLuint);
+		clamp("GL_MAX_COLOR_ATTACHMENTS", &mut self.max_color_attachments, 32);
+		clamp("GL_MAX_VERTEX_ATTRIBS", &mut self.max_vertex_attribs, MAX_VERTEX_ATTRIBS);
+		clamp(
+			"GL_MAX_VERTEX_ATTRIB_BINDINGS",
+			&mut self.max_vertex_attrib_bindings,
+			MAX_VERTEX_ATTRIB_BINDINGS,
+		);
+	}
+}
+
+impl MockContextRef {
+	pub fn limits(&self) -> Limits {
+		crate::context().limits.clone()
+	}
+
+	/// Replace every limit, lowering those above what mock-gl can store
+	pub fn set_limits(&self, mut limits: Limits) {
+		limits.clamp_to_capacity();
+		debug!("set limits to {:?}", limits);
+		crate::context().limits = limits;
+	}
+}
@@ -0,0 +1,162 @@
+use gl::types::{GLint64, GLuint};
+
+use crate::{
+	limits::Limits,
+	surface::Surface,
+	test::{get_integer, gl45_core, test_harness_context, test_harness_limits, test_harness_with},
+	version::VersionType,
+	ErrorHandling,
+	GlVersion,
+};
+
+#[test]
+fn spec_minimums() {
+	let es2 = Limits::new(&GlVersion::from_version(VersionType::ES, 2, 0));
+	assert_eq!(es2.max_texture_size, 64);
+	assert_eq!(es2.max_vertex_attribs, 8);
+	assert_eq!(es2.max_color_attachments, 1);
+	assert_eq!(es2.max_draw_buffers, 1);
+	assert_eq!(es2.max_combined_texture_image_units, 8);
+	assert_eq!(es2.max_viewports, 1);
+
+	let es31 = Limits::new(&GlVersion::from_version(VersionType::ES, 3, 1));
+	assert_eq!(es31.max_texture_size, 2048);
+	assert_eq!(es31.max_draw_buffers, 4);
+	assert_eq!(es31.max_combined_texture_image_units, 48);
+	assert_eq!(es31.max_compute_work_group_size, [128, 128, 64]);
+	assert_eq!(es31.max_compute_work_group_invocations, 128);
+
+	let gl45 = Limits::new(&gl45_core());
+	assert_eq!(gl45.max_texture_size, 16384);
+	assert_eq!(gl45.max_viewport_dims, [16384, 16384]);
+	assert_eq!(gl45.max_viewports, 16);
+	assert_eq!(gl45.max_combined_texture_image_units, 96);
+	assert_eq!(gl45.max_uniform_block_size, 16384);
+	assert_eq!(gl45.max_compute_work_group_count, [65535; 3]);
+}
+
+#[test]
+fn queries() {
+	test_harness_context(gl45_core(), |ctx| unsafe {
+		assert_eq!(get_integer(gl::MAX_TEXTURE_SIZE), 16384);
+		assert_eq!(get_integer(gl::MAX_COLOR_ATTACHMENTS), 8);
+
+		ctx.set_limits(Limits {
+			max_texture_size: 4096,
+			max_vertex_attribs: 12,
+			max_uniform_block_size: 1 << 32,
+			max_compute_work_group_size: [256, 128, 32],
+			..ctx.limits()
+		});
+		assert_eq!(get_integer(gl::MAX_TEXTURE_SIZE), 4096);
+		assert_eq!(get_integer(gl::MAX_VERTEX_ATTRIBS), 12);
+		assert_eq!(get_integer(gl::MAX_COMBINED_TEXTURE_IMAGE_UNITS), 96);
+		assert_eq!(get_integer(gl::MAX_COMPUTE_WORK_GROUP_INVOCATIONS), 1024);
+
+		let mut block_size: GLint64 = 0;
+		gl::GetInteger64v(gl::MAX_UNIFORM_BLOCK_SIZE, &mut block_size);
+		assert_eq!(block_size, 1 << 32);
+
+		let mut size = [0; 3];
+		for (i, size) in size.iter_mut().enumerate() {
+			gl::GetIntegeri_v(gl::MAX_COMPUTE_WORK_GROUP_SIZE, i as GLuint, size);
+		}
+		assert_eq!(size, [256, 128, 32]);
+
+		// limits above what vertex arrays store are lowered
+		ctx.set_limits(Limits {
+			max_vertex_attribs: 64,
+			..ctx.limits()
+		});
+		assert_eq!(get_integer(gl::MAX_VERTEX_ATTRIBS), 32);
+	});
+}
+
+#[test]
+fn surface_beyond_limits() {
+	let limits = Limits {
+		max_viewport_dims: [32, 32],
+		..Limits::new(&gl45_core())
+	};
+	let error_handling = ErrorHandling::PanicEarly { warn: true };
+	let new = || crate::new_with_limits(gl45_core(), error_handling, Surface::new(100, 50), limits);
+
+	// the default viewport covers the surface
+	test_harness_with(new, |ctx| unsafe {
+		let mut viewport = [0; 4];
+		gl::GetIntegerv(gl::VIEWPORT, viewport.as_mut_ptr());
+		assert_eq!(viewport, [0, 0, 100, 50]);
+		assert_eq!(ctx.limits().max_viewport_dims, [100, 50]);
+
+		gl::Viewport(0, 0, 200, 200);
+		gl::GetIntegerv(gl::VIEWPORT, viewport.as_mut_ptr());
+		assert_eq!(viewport, [0, 0, 100, 50]);
+	});
+}
+
+#[test]
+fn enforcement() {
+	let limits = Limits {
+		max_renderbuffer_size: 16,
+		max_viewport_dims: [32, 32],
+		max_viewports: 2,
+		max_samples: 2,
+		max_color_attachments: 2,
+		max_draw_buffers: 2,
+		max_vertex_attribs: 4,
+		max_compute_work_group_count: [8, 8, 1],
+		..Limits::new(&gl45_core())
+	};
+
+	test_harness_limits(gl45_core(), limits, ErrorHandling::DoNotPanic, || unsafe {
+		let mut array = 0;
+		gl::GenVertexArrays(1, &mut array);
+		gl::BindVertexArray(array);
+		gl::EnableVertexAttribArray(3);
+		assert_eq!(gl::GetError(), gl::NO_ERROR);
+		gl::EnableVertexAttribArray(4);
+		assert_eq!(gl::GetError(), gl::INVALID_VALUE);
+
+		let mut renderbuffer = 0;
+		gl::GenRenderbuffers(1, &mut renderbuffer);
+		gl::BindRenderbuffer(gl::RENDERBUFFER, renderbuffer);
+		gl::RenderbufferStorage(gl::RENDERBUFFER, gl::RGBA8, 16, 17);
+		assert_eq!(gl::GetError(), gl::INVALID_VALUE);
+		gl::RenderbufferStorageMultisample(gl::RENDERBUFFER, 4, gl::RGBA8, 16, 16);
+		assert_eq!(gl::GetError(), gl::INVALID_VALUE);
+		gl::RenderbufferStorageMultisample(gl::RENDERBUFFER, 2, gl::RGBA8, 16, 16);
+		assert_eq!(gl::GetError(), gl::NO_ERROR);
+
+		let mut framebuffer = 0;
+		gl::GenFramebuffers(1, &mut framebuffer);
+		gl::BindFramebuffer(gl::FRAMEBUFFER, framebuffer);
+		gl::FramebufferRenderbuffer(
+			gl::FRAMEBUFFER,
+			gl::COLOR_ATTACHMENT2,
+			gl::RENDERBUFFER,
+			renderbuffer,
+		);
+		assert_eq!(gl::GetError(), gl::INVALID_OPERATION);
+		gl::ClearBufferfv(gl::COLOR, 2, [0.0; 4].as_ptr());
+		assert_eq!(gl::GetError(), gl::INVALID_VALUE);
+		gl::ColorMaski(2, gl::TRUE, gl::TRUE, gl::TRUE, gl::TRUE);
+		assert_eq!(gl::GetError(), gl::INVALID_VALUE);
+
+		gl::ViewportIndexedf(2, 0.0, 0.0, 1.0, 1.0);
+		assert_eq!(gl::GetError(), gl::INVALID_VALUE);
+		gl::Viewport(0, 0, 100, 100);
+		let mut viewport = [0; 4];
+		gl::GetIntegerv(gl::VIEWPORT, viewport.as_mut_ptr());
+		assert_eq!(viewport, [0, 0, 32, 32]);
+
+		gl::DispatchCompute(8, 8, 2);
+		assert_eq!(gl::GetError(), gl::INVALID_VALUE);
+		let mut count = 0;
+		gl::GetIntegeri_v(gl::MAX_COMPUTE_WORK_GROUP_COUNT, 3, &mut count);
+		assert_eq!(gl::GetError(), gl::INVALID_VALUE);
+
+		gl::DeleteFramebuffers(1, &framebuffer);
+		gl::DeleteRenderbuffers(1, &renderbuffer);
+		gl::DeleteVertexArrays(1, &array);
+	});
+}
@@ -0,0 +1,358 @@
+use gl::types::{GLenum, GLint};
+
+use crate::{debug, error, version::gl_enum, GlVersion};
+
+#[cfg(test)]
+mod test;
+
+gl_enum! {
+	PixelFormat {
+		RED(gl: 2 . 1, es: 3 . 0);
+		GREEN(gl: 2 . 1);
+		BLUE(gl: 2 . 1);
+		ALPHA(gl: 2 . 1, es: 2 . 0);
+		RG(gl: 3 . 0, es: 3 . 0);
+		RGB(gl: 2 . 1, es: 2 . 0);
+		RGBA(gl: 2 . 1, es: 2 . 0);
+		BGR(gl: 2 . 1);
+		BGRA(gl: 2 . 1);
+		RED_INTEGER(gl: 3 . 0, es: 3 . 0);
+		RG_INTEGER(gl: 3 . 0, es: 3 . 0);
+		RGB_INTEGER(gl: 3 . 0, es: 3 . 0);
+		RGBA_INTEGER(gl: 3 . 0, es: 3 . 0);
+		BGRA_INTEGER(gl: 3 . 0);
+		DEPTH_COMPONENT(gl: 2 . 1);
+		STENCIL_INDEX(gl: 2 . 1);
+		DEPTH_STENCIL(gl: 3 . 0);
+	}
+}
+
+impl PixelFormat {
+	/// Indices into (r, g, b, a) of the components a pixel holds, in memory order
+	///
+	/// Depth and stencil formats hold one component each, at index 0.
+	pub fn components(&self) -> &'static [usize] {
+		match self {
+			Self::RED | Self::RED_INTEGER | Self::DEPTH_COMPONENT | Self::STENCIL_INDEX => &[0],
+			Self::GREEN => &[1],
+			Self::BLUE => &[2],
+			Self::ALPHA => &[3],
+			Self::RG | Self::RG_INTEGER => &[0, 1],
+			Self::RGB | Self::RGB_INTEGER => &[0, 1, 2],
+			Self::RGBA | Self::RGBA_INTEGER => &[0, 1, 2, 3],
+			Self::BGR => &[2, 1, 0],
+			Self::BGRA | Self::BGRA_INTEGER => &[2, 1, 0, 3],
+			Self::DEPTH_STENCIL => &[0, 1],
+		}
+	}
+
+	pub fn is_integer(&self) -> bool {
+		matches!(
+			self,
+			Self::RED_INTEGER
+				| Self::RG_INTEGER
+				| Self::RGB_INTEGER
+				| Self::RGBA_INTEGER
+				| Self::BGRA_INTEGER
+		)
+	}
+
+	pub fn is_color(&self) -> bool {
+		!matches!(self, Self::DEPTH_COMPONENT | Self::STENCIL_INDEX | Self::DEPTH_STENCIL)
+	}
+}
+
+gl_enum! {
+	PixelType {
+		UNSIGNED_BYTE(gl: 2 . 1, es: 2 . 0);
+		BYTE(gl: 2 . 1, es: 3 . 0);
+		UNSIGNED_SHORT(gl: 2 . 1, es: 3 . 0);
+		SHORT(gl: 2 . 1, es: 3 . 0);
+		UNSIGNED_INT(gl: 2 . 1, es: 3 . 0);
+		INT(gl: 2 . 1, es: 3 . 0);
+		HALF_FLOAT(gl: 3 . 0, es: 3 . 0);
+		FLOAT(gl: 2 . 1, es: 3 . 0);
+		UNSIGNED_SHORT_5_6_5(gl: 2 . 1, es: 2 . 0);
+		UNSIGNED_SHORT_4_4_4_4(gl: 2 . 1, es: 2 . 0);
+		UNSIGNED_SHORT_5_5_5_1(gl: 2 . 1, es: 2 . 0);
+		UNSIGNED_INT_2_10_10_10_REV(gl: 2 . 1, es: 3 . 0);
+		UNSIGNED_INT_24_8(gl: 3 . 0, es: 3 . 0);
+		FLOAT_32_UNSIGNED_INT_24_8_REV(gl: 3 . 0, es: 3 . 0);
+	}
+}
+
+impl PixelType {
+	/// Bytes of one element, or of a whole pixel for packed types
+	pub fn size(&self) -> usize {
+		match self {
+			Self::UNSIGNED_BYTE | Self::BYTE => 1,
+			Self::UNSIGNED_SHORT | Self::SHORT | Self::HALF_FLOAT => 2,
+			Self::UNSIGNED_SHORT_5_6_5
+			| Self::UNSIGNED_SHORT_4_4_4_4
+			| Self::UNSIGNED_SHORT_5_5_5_1 => 2,
+			Self::UNSIGNED_INT | Self::INT | Self::FLOAT => 4,
+			Self::UNSIGNED_INT_2_10_10_10_REV | Self::UNSIGNED_INT_24_8 => 4,
+			Self::FLOAT_32_UNSIGNED_INT_24_8_REV => 8,
+		}
+	}
+
+	/// Bits of each component of packed types, most significant first
+	pub fn packed_bits(&self) -> Option<&'static [u32]> {
+		match self {
+			Self::UNSIGNED_SHORT_5_6_5 => Some(&[5, 6, 5]),
+			Self::UNSIGNED_SHORT_4_4_4_4 => Some(&[4, 4, 4, 4]),
+			Self::UNSIGNED_SHORT_5_5_5_1 => Some(&[5, 5, 5, 1]),
+			// reversed, the first component is in the least significant bits
+			Self::UNSIGNED_INT_2_10_10_10_REV => Some(&[2, 10, 10, 10]),
+			Self::UNSIGNED_INT_24_8 => Some(&[24, 8]),
+			Self::FLOAT_32_UNSIGNED_INT_24_8_REV => Some(&[32, 32]),
+			_ => None,
+		}
+	}
+
+	pub fn is_float(&self) -> bool {
+		matches!(self, Self::FLOAT | Self::HALF_FLOAT)
+	}
+}
+
+/// Bytes of one pixel of `format` and `ty`
+pub fn group_size(format: PixelFormat, ty: PixelType) -> usize {
+	match ty.packed_bits() {
+		Some(_) => ty.size(),
+		None => format.components().len() * ty.size(),
+	}
+}
+
+/// Convert a float to the bits of a half float, rounding to nearest even
+pub fn f32_to_f16(value: f32) -> u16 {
+	let bits = value.to_bits();
+	let sign = ((bits >> 16) & 0x8000) as u16;
+	let exponent = ((bits >> 23) & 0xff) as i32;
+	let mantissa = bits & 0x7f_ffff;
+
+	if exponent == 0xff {
+		// infinity or NaN
+		return sign | 0x7c00 | if mantissa != 0 { 0x200 } else { 0 }
+	}
+
+	let exponent = exponent - 127 + 15;
+	if exponent >= 0x1f {
+		return sign | 0x7c00
+	}
+
+	if exponent <= 0 {
+		// subnormal or zero
+		if exponent < -10 {
+			return sign
+		}
+		let mantissa = mantissa | 0x80_0000;
+		let shift = (14 - exponent) as u32;
+		let half = mantissa >> shift;
+		let rest = mantissa & ((1 << shift) - 1);
+		let halfway = 1 << (shift - 1);
+		let round = rest > halfway || (rest == halfway && half & 1 == 1);
+		return sign | (half + round as u32) as u16
+	}
+
+	let half = ((exponent as u32) << 10) | (mantissa >> 13);
+	let rest = mantissa & 0x1fff;
+	let round = rest > 0x1000 || (rest == 0x1000 && half & 1 == 1);
+	sign | (half + round as u32) as u16
+}
+
+/// Convert a normalized value to an unsigned integer of `bits`
+fn unorm(value: f32, bits: u32) -> u64 {
+	let max = ((1u64 << bits) - 1) as f64;
+	(value.clamp(0.0, 1.0) as f64 * max).round() as u64
+}
+
+/// Convert a normalized value to a signed integer of `bits`
+fn snorm(value: f32, bits: u32) -> i64 {
+	let max = ((1i64 << (bits - 1)) - 1) as f64;
+	(value.clamp(-1.0, 1.0) as f64 * max).round() as i64
+}
+
+/// Encode one pixel into memory of `format` and `ty`
+///
+/// `value` holds the color as read, or depth and stencil in its first two components.
+/// Integer formats and stencil indices store values as they are, other formats
+/// convert to normalized integers unless `ty` is a float type.
+pub fn encode(format: PixelFormat, ty: PixelType, value: [f32; 4], out: &mut Vec<u8>) {
+	let components = format.components().iter().map(|i| value[*i]).collect::<Vec<_>>();
+	let raw = format.is_integer() || format == PixelFormat::STENCIL_INDEX;
+
+	match (ty, ty.packed_bits()) {
+		(PixelType::UNSIGNED_INT_24_8, _) => {
+			let packed = (unorm(components[0], 24) << 8) as u32 | (components[1] as u32 & 0xff);
+			out.extend(packed.to_ne_bytes());
+		},
+		(PixelType::FLOAT_32_UNSIGNED_INT_24_8_REV, _) => {
+			out.extend(components[0].to_ne_bytes());
+			out.extend((components[1] as u32 & 0xff).to_ne_bytes());
+		},
+		(PixelType::UNSIGNED_INT_2_10_10_10_REV, Some(bits)) => {
+			// the first component ends up in the least significant bits
+			let packed =
+				components.iter().zip(bits.iter().rev()).rev().fold(0u64, |acc, (c, bits)| {
+					let c = if raw {
+						(c.max(0.0) as u64).min((1 << bits) - 1)
+					} else {
+						unorm(*c, *bits)
+					};
+					(acc << bits) | c
+				});
+			out.extend((packed as u32).to_ne_bytes());
+		},
+		(_, Some(bits)) => {
+			let packed = components
+				.iter()
+				.zip(bits)
+				.fold(0u64, |acc, (c, bits)| (acc << bits) | unorm(*c, *bits));
+			out.extend((packed as u16).to_ne_bytes());
+		},
+		(_, None) => {
+			for c in components {
+				match ty {
+					PixelType::FLOAT => out.extend(c.to_ne_bytes()),
+					PixelType::HALF_FLOAT => out.extend(f32_to_f16(c).to_ne_bytes()),
+					PixelType::UNSIGNED_BYTE if raw => out.push(c.clamp(0.0, 255.0) as u8),
+					PixelType::BYTE if raw => out.push(c.clamp(-128.0, 127.0) as i8 as u8),
+					PixelType::UNSIGNED_SHORT if raw =>
+						out.extend((c.clamp(0.0, 65535.0) as u16).to_ne_bytes()),
+					PixelType::SHORT if raw =>
+						out.extend((c.clamp(-32768.0, 32767.0) as i16).to_ne_bytes()),
+					PixelType::UNSIGNED_INT if raw => out.extend((c.max(0.0) as u32).to_ne_bytes()),
+					PixelType::INT if raw => out.extend((c as i32).to_ne_bytes()),
+					PixelType::UNSIGNED_BYTE => out.push(unorm(c, 8) as u8),
+					PixelType::BYTE => out.push(snorm(c, 8) as i8 as u8),
+					PixelType::UNSIGNED_SHORT => out.extend((unorm(c, 16) as u16).to_ne_bytes()),
+					PixelType::SHORT => out.extend((snorm(c, 16) as i16).to_ne_bytes()),
+					PixelType::UNSIGNED_INT => out.extend((unorm(c, 32) as u32).to_ne_bytes()),
+					PixelType::INT => out.extend((snorm(c, 32) as i32).to_ne_bytes()),
+					// packed types are handled above
+					_ => unreachable!(),
+				}
+			}
+		},
+	}
+}
+
+/// Pixel storage modes set by `glPixelStorei`, for either packing or unpacking
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct PixelStore {
+	pub alignment: GLint,
+	pub row_length: GLint,
+	pub skip_pixels: GLint,
+	pub skip_rows: GLint,
+	pub image_height: GLint,
+	pub skip_images: GLint,
+}
+
+impl Default for PixelStore {
+	fn default() -> Self {
+		Self {
+			alignment: 4,
+			row_length: 0,
+			skip_pixels: 0,
+			skip_rows: 0,
+			image_height: 0,
+			skip_images: 0,
+		}
+	}
+}
+
+/// Where the pixels of an image are in client memory or a buffer
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct PixelLayout {
+	/// Byte offset of the first pixel
+	pub offset: usize,
+	/// Bytes from the start of one row to the next
+	pub stride: usize,
+	/// Bytes of one pixel
+	pub group_size: usize,
+	/// Bytes spanned from the start of memory to the end of the last pixel
+	pub len: usize,
+}
+
+impl PixelStore {
+	/// Layout of a `width` x `height` image of `format` and `ty` under these modes
+	pub fn layout(
+		&self,
+		format: PixelFormat,
+		ty: PixelType,
+		width: usize,
+		height: usize,
+	) -> PixelLayout {
+		let group_size = group_size(format, ty);
+		let row_length = if self.row_length > 0 {
+			self.row_length as usize
+		} else {
+			width
+		};
+
+		// rows are padded to the alignment unless elements are at least as large
+		let alignment = self.alignment as usize;
+		let row_bytes = row_length * group_size;
+		let stride = if ty.size() >= alignment {
+			row_bytes
+		} else {
+			row_bytes.div_ceil(alignment) * alignment
+		};
+
+		let offset = self.skip_rows as usize * stride + self.skip_pixels as usize * group_size;
+		let len = match (width, height) {
+			(0, _) | (_, 0) => 0,
+			_ => offset + (height - 1) * stride + width * group_size,
+		};
+
+		PixelLayout {
+			offset,
+			stride,
+			group_size,
+			len,
+		}
+	}
+
+	/// Set one mode, named by a `GL_PACK_*` or `GL_UNPACK_*` parameter without its prefix
+	pub fn set(&mut self, gl_version: &GlVersion, error: &mut GLenum, pname: GLenum, param: GLint) {
+		let es2 = !crate::version::at_least!(gl_version, gl: 2 . 0, es: 3 . 0);
+
+		let (field, name) = match pname {
+			gl::PACK_ALIGNMENT | gl::UNPACK_ALIGNMENT => {
+				if ![1, 2, 4, 8].contains(&param) {
+					*error = gl::INVALID_VALUE;
+					error!(
+						"glPixelStorei called with alignment {}, it must be 1, 2, 4 or 8",
+						param
+					);
+					return
+				}
+				(&mut self.alignment, "alignment")
+			},
+			gl::PACK_ROW_LENGTH | gl::UNPACK_ROW_LENGTH if !es2 =>
+				(&mut self.row_length, "row length"),
+			gl::PACK_SKIP_PIXELS | gl::UNPACK_SKIP_PIXELS if !es2 =>
+				(&mut self.skip_pixels, "skipped pixels"),
+			gl::PACK_SKIP_ROWS | gl::UNPACK_SKIP_ROWS if !es2 =>
+				(&mut self.skip_rows, "skipped rows"),
+			gl::PACK_IMAGE_HEIGHT | gl::UNPACK_IMAGE_HEIGHT if !es2 =>
+				(&mut self.image_height, "image height"),
+			gl::PACK_SKIP_IMAGES | gl::UNPACK_SKIP_IMAGES if !es2 =>
+				(&mut self.skip_images, "skipped images"),
+			_ => {
+				*error = gl::INVALID_ENUM;
+				error!("mock-gl does not support glPixelStorei parameter {}", pname);
+				return
+			},
+		};
+
+		if param < 0 {
+			*error = gl::INVALID_VALUE;
+			error!("glPixelStorei called with negative {} {}", name, param);
+			return
+		}
+
+		*field = param;
+		debug!("set pixel store {} to {}", name, param);
+	}
+}
@@ -0,0 +1,88 @@
+use crate::pixel::{encode, f32_to_f16, PixelFormat, PixelStore, PixelType};
+
+#[test]
+fn half_floats() {
+	assert_eq!(f32_to_f16(0.0), 0x0000);
+	assert_eq!(f32_to_f16(-0.0), 0x8000);
+	assert_eq!(f32_to_f16(1.0), 0x3c00);
+	assert_eq!(f32_to_f16(-2.5), 0xc100);
+	assert_eq!(f32_to_f16(65504.0), 0x7bff);
+	assert_eq!(f32_to_f16(1e6), 0x7c00);
+	assert_eq!(f32_to_f16(f32::NAN) & 0x7e00, 0x7e00);
+	// smallest subnormal
+	assert_eq!(f32_to_f16(5.96e-8), 0x0001);
+	// 1 + 2^-11 is halfway between 1 and the next half float, and rounds to even
+	assert_eq!(f32_to_f16(1.0 + 2f32.powi(-11)), 0x3c00);
+}
+
+fn encoded(format: PixelFormat, ty: PixelType, value: [f32; 4]) -> Vec<u8> {
+	let mut out = Vec::new();
+	encode(format, ty, value, &mut out);
+	out
+}
+
+#[test]
+fn encoding() {
+	let color = [1.0, 0.5, 0.0, 0.2];
+	assert_eq!(encoded(PixelFormat::RGBA, PixelType::UNSIGNED_BYTE, color), [255, 128, 0, 51]);
+	assert_eq!(encoded(PixelFormat::BGR, PixelType::UNSIGNED_BYTE, color), [0, 128, 255]);
+	assert_eq!(encoded(PixelFormat::ALPHA, PixelType::BYTE, [0.0, 0.0, 0.0, -1.0]), [0x81]);
+	assert_eq!(
+		encoded(PixelFormat::RED, PixelType::FLOAT, [-2.0, 0.0, 0.0, 0.0]),
+		(-2.0f32).to_ne_bytes()
+	);
+
+	let rgb565 = encoded(PixelFormat::RGB, PixelType::UNSIGNED_SHORT_5_6_5, color);
+	assert_eq!(rgb565, (0xf800u16 | 32 << 5).to_ne_bytes());
+	let rgb10_a2 =
+		encoded(PixelFormat::RGBA, PixelType::UNSIGNED_INT_2_10_10_10_REV, [1.0, 0.0, 0.0, 1.0]);
+	assert_eq!(rgb10_a2, (0xc000_03ffu32).to_ne_bytes());
+
+	// integer formats keep their values
+	assert_eq!(
+		encoded(PixelFormat::RGBA_INTEGER, PixelType::INT, [-7.0, 300.0, 0.0, 1.0]),
+		[-7i32, 300, 0, 1].iter().flat_map(|c| c.to_ne_bytes()).collect::<Vec<_>>()
+	);
+	assert_eq!(encoded(PixelFormat::RED_INTEGER, PixelType::UNSIGNED_BYTE, [300.0; 4]), [255]);
+
+	let depth_stencil = [0.5, 3.0, 0.0, 0.0];
+	assert_eq!(
+		encoded(PixelFormat::DEPTH_STENCIL, PixelType::UNSIGNED_INT_24_8, depth_stencil),
+		(0x8000_0003u32).to_ne_bytes()
+	);
+	assert_eq!(encoded(PixelFormat::STENCIL_INDEX, PixelType::UNSIGNED_BYTE, depth_stencil), [
+		0
+	]);
+	assert_eq!(
+		encoded(PixelFormat::DEPTH_COMPONENT, PixelType::UNSIGNED_SHORT, depth_stencil),
+		(0x8000u16).to_ne_bytes()
+	);
+}
+
+#[test]
+fn layout() {
+	let store = PixelStore::default();
+	let layout = store.layout(PixelFormat::RGB, PixelType::UNSIGNED_BYTE, 3, 2);
+	// rows of 9 bytes are padded to the default alignment of 4
+	assert_eq!((layout.offset, layout.stride, layout.group_size, layout.len), (0, 12, 3, 21));
+
+	let store = PixelStore {
+		alignment: 1,
+		row_length: 5,
+		skip_pixels: 1,
+		skip_rows: 2,
+		..PixelStore::default()
+	};
+	let layout = store.layout(PixelFormat::RGB, PixelType::UNSIGNED_BYTE, 3, 2);
+	assert_eq!((layout.offset, layout.stride, layout.len), (33, 15, 57));
+
+	// elements at least as large as the alignment are never padded
+	let store = PixelStore {
+		alignment: 2,
+		..PixelStore::default()
+	};
+	let layout = store.layout(PixelFormat::RED, PixelType::FLOAT, 3, 2);
+	assert_eq!(layout.stride, 12);
+
+	assert_eq!(store.layout(PixelFormat::RGBA, PixelType::UNSIGNED_BYTE, 0, 4).len, 0);
+}
@@ -0,0 +1,232 @@
+use crate::{
+	limits::Limits,
+	version::{ext, GlVersion, Profile, VersionType},
+};
+
+mod info;
+#[cfg(test)]
+mod test;
+
+pub use info::InfoError;
+
+/// Version, extensions, limits and strings of a known driver, so scenarios can be run against
+/// the contexts applications actually get
+pub struct Preset {
+	/// Short name for test output
+	pub name: String,
+	pub version: GlVersion,
+	pub limits: Limits,
+	pub vendor: String,
+	pub renderer: String,
+	/// `GL_VERSION` as the driver reports it, described from `version` if `None`
+	pub version_string: Option<String>,
+	/// `GL_SHADING_LANGUAGE_VERSION` as the driver reports it
+	pub shading_language_version: Option<String>,
+	/// Extensions as the driver lists them, including those mock-gl doesn't know, the
+	/// extensions of `version` if `None`
+	pub extensions: Option<Vec<String>>,
+}
+
+impl Preset {
+	/// Every preset, for running a scenario against each of them
+	pub fn all() -> Vec<Self> {
+		vec![
+			Self::llvmpipe(),
+			Self::intel(),
+			Self::apple(),
+			Self::adreno(),
+			Self::mali(),
+			Self::webgl(),
+		]
+	}
+
+	/// Mesa's software rasterizer, an OpenGL 4.5 core context common on CI machines
+	pub fn llvmpipe() -> Self {
+		let extensions = [&ext::ARB_buffer_storage, &ext::ARB_indirect_parameters];
+		let version =
+			GlVersion::new(VersionType::GL, 4, 5, &extensions).with_profile(Profile::Core);
+		let limits = Limits {
+			max_vertex_uniform_vectors: 4096,
+			max_fragment_uniform_vectors: 4096,
+			max_texture_image_units: 32,
+			max_combined_texture_image_units: 192,
+			max_uniform_block_size: 65536,
+			max_compute_work_group_size: [1024, 1024, 1024],
+			..Limits::new(&version)
+		};
+
+		Self {
+			name: "llvmpipe GL 4.5 core".into(),
+			version,
+			limits,
+			vendor: "Mesa".into(),
+			renderer: "llvmpipe (LLVM 15.0.7, 256 bits)".into(),
+			version_string: None,
+			shading_language_version: None,
+			extensions: None,
+		}
+	}
+
+	/// Intel integrated graphics on Mesa, an OpenGL 4.6 core context
+	pub fn intel() -> Self {
+		let version = GlVersion::from_version(VersionType::GL, 4, 6).with_profile(Profile::Core);
+		let limits = Limits {
+			max_viewport_dims: [32768; 2],
+			max_samples: 16,
+			max_vertex_uniform_vectors: 4096,
+			max_fragment_uniform_vectors: 4096,
+			max_texture_image_units: 32,
+			max_combined_texture_image_units: 192,
+			max_uniform_block_size: 65536,
+			max_compute_work_group_size: [1024, 1024, 1024],
+			..Limits::new(&version)
+		};
+
+		Self {
+			name: "Intel GL 4.6 core".into(),
+			version,
+			limits,
+			vendor: "Intel".into(),
+			renderer: "Mesa Intel(R) UHD Graphics 620 (KBL GT2)".into(),
+			version_string: None,
+			shading_language_version: None,
+			extensions: None,
+		}
+	}
+
+	/// macOS, frozen at an OpenGL 4.1 core context without compute shaders or
+	/// direct state access
+	pub fn apple() -> Self {
+		let extensions = [
+			&ext::ARB_draw_buffers_blend,
+			&ext::ARB_draw_indirect,
+			&ext::ARB_ES2_compatibility,
+			&ext::ARB_instanced_arrays,
+			&ext::ARB_internalformat_query,
+			&ext::ARB_viewport_array,
+		];
+		let version =
+			GlVersion::new(VersionType::GL, 4, 1, &extensions).with_profile(Profile::Core);
+		let limits = Limits {
+			max_vertex_uniform_vectors: 1024,
+			max_fragment_uniform_vectors: 1024,
+			max_combined_texture_image_units: 80,
+			..Limits::new(&version)
+		};
+
+		Self {
+			name: "macOS GL 4.1 core".into(),
+			version,
+			limits,
+			vendor: "Apple".into(),
+			renderer: "Apple M1".into(),
+			version_string: None,
+			shading_language_version: None,
+			extensions: None,
+		}
+	}
+
+	/// Qualcomm Adreno, an OpenGL ES 3.2 context with 32 vertex attributes, rendering to float
+	/// formats but with only 256 uniform vectors per stage
+	pub fn adreno() -> Self {
+		let extensions = [
+			&ext::OES_rgb8_rgba8,
+			&ext::OES_depth24,
+			&ext::OES_packed_depth_stencil,
+			&ext::OES_element_index_uint,
+			&ext::OES_vertex_array_object,
+			&ext::EXT_color_buffer_float,
+			&ext::EXT_color_buffer_half_float,
+			&ext::EXT_draw_buffers_indexed,
+		];
+		let version = GlVersion::new(VersionType::ES, 3, 2, &extensions);
+		let limits = Limits {
+			max_texture_size: 16384,
+			max_renderbuffer_size: 16384,
+			max_viewport_dims: [16384; 2],
+			max_color_attachments: 8,
+			max_draw_buffers: 8,
+			max_vertex_attribs: 32,
+			max_vertex_attrib_bindings: 32,
+			max_vertex_uniform_vectors: 256,
+			max_fragment_uniform_vectors: 256,
+			max_uniform_block_size: 65536,
+			max_compute_work_group_size: [1024, 1024, 64],
+			max_compute_work_group_invocations: 1024,
+			..Limits::new(&version)
+		};
+
+		Self {
+			name: "Adreno ES 3.2".into(),
+			version,
+			limits,
+			vendor: "Qualcomm".into(),
+			renderer: "Adreno (TM) 640".into(),
+			version_string: None,
+			shading_language_version: None,
+			extensions: None,
+		}
+	}
+
+	/// ARM Mali, an OpenGL ES 3.1 context that can't render to 32 bit float formats
+	pub fn mali() -> Self {
+		let extensions = [
+			&ext::OES_rgb8_rgba8,
+			&ext::OES_depth24,
+			&ext::OES_packed_depth_stencil,
+			&ext::OES_element_index_uint,
+			&ext::OES_vertex_array_object,
+			&ext::EXT_blend_minmax,
+			&ext::EXT_color_buffer_half_float,
+			&ext::EXT_draw_buffers_indexed,
+			&ext::OES_draw_buffers_indexed,
+		];
+		let version = GlVersion::new(VersionType::ES, 3, 1, &extensions);
+		let limits = Limits {
+			max_texture_size: 8192,
+			max_renderbuffer_size: 8192,
+			max_viewport_dims: [8192; 2],
+			max_samples: 16,
+			max_vertex_uniform_vectors: 256,
+			max_fragment_uniform_vectors: 256,
+			max_combined_texture_image_units: 96,
+			max_compute_work_group_size: [256, 256, 256],
+			max_compute_work_group_invocations: 256,
+			..Limits::new(&version)
+		};
+
+		Self {
+			name: "Mali ES 3.1".into(),
+			version,
+			limits,
+			vendor: "ARM".into(),
+			renderer: "Mali-T760".into(),
+			version_string: None,
+			shading_language_version: None,
+			extensions: None,
+		}
+	}
+
+	/// A WebGL 2 context, OpenGL ES 3.0 without the extensions pages have to enable,
+	/// such as `EXT_color_buffer_float`
+	pub fn webgl() -> Self {
+		let version = GlVersion::from_version(VersionType::ES, 3, 0);
+		let limits = Limits {
+			max_texture_size: 4096,
+			max_renderbuffer_size: 4096,
+			max_viewport_dims: [4096; 2],
+			..Limits::new(&version)
+		};
+
+		Self {
+			name: "WebGL 2".into(),
+			version,
+			limits,
+			vendor: "WebKit".into(),
+			renderer: "WebKit WebGL".into(),
+			version_string: None,
+			shading_language_version: None,
+			extensions: None,
+		}
+	}
+}
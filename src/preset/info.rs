@@ -0,0 +1,324 @@
+use std::{collections::HashMap, fs, io, path::Path};
+
+use crate::{
+	debug,
+	limits::Limits,
+	preset::Preset,
+	version::{ext, GlVersion, Profile, VersionType},
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum InfoError {
+	#[error(transparent)]
+	Io(#[from] io::Error),
+	#[error("the dump has no {0} context")]
+	MissingContext(&'static str),
+	#[error("the dump has no {0} of the {1} context")]
+	MissingField(&'static str, &'static str),
+	#[error("invalid version string {0:?}")]
+	Version(String),
+	#[error("invalid value {1:?} of {0}")]
+	Limit(String, String),
+}
+
+/// Contexts a dump can describe, each printed with its own prefix
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+enum Context {
+	Core,
+	Compatibility,
+	Es,
+}
+
+impl Context {
+	fn new(ty: VersionType, profile: Profile) -> Self {
+		match (ty, profile) {
+			(VersionType::ES, _) => Self::Es,
+			(VersionType::GL, Profile::Core) => Self::Core,
+			(VersionType::GL, Profile::Compatibility) => Self::Compatibility,
+		}
+	}
+
+	fn name(&self) -> &'static str {
+		match self {
+			Self::Core => "OpenGL core profile",
+			Self::Compatibility => "OpenGL compatibility profile",
+			Self::Es => "OpenGL ES profile",
+		}
+	}
+
+	/// Label of the API, as preset names end with
+	fn label(&self, major: u8, minor: u8) -> String {
+		match self {
+			Self::Core => format!("GL {major}.{minor} core"),
+			Self::Compatibility => format!("GL {major}.{minor} compatibility"),
+			Self::Es => format!("ES {major}.{minor}"),
+		}
+	}
+
+	/// Context a line describes, and the rest of the line
+	///
+	/// glxinfo leaves the compatibility profile unprefixed, eglinfo names it.
+	fn of_line(line: &str) -> Option<(Self, &str)> {
+		let rest = line.strip_prefix("OpenGL ")?;
+		let prefixes = [
+			("core profile ", Self::Core),
+			("compatibility profile ", Self::Compatibility),
+			("ES profile ", Self::Es),
+		];
+
+		Some(
+			prefixes
+				.into_iter()
+				.find_map(|(prefix, context)| Some((context, rest.strip_prefix(prefix)?)))
+				.unwrap_or((Self::Compatibility, rest)),
+		)
+	}
+}
+
+/// Fields, extensions and limits printed for one context
+#[derive(Default)]
+struct Section<'a> {
+	fields: HashMap<&'a str, &'a str>,
+	extensions: Vec<&'a str>,
+	limits: HashMap<&'a str, &'a str>,
+}
+
+/// Sections of every context in `dump`, keeping the first of each when a dump lists
+/// several displays
+fn sections(dump: &str) -> HashMap<Context, Section<'_>> {
+	let mut sections: HashMap<Context, Section> = HashMap::new();
+	let mut seen = Vec::new();
+	// list whose indented lines follow, `None` inside lists of a repeated context
+	let mut list = None;
+
+	for line in dump.lines() {
+		if line.starts_with(char::is_whitespace) {
+			let Some((context, key)) = list else { continue };
+			let section = sections.entry(context).or_default();
+			match key {
+				"extensions" => section
+					.extensions
+					.extend(line.split([',', ' ']).map(str::trim).filter(|name| !name.is_empty())),
+				// limits of extensions are nested under a heading, and listed the same way
+				_ =>
+					if let Some((name, value)) = line.split_once(" = ") {
+						section.limits.entry(name.trim()).or_insert(value.trim());
+					},
+			}
+			continue
+		}
+
+		list = None;
+		let Some((context, rest)) = Context::of_line(line) else {
+			continue
+		};
+		let Some((key, value)) = rest.split_once(':') else {
+			continue
+		};
+		// glxinfo says "version string", eglinfo only "version"
+		let key = key.strip_suffix(" string").unwrap_or(key);
+
+		let first = !seen.contains(&(context, key));
+		seen.push((context, key));
+		match key {
+			"extensions" | "limits" if first => list = Some((context, key)),
+			_ if first => {
+				sections.entry(context).or_default().fields.insert(key, value.trim());
+			},
+			_ => {},
+		}
+	}
+
+	sections
+}
+
+/// Major and minor version of a version string such as `4.6 (Core Profile) Mesa 23.1.0`
+fn parse_version(ty: VersionType, string: &str) -> Result<(u8, u8), InfoError> {
+	let invalid = || InfoError::Version(string.to_string());
+	let numbers = match ty {
+		VersionType::GL => string,
+		VersionType::ES => string.strip_prefix("OpenGL ES ").ok_or_else(invalid)?,
+	};
+
+	let mut parts = numbers.split_whitespace().next().ok_or_else(invalid)?.split('.');
+	let mut part = || parts.next().and_then(|part| part.parse().ok()).ok_or_else(invalid);
+	Ok((part()?, part()?))
+}
+
+/// Values of the limit `name`, `None` if the dump doesn't list it
+fn limit(limits: &HashMap<&str, &str>, name: &str) -> Result<Option<Vec<i64>>, InfoError> {
+	let Some(value) = limits.get(name) else {
+		return Ok(None)
+	};
+
+	value
+		.split(',')
+		.map(|v| v.trim().parse().map_err(|_| InfoError::Limit(name.into(), value.to_string())))
+		.collect::<Result<_, _>>()
+		.map(Some)
+}
+
+/// Overwrite `field` with the limit `name` if the dump lists it, a single value
+/// answering for every dimension of indexed limits
+fn apply<T: TryFrom<i64> + Copy, const N: usize>(
+	limits: &HashMap<&str, &str>,
+	name: &str,
+	field: &mut [T; N],
+) -> Result<(), InfoError> {
+	let Some(values) = limit(limits, name)? else {
+		return Ok(())
+	};
+
+	let invalid = || InfoError::Limit(name.into(), limits[name].to_string());
+	if values.len() != N && values.len() != 1 {
+		return Err(invalid())
+	}
+	for (i, field) in field.iter_mut().enumerate() {
+		let value = values[i.min(values.len() - 1)];
+		*field = T::try_from(value).map_err(|_| invalid())?;
+	}
+
+	Ok(())
+}
+
+fn apply_one<T: TryFrom<i64> + Copy>(
+	limits: &HashMap<&str, &str>,
+	name: &str,
+	field: &mut T,
+) -> Result<(), InfoError> {
+	let mut array = [*field];
+	apply(limits, name, &mut array)?;
+	*field = array[0];
+	Ok(())
+}
+
+/// Limits listed in a dump, the minimums of `version` standing in for the others
+fn parse_limits(version: &GlVersion, listed: &HashMap<&str, &str>) -> Result<Limits, InfoError> {
+	let mut limits = Limits::new(version);
+
+	// desktop dumps list uniform components rather than the vectors OpenGL ES counts
+	for (components, vectors) in [
+		("GL_MAX_VERTEX_UNIFORM_COMPONENTS", &mut limits.max_vertex_uniform_vectors),
+		("GL_MAX_FRAGMENT_UNIFORM_COMPONENTS", &mut limits.max_fragment_uniform_vectors),
+	] {
+		apply_one(listed, components, vectors)?;
+		if listed.contains_key(components) {
+			*vectors /= 4;
+		}
+	}
+
+	apply_one(listed, "GL_MAX_TEXTURE_SIZE", &mut limits.max_texture_size)?;
+	apply_one(listed, "GL_MAX_RENDERBUFFER_SIZE", &mut limits.max_renderbuffer_size)?;
+	apply(listed, "GL_MAX_VIEWPORT_DIMS", &mut limits.max_viewport_dims)?;
+	apply_one(listed, "GL_MAX_VIEWPORTS", &mut limits.max_viewports)?;
+	apply_one(listed, "GL_MAX_SAMPLES", &mut limits.max_samples)?;
+	apply_one(listed, "GL_MAX_COLOR_ATTACHMENTS", &mut limits.max_color_attachments)?;
+	apply_one(listed, "GL_MAX_DRAW_BUFFERS", &mut limits.max_draw_buffers)?;
+	apply_one(listed, "GL_MAX_VERTEX_ATTRIBS", &mut limits.max_vertex_attribs)?;
+	apply_one(listed, "GL_MAX_VERTEX_ATTRIB_BINDINGS", &mut limits.max_vertex_attrib_bindings)?;
+	apply_one(
+		listed,
+		"GL_MAX_VERTEX_ATTRIB_RELATIVE_OFFSET",
+		&mut limits.max_vertex_attrib_relative_offset,
+	)?;
+	apply_one(listed, "GL_MAX_VERTEX_ATTRIB_STRIDE", &mut limits.max_vertex_attrib_stride)?;
+	apply_one(listed, "GL_MAX_VERTEX_UNIFORM_VECTORS", &mut limits.max_vertex_uniform_vectors)?;
+	apply_one(
+		listed,
+		"GL_MAX_FRAGMENT_UNIFORM_VECTORS",
+		&mut limits.max_fragment_uniform_vectors,
+	)?;
+	apply_one(listed, "GL_MAX_TEXTURE_IMAGE_UNITS", &mut limits.max_texture_image_units)?;
+	apply_one(
+		listed,
+		"GL_MAX_COMBINED_TEXTURE_IMAGE_UNITS",
+		&mut limits.max_combined_texture_image_units,
+	)?;
+	apply_one(listed, "GL_MAX_UNIFORM_BLOCK_SIZE", &mut limits.max_uniform_block_size)?;
+	apply(
+		listed,
+		"GL_MAX_COMPUTE_WORK_GROUP_COUNT",
+		&mut limits.max_compute_work_group_count,
+	)?;
+	apply(
+		listed,
+		"GL_MAX_COMPUTE_WORK_GROUP_SIZE",
+		&mut limits.max_compute_work_group_size,
+	)?;
+	apply_one(
+		listed,
+		"GL_MAX_COMPUTE_WORK_GROUP_INVOCATIONS",
+		&mut limits.max_compute_work_group_invocations,
+	)?;
+
+	Ok(limits)
+}
+
+impl Preset {
+	/// Preset replaying the context of type `ty` and `profile` described by the output of
+	/// `glxinfo -l` or `eglinfo`
+	///
+	/// The context reports the dump's version strings and extension list, but only emulates the
+	/// extensions mock-gl knows. Limits missing from the dump, as without `-l`, are the minimums
+	/// of the version.
+	pub fn from_info(dump: &str, ty: VersionType, profile: Profile) -> Result<Self, InfoError> {
+		let context = Context::new(ty, profile);
+		let sections = sections(dump);
+		let section = sections
+			.get(&context)
+			.filter(|section| section.fields.contains_key("version"))
+			.ok_or(InfoError::MissingContext(context.name()))?;
+
+		// glxinfo prints the vendor and renderer once, before the core profile
+		let field = |key: &'static str| {
+			[
+				section,
+				sections.get(&Context::Compatibility).unwrap_or(section),
+			]
+			.into_iter()
+			.find_map(|section| section.fields.get(key))
+			.ok_or(InfoError::MissingField(key, context.name()))
+		};
+
+		let version_string = field("version")?;
+		let (major, minor) = parse_version(ty, version_string)?;
+
+		let mut extensions = Vec::new();
+		for name in &section.extensions {
+			match name.strip_prefix("GL_").and_then(ext::from_name) {
+				Some(extension) => extensions.push(extension),
+				None => {
+					debug!("extension {} is listed, but unknown to mock-gl", name);
+				},
+			}
+		}
+
+		let version = GlVersion::new(ty, major, minor, &extensions).with_profile(profile);
+		let limits = parse_limits(&version, &section.limits)?;
+		let renderer = field("renderer")?.to_string();
+
+		Ok(Self {
+			name: format!("{renderer} {}", context.label(major, minor)),
+			version,
+			limits,
+			vendor: field("vendor")?.to_string(),
+			renderer,
+			version_string: Some(version_string.to_string()),
+			// the compatibility profile's shading language may differ, so there's no fallback
+			shading_language_version: section
+				.fields
+				.get("shading language version")
+				.map(|version| version.to_string()),
+			extensions: Some(section.extensions.iter().map(|name| name.to_string()).collect()),
+		})
+	}
+
+	/// Preset read from a `glxinfo -l` or `eglinfo` dump saved at `path`, see `from_info`
+	pub fn load_info(
+		path: impl AsRef<Path>,
+		ty: VersionType,
+		profile: Profile,
+	) -> Result<Self, InfoError> {
+		Self::from_info(&fs::read_to_string(path)?, ty, profile)
+	}
+}
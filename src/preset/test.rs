@@ -0,0 +1,291 @@
+use gl::types::GLuint;
+
+use crate::{
+	preset::{InfoError, Preset},
+	surface::Surface,
+	test::{get_extensions, get_integer, get_string, test_harness_preset, test_harness_with},
+	version::{ext, Profile, VersionType},
+	ErrorHandling,
+};
+
+#[test]
+fn presets_report_their_driver() {
+	for preset in Preset::all() {
+		let (name, vendor, renderer) =
+			(preset.name.clone(), preset.vendor.clone(), preset.renderer.clone());
+		let limits = preset.limits.clone();
+
+		test_harness_preset(preset, ErrorHandling::PanicEarly { warn: true }, || unsafe {
+			assert_eq!(get_string(gl::VENDOR), vendor, "{name}");
+			assert_eq!(get_string(gl::RENDERER), renderer, "{name}");
+			assert_eq!(get_integer(gl::MAX_TEXTURE_SIZE), limits.max_texture_size, "{name}");
+			assert_eq!(
+				get_integer(gl::MAX_VERTEX_ATTRIBS) as GLuint,
+				limits.max_vertex_attribs,
+				"{name}"
+			);
+			assert_eq!(
+				get_integer(gl::MAX_VERTEX_UNIFORM_VECTORS) as GLuint,
+				limits.max_vertex_uniform_vectors,
+				"{name}"
+			);
+			assert_eq!(gl::GetError(), gl::NO_ERROR, "{name}");
+		});
+	}
+}
+
+#[test]
+fn float_render_targets() {
+	// the same scenario succeeds on desktop drivers and fails where EXT_color_buffer_float
+	// is missing
+	for (preset, error) in [
+		(Preset::apple(), gl::NO_ERROR),
+		(Preset::adreno(), gl::NO_ERROR),
+		(Preset::mali(), gl::INVALID_ENUM),
+		(Preset::webgl(), gl::INVALID_ENUM),
+	] {
+		let name = preset.name.clone();
+		test_harness_preset(preset, ErrorHandling::DoNotPanic, || unsafe {
+			let mut renderbuffer = 0;
+			gl::GenRenderbuffers(1, &mut renderbuffer);
+			gl::BindRenderbuffer(gl::RENDERBUFFER, renderbuffer);
+			gl::RenderbufferStorage(gl::RENDERBUFFER, gl::RGBA32F, 4, 4);
+			assert_eq!(gl::GetError(), error, "{name}");
+			gl::DeleteRenderbuffers(1, &renderbuffer);
+		});
+	}
+}
+
+#[test]
+fn extension_lists() {
+	for (preset, float, half_float) in [
+		(Preset::adreno(), true, true),
+		(Preset::mali(), false, true),
+		(Preset::webgl(), false, false),
+	] {
+		let name = preset.name.clone();
+		test_harness_preset(preset, ErrorHandling::PanicEarly { warn: true }, || unsafe {
+			let extensions = get_extensions();
+			let listed = |name: &str| extensions.iter().any(|extension| extension == name);
+			assert_eq!(listed("GL_EXT_color_buffer_float"), float, "{name}");
+			assert_eq!(listed("GL_EXT_color_buffer_half_float"), half_float, "{name}");
+			assert!(listed("GL_OES_vertex_array_object"), "{name}");
+		});
+	}
+}
+
+#[test]
+fn vertex_attributes() {
+	for (preset, attribs) in [(Preset::adreno(), 32), (Preset::mali(), 16)] {
+		let name = preset.name.clone();
+		test_harness_preset(preset, ErrorHandling::DoNotPanic, || unsafe {
+			let mut array = 0;
+			gl::GenVertexArrays(1, &mut array);
+			gl::BindVertexArray(array);
+			gl::EnableVertexAttribArray(attribs - 1);
+			assert_eq!(gl::GetError(), gl::NO_ERROR, "{name}");
+			gl::EnableVertexAttribArray(attribs);
+			assert_eq!(gl::GetError(), gl::INVALID_VALUE, "{name}");
+			gl::DeleteVertexArrays(1, &array);
+		});
+	}
+}
+
+#[test]
+fn surface_beyond_preset() {
+	// WebGL only guarantees 4096 pixel viewports, but a canvas can be larger
+	let error_handling = ErrorHandling::PanicEarly { warn: true };
+	let new = || crate::new_with_preset(Preset::webgl(), error_handling, Surface::new(5000, 4000));
+	test_harness_with(new, |_| unsafe {
+		let mut viewport = [0; 4];
+		gl::GetIntegerv(gl::VIEWPORT, viewport.as_mut_ptr());
+		assert_eq!(viewport, [0, 0, 5000, 4000]);
+		gl::Viewport(0, 0, 5000, 4000);
+		gl::GetIntegerv(gl::VIEWPORT, viewport.as_mut_ptr());
+		assert_eq!(viewport, [0, 0, 5000, 4000]);
+
+		let mut dims = [0; 2];
+		gl::GetIntegerv(gl::MAX_VIEWPORT_DIMS, dims.as_mut_ptr());
+		assert_eq!(dims, [5000, 4096]);
+	});
+}
+
+/// Abbreviated `glxinfo -l` output of a Mesa driver
+const GLXINFO: &str = "\
+name of display: :0
+direct rendering: Yes
+Extended renderer info (GLX_MESA_query_renderer):
+    Vendor: Intel (0x8086)
+    Version: 23.1.0
+OpenGL vendor string: Intel
+OpenGL renderer string: Mesa Intel(R) UHD Graphics 620 (KBL GT2)
+OpenGL core profile version string: 4.6 (Core Profile) Mesa 23.1.0
+OpenGL core profile shading language version string: 4.60
+OpenGL core profile context flags: (none)
+OpenGL core profile profile mask: core profile
+OpenGL core profile extensions:
+    GL_ARB_buffer_storage, GL_EXT_multi_draw_arrays, GL_INTEL_performance_query,
+    GL_KHR_debug
+OpenGL core profile limits:
+    GL_MAX_TEXTURE_SIZE = 16384
+    GL_MAX_VIEWPORT_DIMS = 32768, 32768
+    GL_MAX_VERTEX_ATTRIBS = 16
+    GL_MAX_VERTEX_UNIFORM_COMPONENTS = 16384
+    GL_MAX_FRAGMENT_UNIFORM_COMPONENTS = 16384
+    GL_MAX_SAMPLES = 16
+    GL_ARB_compute_shader:
+      GL_MAX_COMPUTE_WORK_GROUP_COUNT = 65535, 65535, 65535
+      GL_MAX_COMPUTE_WORK_GROUP_SIZE = 1024, 1024, 1024
+OpenGL version string: 4.6 (Compatibility Profile) Mesa 23.1.0
+OpenGL shading language version string: 4.60
+OpenGL extensions:
+    GL_ARB_buffer_storage, GL_ARB_compatibility
+OpenGL limits:
+    GL_MAX_TEXTURE_SIZE = 8192
+OpenGL ES profile version string: OpenGL ES 3.2 Mesa 23.1.0
+OpenGL ES profile shading language version string: OpenGL ES GLSL ES 3.20
+OpenGL ES profile extensions:
+    GL_EXT_clip_control, GL_OES_viewport_array
+OpenGL ES profile limits:
+    GL_MAX_VERTEX_UNIFORM_VECTORS = 4096
+    GL_MAX_COMPUTE_WORK_GROUP_SIZE = 512
+";
+
+/// Abbreviated `eglinfo` output, listing the same driver once per platform
+const EGLINFO: &str = "\
+GBM platform:
+EGL API version: 1.5
+EGL vendor string: Mesa Project
+OpenGL core profile vendor: Mesa
+OpenGL core profile renderer: llvmpipe (LLVM 15.0.7, 256 bits)
+OpenGL core profile version: 4.5 (Core Profile) Mesa 23.1.0
+OpenGL core profile extensions:
+    GL_ARB_buffer_storage, GL_EXT_multi_draw_indirect
+OpenGL ES profile vendor: Mesa
+OpenGL ES profile renderer: llvmpipe (LLVM 15.0.7, 256 bits)
+OpenGL ES profile version: OpenGL ES 3.1 Mesa 23.1.0
+OpenGL ES profile extensions:
+    GL_EXT_color_buffer_half_float
+
+Surfaceless platform:
+OpenGL core profile vendor: Other
+OpenGL core profile renderer: Other
+OpenGL core profile version: 3.3 (Core Profile) Mesa 23.1.0
+OpenGL core profile extensions:
+    GL_EXT_base_instance
+";
+
+#[test]
+fn glxinfo_contexts() {
+	let core = Preset::from_info(GLXINFO, VersionType::GL, Profile::Core).unwrap();
+	assert_eq!(core.name, "Mesa Intel(R) UHD Graphics 620 (KBL GT2) GL 4.6 core");
+	assert_eq!(core.vendor, "Intel");
+	assert_eq!(core.renderer, "Mesa Intel(R) UHD Graphics 620 (KBL GT2)");
+	assert_eq!((core.version.major, core.version.minor), (4, 6));
+	assert!(core.version.is_core());
+	assert!(core.version.has_extension(&ext::EXT_multi_draw_arrays));
+	assert!(!core.version.has_extension(&ext::EXT_base_instance));
+	assert_eq!(core.extensions.as_ref().unwrap().len(), 4);
+
+	assert_eq!(core.limits.max_texture_size, 16384);
+	assert_eq!(core.limits.max_viewport_dims, [32768, 32768]);
+	assert_eq!(core.limits.max_samples, 16);
+	assert_eq!(core.limits.max_vertex_uniform_vectors, 4096);
+	assert_eq!(core.limits.max_fragment_uniform_vectors, 4096);
+	assert_eq!(core.limits.max_compute_work_group_size, [1024, 1024, 1024]);
+	// limits the dump doesn't list are the minimums of the version
+	assert_eq!(core.limits.max_draw_buffers, 8);
+
+	let compatibility =
+		Preset::from_info(GLXINFO, VersionType::GL, Profile::Compatibility).unwrap();
+	assert_eq!(compatibility.renderer, core.renderer);
+	assert!(!compatibility.version.is_core());
+	assert_eq!(compatibility.limits.max_texture_size, 8192);
+
+	let es = Preset::from_info(GLXINFO, VersionType::ES, Profile::Core).unwrap();
+	assert_eq!(es.name, "Mesa Intel(R) UHD Graphics 620 (KBL GT2) ES 3.2");
+	assert_eq!(es.version_string.as_deref(), Some("OpenGL ES 3.2 Mesa 23.1.0"));
+	assert_eq!(es.shading_language_version.as_deref(), Some("OpenGL ES GLSL ES 3.20"));
+	assert!(matches!(es.version.ty, VersionType::ES));
+	assert_eq!((es.version.major, es.version.minor), (3, 2));
+	assert!(es.version.has_extension(&ext::EXT_clip_control));
+	assert!(!es.version.has_extension(&ext::EXT_multi_draw_arrays));
+	assert_eq!(es.limits.max_viewports, 16);
+	assert_eq!(es.limits.max_vertex_uniform_vectors, 4096);
+	assert_eq!(es.limits.max_compute_work_group_size, [512; 3]);
+}
+
+#[test]
+fn eglinfo_contexts() {
+	// the first platform wins
+	let core = Preset::from_info(EGLINFO, VersionType::GL, Profile::Core).unwrap();
+	assert_eq!(core.vendor, "Mesa");
+	assert_eq!(core.renderer, "llvmpipe (LLVM 15.0.7, 256 bits)");
+	assert_eq!((core.version.major, core.version.minor), (4, 5));
+	assert!(core.version.has_extension(&ext::EXT_multi_draw_indirect));
+	assert!(!core.version.has_extension(&ext::EXT_base_instance));
+
+	let es = Preset::from_info(EGLINFO, VersionType::ES, Profile::Core).unwrap();
+	assert_eq!((es.version.major, es.version.minor), (3, 1));
+	// eglinfo doesn't print the shading language version
+	assert_eq!(es.shading_language_version, None);
+	assert!(es.version.has_extension(&ext::EXT_color_buffer_half_float));
+
+	assert!(matches!(
+		Preset::from_info(EGLINFO, VersionType::GL, Profile::Compatibility),
+		Err(InfoError::MissingContext(_))
+	));
+}
+
+#[test]
+fn invalid_dumps() {
+	let version = "OpenGL core profile version string: 4.x (Core Profile)\n";
+	assert!(matches!(
+		Preset::from_info(version, VersionType::GL, Profile::Core),
+		Err(InfoError::Version(_))
+	));
+
+	let renderer = "OpenGL core profile version string: 4.6 (Core Profile)\n";
+	assert!(matches!(
+		Preset::from_info(renderer, VersionType::GL, Profile::Core),
+		Err(InfoError::MissingField("renderer", _))
+	));
+
+	let limit = GLXINFO.replace("GL_MAX_SAMPLES = 16", "GL_MAX_SAMPLES = -1, 2");
+	assert!(matches!(
+		Preset::from_info(&limit, VersionType::GL, Profile::Core),
+		Err(InfoError::Limit(..))
+	));
+
+	assert!(matches!(
+		Preset::load_info("missing-glxinfo.txt", VersionType::GL, Profile::Core),
+		Err(InfoError::Io(_))
+	));
+}
+
+#[test]
+fn replay_dump() {
+	let preset = Preset::from_info(GLXINFO, VersionType::GL, Profile::Core).unwrap();
+	test_harness_preset(preset, ErrorHandling::PanicEarly { warn: true }, || unsafe {
+		assert_eq!(get_string(gl::VENDOR), "Intel");
+		assert_eq!(get_string(gl::VERSION), "4.6 (Core Profile) Mesa 23.1.0");
+		assert_eq!(get_string(gl::SHADING_LANGUAGE_VERSION), "4.60");
+		// extensions mock-gl doesn't know are still listed
+		assert_eq!(get_integer(gl::NUM_EXTENSIONS), 4);
+		assert_eq!(get_extensions(), [
+			"GL_ARB_buffer_storage",
+			"GL_EXT_multi_draw_arrays",
+			"GL_INTEL_performance_query",
+			"GL_KHR_debug",
+		]);
+		assert_eq!(get_integer(gl::MAX_SAMPLES), 16);
+		assert_eq!(get_integer(gl::MAX_VERTEX_UNIFORM_VECTORS), 4096);
+
+		let mut renderbuffer = 0;
+		gl::GenRenderbuffers(1, &mut renderbuffer);
+		gl::BindRenderbuffer(gl::RENDERBUFFER, renderbuffer);
+		gl::RenderbufferStorageMultisample(gl::RENDERBUFFER, 16, gl::RGBA8, 4, 4);
+		assert_eq!(gl::GetError(), gl::NO_ERROR);
+		gl::DeleteRenderbuffers(1, &renderbuffer);
+	});
+}
@@ -0,0 +1,451 @@
+//! State queried by the `glGet*` functions, and the rules converting it between their types
+
+use std::fmt;
+
+use gl::types::{GLboolean, GLdouble, GLenum, GLfloat, GLint, GLint64, GLuint};
+
+use crate::{
+	buffer::{BufferBinding, BufferManager},
+	error,
+	framebuffer::{implementation_read_format, Attachment, FramebufferManager, FramebufferTarget},
+	limits::Limits,
+	renderbuffer::RenderbufferManager,
+	state::{Capability, StateManager},
+	version::{at_least, ext, GlExtension, Profile},
+	vertex_array::VertexArrayManager,
+	GlVersion,
+	MockContextData,
+};
+
+pub mod gl_functions;
+mod string;
+
+pub use string::{Strings, DEFAULT_RENDERER, DEFAULT_VENDOR};
+
+#[cfg(test)]
+mod test;
+
+/// Value of a state variable in the type it is kept as,
+/// converted to the type of the `glGet*` function querying it
+#[derive(Clone, Debug, PartialEq)]
+pub enum StateValue {
+	Boolean(Vec<bool>),
+	/// Integers and enums
+	Integer(Vec<i64>),
+	Float(Vec<f64>),
+	/// Color components and depth values, which integer queries map linearly
+	/// from `[-1, 1]` to the full range of the integer type, as `((2^b - 1) * f - 1) / 2`
+	Normalized(Vec<f64>),
+}
+
+impl StateValue {
+	pub fn boolean(value: bool) -> Self {
+		Self::Boolean(vec![value])
+	}
+
+	pub fn int(value: impl Into<i64>) -> Self {
+		Self::Integer(vec![value.into()])
+	}
+
+	/// Booleans as queried by `glGetBooleanv`, where only zero is false
+	pub fn booleans(&self) -> Vec<GLboolean> {
+		let booleans: Vec<bool> = match self {
+			Self::Boolean(values) => values.clone(),
+			Self::Integer(values) => values.iter().map(|v| *v != 0).collect(),
+			Self::Float(values) | Self::Normalized(values) =>
+				values.iter().map(|v| *v != 0.0).collect(),
+		};
+
+		booleans.into_iter().map(|b| b as GLboolean).collect()
+	}
+
+	/// Integers with at most `max` as their magnitude, floats being rounded
+	/// and values out of range clamped
+	fn integers(&self, max: i64) -> Vec<i64> {
+		match self {
+			Self::Boolean(values) => values.iter().map(|v| *v as i64).collect(),
+			Self::Integer(values) => values.iter().map(|v| (*v).clamp(-max - 1, max)).collect(),
+			Self::Float(values) => values
+				.iter()
+				.map(|v| v.round().clamp(-max as f64 - 1.0, max as f64) as i64)
+				.collect(),
+			Self::Normalized(values) => {
+				// 2^b - 1, with `max` being 2^(b - 1) - 1
+				let range = 2.0 * max as f64 + 1.0;
+				values
+					.iter()
+					.map(|v| ((range * v.clamp(-1.0, 1.0) - 1.0) / 2.0) as i64)
+					.collect()
+			},
+		}
+	}
+
+	/// Integers as queried by `glGetIntegerv`
+	pub fn ints(&self) -> Vec<GLint> {
+		self.integers(GLint::MAX as i64).into_iter().map(|v| v as GLint).collect()
+	}
+
+	/// Integers as queried by `glGetInteger64v`
+	pub fn int64s(&self) -> Vec<GLint64> {
+		self.integers(GLint64::MAX)
+	}
+
+	/// Floats as queried by `glGetFloatv`
+	pub fn floats(&self) -> Vec<GLfloat> {
+		self.doubles().into_iter().map(|v| v as GLfloat).collect()
+	}
+
+	/// Doubles as queried by `glGetDoublev`, booleans being one or zero
+	pub fn doubles(&self) -> Vec<GLdouble> {
+		match self {
+			Self::Boolean(values) => values.iter().map(|v| *v as u8 as f64).collect(),
+			Self::Integer(values) => values.iter().map(|v| *v as f64).collect(),
+			Self::Float(values) | Self::Normalized(values) => values.clone(),
+		}
+	}
+}
+
+/// Versions or extensions providing a state variable
+#[derive(Copy, Clone)]
+struct Requires {
+	gl: Option<(u8, u8)>,
+	es: Option<(u8, u8)>,
+	extensions: &'static [&'static GlExtension],
+}
+
+macro_rules! requires {
+	($(gl: $gl_major:literal . $gl_minor:literal)? $(,)? $(es: $es_major:literal . $es_minor:literal)? $(; $($ext:ident)|+)?) => {
+		Requires {
+			gl: at_least!(opt $(($gl_major, $gl_minor))?),
+			es: at_least!(opt $(($es_major, $es_minor))?),
+			extensions: &[$($(&ext::$ext),+)?],
+		}
+	};
+}
+
+/// State of every version
+const ALL: Requires = requires!(gl: 2 . 1, es: 2 . 0);
+/// State OpenGL ES 2.0 lacks
+const ES3: Requires = requires!(gl: 2 . 1, es: 3 . 0);
+/// State only desktop OpenGL has
+const DESKTOP: Requires = requires!(gl: 2 . 1);
+/// Compute shader limits
+const COMPUTE: Requires = requires!(gl: 4 . 3, es: 3 . 1; ARB_compute_shader);
+
+impl Requires {
+	fn met(&self, gl_version: &GlVersion) -> bool {
+		gl_version.at_least(self.gl, self.es)
+			|| self.extensions.iter().any(|extension| gl_version.has_extension(extension))
+	}
+}
+
+impl fmt::Display for Requires {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let versions = [("OpenGL", self.gl), ("OpenGL ES", self.es)];
+		let versions = versions.into_iter().filter_map(|(api, version)| {
+			version.map(|(major, minor)| format!("{api} {major}.{minor}"))
+		});
+		let extensions = self.extensions.iter().map(|extension| extension.name.to_string());
+
+		write!(f, "{}", versions.chain(extensions).collect::<Vec<_>>().join(" or "))
+	}
+}
+
+/// State variable `pname` as queried by `func`, `None` with `GL_INVALID_ENUM` if the context
+/// has no such state
+///
+/// This is the table of every piece of state mock-gl models, gated by the versions providing it.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn get(
+	gl_version: &GlVersion,
+	limits: &Limits,
+	strings: &Strings,
+	error: &mut GLenum,
+	buffer_manager: &BufferManager,
+	vertex_array_manager: &VertexArrayManager,
+	state_manager: &StateManager,
+	framebuffer_manager: &FramebufferManager,
+	renderbuffer_manager: &RenderbufferManager,
+	func: &str,
+	pname: GLenum,
+) -> Option<StateValue> {
+	let render = state_manager.render();
+	let [front, back] = &render.stencil;
+	let (pack, unpack) = (state_manager.pack(), state_manager.unpack());
+	let read_color = || {
+		let read_framebuffer = framebuffer_manager.bound(FramebufferTarget::READ_FRAMEBUFFER);
+		let format = framebuffer_manager.attachment_format(
+			renderbuffer_manager,
+			read_framebuffer,
+			Attachment::Color(0),
+		);
+		implementation_read_format(format)
+	};
+
+	if let Some(cap) = Capability::from_gl(pname) {
+		if !cap.supported(gl_version) {
+			*error = gl::INVALID_ENUM;
+			cap.check_version(gl_version);
+			return None
+		}
+		return Some(StateValue::boolean(state_manager.enabled(cap)))
+	}
+	if let Some(target) = BufferBinding::from_binding(pname) {
+		if !target.supported(gl_version) {
+			*error = gl::INVALID_ENUM;
+			target.check_version(gl_version);
+			return None
+		}
+		return Some(StateValue::int(buffer_manager.bound(vertex_array_manager, target)))
+	}
+
+	let (requires, value) = match pname {
+		gl::MAJOR_VERSION => (requires!(gl: 3 . 0, es: 3 . 0), StateValue::int(gl_version.major)),
+		gl::MINOR_VERSION => (requires!(gl: 3 . 0, es: 3 . 0), StateValue::int(gl_version.minor)),
+		gl::CONTEXT_PROFILE_MASK => {
+			let mask = match gl_version.profile {
+				Profile::Core => gl::CONTEXT_CORE_PROFILE_BIT,
+				Profile::Compatibility => gl::CONTEXT_COMPATIBILITY_PROFILE_BIT,
+			};
+			(requires!(gl: 3 . 2), StateValue::int(mask))
+		},
+		// mock contexts are never created with debug, forward compatible or robust flags
+		gl::CONTEXT_FLAGS => (requires!(gl: 3 . 0, es: 3 . 2), StateValue::int(0)),
+		gl::NUM_EXTENSIONS => (
+			requires!(gl: 3 . 0, es: 3 . 0),
+			StateValue::int(strings.extension_count() as u32),
+		),
+
+		// buffers and vertex arrays
+		gl::VERTEX_ARRAY_BINDING => (
+			requires!(gl: 3 . 0, es: 3 . 0; ARB_vertex_array_object | OES_vertex_array_object),
+			StateValue::int(vertex_array_manager.bound_id()),
+		),
+		gl::MAX_VERTEX_ATTRIBS => (ALL, StateValue::int(limits.max_vertex_attribs)),
+		gl::MAX_VERTEX_ATTRIB_BINDINGS => (
+			requires!(gl: 4 . 3, es: 3 . 1; ARB_vertex_attrib_binding),
+			StateValue::int(limits.max_vertex_attrib_bindings),
+		),
+		gl::MAX_VERTEX_ATTRIB_RELATIVE_OFFSET => (
+			requires!(gl: 4 . 3, es: 3 . 1; ARB_vertex_attrib_binding),
+			StateValue::int(limits.max_vertex_attrib_relative_offset),
+		),
+		gl::MAX_VERTEX_ATTRIB_STRIDE => (
+			requires!(gl: 4 . 4, es: 3 . 1),
+			StateValue::int(limits.max_vertex_attrib_stride),
+		),
+		gl::PRIMITIVE_RESTART_INDEX =>
+			(requires!(gl: 3 . 1), StateValue::int(state_manager.primitive_restart_index())),
+
+		// framebuffers and renderbuffers, GL_DRAW_FRAMEBUFFER_BINDING is also GL_FRAMEBUFFER_BINDING
+		gl::DRAW_FRAMEBUFFER_BINDING => (
+			ALL,
+			StateValue::int(framebuffer_manager.bound(FramebufferTarget::DRAW_FRAMEBUFFER)),
+		),
+		gl::READ_FRAMEBUFFER_BINDING => (
+			requires!(gl: 3 . 0, es: 3 . 0; ARB_framebuffer_object),
+			StateValue::int(framebuffer_manager.bound(FramebufferTarget::READ_FRAMEBUFFER)),
+		),
+		gl::RENDERBUFFER_BINDING => (
+			requires!(gl: 3 . 0, es: 2 . 0; ARB_framebuffer_object),
+			StateValue::int(renderbuffer_manager.bound()),
+		),
+		gl::MAX_RENDERBUFFER_SIZE => (
+			requires!(gl: 3 . 0, es: 2 . 0; ARB_framebuffer_object),
+			StateValue::int(limits.max_renderbuffer_size),
+		),
+		gl::MAX_SAMPLES => (
+			requires!(gl: 3 . 0, es: 3 . 0; ARB_framebuffer_object),
+			StateValue::int(limits.max_samples),
+		),
+		gl::MAX_COLOR_ATTACHMENTS => (
+			requires!(gl: 3 . 0, es: 3 . 0; ARB_framebuffer_object),
+			StateValue::int(limits.max_color_attachments),
+		),
+		gl::MAX_DRAW_BUFFERS => (ES3, StateValue::int(limits.max_draw_buffers)),
+		gl::SAMPLES | gl::SAMPLE_BUFFERS => {
+			let draw_framebuffer = framebuffer_manager.bound(FramebufferTarget::DRAW_FRAMEBUFFER);
+			let samples = framebuffer_manager.samples(renderbuffer_manager, draw_framebuffer);
+			match pname {
+				gl::SAMPLES => (ALL, StateValue::int(samples)),
+				_ => (ALL, StateValue::int(samples > 0)),
+			}
+		},
+		gl::DOUBLEBUFFER => {
+			let draw_framebuffer = framebuffer_manager.bound(FramebufferTarget::DRAW_FRAMEBUFFER);
+			let double_buffered = framebuffer_manager.surface().double_buffered;
+			(DESKTOP, StateValue::boolean(draw_framebuffer == 0 && double_buffered))
+		},
+		gl::IMPLEMENTATION_COLOR_READ_FORMAT => (
+			requires!(gl: 4 . 1, es: 2 . 0; ARB_ES2_compatibility),
+			StateValue::int(read_color().0.to_gl()),
+		),
+		gl::IMPLEMENTATION_COLOR_READ_TYPE => (
+			requires!(gl: 4 . 1, es: 2 . 0; ARB_ES2_compatibility),
+			StateValue::int(read_color().1.to_gl()),
+		),
+
+		// per-fragment operations, draw buffer 0 answering for the indexed state
+		gl::BLEND_SRC_RGB
+		| gl::BLEND_DST_RGB
+		| gl::BLEND_SRC_ALPHA
+		| gl::BLEND_DST_ALPHA
+		| gl::BLEND_EQUATION_RGB
+		| gl::BLEND_EQUATION_ALPHA
+		| gl::COLOR_WRITEMASK => (ALL, state_manager.indexed_state(pname, 0)?),
+		gl::BLEND_COLOR =>
+			(ALL, StateValue::Normalized(render.blend_color.map(f64::from).to_vec())),
+		gl::DEPTH_FUNC => (ALL, StateValue::int(render.depth_func.to_gl())),
+		gl::DEPTH_WRITEMASK => (ALL, StateValue::boolean(render.depth_mask)),
+		gl::STENCIL_FUNC => (ALL, StateValue::int(front.func.to_gl())),
+		gl::STENCIL_REF => (ALL, StateValue::int(front.reference)),
+		gl::STENCIL_VALUE_MASK => (ALL, StateValue::int(front.value_mask as GLint)),
+		gl::STENCIL_FAIL => (ALL, StateValue::int(front.fail.to_gl())),
+		gl::STENCIL_PASS_DEPTH_FAIL => (ALL, StateValue::int(front.depth_fail.to_gl())),
+		gl::STENCIL_PASS_DEPTH_PASS => (ALL, StateValue::int(front.depth_pass.to_gl())),
+		gl::STENCIL_WRITEMASK => (ALL, StateValue::int(front.writemask as GLint)),
+		gl::STENCIL_BACK_FUNC => (ALL, StateValue::int(back.func.to_gl())),
+		gl::STENCIL_BACK_REF => (ALL, StateValue::int(back.reference)),
+		gl::STENCIL_BACK_VALUE_MASK => (ALL, StateValue::int(back.value_mask as GLint)),
+		gl::STENCIL_BACK_FAIL => (ALL, StateValue::int(back.fail.to_gl())),
+		gl::STENCIL_BACK_PASS_DEPTH_FAIL => (ALL, StateValue::int(back.depth_fail.to_gl())),
+		gl::STENCIL_BACK_PASS_DEPTH_PASS => (ALL, StateValue::int(back.depth_pass.to_gl())),
+		gl::STENCIL_BACK_WRITEMASK => (ALL, StateValue::int(back.writemask as GLint)),
+
+		// rasterization
+		gl::CULL_FACE_MODE => (ALL, StateValue::int(render.cull_face.to_gl())),
+		gl::FRONT_FACE => (ALL, StateValue::int(render.front_face.to_gl())),
+		gl::VIEWPORT | gl::SCISSOR_BOX | gl::DEPTH_RANGE =>
+			(ALL, state_manager.indexed_state(pname, 0)?),
+		gl::MAX_VIEWPORT_DIMS =>
+			(ALL, StateValue::Integer(limits.max_viewport_dims.map(i64::from).to_vec())),
+		gl::MAX_VIEWPORTS => (
+			requires!(gl: 4 . 1; ARB_viewport_array | OES_viewport_array),
+			StateValue::int(limits.max_viewports),
+		),
+		gl::VIEWPORT_BOUNDS_RANGE => {
+			let bounds = limits.viewport_bounds().map(f64::from);
+			(
+				requires!(gl: 4 . 1; ARB_viewport_array | OES_viewport_array),
+				StateValue::Float(bounds.to_vec()),
+			)
+		},
+		gl::CLIP_ORIGIN => (
+			requires!(gl: 4 . 5; ARB_clip_control | EXT_clip_control),
+			StateValue::int(render.clip_origin.to_gl()),
+		),
+		gl::CLIP_DEPTH_MODE => (
+			requires!(gl: 4 . 5; ARB_clip_control | EXT_clip_control),
+			StateValue::int(render.clip_depth_mode.to_gl()),
+		),
+
+		// textures, programs and compute, whose limits are only reported
+		gl::MAX_TEXTURE_SIZE => (ALL, StateValue::int(limits.max_texture_size)),
+		gl::MAX_TEXTURE_IMAGE_UNITS => (ALL, StateValue::int(limits.max_texture_image_units)),
+		gl::MAX_COMBINED_TEXTURE_IMAGE_UNITS =>
+			(ALL, StateValue::int(limits.max_combined_texture_image_units)),
+		gl::MAX_VERTEX_UNIFORM_VECTORS => (
+			requires!(gl: 4 . 1, es: 2 . 0; ARB_ES2_compatibility),
+			StateValue::int(limits.max_vertex_uniform_vectors),
+		),
+		gl::MAX_FRAGMENT_UNIFORM_VECTORS => (
+			requires!(gl: 4 . 1, es: 2 . 0; ARB_ES2_compatibility),
+			StateValue::int(limits.max_fragment_uniform_vectors),
+		),
+		gl::MAX_UNIFORM_BLOCK_SIZE =>
+			(requires!(gl: 3 . 1, es: 3 . 0), StateValue::int(limits.max_uniform_block_size)),
+		gl::MAX_COMPUTE_WORK_GROUP_INVOCATIONS =>
+			(COMPUTE, StateValue::int(limits.max_compute_work_group_invocations)),
+
+		// clears
+		gl::COLOR_CLEAR_VALUE =>
+			(ALL, StateValue::Normalized(state_manager.clear_color().map(f64::from).to_vec())),
+		gl::DEPTH_CLEAR_VALUE =>
+			(ALL, StateValue::Normalized(vec![state_manager.clear_depth() as f64])),
+		gl::STENCIL_CLEAR_VALUE => (ALL, StateValue::int(state_manager.clear_stencil())),
+
+		// pixel storage, OpenGL ES 2.0 only has the alignments
+		gl::PACK_ALIGNMENT => (ALL, StateValue::int(pack.alignment)),
+		gl::PACK_ROW_LENGTH => (ES3, StateValue::int(pack.row_length)),
+		gl::PACK_SKIP_PIXELS => (ES3, StateValue::int(pack.skip_pixels)),
+		gl::PACK_SKIP_ROWS => (ES3, StateValue::int(pack.skip_rows)),
+		gl::PACK_IMAGE_HEIGHT => (ES3, StateValue::int(pack.image_height)),
+		gl::PACK_SKIP_IMAGES => (ES3, StateValue::int(pack.skip_images)),
+		gl::UNPACK_ALIGNMENT => (ALL, StateValue::int(unpack.alignment)),
+		gl::UNPACK_ROW_LENGTH => (ES3, StateValue::int(unpack.row_length)),
+		gl::UNPACK_SKIP_PIXELS => (ES3, StateValue::int(unpack.skip_pixels)),
+		gl::UNPACK_SKIP_ROWS => (ES3, StateValue::int(unpack.skip_rows)),
+		gl::UNPACK_IMAGE_HEIGHT => (ES3, StateValue::int(unpack.image_height)),
+		gl::UNPACK_SKIP_IMAGES => (ES3, StateValue::int(unpack.skip_images)),
+
+		_ => {
+			*error = gl::INVALID_ENUM;
+			error!("mock-gl does not support {} target {}", func, pname);
+			return None
+		},
+	};
+
+	if !requires.met(gl_version) {
+		*error = gl::INVALID_ENUM;
+		error!("{} target {} requires {}", func, pname, requires);
+		return None
+	}
+
+	Some(value)
+}
+
+/// Indexed state variable `pname` as queried by `glGetIntegeri_v` and friends, the work group
+/// limits of one dimension or the state of a draw buffer or viewport
+pub(crate) fn get_indexed(
+	gl_version: &GlVersion,
+	limits: &Limits,
+	error: &mut GLenum,
+	state_manager: &StateManager,
+	func: &str,
+	pname: GLenum,
+	index: GLuint,
+) -> Option<StateValue> {
+	let dimensions = match pname {
+		gl::MAX_COMPUTE_WORK_GROUP_COUNT => limits.max_compute_work_group_count,
+		gl::MAX_COMPUTE_WORK_GROUP_SIZE => limits.max_compute_work_group_size,
+		_ => return state_manager.get_indexed(limits, error, func, pname, index),
+	};
+
+	if !COMPUTE.met(gl_version) {
+		*error = gl::INVALID_ENUM;
+		error!("{} target {} requires {}", func, pname, COMPUTE);
+		return None
+	}
+
+	match dimensions.get(index as usize) {
+		Some(value) => Some(StateValue::int(*value)),
+		None => {
+			*error = gl::INVALID_VALUE;
+			error!("{} called with dimension {} of work group limit {}", func, index, pname);
+			None
+		},
+	}
+}
+
+impl MockContextData {
+	/// Answer the `glGet*` query `func` of `pname`, handing the value to `write` unless the
+	/// context has no such state
+	pub(crate) fn query(&mut self, func: &str, pname: GLenum, write: impl FnOnce(StateValue)) {
+		let value = get(
+			&self.gl_version,
+			&self.limits,
+			&self.strings,
+			&mut self.error,
+			&self.buffer_manager,
+			&self.vertex_array_manager,
+			&self.state_manager,
+			&self.framebuffer_manager,
+			&self.renderbuffer_manager,
+			func,
+			pname,
+		);
+		if let Some(value) = value {
+			write(value);
+		}
+	}
+}
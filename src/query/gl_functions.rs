@@ -0,0 +1,172 @@
+use std::ptr;
+
+use gl::types::{GLboolean, GLdouble, GLenum, GLfloat, GLint, GLint64, GLubyte, GLuint};
+
+use crate::{function_mapping::gl_functions, query};
+
+gl_functions! {
+	fn glGetBooleanv(pname: GLenum, data: *mut GLboolean);
+	require gl 2 . 1;
+	require es 2 . 0;
+	take context
+	{
+		context.query("glGetBooleanv", pname, |value| {
+			let booleans = value.booleans();
+			ptr::copy_nonoverlapping(booleans.as_ptr(), data, booleans.len());
+		});
+	}
+
+	fn glGetIntegerv(pname: GLenum, data: *mut GLint);
+	require gl 2 . 1;
+	require es 2 . 0;
+	take context
+	{
+		context.query("glGetIntegerv", pname, |value| {
+			let ints = value.ints();
+			ptr::copy_nonoverlapping(ints.as_ptr(), data, ints.len());
+		});
+	}
+
+	fn glGetInteger64v(pname: GLenum, data: *mut GLint64);
+	require gl 3 . 2;
+	require es 3 . 0;
+	take context
+	{
+		context.query("glGetInteger64v", pname, |value| {
+			let ints = value.int64s();
+			ptr::copy_nonoverlapping(ints.as_ptr(), data, ints.len());
+		});
+	}
+
+	fn glGetFloatv(pname: GLenum, data: *mut GLfloat);
+	require gl 2 . 1;
+	require es 2 . 0;
+	take context
+	{
+		context.query("glGetFloatv", pname, |value| {
+			let floats = value.floats();
+			ptr::copy_nonoverlapping(floats.as_ptr(), data, floats.len());
+		});
+	}
+
+	fn glGetDoublev(pname: GLenum, data: *mut GLdouble);
+	require gl 2 . 1;
+	take context
+	{
+		context.query("glGetDoublev", pname, |value| {
+			let doubles = value.doubles();
+			ptr::copy_nonoverlapping(doubles.as_ptr(), data, doubles.len());
+		});
+	}
+
+	fn glGetBooleani_v(target: GLenum, index: GLuint, data: *mut GLboolean);
+	require gl 3 . 0;
+	require es 3 . 1;
+	take [gl_version, limits, error, state_manager]
+	{
+		if let Some(value) = query::get_indexed(
+			gl_version,
+			limits,
+			error,
+			state_manager,
+			"glGetBooleani_v",
+			target,
+			index,
+		) {
+			let booleans = value.booleans();
+			ptr::copy_nonoverlapping(booleans.as_ptr(), data, booleans.len());
+		}
+	}
+
+	fn glGetIntegeri_v(target: GLenum, index: GLuint, data: *mut GLint);
+	require gl 3 . 0;
+	require es 3 . 0;
+	take [gl_version, limits, error, state_manager]
+	{
+		if let Some(value) = query::get_indexed(
+			gl_version,
+			limits,
+			error,
+			state_manager,
+			"glGetIntegeri_v",
+			target,
+			index,
+		) {
+			let ints = value.ints();
+			ptr::copy_nonoverlapping(ints.as_ptr(), data, ints.len());
+		}
+	}
+
+	fn glGetInteger64i_v(target: GLenum, index: GLuint, data: *mut GLint64);
+	require gl 3 . 2;
+	require es 3 . 0;
+	take [gl_version, limits, error, state_manager]
+	{
+		if let Some(value) = query::get_indexed(
+			gl_version,
+			limits,
+			error,
+			state_manager,
+			"glGetInteger64i_v",
+			target,
+			index,
+		) {
+			let ints = value.int64s();
+			ptr::copy_nonoverlapping(ints.as_ptr(), data, ints.len());
+		}
+	}
+
+	fn glGetFloati_v(target: GLenum, index: GLuint, data: *mut GLfloat);
+	require gl 4 . 1;
+	require ext ARB_viewport_array | OES_viewport_array;
+	take [gl_version, limits, error, state_manager]
+	{
+		if let Some(value) = query::get_indexed(
+			gl_version,
+			limits,
+			error,
+			state_manager,
+			"glGetFloati_v",
+			target,
+			index,
+		) {
+			let floats = value.floats();
+			ptr::copy_nonoverlapping(floats.as_ptr(), data, floats.len());
+		}
+	}
+
+	fn glGetDoublei_v(target: GLenum, index: GLuint, data: *mut GLdouble);
+	require gl 4 . 1;
+	require ext ARB_viewport_array;
+	take [gl_version, limits, error, state_manager]
+	{
+		if let Some(value) = query::get_indexed(
+			gl_version,
+			limits,
+			error,
+			state_manager,
+			"glGetDoublei_v",
+			target,
+			index,
+		) {
+			let doubles = value.doubles();
+			ptr::copy_nonoverlapping(doubles.as_ptr(), data, doubles.len());
+		}
+	}
+
+	fn glGetString(name: GLenum) -> *const GLubyte;
+	require gl 2 . 1;
+	require es 2 . 0;
+	take [gl_version, error, strings]
+	{
+		strings.get(gl_version, error, name)
+	}
+
+	fn glGetStringi(name: GLenum, index: GLuint) -> *const GLubyte;
+	require gl 3 . 0;
+	require es 3 . 0;
+	take [error, strings]
+	{
+		strings.get_indexed(error, name, index)
+	}
+}
@@ -0,0 +1,211 @@
+use std::ffi::CString;
+
+use gl::types::{GLenum, GLubyte, GLuint};
+
+use crate::{
+	debug,
+	error,
+	version::{GlVersion, Profile, VersionType},
+	MockContextRef,
+};
+
+/// Vendor and renderer reported unless overridden
+pub const DEFAULT_VENDOR: &str = "mock-gl";
+pub const DEFAULT_RENDERER: &str = "mock-gl renderer";
+
+/// Strings returned by `glGetString` and `glGetStringi`, kept alive for the pointers handed out
+pub struct Strings {
+	vendor: CString,
+	renderer: CString,
+	version: CString,
+	shading_language_version: CString,
+	extensions: Vec<CString>,
+	/// Extensions joined by spaces, as `glGetString(GL_EXTENSIONS)` returns them
+	extension_list: CString,
+}
+
+/// Version of the shading language shipped with `gl_version`
+fn shading_language_version(gl_version: &GlVersion) -> (u8, u8) {
+	match (gl_version.ty, gl_version.major, gl_version.minor) {
+		(VersionType::ES, 2, _) => (1, 0),
+		(VersionType::ES, major, minor) => (major, minor * 10),
+		// OpenGL 3.3 aligned GLSL with the version of OpenGL
+		(VersionType::GL, major, minor) if (major, minor) >= (3, 3) => (major, minor * 10),
+		(VersionType::GL, 3, minor) => (1, 30 + minor * 10),
+		(VersionType::GL, 2, 1) => (1, 20),
+		(VersionType::GL, ..) => (1, 10),
+	}
+}
+
+/// Names of the extensions of `gl_version` as listed by `GL_EXTENSIONS`, without duplicates
+fn extension_names(gl_version: &GlVersion) -> Vec<String> {
+	let mut names: Vec<String> = Vec::new();
+	for extension in &gl_version.extensions {
+		let name = format!("GL_{}", extension.name);
+		if !names.contains(&name) {
+			names.push(name);
+		}
+	}
+
+	names
+}
+
+fn c_string(string: impl Into<Vec<u8>>) -> CString {
+	CString::new(string).expect("mock-gl strings must not contain nul bytes")
+}
+
+impl Strings {
+	/// Strings describing `gl_version`, formatted like drivers of its API do
+	pub fn new(gl_version: &GlVersion) -> Self {
+		let package = concat!("mock-gl ", env!("CARGO_PKG_VERSION"));
+		let (glsl_major, glsl_minor) = shading_language_version(gl_version);
+		let (version, shading_language_version) = match gl_version.ty {
+			VersionType::ES => (
+				format!("OpenGL ES {}.{} {package}", gl_version.major, gl_version.minor),
+				format!("OpenGL ES GLSL ES {glsl_major}.{glsl_minor:02}"),
+			),
+			VersionType::GL => {
+				let profile = match gl_version.profile {
+					_ if (gl_version.major, gl_version.minor) < (3, 2) => "",
+					Profile::Core => " (Core Profile)",
+					Profile::Compatibility => " (Compatibility Profile)",
+				};
+				(
+					format!("{}.{}{profile} {package}", gl_version.major, gl_version.minor),
+					format!("{glsl_major}.{glsl_minor:02}"),
+				)
+			},
+		};
+
+		let names = extension_names(gl_version);
+		Self {
+			vendor: c_string(DEFAULT_VENDOR),
+			renderer: c_string(DEFAULT_RENDERER),
+			version: c_string(version),
+			shading_language_version: c_string(shading_language_version),
+			extension_list: c_string(names.join(" ")),
+			extensions: names.into_iter().map(c_string).collect(),
+		}
+	}
+
+	pub fn vendor(&self) -> &str {
+		self.vendor.to_str().unwrap()
+	}
+
+	pub fn renderer(&self) -> &str {
+		self.renderer.to_str().unwrap()
+	}
+
+	pub fn version(&self) -> &str {
+		self.version.to_str().unwrap()
+	}
+
+	pub fn shading_language_version(&self) -> &str {
+		self.shading_language_version.to_str().unwrap()
+	}
+
+	/// Number of extensions, as `GL_NUM_EXTENSIONS` reports it
+	pub fn extension_count(&self) -> usize {
+		self.extensions.len()
+	}
+
+	/// Report `GL_VERSION` as a driver did, instead of describing the context version
+	pub(crate) fn set_version(&mut self, version: &str) {
+		set_string(&mut self.version, "GL_VERSION", version);
+	}
+
+	/// Report `GL_SHADING_LANGUAGE_VERSION` as a driver did
+	pub(crate) fn set_shading_language_version(&mut self, version: &str) {
+		set_string(&mut self.shading_language_version, "GL_SHADING_LANGUAGE_VERSION", version);
+	}
+
+	/// List the extensions a driver did, including those mock-gl doesn't know, instead of the
+	/// ones of the context version
+	pub(crate) fn set_extensions(&mut self, names: &[String]) {
+		let mut extensions = Vec::new();
+		for name in names {
+			match CString::new(name.as_str()) {
+				Ok(name) => extensions.push(name),
+				Err(_) => {
+					error!("extension {:?} can't be listed, it contains a nul byte", name);
+				},
+			}
+		}
+
+		let list = extensions.iter().map(|name| name.to_str().unwrap()).collect::<Vec<_>>();
+		self.extension_list = c_string(list.join(" "));
+		debug!("set GL_EXTENSIONS to {}", list.join(" "));
+		self.extensions = extensions;
+	}
+
+	/// String `name` as returned by `glGetString`, null with `GL_INVALID_ENUM` if there is none
+	///
+	/// Core profiles only list extensions through `glGetStringi`.
+	pub fn get(&self, gl_version: &GlVersion, error: &mut GLenum, name: GLenum) -> *const GLubyte {
+		let string = match name {
+			gl::VENDOR => &self.vendor,
+			gl::RENDERER => &self.renderer,
+			gl::VERSION => &self.version,
+			gl::SHADING_LANGUAGE_VERSION => &self.shading_language_version,
+			gl::EXTENSIONS if !gl_version.is_core() => &self.extension_list,
+			gl::EXTENSIONS => {
+				*error = gl::INVALID_ENUM;
+				error!("glGetString called with GL_EXTENSIONS in a core profile, use glGetStringi");
+				return std::ptr::null()
+			},
+			_ => {
+				*error = gl::INVALID_ENUM;
+				error!("glGetString called with invalid name {}", name);
+				return std::ptr::null()
+			},
+		};
+
+		string.as_ptr() as *const GLubyte
+	}
+
+	/// Extension `index` as returned by `glGetStringi`
+	pub fn get_indexed(&self, error: &mut GLenum, name: GLenum, index: GLuint) -> *const GLubyte {
+		if name != gl::EXTENSIONS {
+			*error = gl::INVALID_ENUM;
+			error!("glGetStringi called with invalid name {}", name);
+			return std::ptr::null()
+		}
+
+		match self.extensions.get(index as usize) {
+			Some(extension) => extension.as_ptr() as *const GLubyte,
+			None => {
+				*error = gl::INVALID_VALUE;
+				error!(
+					"glGetStringi called with extension {}, but GL_NUM_EXTENSIONS is {}",
+					index,
+					self.extensions.len()
+				);
+				std::ptr::null()
+			},
+		}
+	}
+}
+
+/// Replace the string `name` with `value`, keeping the previous one if `value` has a nul byte
+fn set_string(string: &mut CString, name: &str, value: &str) {
+	match CString::new(value) {
+		Ok(value) => *string = value,
+		Err(_) => {
+			error!("{} can't be set to {:?}, which contains a nul byte", name, value);
+			return
+		},
+	}
+	debug!("set {} to {}", name, value);
+}
+
+impl MockContextRef {
+	/// Set the `GL_VENDOR` string, invalidating pointers to the previous one
+	pub fn set_vendor(&self, vendor: &str) {
+		set_string(&mut crate::context().strings.vendor, "GL_VENDOR", vendor);
+	}
+
+	/// Set the `GL_RENDERER` string, invalidating pointers to the previous one
+	pub fn set_renderer(&self, renderer: &str) {
+		set_string(&mut crate::context().strings.renderer, "GL_RENDERER", renderer);
+	}
+}
@@ -0,0 +1,230 @@
+use std::ffi::{c_char, CStr};
+
+use gl::types::{GLboolean, GLdouble, GLfloat, GLint, GLint64, GLuint};
+
+use crate::{
+	query::{StateValue, DEFAULT_RENDERER, DEFAULT_VENDOR},
+	test::{
+		get_integer,
+		get_string,
+		gl45_core,
+		test_harness,
+		test_harness_context,
+		test_harness_handling,
+		test_harness_with,
+	},
+	version::{ext, Profile, VersionType},
+	ErrorHandling,
+	GlVersion,
+};
+
+#[test]
+fn conversions() {
+	let booleans = StateValue::Boolean(vec![true, false]);
+	assert_eq!(booleans.ints(), [1, 0]);
+	assert_eq!(booleans.doubles(), [1.0, 0.0]);
+
+	let ints = StateValue::Integer(vec![0, -3, i64::MAX]);
+	assert_eq!(ints.booleans(), [gl::FALSE, gl::TRUE, gl::TRUE]);
+	assert_eq!(ints.ints(), [0, -3, GLint::MAX]);
+	assert_eq!(ints.int64s(), [0, -3, i64::MAX]);
+
+	// floats are rounded, and clamped to the integer range
+	let floats = StateValue::Float(vec![0.0, 1.5, -2.4, 1e20]);
+	assert_eq!(floats.booleans(), [gl::FALSE, gl::TRUE, gl::TRUE, gl::TRUE]);
+	assert_eq!(floats.ints(), [0, 2, -2, GLint::MAX]);
+	assert_eq!(floats.floats(), [0.0, 1.5, -2.4, 1e20]);
+
+	// normalized values span the integer range
+	let normalized = StateValue::Normalized(vec![1.0, 0.5, -1.0, 0.0]);
+	assert_eq!(normalized.ints(), [GLint::MAX, GLint::MAX / 2, GLint::MIN, 0]);
+	let int64s = normalized.int64s();
+	assert_eq!((int64s[0], int64s[2]), (GLint64::MAX, GLint64::MIN));
+	assert_eq!(normalized.floats(), [1.0, 0.5, -1.0, 0.0]);
+}
+
+#[test]
+fn typed_queries() {
+	test_harness(gl45_core(), || unsafe {
+		gl::Enable(gl::DEPTH_TEST);
+		gl::ClearColor(1.0, 0.5, 0.0, 2.0);
+		gl::Viewport(1, 2, 3, 4);
+		gl::ColorMask(gl::TRUE, gl::FALSE, gl::TRUE, gl::FALSE);
+
+		let mut boolean: GLboolean = gl::FALSE;
+		gl::GetBooleanv(gl::DEPTH_TEST, &mut boolean);
+		assert_eq!(boolean, gl::TRUE);
+		let mut int = 0;
+		gl::GetIntegerv(gl::DEPTH_TEST, &mut int);
+		assert_eq!(int, 1);
+
+		let mut mask = [gl::FALSE; 4];
+		gl::GetBooleanv(gl::COLOR_WRITEMASK, mask.as_mut_ptr());
+		assert_eq!(mask, [gl::TRUE, gl::FALSE, gl::TRUE, gl::FALSE]);
+
+		// colors keep their values as floats, and are clamped when converted to integers
+		let mut color: [GLfloat; 4] = [0.0; 4];
+		gl::GetFloatv(gl::COLOR_CLEAR_VALUE, color.as_mut_ptr());
+		assert_eq!(color, [1.0, 0.5, 0.0, 2.0]);
+		let mut ints = [0; 4];
+		gl::GetIntegerv(gl::COLOR_CLEAR_VALUE, ints.as_mut_ptr());
+		assert_eq!(ints, [GLint::MAX, GLint::MAX / 2, 0, GLint::MAX]);
+
+		let mut depth: GLint64 = 0;
+		gl::GetInteger64v(gl::DEPTH_CLEAR_VALUE, &mut depth);
+		assert_eq!(depth, GLint64::MAX);
+
+		let mut viewport: [GLdouble; 4] = [0.0; 4];
+		gl::GetDoublev(gl::VIEWPORT, viewport.as_mut_ptr());
+		assert_eq!(viewport, [1.0, 2.0, 3.0, 4.0]);
+
+		// enums convert to their values
+		let mut func: GLfloat = 0.0;
+		gl::GetFloatv(gl::DEPTH_FUNC, &mut func);
+		assert_eq!(func, gl::LESS as GLfloat);
+
+		let mut range: [GLint; 2] = [0; 2];
+		gl::GetIntegerv(gl::DEPTH_RANGE, range.as_mut_ptr());
+		assert_eq!(range, [0, GLint::MAX]);
+	})
+}
+
+#[test]
+fn version_gating() {
+	let es2 = GlVersion::from_version(VersionType::ES, 2, 0);
+	test_harness_handling(es2, ErrorHandling::DoNotPanic, || unsafe {
+		let mut int = 0;
+		gl::GetIntegerv(gl::UNPACK_ALIGNMENT, &mut int);
+		assert_eq!((gl::GetError(), int), (gl::NO_ERROR, 4));
+
+		for pname in [
+			gl::UNPACK_ROW_LENGTH,
+			gl::MAX_DRAW_BUFFERS,
+			gl::VERTEX_ARRAY_BINDING,
+		] {
+			gl::GetIntegerv(pname, &mut int);
+			assert_eq!(gl::GetError(), gl::INVALID_ENUM);
+		}
+	});
+
+	let es3 = GlVersion::from_version(VersionType::ES, 3, 2);
+	test_harness_handling(es3, ErrorHandling::DoNotPanic, || unsafe {
+		let mut int = -1;
+		for pname in [
+			gl::DOUBLEBUFFER,
+			gl::PRIMITIVE_RESTART_INDEX,
+			gl::PRIMITIVE_RESTART,
+			gl::QUERY_BUFFER_BINDING,
+			gl::CLIP_ORIGIN,
+		] {
+			gl::GetIntegerv(pname, &mut int);
+			assert_eq!(gl::GetError(), gl::INVALID_ENUM);
+		}
+		assert_eq!(int, -1);
+
+		gl::GetIntegerv(gl::TEXTURE_BINDING_2D, &mut int);
+		assert_eq!(gl::GetError(), gl::INVALID_ENUM);
+	});
+
+	let gl33 = GlVersion::from_version(VersionType::GL, 3, 3).with_profile(Profile::Core);
+	test_harness_handling(gl33, ErrorHandling::DoNotPanic, || unsafe {
+		let mut bounds = [0.0; 2];
+		gl::GetFloatv(gl::VIEWPORT_BOUNDS_RANGE, bounds.as_mut_ptr());
+		assert_eq!(gl::GetError(), gl::INVALID_ENUM);
+		gl::GetDoublev(gl::MAX_VIEWPORT_DIMS, [0.0; 2].as_mut_ptr());
+		assert_eq!(gl::GetError(), gl::NO_ERROR);
+	});
+}
+
+#[test]
+fn context_queries() {
+	test_harness(gl45_core(), || unsafe {
+		assert_eq!(get_integer(gl::MAJOR_VERSION), 4);
+		assert_eq!(get_integer(gl::MINOR_VERSION), 5);
+		assert_eq!(get_integer(gl::CONTEXT_PROFILE_MASK), gl::CONTEXT_CORE_PROFILE_BIT as GLint);
+		assert_eq!(get_integer(gl::CONTEXT_FLAGS), 0);
+	});
+
+	let gl33 = GlVersion::from_version(VersionType::GL, 3, 3);
+	test_harness(gl33, || unsafe {
+		let mask = gl::CONTEXT_COMPATIBILITY_PROFILE_BIT as GLint;
+		assert_eq!(get_integer(gl::CONTEXT_PROFILE_MASK), mask);
+	});
+
+	let es3 = GlVersion::from_version(VersionType::ES, 3, 1);
+	test_harness_handling(es3, ErrorHandling::DoNotPanic, || unsafe {
+		assert_eq!((get_integer(gl::MAJOR_VERSION), get_integer(gl::MINOR_VERSION)), (3, 1));
+		// ES has no profiles, and context flags only since 3.2
+		get_integer(gl::CONTEXT_PROFILE_MASK);
+		assert_eq!(gl::GetError(), gl::INVALID_ENUM);
+		get_integer(gl::CONTEXT_FLAGS);
+		assert_eq!(gl::GetError(), gl::INVALID_ENUM);
+	});
+}
+
+#[test]
+fn strings() {
+	test_harness_context(gl45_core(), |ctx| unsafe {
+		assert_eq!(get_string(gl::VENDOR), DEFAULT_VENDOR);
+		assert!(get_string(gl::VERSION).starts_with("4.5 (Core Profile) mock-gl"));
+		assert_eq!(get_string(gl::SHADING_LANGUAGE_VERSION), "4.50");
+
+		ctx.set_vendor("Intel");
+		ctx.set_renderer("Mesa Intel(R) UHD Graphics 620 (KBL GT2)");
+		assert_eq!(get_string(gl::VENDOR), "Intel");
+		assert_eq!(get_string(gl::RENDERER), "Mesa Intel(R) UHD Graphics 620 (KBL GT2)");
+
+		let mut count = 0;
+		gl::GetIntegerv(gl::NUM_EXTENSIONS, &mut count);
+		let extensions: Vec<_> = (0..count as GLuint)
+			.map(|i| CStr::from_ptr(gl::GetStringi(gl::EXTENSIONS, i) as *const c_char))
+			.collect();
+		assert!(extensions.contains(&c"GL_ARB_clip_control"));
+	});
+
+	let es3 = GlVersion::new(VersionType::ES, 3, 0, &[&ext::EXT_clip_control]);
+	test_harness(es3, || unsafe {
+		assert!(get_string(gl::VERSION).starts_with("OpenGL ES 3.0 mock-gl"));
+		assert_eq!(get_string(gl::SHADING_LANGUAGE_VERSION), "OpenGL ES GLSL ES 3.00");
+		// ES still lists extensions through glGetString
+		assert!(get_string(gl::EXTENSIONS).split(' ').any(|name| name == "GL_EXT_clip_control"));
+	});
+
+	test_harness(GlVersion::from_version(VersionType::ES, 2, 0), || unsafe {
+		assert_eq!(get_string(gl::SHADING_LANGUAGE_VERSION), "OpenGL ES GLSL ES 1.00");
+	});
+
+	let gl31 = GlVersion::from_version(VersionType::GL, 3, 1);
+	test_harness(gl31, || unsafe {
+		assert!(get_string(gl::VERSION).starts_with("3.1 mock-gl"));
+		assert_eq!(get_string(gl::SHADING_LANGUAGE_VERSION), "1.40");
+	});
+}
+
+#[test]
+fn nul_strings() {
+	let new = || crate::new(gl45_core(), ErrorHandling::DoNotPanic);
+	test_harness_with(new, |ctx| unsafe {
+		ctx.set_vendor("Intel");
+		ctx.set_vendor("Intel\0");
+		ctx.set_renderer("Mesa\0Intel");
+		assert_eq!(get_string(gl::VENDOR), "Intel");
+		assert_eq!(get_string(gl::RENDERER), DEFAULT_RENDERER);
+	});
+}
+
+#[test]
+fn string_errors() {
+	test_harness_handling(gl45_core(), ErrorHandling::DoNotPanic, || unsafe {
+		// core profiles have no extension string
+		assert!(gl::GetString(gl::EXTENSIONS).is_null());
+		assert_eq!(gl::GetError(), gl::INVALID_ENUM);
+		assert!(gl::GetString(gl::BLEND).is_null());
+		assert_eq!(gl::GetError(), gl::INVALID_ENUM);
+
+		assert!(gl::GetStringi(gl::VENDOR, 0).is_null());
+		assert_eq!(gl::GetError(), gl::INVALID_ENUM);
+		assert!(gl::GetStringi(gl::EXTENSIONS, 1000).is_null());
+		assert_eq!(gl::GetError(), gl::INVALID_VALUE);
+	})
+}
@@ -0,0 +1,569 @@
+use std::collections::BTreeMap;
+
+use gl::types::GLuint;
+
+use crate::{
+	debug,
+	draw::{DrawCall, PrimitiveMode},
+	framebuffer::Framebuffer,
+	state::{Capability, ClipDepthMode, ClipOrigin, FrontFace, RenderState},
+	vertex_array::AttribValue,
+	MockContextRef,
+};
+
+#[cfg(test)]
+mod test;
+
+/// Inputs of a vertex shader invocation
+#[derive(Clone, Debug, PartialEq)]
+pub struct VertexInput {
+	/// Vertex id as fetched, `gl_VertexID`
+	pub vertex_id: u32,
+	/// Instance being drawn, not including the base instance, `gl_InstanceID`
+	pub instance_id: u32,
+	attributes: BTreeMap<GLuint, AttribValue>,
+}
+
+impl VertexInput {
+	/// Value of attribute `index`, (0, 0, 0, 1) if its array is disabled
+	pub fn attribute(&self, index: GLuint) -> AttribValue {
+		self.attributes
+			.get(&index)
+			.copied()
+			.unwrap_or(AttribValue::Float([0.0, 0.0, 0.0, 1.0]))
+	}
+}
+
+/// Outputs of a vertex shader invocation
+#[derive(Clone, Debug, PartialEq)]
+pub struct VertexOutput {
+	/// Clip coordinates, `gl_Position`
+	pub position: [f32; 4],
+	/// Values interpolated across each primitive for the fragment shader
+	pub varyings: Vec<f32>,
+}
+
+impl VertexOutput {
+	pub fn new(position: [f32; 4]) -> Self {
+		Self {
+			position,
+			varyings: Vec::new(),
+		}
+	}
+
+	fn lerp(&self, other: &Self, t: f32) -> Self {
+		Self {
+			position: [0, 1, 2, 3]
+				.map(|i| self.position[i] + (other.position[i] - self.position[i]) * t),
+			varyings: self
+				.varyings
+				.iter()
+				.zip(&other.varyings)
+				.map(|(a, b)| a + (b - a) * t)
+				.collect(),
+		}
+	}
+}
+
+/// Inputs of a fragment shader invocation
+#[derive(Clone, Debug, PartialEq)]
+pub struct FragmentInput {
+	/// Window coordinates of the pixel center and the fragment's depth, `gl_FragCoord.xyz`
+	pub coord: [f32; 3],
+	/// `gl_FrontFacing`, always true for points and lines
+	pub front_facing: bool,
+	/// Perspective correct interpolation of the vertex shader's varyings
+	pub varyings: Vec<f32>,
+}
+
+pub type VertexShader = Box<dyn FnMut(&VertexInput) -> VertexOutput + Send>;
+pub type FragmentShader = Box<dyn FnMut(&FragmentInput) -> [f32; 4] + Send>;
+
+/// Source of each vertex's clip coordinates
+pub enum VertexStage {
+	/// Use an attribute's value as the clip coordinates
+	Attribute(GLuint),
+	Shader(VertexShader),
+}
+
+/// Source of each fragment's color
+pub enum FragmentStage {
+	Constant([f32; 4]),
+	Shader(FragmentShader),
+}
+
+/// Reference rasterizer drawing validated draw calls into framebuffer storage
+///
+/// Covers points, lines and triangles with clipping, viewport, scissor, face culling,
+/// stencil and depth tests, blending and color masks of the state each draw was made with.
+/// Adjacency primitives and patches are skipped.
+pub struct Rasterizer {
+	vertex: VertexStage,
+	fragment: FragmentStage,
+}
+
+impl Default for Rasterizer {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl MockContextRef {
+	/// Take clip coordinates from attribute `index`, replacing any vertex shader
+	///
+	/// Attribute 0 is used by default.
+	pub fn set_position_attribute(&self, index: GLuint) {
+		crate::context().rasterizer.vertex = VertexStage::Attribute(index);
+	}
+
+	/// Run `shader` for every vertex drawn
+	///
+	/// The shader runs while the context is locked, so it must not call OpenGL.
+	pub fn set_vertex_shader(
+		&self,
+		shader: impl FnMut(&VertexInput) -> VertexOutput + Send + 'static,
+	) {
+		crate::context().rasterizer.vertex = VertexStage::Shader(Box::new(shader));
+	}
+
+	/// Color every fragment with `color`, replacing any fragment shader
+	///
+	/// Fragments are opaque white by default.
+	pub fn set_fragment_color(&self, color: [f32; 4]) {
+		crate::context().rasterizer.fragment = FragmentStage::Constant(color);
+	}
+
+	/// Run `shader` for every fragment passing the depth test
+	///
+	/// The shader runs while the context is locked, so it must not call OpenGL.
+	pub fn set_fragment_shader(
+		&self,
+		shader: impl FnMut(&FragmentInput) -> [f32; 4] + Send + 'static,
+	) {
+		crate::context().rasterizer.fragment = FragmentStage::Shader(Box::new(shader));
+	}
+}
+
+/// Distance of `position` inside each of the 6 clip planes, negative if outside
+///
+/// The near plane is at z = 0 rather than z = -w with a `GL_ZERO_TO_ONE` depth mode.
+fn plane_distance(position: [f32; 4], plane: usize, depth_mode: ClipDepthMode) -> f32 {
+	if plane == 4 && depth_mode == ClipDepthMode::ZERO_TO_ONE {
+		return position[2]
+	}
+
+	let sign = if plane.is_multiple_of(2) { 1.0 } else { -1.0 };
+	position[3] + sign * position[plane / 2]
+}
+
+/// Clip a convex polygon against the view volume
+fn clip_polygon(mut polygon: Vec<VertexOutput>, depth_mode: ClipDepthMode) -> Vec<VertexOutput> {
+	for plane in 0..6 {
+		let mut clipped = Vec::with_capacity(polygon.len() + 1);
+
+		for (i, a) in polygon.iter().enumerate() {
+			let b = &polygon[(i + 1) % polygon.len()];
+			let da = plane_distance(a.position, plane, depth_mode);
+			let db = plane_distance(b.position, plane, depth_mode);
+
+			if da >= 0.0 {
+				clipped.push(a.clone());
+			}
+			if (da >= 0.0) != (db >= 0.0) {
+				clipped.push(a.lerp(b, da / (da - db)));
+			}
+		}
+
+		polygon = clipped;
+	}
+
+	polygon
+}
+
+/// Clip a line against the view volume
+fn clip_line(
+	mut a: VertexOutput,
+	mut b: VertexOutput,
+	depth_mode: ClipDepthMode,
+) -> Option<(VertexOutput, VertexOutput)> {
+	for plane in 0..6 {
+		let da = plane_distance(a.position, plane, depth_mode);
+		let db = plane_distance(b.position, plane, depth_mode);
+
+		if da < 0.0 && db < 0.0 {
+			return None
+		} else if da < 0.0 {
+			a = a.lerp(&b, da / (da - db));
+		} else if db < 0.0 {
+			b = b.lerp(&a, db / (db - da));
+		}
+	}
+
+	Some((a, b))
+}
+
+/// A vertex after perspective division and the viewport transform
+struct WindowVertex {
+	x: f32,
+	y: f32,
+	z: f32,
+	/// Reciprocal of the clip w, for perspective correct interpolation
+	inv_w: f32,
+	/// Varyings divided by the clip w
+	varyings: Vec<f32>,
+}
+
+impl WindowVertex {
+	/// Transform `vertex` by viewport 0 and the clip control of `state`
+	fn new(vertex: &VertexOutput, state: &RenderState) -> Option<Self> {
+		let [x, y, z, w] = vertex.position;
+		if w <= 0.0 {
+			return None
+		}
+
+		let inv_w = 1.0 / w;
+		let viewport = &state.viewports[0];
+		let y = match state.clip_origin {
+			ClipOrigin::LOWER_LEFT => y * inv_w,
+			ClipOrigin::UPPER_LEFT => -y * inv_w,
+		};
+		let z = match state.clip_depth_mode {
+			ClipDepthMode::NEGATIVE_ONE_TO_ONE => (z * inv_w + 1.0) / 2.0,
+			ClipDepthMode::ZERO_TO_ONE => z * inv_w,
+		};
+		let [near, far] = viewport.depth_range;
+
+		Some(Self {
+			x: viewport.x + (x * inv_w + 1.0) * viewport.width / 2.0,
+			y: viewport.y + (y + 1.0) * viewport.height / 2.0,
+			z: near + (far - near) * z,
+			inv_w,
+			varyings: vertex.varyings.iter().map(|v| v * inv_w).collect(),
+		})
+	}
+}
+
+/// Twice the signed area of `a`, `b`, `p`, positive if `p` is left of `a` to `b`
+fn edge(a: &WindowVertex, b: &WindowVertex, p: (f32, f32)) -> f32 {
+	(b.x - a.x) * (p.1 - a.y) - (b.y - a.y) * (p.0 - a.x)
+}
+
+/// Top-left fill rule for counter-clockwise triangles, so shared edges are drawn once
+fn is_top_left(a: &WindowVertex, b: &WindowVertex) -> bool {
+	(a.y == b.y && b.x < a.x) || b.y < a.y
+}
+
+/// Per-fragment operations on one draw's target
+struct Target<'a> {
+	fragment: &'a mut FragmentStage,
+	state: &'a RenderState,
+	framebuffer: &'a mut Framebuffer,
+}
+
+impl Target<'_> {
+	/// Interpolate `weights` of `vertices` and process the resulting fragment
+	fn fragment(
+		&mut self,
+		x: i64,
+		y: i64,
+		front_facing: bool,
+		vertices: &[&WindowVertex],
+		weights: &[f32],
+	) {
+		if x < 0
+			|| y < 0 || x as usize >= self.framebuffer.width()
+			|| y as usize >= self.framebuffer.height()
+		{
+			return
+		}
+		let (px, py) = (x as usize, y as usize);
+
+		if self.state.enabled(Capability::SCISSOR_TEST)
+			&& !self.state.scissors[0].rect.contains(x as i32, y as i32)
+		{
+			return
+		}
+
+		let z: f32 = vertices.iter().zip(weights).map(|(v, w)| v.z * w).sum();
+
+		// without a stencil buffer the stencil test always passes and nothing is written
+		let stencil = self.state.stencil(front_facing);
+		let stored_stencil = self.framebuffer.stencil(px, py);
+		let stencil_test = self.state.enabled(Capability::STENCIL_TEST) && stored_stencil.is_some();
+		if stencil_test && !stencil.test(stored_stencil.unwrap()) {
+			self.framebuffer.set_stencil(
+				px,
+				py,
+				stencil.update(stencil.fail, stored_stencil.unwrap()),
+			);
+			return
+		}
+
+		// without a depth buffer the depth test always passes
+		let stored_depth = self.framebuffer.depth(px, py);
+		let depth_test = self.state.enabled(Capability::DEPTH_TEST) && stored_depth.is_some();
+		let depth_pass = !depth_test || self.state.depth_func.test(z, stored_depth.unwrap());
+		if stencil_test {
+			let op = if depth_pass {
+				stencil.depth_pass
+			} else {
+				stencil.depth_fail
+			};
+			self.framebuffer
+				.set_stencil(px, py, stencil.update(op, stored_stencil.unwrap()));
+		}
+		if !depth_pass {
+			return
+		}
+
+		let color = match self.fragment {
+			FragmentStage::Constant(color) => *color,
+			FragmentStage::Shader(shader) => {
+				let inv_w: f32 = vertices.iter().zip(weights).map(|(v, w)| v.inv_w * w).sum();
+				let varyings = (0..vertices[0].varyings.len())
+					.map(|i| {
+						vertices.iter().zip(weights).map(|(v, w)| v.varyings[i] * w).sum::<f32>()
+							/ inv_w
+					})
+					.collect();
+
+				shader(&FragmentInput {
+					coord: [x as f32 + 0.5, y as f32 + 0.5, z],
+					front_facing,
+					varyings,
+				})
+			},
+		};
+
+		if depth_test && self.state.depth_mask {
+			self.framebuffer.set_depth(px, py, z);
+		}
+
+		let Some(dst) = self.framebuffer.color(px, py) else {
+			return
+		};
+
+		// only GL_COLOR_ATTACHMENT0 is drawn to, which is draw buffer 0
+		let buffer = &self.state.draw_buffers[0];
+		let color = buffer.blend(color, dst, self.state.blend_color);
+		let color = [0, 1, 2, 3].map(|i| {
+			if buffer.color_mask[i] {
+				color[i]
+			} else {
+				dst[i]
+			}
+		});
+
+		self.framebuffer.set_color(px, py, color);
+	}
+
+	fn point(&mut self, vertex: &VertexOutput) {
+		let depth_mode = self.state.clip_depth_mode;
+		if (0..6).any(|plane| plane_distance(vertex.position, plane, depth_mode) < 0.0) {
+			return
+		}
+
+		if let Some(v) = WindowVertex::new(vertex, self.state) {
+			self.fragment(v.x.floor() as i64, v.y.floor() as i64, true, &[&v], &[1.0]);
+		}
+	}
+
+	fn line(&mut self, a: &VertexOutput, b: &VertexOutput) {
+		let Some((a, b)) = clip_line(a.clone(), b.clone(), self.state.clip_depth_mode) else {
+			return
+		};
+		let (Some(a), Some(b)) =
+			(WindowVertex::new(&a, self.state), WindowVertex::new(&b, self.state))
+		else {
+			return
+		};
+
+		// step along the major axis, sampling at pixel centers and leaving out the last pixel
+		let x_major = (b.x - a.x).abs() >= (b.y - a.y).abs();
+		let (start, end) = if x_major { (a.x, b.x) } else { (a.y, b.y) };
+		if start == end {
+			return
+		}
+
+		let first = (start.min(end) + 0.5).floor() as i64;
+		let last = (start.max(end) + 0.5).floor() as i64;
+		for i in first..last {
+			let t = (i as f32 + 0.5 - start) / (end - start);
+			let (x, y) = if x_major {
+				(i, (a.y + (b.y - a.y) * t).floor() as i64)
+			} else {
+				((a.x + (b.x - a.x) * t).floor() as i64, i)
+			};
+			self.fragment(x, y, true, &[&a, &b], &[1.0 - t, t]);
+		}
+	}
+
+	fn triangle(&mut self, vertices: [&VertexOutput; 3]) {
+		let polygon =
+			clip_polygon(vertices.map(|v| v.clone()).to_vec(), self.state.clip_depth_mode);
+		let Some(polygon) = polygon
+			.iter()
+			.map(|v| WindowVertex::new(v, self.state))
+			.collect::<Option<Vec<_>>>()
+		else {
+			return
+		};
+
+		// facing is decided by the whole polygon, which clipping does not change
+		let area: f32 = (0..polygon.len())
+			.map(|i| {
+				let (a, b) = (&polygon[i], &polygon[(i + 1) % polygon.len()]);
+				a.x * b.y - b.x * a.y
+			})
+			.sum();
+		let counter_clockwise = area > 0.0;
+		// an upper left origin mirrors the window, so the winding of front faces is reversed too
+		let front_ccw = (self.state.front_face == FrontFace::CCW)
+			!= (self.state.clip_origin == ClipOrigin::UPPER_LEFT);
+		let front_facing = counter_clockwise == front_ccw;
+		if self.state.culled(front_facing) {
+			return
+		}
+
+		for i in 1..polygon.len().saturating_sub(1) {
+			let triangle = if counter_clockwise {
+				[&polygon[0], &polygon[i], &polygon[i + 1]]
+			} else {
+				[&polygon[0], &polygon[i + 1], &polygon[i]]
+			};
+			self.fill(triangle, front_facing);
+		}
+	}
+
+	/// Fill a counter-clockwise triangle
+	fn fill(&mut self, [v0, v1, v2]: [&WindowVertex; 3], front_facing: bool) {
+		let area = edge(v0, v1, (v2.x, v2.y));
+		if area <= 0.0 || !area.is_finite() {
+			return
+		}
+
+		let min_x = v0.x.min(v1.x).min(v2.x).floor().max(0.0) as i64;
+		let min_y = v0.y.min(v1.y).min(v2.y).floor().max(0.0) as i64;
+		let max_x = v0.x.max(v1.x).max(v2.x).ceil().min(self.framebuffer.width() as f32) as i64;
+		let max_y = v0.y.max(v1.y).max(v2.y).ceil().min(self.framebuffer.height() as f32) as i64;
+
+		let covers =
+			|w: f32, a: &WindowVertex, b: &WindowVertex| w > 0.0 || (w == 0.0 && is_top_left(a, b));
+
+		for y in min_y..max_y {
+			for x in min_x..max_x {
+				let p = (x as f32 + 0.5, y as f32 + 0.5);
+				let w0 = edge(v1, v2, p);
+				let w1 = edge(v2, v0, p);
+				let w2 = edge(v0, v1, p);
+
+				if covers(w0, v1, v2) && covers(w1, v2, v0) && covers(w2, v0, v1) {
+					let weights = [w0 / area, w1 / area, w2 / area];
+					self.fragment(x, y, front_facing, &[v0, v1, v2], &weights);
+				}
+			}
+		}
+	}
+
+	/// Assemble and draw the primitives of one strip of vertices
+	fn primitives(&mut self, mode: PrimitiveMode, v: &[VertexOutput]) {
+		match mode {
+			PrimitiveMode::POINTS => v.iter().for_each(|v| self.point(v)),
+			PrimitiveMode::LINES => v.chunks_exact(2).for_each(|l| self.line(&l[0], &l[1])),
+			PrimitiveMode::LINE_STRIP => v.windows(2).for_each(|l| self.line(&l[0], &l[1])),
+			PrimitiveMode::LINE_LOOP => {
+				v.windows(2).for_each(|l| self.line(&l[0], &l[1]));
+				if v.len() > 2 {
+					self.line(&v[v.len() - 1], &v[0]);
+				}
+			},
+			PrimitiveMode::TRIANGLES =>
+				v.chunks_exact(3).for_each(|t| self.triangle([&t[0], &t[1], &t[2]])),
+			PrimitiveMode::TRIANGLE_STRIP => {
+				for i in 0..v.len().saturating_sub(2) {
+					// every other triangle is flipped to keep the strip's winding
+					if i.is_multiple_of(2) {
+						self.triangle([&v[i], &v[i + 1], &v[i + 2]]);
+					} else {
+						self.triangle([&v[i + 1], &v[i], &v[i + 2]]);
+					}
+				}
+			},
+			PrimitiveMode::TRIANGLE_FAN =>
+				for i in 1..v.len().saturating_sub(1) {
+					self.triangle([&v[0], &v[i], &v[i + 1]]);
+				},
+			_ => {},
+		}
+	}
+}
+
+impl Rasterizer {
+	pub fn new() -> Self {
+		Self {
+			vertex: VertexStage::Attribute(0),
+			fragment: FragmentStage::Constant([1.0; 4]),
+		}
+	}
+
+	fn run_vertex(&mut self, draw: &DrawCall, index: usize, instance: u32) -> VertexOutput {
+		let attributes = draw
+			.attributes
+			.iter()
+			.map(|(attrib, values)| (*attrib, values[index]))
+			.chain(
+				draw.instanced_attributes
+					.iter()
+					.map(|(attrib, values)| (*attrib, values[instance as usize])),
+			)
+			.collect();
+		let input = VertexInput {
+			vertex_id: draw.vertices[index],
+			instance_id: instance,
+			attributes,
+		};
+
+		match &mut self.vertex {
+			VertexStage::Attribute(index) => VertexOutput::new(input.attribute(*index).to_f32()),
+			VertexStage::Shader(shader) => shader(&input),
+		}
+	}
+
+	/// Rasterize `draws` into `framebuffer`, each with the state it was made with
+	pub fn rasterize(&mut self, draws: &[DrawCall], framebuffer: &mut Framebuffer) {
+		if framebuffer.width() == 0 || framebuffer.height() == 0 {
+			return
+		}
+
+		for draw in draws {
+			if matches!(
+				draw.mode,
+				PrimitiveMode::LINES_ADJACENCY
+					| PrimitiveMode::LINE_STRIP_ADJACENCY
+					| PrimitiveMode::TRIANGLES_ADJACENCY
+					| PrimitiveMode::TRIANGLE_STRIP_ADJACENCY
+					| PrimitiveMode::PATCHES
+			) {
+				debug!("rasterizer skipped draw of {}", draw.mode);
+				continue
+			}
+			if draw.state.enabled(Capability::RASTERIZER_DISCARD) {
+				continue
+			}
+
+			for instance in 0..draw.instances.count as u32 {
+				let outputs: Vec<_> =
+					(0..draw.count()).map(|i| self.run_vertex(draw, i, instance)).collect();
+				let mut target = Target {
+					fragment: &mut self.fragment,
+					state: &draw.state,
+					framebuffer,
+				};
+
+				for strip in draw.strips() {
+					target.primitives(draw.mode, &outputs[strip]);
+				}
+			}
+		}
+	}
+}
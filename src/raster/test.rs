@@ -0,0 +1,363 @@
+use std::ffi::c_void;
+
+use gl::types::{GLsizeiptr, GLuint};
+
+use crate::{
+	framebuffer::Framebuffer,
+	raster::VertexOutput,
+	state::{Capability, ClipOrigin, CompareFunc},
+	test::test_harness_context,
+	version::{Profile, VersionType},
+	vertex_array::AttribValue,
+	GlVersion,
+	MockContextRef,
+};
+
+const WHITE: [f32; 4] = [1.0; 4];
+const BLACK: [f32; 4] = [0.0; 4];
+
+/// Run `f` with an 8x8 default framebuffer and attribute 0 reading vec3 positions
+/// from `positions`
+fn with_positions(positions: &[[f32; 3]], f: impl FnOnce(&MockContextRef)) {
+	let version = GlVersion::from_version(VersionType::GL, 3, 3).with_profile(Profile::Core);
+	with_positions_on(version, positions, f)
+}
+
+/// Like `with_positions`, on a context of `version`
+fn with_positions_on(version: GlVersion, positions: &[[f32; 3]], f: impl FnOnce(&MockContextRef)) {
+	test_harness_context(version, |ctx| unsafe {
+		ctx.resize(8, 8);
+
+		let mut array = 0;
+		let mut buffer: GLuint = 0;
+		gl::GenVertexArrays(1, &mut array);
+		gl::BindVertexArray(array);
+		gl::GenBuffers(1, &mut buffer);
+		gl::BindBuffer(gl::ARRAY_BUFFER, buffer);
+		gl::BufferData(
+			gl::ARRAY_BUFFER,
+			std::mem::size_of_val(positions) as GLsizeiptr,
+			positions.as_ptr() as *const c_void,
+			gl::STATIC_DRAW,
+		);
+		gl::VertexAttribPointer(0, 3, gl::FLOAT, gl::FALSE, 0, std::ptr::null());
+		gl::EnableVertexAttribArray(0);
+
+		f(ctx);
+
+		gl::DeleteBuffers(1, &buffer);
+		gl::DeleteVertexArrays(1, &array);
+	})
+}
+
+fn count(framebuffer: &Framebuffer, color: [f32; 4]) -> usize {
+	(0..framebuffer.height())
+		.flat_map(|y| (0..framebuffer.width()).map(move |x| (x, y)))
+		.filter(|(x, y)| framebuffer.color(*x, *y) == Some(color))
+		.count()
+}
+
+const QUAD: [[f32; 3]; 6] = [
+	[-1.0, -1.0, 0.0],
+	[1.0, -1.0, 0.0],
+	[1.0, 1.0, 0.0],
+	[-1.0, -1.0, 0.0],
+	[1.0, 1.0, 0.0],
+	[-1.0, 1.0, 0.0],
+];
+
+#[test]
+fn triangle() {
+	with_positions(&[[-1.0, -1.0, 0.0], [1.0, -1.0, 0.0], [-1.0, 1.0, 0.0]], |ctx| unsafe {
+		gl::DrawArrays(gl::TRIANGLES, 0, 3);
+
+		let framebuffer = ctx.default_framebuffer();
+		assert_eq!(framebuffer.color(0, 0), Some(WHITE));
+		assert_eq!(framebuffer.color(7, 7), Some(BLACK));
+		// pixel centers strictly below the diagonal, the diagonal itself is not a top or left edge
+		assert_eq!(count(&framebuffer, WHITE), 28);
+	})
+}
+
+#[test]
+fn shared_edges_drawn_once() {
+	with_positions(&QUAD, |ctx| unsafe {
+		ctx.set_fragment_color([0.25; 4]);
+		gl::Enable(gl::BLEND);
+		gl::BlendFunc(gl::ONE, gl::ONE);
+		gl::DrawArrays(gl::TRIANGLES, 0, 6);
+
+		assert_eq!(count(&ctx.default_framebuffer(), [0.25; 4]), 64);
+	})
+}
+
+#[test]
+fn viewport_and_scissor() {
+	with_positions(&QUAD, |ctx| unsafe {
+		gl::Viewport(4, 4, 4, 4);
+		gl::DrawArrays(gl::TRIANGLES, 0, 6);
+		let framebuffer = ctx.default_framebuffer();
+		assert_eq!(count(&framebuffer, WHITE), 16);
+		assert_eq!(framebuffer.color(4, 4), Some(WHITE));
+		assert_eq!(framebuffer.color(3, 3), Some(BLACK));
+
+		ctx.set_fragment_color([1.0, 0.0, 0.0, 1.0]);
+		gl::Viewport(0, 0, 8, 8);
+		gl::Enable(gl::SCISSOR_TEST);
+		gl::Scissor(1, 1, 2, 3);
+		gl::DrawArrays(gl::TRIANGLES, 0, 6);
+		assert_eq!(count(&ctx.default_framebuffer(), [1.0, 0.0, 0.0, 1.0]), 6);
+	})
+}
+
+#[test]
+fn depth_test() {
+	let near = QUAD.map(|[x, y, _]| [x, y, -0.5]);
+	let far = QUAD.map(|[x, y, _]| [x, y, 0.5]);
+	with_positions(&[near, far].concat(), |ctx| unsafe {
+		gl::Enable(gl::DEPTH_TEST);
+
+		ctx.set_fragment_color([1.0, 0.0, 0.0, 1.0]);
+		gl::DrawArrays(gl::TRIANGLES, 0, 6);
+		ctx.set_fragment_color([0.0, 1.0, 0.0, 1.0]);
+		gl::DrawArrays(gl::TRIANGLES, 6, 6);
+
+		let framebuffer = ctx.default_framebuffer();
+		assert_eq!(count(&framebuffer, [1.0, 0.0, 0.0, 1.0]), 64);
+		assert_eq!(framebuffer.depth(2, 2), Some(0.25));
+
+		gl::DepthFunc(gl::GREATER);
+		gl::DrawArrays(gl::TRIANGLES, 6, 6);
+		assert_eq!(count(&ctx.default_framebuffer(), [0.0, 1.0, 0.0, 1.0]), 64);
+	})
+}
+
+#[test]
+fn shaders_and_varyings() {
+	with_positions(&QUAD, |ctx| unsafe {
+		ctx.set_vertex_shader(|input| {
+			let [x, y, z, w] = input.attribute(0).to_f32();
+			VertexOutput {
+				position: [x, y, z, w],
+				varyings: vec![(x + 1.0) / 2.0, input.vertex_id as f32],
+			}
+		});
+		ctx.set_fragment_shader(|input| [input.varyings[0], input.coord[0] / 8.0, 0.0, 1.0]);
+		gl::DrawArrays(gl::TRIANGLES, 0, 6);
+
+		let framebuffer = ctx.default_framebuffer();
+		for x in 0..8 {
+			let [r, g, ..] = framebuffer.color(x, 3).unwrap();
+			assert!((r - g).abs() < 1e-6, "{} != {}", r, g);
+			assert!((r - (x as f32 + 0.5) / 8.0).abs() < 1e-6);
+		}
+	})
+}
+
+#[test]
+fn perspective_correct_interpolation() {
+	// a quad leaning away from the viewer, w growing with y
+	with_positions(&QUAD, |ctx| unsafe {
+		ctx.set_vertex_shader(|input| {
+			let [x, y, ..] = input.attribute(0).to_f32();
+			let w = if y > 0.0 { 3.0 } else { 1.0 };
+			VertexOutput {
+				position: [x * w, y * w, 0.0, w],
+				varyings: vec![(y + 1.0) / 2.0],
+			}
+		});
+		ctx.set_fragment_shader(|input| [input.varyings[0], 0.0, 0.0, 1.0]);
+		gl::DrawArrays(gl::TRIANGLES, 0, 6);
+
+		// halfway up the screen, 1 / w is halfway between 1 and 1/3
+		let [v, ..] = ctx.default_framebuffer().color(0, 4).unwrap();
+		let t = 0.5 / 8.0 + 0.5;
+		let expected = (t / 3.0) / ((1.0 - t) + t / 3.0);
+		assert!((v - expected).abs() < 1e-5, "{} != {}", v, expected);
+	})
+}
+
+#[test]
+fn clipping() {
+	// one vertex behind the viewer, the visible part still covers the whole screen
+	with_positions(&[[-1.0, -1.0, 0.0], [3.0, -1.0, 0.0], [-1.0, 3.0, 0.0]], |ctx| unsafe {
+		ctx.set_vertex_shader(|input| {
+			let [x, y, ..] = input.attribute(0).to_f32();
+			let w = if x > 2.0 { -1.0 } else { 1.0 };
+			VertexOutput::new([x, y, 0.0, w])
+		});
+		gl::DrawArrays(gl::TRIANGLES, 0, 3);
+		assert!(count(&ctx.default_framebuffer(), WHITE) > 0);
+	});
+
+	with_positions(&[[-3.0, -3.0, 0.0], [5.0, -3.0, 0.0], [-3.0, 5.0, 0.0]], |ctx| unsafe {
+		gl::DrawArrays(gl::TRIANGLES, 0, 3);
+		assert_eq!(count(&ctx.default_framebuffer(), WHITE), 64);
+	})
+}
+
+#[test]
+fn lines_and_points() {
+	let positions = [[-1.0, -0.9, 0.0], [1.0, -0.9, 0.0], [0.1, 0.1, 0.0], [
+		2.0, 0.1, 0.0,
+	]];
+	with_positions(&positions, |ctx| unsafe {
+		gl::DrawArrays(gl::LINES, 0, 2);
+		let framebuffer = ctx.default_framebuffer();
+		assert_eq!(count(&framebuffer, WHITE), 8);
+		assert!((0..8).all(|x| framebuffer.color(x, 0) == Some(WHITE)));
+
+		ctx.set_fragment_color([0.0, 0.0, 1.0, 1.0]);
+		gl::DrawArrays(gl::POINTS, 2, 2);
+		let framebuffer = ctx.default_framebuffer();
+		assert_eq!(count(&framebuffer, [0.0, 0.0, 1.0, 1.0]), 1);
+		assert_eq!(framebuffer.color(4, 4), Some([0.0, 0.0, 1.0, 1.0]));
+	})
+}
+
+#[test]
+fn instanced_vertex_input() {
+	with_positions(&QUAD, |ctx| unsafe {
+		ctx.set_vertex_shader(|input| {
+			let [x, y, ..] = input.attribute(0).to_f32();
+			assert_eq!(input.attribute(5), AttribValue::Float([0.0, 0.0, 0.0, 1.0]));
+			// instance 1 covers the right half
+			let x = (x + 1.0) / 2.0 + input.instance_id as f32 - 1.0;
+			VertexOutput::new([x, y, 0.0, 1.0])
+		});
+		ctx.set_fragment_color([0.5; 4]);
+		gl::Enable(gl::BLEND);
+		gl::BlendFunc(gl::ONE, gl::ONE);
+		gl::DrawArraysInstanced(gl::TRIANGLES, 0, 6, 2);
+
+		assert_eq!(count(&ctx.default_framebuffer(), [0.5; 4]), 64);
+	})
+}
+
+#[test]
+fn face_culling() {
+	// counter-clockwise lower left triangle and clockwise upper right triangle
+	let triangles = [
+		[-1.0, -1.0, 0.0],
+		[1.0, -1.0, 0.0],
+		[-1.0, 1.0, 0.0],
+		[1.0, -1.0, 0.0],
+		[-1.0, 1.0, 0.0],
+		[1.0, 1.0, 0.0],
+	];
+	with_positions(&triangles, |ctx| unsafe {
+		gl::Enable(gl::CULL_FACE);
+		gl::DrawArrays(gl::TRIANGLES, 0, 6);
+		assert_eq!(count(&ctx.default_framebuffer(), WHITE), 28);
+
+		ctx.set_fragment_color([1.0, 0.0, 0.0, 1.0]);
+		gl::FrontFace(gl::CW);
+		gl::DrawArrays(gl::TRIANGLES, 0, 6);
+		assert_eq!(count(&ctx.default_framebuffer(), [1.0, 0.0, 0.0, 1.0]), 36);
+
+		gl::CullFace(gl::FRONT_AND_BACK);
+		gl::ClearColor(0.0, 0.0, 0.0, 0.0);
+		gl::Clear(gl::COLOR_BUFFER_BIT);
+		gl::DrawArrays(gl::TRIANGLES, 0, 6);
+		assert_eq!(count(&ctx.default_framebuffer(), BLACK), 64);
+	})
+}
+
+#[test]
+fn stencil_test() {
+	let left = QUAD.map(|[x, y, z]| [x.min(0.0), y, z]);
+	with_positions(&[left, QUAD].concat(), |ctx| unsafe {
+		// mark the left half with 1
+		gl::Enable(gl::STENCIL_TEST);
+		gl::StencilFunc(gl::ALWAYS, 1, 0xff);
+		gl::StencilOp(gl::KEEP, gl::KEEP, gl::REPLACE);
+		gl::ColorMask(gl::FALSE, gl::FALSE, gl::FALSE, gl::FALSE);
+		gl::DrawArrays(gl::TRIANGLES, 0, 6);
+		assert_eq!(count(&ctx.default_framebuffer(), BLACK), 64);
+		assert_eq!(ctx.default_framebuffer().stencil(1, 1), Some(1));
+		assert_eq!(ctx.default_framebuffer().stencil(6, 1), Some(0));
+
+		// draw everywhere but the marked half, incrementing where the test fails
+		gl::ColorMask(gl::TRUE, gl::TRUE, gl::TRUE, gl::TRUE);
+		gl::StencilFunc(gl::NOTEQUAL, 1, 0xff);
+		gl::StencilOp(gl::INCR, gl::KEEP, gl::KEEP);
+		gl::DrawArrays(gl::TRIANGLES, 6, 6);
+
+		let framebuffer = ctx.default_framebuffer();
+		assert_eq!(count(&framebuffer, WHITE), 32);
+		assert_eq!(framebuffer.color(1, 1), Some(BLACK));
+		assert_eq!(framebuffer.stencil(1, 1), Some(2));
+		assert_eq!(framebuffer.stencil(6, 1), Some(0));
+	})
+}
+
+#[test]
+fn blending_and_color_masks() {
+	with_positions(&QUAD, |ctx| unsafe {
+		gl::ClearColor(0.5, 0.5, 0.5, 0.5);
+		gl::Clear(gl::COLOR_BUFFER_BIT);
+
+		ctx.set_fragment_color([0.25, 1.0, 0.25, 1.0]);
+		gl::Enable(gl::BLEND);
+		gl::BlendFuncSeparate(gl::ONE, gl::ONE, gl::CONSTANT_ALPHA, gl::ZERO);
+		gl::BlendEquationSeparate(gl::FUNC_REVERSE_SUBTRACT, gl::FUNC_ADD);
+		gl::BlendColor(0.0, 0.0, 0.0, 0.5);
+		gl::ColorMask(gl::TRUE, gl::FALSE, gl::TRUE, gl::TRUE);
+		gl::DrawArrays(gl::TRIANGLES, 0, 6);
+		assert_eq!(ctx.default_framebuffer().color(3, 3), Some([0.25, 0.5, 0.25, 0.5]));
+
+		gl::BlendEquation(gl::MAX);
+		gl::DrawArrays(gl::TRIANGLES, 0, 6);
+		assert_eq!(ctx.default_framebuffer().color(3, 3), Some([0.25, 0.5, 0.25, 1.0]));
+	})
+}
+
+#[test]
+fn draws_record_their_state() {
+	with_positions(&QUAD, |ctx| unsafe {
+		gl::Enable(gl::RASTERIZER_DISCARD);
+		gl::DepthFunc(gl::ALWAYS);
+		gl::DrawArrays(gl::TRIANGLES, 0, 6);
+		gl::Disable(gl::RASTERIZER_DISCARD);
+		gl::Enable(gl::BLEND);
+		gl::DrawArrays(gl::TRIANGLES, 0, 3);
+
+		let draws = ctx.take_draw_calls();
+		assert!(draws[0].state.enabled(Capability::RASTERIZER_DISCARD));
+		assert_eq!(draws[0].state.depth_func, CompareFunc::ALWAYS);
+		assert!(!draws[0].state.draw_buffers[0].blend);
+		assert!(!draws[1].state.enabled(Capability::RASTERIZER_DISCARD));
+		assert!(draws[1].state.enabled(Capability::BLEND));
+
+		// the discarded draw left no pixels
+		assert_eq!(count(&ctx.default_framebuffer(), WHITE), 36);
+	})
+}
+
+#[test]
+fn depth_range_and_clip_control() {
+	let triangle = [[-1.0, -1.0, 0.0], [1.0, -1.0, 0.0], [-1.0, 1.0, 0.0]];
+	let version = GlVersion::from_version(VersionType::GL, 4, 5).with_profile(Profile::Core);
+	with_positions_on(version, &triangle, |ctx| unsafe {
+		gl::Enable(gl::DEPTH_TEST);
+		gl::DepthFunc(gl::ALWAYS);
+		gl::DepthRange(0.5, 1.0);
+		gl::DrawArrays(gl::TRIANGLES, 0, 3);
+		assert_eq!(ctx.default_framebuffer().depth(0, 0), Some(0.75));
+
+		// clip space depth already is [0, 1], and rows are flipped
+		ctx.set_fragment_color([1.0, 0.0, 0.0, 1.0]);
+		gl::ClipControl(gl::UPPER_LEFT, gl::ZERO_TO_ONE);
+		gl::DrawArrays(gl::TRIANGLES, 0, 3);
+		let framebuffer = ctx.default_framebuffer();
+		assert_eq!(framebuffer.depth(0, 7), Some(0.5));
+		assert_eq!(framebuffer.color(0, 7), Some([1.0, 0.0, 0.0, 1.0]));
+		assert_eq!(framebuffer.color(0, 0), Some(WHITE));
+
+		let draws = ctx.take_draw_calls();
+		assert_eq!(draws[0].state.viewports[0].depth_range, [0.5, 1.0]);
+		assert_eq!(draws[0].state.clip_origin, ClipOrigin::LOWER_LEFT);
+		assert_eq!(draws[1].state.clip_origin, ClipOrigin::UPPER_LEFT);
+		assert_eq!(draws[1].state.viewports[0].width, 8.0);
+	})
+}
@@ -0,0 +1,462 @@
+use std::{collections::HashMap, slice};
+
+use gl::types::{GLboolean, GLenum, GLint, GLsizei, GLuint};
+
+use crate::{
+	debug,
+	error,
+	format::{ComponentType, InternalFormat},
+	framebuffer::{Framebuffer, FramebufferManager},
+	limits::Limits,
+	version::{at_least, VersionType},
+	warning,
+	GlVersion,
+};
+
+pub mod gl_functions;
+
+#[cfg(test)]
+mod test;
+
+/// Renderbuffer object, without a format until storage is allocated
+///
+/// Multisample renderbuffers store one value per pixel that all of its samples share,
+/// as the reference rasterizer does not compute coverage per sample.
+#[derive(Clone, Debug)]
+pub struct Renderbuffer {
+	format: Option<InternalFormat>,
+	samples: GLsizei,
+	image: Framebuffer,
+}
+
+impl Default for Renderbuffer {
+	fn default() -> Self {
+		Self {
+			format: None,
+			samples: 0,
+			image: Framebuffer::new(0, 0),
+		}
+	}
+}
+
+impl Renderbuffer {
+	pub fn format(&self) -> Option<InternalFormat> {
+		self.format
+	}
+
+	pub fn samples(&self) -> GLsizei {
+		self.samples
+	}
+
+	pub fn image(&self) -> &Framebuffer {
+		&self.image
+	}
+
+	pub(crate) fn image_mut(&mut self) -> &mut Framebuffer {
+		&mut self.image
+	}
+}
+
+pub struct RenderbufferManager {
+	renderbuffer_index: GLuint,
+	/// Generated names, which only become objects once bound
+	active_renderbuffers: HashMap<GLuint, Option<Renderbuffer>>,
+	deleted_renderbuffers: Vec<GLuint>,
+	/// Renderbuffers deleted while still attached to a framebuffer that was not bound,
+	/// kept alive until the last attachment is removed
+	orphaned_renderbuffers: HashMap<GLuint, Renderbuffer>,
+	bound_renderbuffer: GLuint,
+}
+
+impl Default for RenderbufferManager {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl RenderbufferManager {
+	pub fn new() -> Self {
+		Self {
+			renderbuffer_index: 1,
+			active_renderbuffers: HashMap::new(),
+			deleted_renderbuffers: Vec::new(),
+			orphaned_renderbuffers: HashMap::new(),
+			bound_renderbuffer: 0,
+		}
+	}
+
+	/// # Safety
+	/// `renderbuffers` must be valid for `count` writes
+	pub unsafe fn gen_renderbuffers(
+		&mut self,
+		error: &mut GLenum,
+		count: GLsizei,
+		renderbuffers: *mut GLuint,
+	) {
+		if count < 0 {
+			*error = gl::INVALID_VALUE;
+			error!("glGenRenderbuffers called with invalid renderbuffer count {}", count);
+		} else {
+			let renderbuffers = slice::from_raw_parts_mut(renderbuffers, count as usize);
+
+			for renderbuffer_id in renderbuffers.iter_mut() {
+				*renderbuffer_id = self.renderbuffer_index;
+				self.renderbuffer_index += 1;
+
+				self.active_renderbuffers.insert(*renderbuffer_id, None);
+			}
+
+			debug!("created {} renderbuffer(s) {:?}", renderbuffers.len(), renderbuffers);
+		}
+	}
+
+	/// # Safety
+	/// `renderbuffers` must be valid for `count` reads
+	pub unsafe fn free_renderbuffers(
+		&mut self,
+		framebuffers: &mut FramebufferManager,
+		error: &mut GLenum,
+		count: GLsizei,
+		renderbuffers: *const GLuint,
+	) {
+		if count < 0 {
+			*error = gl::INVALID_VALUE;
+			error!("glDeleteRenderbuffers called with invalid renderbuffer count {}", count);
+			return
+		}
+
+		let renderbuffers = slice::from_raw_parts(renderbuffers, count as usize);
+
+		for renderbuffer_id in renderbuffers.iter() {
+			if *renderbuffer_id == 0 {
+				continue
+			}
+
+			if self.deleted_renderbuffers.contains(renderbuffer_id) {
+				warning!("double freed renderbuffer {}", renderbuffer_id);
+			} else if let Some(renderbuffer) = self.active_renderbuffers.remove(renderbuffer_id) {
+				self.deleted_renderbuffers.push(*renderbuffer_id);
+				if self.bound_renderbuffer == *renderbuffer_id {
+					self.bound_renderbuffer = 0;
+				}
+
+				// only the bound framebuffers lose their attachments,
+				// other framebuffers keep the storage alive
+				framebuffers.detach_renderbuffer(*renderbuffer_id);
+				if let Some(renderbuffer) = renderbuffer {
+					if framebuffers.references_renderbuffer(*renderbuffer_id) {
+						debug!(
+							"renderbuffer {} is still attached to a framebuffer",
+							renderbuffer_id
+						);
+						self.orphaned_renderbuffers.insert(*renderbuffer_id, renderbuffer);
+					}
+				}
+
+				debug!("freed renderbuffer {}", renderbuffer_id);
+			} else {
+				warning!("attempted to free unallocated renderbuffer {}", renderbuffer_id);
+			}
+		}
+	}
+
+	pub fn is_renderbuffer(&self, renderbuffer: GLuint) -> GLboolean {
+		matches!(self.active_renderbuffers.get(&renderbuffer), Some(Some(_))) as u8
+	}
+
+	pub fn bind_renderbuffer(
+		&mut self,
+		error: &mut GLenum,
+		target: GLenum,
+		renderbuffer_id: GLuint,
+	) {
+		if target != gl::RENDERBUFFER {
+			*error = gl::INVALID_ENUM;
+			error!(
+				"attempted to bind renderbuffer {} to invalid target {}",
+				renderbuffer_id, target
+			);
+			return
+		}
+
+		if renderbuffer_id != 0 {
+			match self.active_renderbuffers.get_mut(&renderbuffer_id) {
+				Some(renderbuffer) => {
+					renderbuffer.get_or_insert_with(Renderbuffer::default);
+				},
+				None => {
+					*error = gl::INVALID_OPERATION;
+					if self.deleted_renderbuffers.contains(&renderbuffer_id) {
+						error!("attempted to bind renderbuffer that has already been freed");
+					} else {
+						error!("attempted to bind an unallocated renderbuffer");
+					}
+					return
+				},
+			}
+		}
+
+		self.bound_renderbuffer = renderbuffer_id;
+		debug!("bound renderbuffer {}", renderbuffer_id);
+	}
+
+	/// Sample counts `format` supports, in the descending order `glGetInternalformativ` lists them
+	///
+	/// These are `GL_MAX_SAMPLES` and the powers of two below it. OpenGL ES 3.0
+	/// has no multisampled integer formats.
+	pub fn sample_counts(
+		gl_version: &GlVersion,
+		limits: &Limits,
+		format: InternalFormat,
+	) -> Vec<GLsizei> {
+		let max = match format.component_type() {
+			Some(ComponentType::Int | ComponentType::UnsignedInt)
+				if matches!(gl_version.ty, VersionType::ES)
+					&& !at_least!(gl_version, es: 3 . 1) =>
+				0,
+			_ => limits.max_samples,
+		};
+
+		(1..=max)
+			.rev()
+			.filter(|count| *count == max || (*count > 1 && (*count as u32).is_power_of_two()))
+			.collect()
+	}
+
+	/// Allocate storage of the bound renderbuffer, discarding its contents
+	///
+	/// The sample count is rounded up to one the format supports.
+	#[allow(clippy::too_many_arguments)]
+	pub fn renderbuffer_storage(
+		&mut self,
+		gl_version: &GlVersion,
+		limits: &Limits,
+		error: &mut GLenum,
+		func: &str,
+		target: GLenum,
+		samples: GLsizei,
+		internal_format: GLenum,
+		width: GLsizei,
+		height: GLsizei,
+	) {
+		if target != gl::RENDERBUFFER {
+			*error = gl::INVALID_ENUM;
+			error!("{} called with invalid target {}", func, target);
+			return
+		}
+
+		let format = match InternalFormat::from_gl(internal_format) {
+			Some(format) if format.renderbuffer_supported(gl_version) => format,
+			Some(format) => {
+				*error = gl::INVALID_ENUM;
+				error!("renderbuffers of {} are not supported by this context", format);
+				return
+			},
+			None => {
+				*error = gl::INVALID_ENUM;
+				error!("{} called with invalid internal format {}", func, internal_format);
+				return
+			},
+		};
+
+		let max_size = limits.max_renderbuffer_size;
+		if !(0..=max_size).contains(&width) || !(0..=max_size).contains(&height) {
+			*error = gl::INVALID_VALUE;
+			error!(
+				"{} called with size {}x{}, GL_MAX_RENDERBUFFER_SIZE is {}",
+				func, width, height, max_size
+			);
+			return
+		}
+
+		if !(0..=limits.max_samples).contains(&samples) {
+			*error = gl::INVALID_VALUE;
+			error!(
+				"{} called with {} samples, GL_MAX_SAMPLES is {}",
+				func, samples, limits.max_samples
+			);
+			return
+		}
+
+		let samples = match Self::sample_counts(gl_version, limits, format)
+			.into_iter()
+			.rfind(|c| *c >= samples)
+		{
+			_ if samples == 0 => 0,
+			Some(samples) => samples,
+			None => {
+				*error = gl::INVALID_OPERATION;
+				error!("{} called with {} samples, more than {} supports", func, samples, format);
+				return
+			},
+		};
+
+		let renderbuffer_id = self.bound_renderbuffer;
+		let Some(Some(renderbuffer)) = self.active_renderbuffers.get_mut(&renderbuffer_id) else {
+			*error = gl::INVALID_OPERATION;
+			error!("{} called without a bound renderbuffer", func);
+			return
+		};
+
+		*renderbuffer = Renderbuffer {
+			format: Some(format),
+			samples,
+			image: Framebuffer::with_format(width as usize, height as usize, format),
+		};
+		debug!(
+			"allocated renderbuffer {} as {}x{} {} with {} samples",
+			renderbuffer_id, width, height, format, samples
+		);
+	}
+
+	/// # Safety
+	/// `params` must be valid for `buf_size` writes
+	#[allow(clippy::too_many_arguments)]
+	pub unsafe fn get_internalformat(
+		&self,
+		gl_version: &GlVersion,
+		limits: &Limits,
+		error: &mut GLenum,
+		target: GLenum,
+		internal_format: GLenum,
+		pname: GLenum,
+		buf_size: GLsizei,
+		params: *mut GLint,
+	) {
+		if target != gl::RENDERBUFFER {
+			*error = gl::INVALID_ENUM;
+			error!(
+				"mock-gl only supports glGetInternalformativ for GL_RENDERBUFFER, not {}",
+				target
+			);
+			return
+		}
+
+		if buf_size < 0 {
+			*error = gl::INVALID_VALUE;
+			error!("glGetInternalformativ called with negative buffer size {}", buf_size);
+			return
+		}
+
+		let Some(format) = InternalFormat::from_gl(internal_format) else {
+			*error = gl::INVALID_ENUM;
+			error!("glGetInternalformativ called with invalid internal format {}", internal_format);
+			return
+		};
+
+		// OpenGL ES only answers for renderable formats, OpenGL reports no sample counts
+		let counts = if format.renderbuffer_supported(gl_version) {
+			Self::sample_counts(gl_version, limits, format)
+		} else if matches!(gl_version.ty, VersionType::ES) {
+			*error = gl::INVALID_ENUM;
+			error!("glGetInternalformativ called with {}, which is not renderable", format);
+			return
+		} else {
+			Vec::new()
+		};
+
+		let values = match pname {
+			gl::NUM_SAMPLE_COUNTS => vec![counts.len() as GLint],
+			gl::SAMPLES => counts,
+			_ => {
+				*error = gl::INVALID_ENUM;
+				error!("mock-gl does not support glGetInternalformativ parameter {}", pname);
+				return
+			},
+		};
+
+		// nothing is written, `params` may be null
+		if buf_size == 0 {
+			return
+		}
+
+		let params = slice::from_raw_parts_mut(params, buf_size as usize);
+		for (param, value) in params.iter_mut().zip(values) {
+			*param = value;
+		}
+	}
+
+	pub fn get_renderbuffer_parameter(
+		&self,
+		error: &mut GLenum,
+		target: GLenum,
+		pname: GLenum,
+	) -> Option<GLint> {
+		if target != gl::RENDERBUFFER {
+			*error = gl::INVALID_ENUM;
+			error!("glGetRenderbufferParameteriv called with invalid target {}", target);
+			return None
+		}
+
+		let Some(Some(renderbuffer)) = self.active_renderbuffers.get(&self.bound_renderbuffer)
+		else {
+			*error = gl::INVALID_OPERATION;
+			error!("glGetRenderbufferParameteriv called without a bound renderbuffer");
+			return None
+		};
+
+		let format = renderbuffer.format;
+		let bits = format.map_or([0; 6], |format| {
+			let [r, g, b, a] = format.color_bits();
+			[r, g, b, a, format.depth_bits(), format.stencil_bits()]
+		});
+
+		Some(match pname {
+			gl::RENDERBUFFER_WIDTH => renderbuffer.image.width() as GLint,
+			gl::RENDERBUFFER_HEIGHT => renderbuffer.image.height() as GLint,
+			gl::RENDERBUFFER_INTERNAL_FORMAT =>
+				format.map_or(gl::RGBA, |format| format.to_gl()) as GLint,
+			gl::RENDERBUFFER_SAMPLES => renderbuffer.samples,
+			gl::RENDERBUFFER_RED_SIZE => bits[0] as GLint,
+			gl::RENDERBUFFER_GREEN_SIZE => bits[1] as GLint,
+			gl::RENDERBUFFER_BLUE_SIZE => bits[2] as GLint,
+			gl::RENDERBUFFER_ALPHA_SIZE => bits[3] as GLint,
+			gl::RENDERBUFFER_DEPTH_SIZE => bits[4] as GLint,
+			gl::RENDERBUFFER_STENCIL_SIZE => bits[5] as GLint,
+			_ => {
+				*error = gl::INVALID_ENUM;
+				error!("glGetRenderbufferParameteriv called with invalid parameter {}", pname);
+				return None
+			},
+		})
+	}
+
+	/// Live renderbuffer, or a deleted renderbuffer still attached to a framebuffer
+	pub fn renderbuffer(&self, renderbuffer_id: GLuint) -> Option<&Renderbuffer> {
+		match self.active_renderbuffers.get(&renderbuffer_id) {
+			Some(renderbuffer) => renderbuffer.as_ref(),
+			None => self.orphaned_renderbuffers.get(&renderbuffer_id),
+		}
+	}
+
+	pub fn renderbuffer_mut(&mut self, renderbuffer_id: GLuint) -> Option<&mut Renderbuffer> {
+		match self.active_renderbuffers.get_mut(&renderbuffer_id) {
+			Some(renderbuffer) => renderbuffer.as_mut(),
+			None => self.orphaned_renderbuffers.get_mut(&renderbuffer_id),
+		}
+	}
+
+	/// Release storage of deleted renderbuffers no framebuffer is attached to anymore
+	pub fn collect_orphans(&mut self, framebuffers: &FramebufferManager) {
+		self.orphaned_renderbuffers.retain(|renderbuffer_id, _| {
+			let referenced = framebuffers.references_renderbuffer(*renderbuffer_id);
+			if !referenced {
+				debug!("released storage of deleted renderbuffer {}", renderbuffer_id);
+			}
+			referenced
+		});
+	}
+
+	pub fn bound(&self) -> GLuint {
+		self.bound_renderbuffer
+	}
+
+	pub fn finalize(self) {
+		if !self.active_renderbuffers.is_empty() {
+			error!(
+				"mock-gl context was dropped with dangling renderbuffers {:?}",
+				self.active_renderbuffers.keys().collect::<Vec<_>>()
+			);
+		}
+	}
+}
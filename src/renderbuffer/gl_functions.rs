@@ -0,0 +1,125 @@
+use gl::types::{GLboolean, GLenum, GLint, GLsizei, GLuint};
+
+use crate::function_mapping::gl_functions;
+
+gl_functions! {
+	fn glGenRenderbuffers(count: GLsizei, renderbuffers: *mut GLuint);
+	require gl 3 . 0;
+	require es 2 . 0;
+	require ext ARB_framebuffer_object;
+	take [error, renderbuffer_manager]
+	{
+		renderbuffer_manager.gen_renderbuffers(error, count, renderbuffers);
+	}
+
+	fn glDeleteRenderbuffers(count: GLsizei, renderbuffers: *const GLuint);
+	require gl 3 . 0;
+	require es 2 . 0;
+	require ext ARB_framebuffer_object;
+	take [error, framebuffer_manager, renderbuffer_manager]
+	{
+		renderbuffer_manager.free_renderbuffers(framebuffer_manager, error, count, renderbuffers);
+	}
+
+	fn glIsRenderbuffer(renderbuffer: GLuint) -> GLboolean;
+	require gl 3 . 0;
+	require es 2 . 0;
+	require ext ARB_framebuffer_object;
+	take [renderbuffer_manager]
+	{
+		renderbuffer_manager.is_renderbuffer(renderbuffer)
+	}
+
+	fn glBindRenderbuffer(target: GLenum, renderbuffer: GLuint);
+	require gl 3 . 0;
+	require es 2 . 0;
+	require ext ARB_framebuffer_object;
+	take [error, renderbuffer_manager]
+	{
+		renderbuffer_manager.bind_renderbuffer(error, target, renderbuffer);
+	}
+
+	fn glRenderbufferStorage(
+		target: GLenum,
+		internal_format: GLenum,
+		width: GLsizei,
+		height: GLsizei,
+	);
+	require gl 3 . 0;
+	require es 2 . 0;
+	require ext ARB_framebuffer_object;
+	take [gl_version, limits, error, renderbuffer_manager]
+	{
+		renderbuffer_manager.renderbuffer_storage(
+			gl_version,
+			limits,
+			error,
+			"glRenderbufferStorage",
+			target,
+			0,
+			internal_format,
+			width,
+			height,
+		);
+	}
+
+	fn glRenderbufferStorageMultisample(
+		target: GLenum,
+		samples: GLsizei,
+		internal_format: GLenum,
+		width: GLsizei,
+		height: GLsizei,
+	);
+	require gl 3 . 0;
+	require es 3 . 0;
+	require ext ARB_framebuffer_object;
+	take [gl_version, limits, error, renderbuffer_manager]
+	{
+		renderbuffer_manager.renderbuffer_storage(
+			gl_version,
+			limits,
+			error,
+			"glRenderbufferStorageMultisample",
+			target,
+			samples,
+			internal_format,
+			width,
+			height,
+		);
+	}
+
+	fn glGetRenderbufferParameteriv(target: GLenum, pname: GLenum, params: *mut GLint);
+	require gl 3 . 0;
+	require es 2 . 0;
+	require ext ARB_framebuffer_object;
+	take [error, renderbuffer_manager]
+	{
+		if let Some(value) = renderbuffer_manager.get_renderbuffer_parameter(error, target, pname) {
+			*params = value;
+		}
+	}
+
+	fn glGetInternalformativ(
+		target: GLenum,
+		internal_format: GLenum,
+		pname: GLenum,
+		buf_size: GLsizei,
+		params: *mut GLint,
+	);
+	require gl 4 . 2;
+	require es 3 . 0;
+	require ext ARB_internalformat_query;
+	take [gl_version, limits, error, renderbuffer_manager]
+	{
+		renderbuffer_manager.get_internalformat(
+			gl_version,
+			limits,
+			error,
+			target,
+			internal_format,
+			pname,
+			buf_size,
+			params,
+		);
+	}
+}
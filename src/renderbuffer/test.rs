@@ -0,0 +1,218 @@
+use std::ptr;
+
+use gl::types::{GLenum, GLint};
+
+use crate::{
+	format::InternalFormat,
+	limits::Limits,
+	test::{test_harness, test_harness_context, test_harness_handling},
+	version::{ext, Profile, VersionType},
+	ErrorHandling,
+	GlVersion,
+};
+
+#[test]
+fn storage() {
+	let version = GlVersion::from_version(VersionType::GL, 3, 3).with_profile(Profile::Core);
+	test_harness(version, || unsafe {
+		let mut renderbuffer = 0;
+		gl::GenRenderbuffers(1, &mut renderbuffer);
+		assert_eq!(gl::IsRenderbuffer(renderbuffer), gl::FALSE);
+
+		gl::BindRenderbuffer(gl::RENDERBUFFER, renderbuffer);
+		assert_eq!(gl::IsRenderbuffer(renderbuffer), gl::TRUE);
+		let mut binding: GLint = 0;
+		gl::GetIntegerv(gl::RENDERBUFFER_BINDING, &mut binding);
+		assert_eq!(binding, renderbuffer as GLint);
+
+		gl::RenderbufferStorage(gl::RENDERBUFFER, gl::R11F_G11F_B10F, 3, 2);
+		{
+			let context = crate::context();
+			let renderbuffer = context.renderbuffer_manager.renderbuffer(renderbuffer).unwrap();
+			assert_eq!(renderbuffer.format(), Some(InternalFormat::R11F_G11F_B10F));
+			assert_eq!((renderbuffer.image().width(), renderbuffer.image().height()), (3, 2));
+			assert_eq!(renderbuffer.image().color(2, 1), Some([0.0, 0.0, 0.0, 1.0]));
+		}
+
+		gl::DeleteRenderbuffers(1, &renderbuffer);
+		gl::GetIntegerv(gl::RENDERBUFFER_BINDING, &mut binding);
+		assert_eq!(binding, 0);
+	})
+}
+
+#[test]
+fn es_formats() {
+	test_harness_handling(
+		GlVersion::from_version(VersionType::ES, 2, 0),
+		ErrorHandling::DoNotPanic,
+		|| unsafe {
+			let mut renderbuffer = 0;
+			gl::GenRenderbuffers(1, &mut renderbuffer);
+			gl::BindRenderbuffer(gl::RENDERBUFFER, renderbuffer);
+
+			gl::RenderbufferStorage(gl::RENDERBUFFER, gl::RGB565, 4, 4);
+			assert_eq!(gl::GetError(), gl::NO_ERROR);
+
+			gl::RenderbufferStorage(gl::RENDERBUFFER, gl::RGBA8, 4, 4);
+			assert_eq!(gl::GetError(), gl::INVALID_ENUM);
+
+			gl::DeleteRenderbuffers(1, &renderbuffer);
+		},
+	);
+
+	let version = GlVersion::new(VersionType::ES, 2, 0, &[&ext::OES_rgb8_rgba8]);
+	test_harness(version, || unsafe {
+		let mut renderbuffer = 0;
+		gl::GenRenderbuffers(1, &mut renderbuffer);
+		gl::BindRenderbuffer(gl::RENDERBUFFER, renderbuffer);
+		gl::RenderbufferStorage(gl::RENDERBUFFER, gl::RGBA8, 4, 4);
+		gl::DeleteRenderbuffers(1, &renderbuffer);
+	});
+
+	// float color buffers need EXT_color_buffer_float before OpenGL ES 3.2
+	test_harness_handling(
+		GlVersion::from_version(VersionType::ES, 3, 0),
+		ErrorHandling::DoNotPanic,
+		|| unsafe {
+			let mut renderbuffer = 0;
+			gl::GenRenderbuffers(1, &mut renderbuffer);
+			gl::BindRenderbuffer(gl::RENDERBUFFER, renderbuffer);
+
+			gl::RenderbufferStorage(gl::RENDERBUFFER, gl::RGBA16F, 4, 4);
+			assert_eq!(gl::GetError(), gl::INVALID_ENUM);
+
+			gl::DeleteRenderbuffers(1, &renderbuffer);
+		},
+	);
+}
+
+#[test]
+fn gl_errors() {
+	let version = GlVersion::from_version(VersionType::GL, 3, 3).with_profile(Profile::Core);
+	test_harness_handling(version, ErrorHandling::DoNotPanic, || unsafe {
+		gl::RenderbufferStorage(gl::RENDERBUFFER, gl::RGBA8, 4, 4);
+		assert_eq!(gl::GetError(), gl::INVALID_OPERATION);
+
+		gl::BindRenderbuffer(gl::RENDERBUFFER, 3);
+		assert_eq!(gl::GetError(), gl::INVALID_OPERATION);
+
+		let mut renderbuffer = 0;
+		gl::GenRenderbuffers(1, &mut renderbuffer);
+		gl::BindRenderbuffer(gl::FRAMEBUFFER, renderbuffer);
+		assert_eq!(gl::GetError(), gl::INVALID_ENUM);
+		gl::BindRenderbuffer(gl::RENDERBUFFER, renderbuffer);
+
+		gl::RenderbufferStorage(gl::RENDERBUFFER, gl::RGBA, 4, 4);
+		assert_eq!(gl::GetError(), gl::INVALID_ENUM);
+
+		gl::RenderbufferStorage(gl::RENDERBUFFER, gl::RGBA8, -1, 4);
+		assert_eq!(gl::GetError(), gl::INVALID_VALUE);
+
+		gl::RenderbufferStorage(gl::RENDERBUFFER, gl::RGBA8, 4, 100_000);
+		assert_eq!(gl::GetError(), gl::INVALID_VALUE);
+
+		gl::DeleteRenderbuffers(1, &renderbuffer);
+	})
+}
+
+#[test]
+fn multisample() {
+	let version = GlVersion::from_version(VersionType::GL, 4, 3).with_profile(Profile::Core);
+	test_harness_context(version, |ctx| unsafe {
+		let mut max_samples = 0;
+		gl::GetIntegerv(gl::MAX_SAMPLES, &mut max_samples);
+		assert_eq!(max_samples, 4);
+
+		ctx.set_limits(Limits {
+			max_samples: 8,
+			..ctx.limits()
+		});
+		let mut counts = [0; 4];
+		gl::GetInternalformativ(
+			gl::RENDERBUFFER,
+			gl::RGBA8,
+			gl::NUM_SAMPLE_COUNTS,
+			1,
+			counts.as_mut_ptr(),
+		);
+		assert_eq!(counts[0], 3);
+		gl::GetInternalformativ(gl::RENDERBUFFER, gl::RGBA8, gl::SAMPLES, 4, counts.as_mut_ptr());
+		assert_eq!(counts, [8, 4, 2, 0]);
+		gl::GetInternalformativ(gl::RENDERBUFFER, gl::RGBA8, gl::SAMPLES, 0, ptr::null_mut());
+
+		// sample counts are rounded up to a supported one
+		let mut renderbuffer = 0;
+		gl::GenRenderbuffers(1, &mut renderbuffer);
+		gl::BindRenderbuffer(gl::RENDERBUFFER, renderbuffer);
+		gl::RenderbufferStorageMultisample(gl::RENDERBUFFER, 3, gl::DEPTH24_STENCIL8, 4, 2);
+
+		let mut value = 0;
+		gl::GetRenderbufferParameteriv(gl::RENDERBUFFER, gl::RENDERBUFFER_SAMPLES, &mut value);
+		assert_eq!(value, 4);
+		gl::GetRenderbufferParameteriv(gl::RENDERBUFFER, gl::RENDERBUFFER_HEIGHT, &mut value);
+		assert_eq!(value, 2);
+		gl::GetRenderbufferParameteriv(gl::RENDERBUFFER, gl::RENDERBUFFER_STENCIL_SIZE, &mut value);
+		assert_eq!(value, 8);
+		gl::GetRenderbufferParameteriv(
+			gl::RENDERBUFFER,
+			gl::RENDERBUFFER_INTERNAL_FORMAT,
+			&mut value,
+		);
+		assert_eq!(value as GLenum, gl::DEPTH24_STENCIL8);
+
+		gl::DeleteRenderbuffers(1, &renderbuffer);
+	})
+}
+
+#[test]
+fn multisample_gl_errors() {
+	test_harness_handling(
+		GlVersion::from_version(VersionType::ES, 3, 0),
+		ErrorHandling::DoNotPanic,
+		|| unsafe {
+			let mut renderbuffer = 0;
+			gl::GenRenderbuffers(1, &mut renderbuffer);
+			gl::BindRenderbuffer(gl::RENDERBUFFER, renderbuffer);
+
+			gl::RenderbufferStorageMultisample(gl::RENDERBUFFER, 5, gl::RGBA8, 4, 4);
+			assert_eq!(gl::GetError(), gl::INVALID_VALUE);
+
+			// OpenGL ES 3.0 has no multisample integer formats
+			gl::RenderbufferStorageMultisample(gl::RENDERBUFFER, 2, gl::RGBA8UI, 4, 4);
+			assert_eq!(gl::GetError(), gl::INVALID_OPERATION);
+
+			let mut count = -1;
+			gl::GetInternalformativ(
+				gl::RENDERBUFFER,
+				gl::RGBA8UI,
+				gl::NUM_SAMPLE_COUNTS,
+				1,
+				&mut count,
+			);
+			assert_eq!(count, 0);
+
+			gl::GetInternalformativ(
+				gl::RENDERBUFFER,
+				gl::RGBA32F,
+				gl::NUM_SAMPLE_COUNTS,
+				1,
+				&mut count,
+			);
+			assert_eq!(gl::GetError(), gl::INVALID_ENUM);
+
+			gl::GetInternalformativ(
+				gl::TEXTURE_2D,
+				gl::RGBA8,
+				gl::NUM_SAMPLE_COUNTS,
+				1,
+				&mut count,
+			);
+			assert_eq!(gl::GetError(), gl::INVALID_ENUM);
+
+			gl::GetInternalformativ(gl::RENDERBUFFER, gl::RGBA8, gl::SAMPLES, -1, &mut count);
+			assert_eq!(gl::GetError(), gl::INVALID_VALUE);
+
+			gl::DeleteRenderbuffers(1, &renderbuffer);
+		},
+	);
+}
@@ -0,0 +1,790 @@
+use std::{ops::Range, slice};
+
+use gl::types::{GLboolean, GLenum, GLint, GLsizei, GLuint};
+
+use crate::{
+	debug,
+	draw::IndexType,
+	error,
+	limits::Limits,
+	pixel::PixelStore,
+	query::StateValue,
+	version::{ext, gl_enum},
+	GlVersion,
+};
+
+pub mod gl_functions;
+mod render;
+
+pub use render::{
+	BlendEquation,
+	BlendFactor,
+	BlendFunc,
+	ClipDepthMode,
+	ClipOrigin,
+	CompareFunc,
+	DrawBufferState,
+	Face,
+	FrontFace,
+	RenderState,
+	Scissor,
+	StencilOp,
+	StencilState,
+	Viewport,
+	MAX_DRAW_BUFFERS,
+	MAX_VIEWPORTS,
+};
+
+#[cfg(test)]
+mod test;
+
+gl_enum! {
+	Capability {
+		BLEND(gl: 2 . 1, es: 2 . 0);
+		CULL_FACE(gl: 2 . 1, es: 2 . 0);
+		DEPTH_TEST(gl: 2 . 1, es: 2 . 0);
+		DITHER(gl: 2 . 1, es: 2 . 0);
+		POLYGON_OFFSET_FILL(gl: 2 . 1, es: 2 . 0);
+		SAMPLE_ALPHA_TO_COVERAGE(gl: 2 . 1, es: 2 . 0);
+		SAMPLE_COVERAGE(gl: 2 . 1, es: 2 . 0);
+		SCISSOR_TEST(gl: 2 . 1, es: 2 . 0);
+		STENCIL_TEST(gl: 2 . 1, es: 2 . 0);
+		RASTERIZER_DISCARD(gl: 3 . 0, es: 3 . 0);
+		PRIMITIVE_RESTART(gl: 3 . 1);
+		PRIMITIVE_RESTART_FIXED_INDEX(gl: 4 . 3, es: 3 . 0);
+		SAMPLE_MASK(gl: 3 . 2, es: 3 . 1);
+		SAMPLE_SHADING(gl: 4 . 0, es: 3 . 2);
+		DEBUG_OUTPUT(gl: 4 . 3, es: 3 . 2);
+		DEBUG_OUTPUT_SYNCHRONOUS(gl: 4 . 3, es: 3 . 2);
+		COLOR_LOGIC_OP(gl: 2 . 1);
+		DEPTH_CLAMP(gl: 3 . 2);
+		FRAMEBUFFER_SRGB(gl: 3 . 0);
+		LINE_SMOOTH(gl: 2 . 1);
+		MULTISAMPLE(gl: 2 . 1);
+		POLYGON_OFFSET_LINE(gl: 2 . 1);
+		POLYGON_OFFSET_POINT(gl: 2 . 1);
+		POLYGON_SMOOTH(gl: 2 . 1);
+		PROGRAM_POINT_SIZE(gl: 3 . 2);
+		SAMPLE_ALPHA_TO_ONE(gl: 2 . 1);
+		TEXTURE_CUBE_MAP_SEAMLESS(gl: 3 . 2);
+		CLIP_DISTANCE0(gl: 3 . 0);
+		CLIP_DISTANCE1(gl: 3 . 0);
+		CLIP_DISTANCE2(gl: 3 . 0);
+		CLIP_DISTANCE3(gl: 3 . 0);
+		CLIP_DISTANCE4(gl: 3 . 0);
+		CLIP_DISTANCE5(gl: 3 . 0);
+		CLIP_DISTANCE6(gl: 3 . 0);
+		CLIP_DISTANCE7(gl: 3 . 0);
+	}
+}
+
+/// A window space rectangle, as set by `glViewport` and `glScissor`
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Rect {
+	pub x: GLint,
+	pub y: GLint,
+	pub width: GLsizei,
+	pub height: GLsizei,
+}
+
+impl Rect {
+	pub fn new(x: GLint, y: GLint, width: GLsizei, height: GLsizei) -> Self {
+		Self {
+			x,
+			y,
+			width,
+			height,
+		}
+	}
+
+	pub fn contains(&self, x: GLint, y: GLint) -> bool {
+		x >= self.x && y >= self.y && x - self.x < self.width && y - self.y < self.height
+	}
+}
+
+/// Values passed to one of the `*Arrayv` functions,
+/// `None` with `GL_INVALID_VALUE` if `count` is negative
+///
+/// # Safety
+/// `values` must be valid for `count` reads
+pub(crate) unsafe fn array_values<'a, T>(
+	error: &mut GLenum,
+	func: &str,
+	values: *const T,
+	count: GLsizei,
+) -> Option<&'a [T]> {
+	match count {
+		..0 => {
+			*error = gl::INVALID_VALUE;
+			error!("{} called with negative count {}", func, count);
+			None
+		},
+		0 => Some(&[]),
+		count => Some(slice::from_raw_parts(values, count as usize)),
+	}
+}
+
+/// Context state toggled by `glEnable` and friends
+pub struct StateManager {
+	render: RenderState,
+	primitive_restart_index: GLuint,
+	clear_color: [f32; 4],
+	clear_depth: f32,
+	clear_stencil: GLint,
+	pack: PixelStore,
+	unpack: PixelStore,
+}
+
+impl Default for StateManager {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl StateManager {
+	pub fn new() -> Self {
+		Self {
+			render: RenderState::default(),
+			primitive_restart_index: 0,
+			clear_color: [0.0; 4],
+			clear_depth: 1.0,
+			clear_stencil: 0,
+			pack: PixelStore::default(),
+			unpack: PixelStore::default(),
+		}
+	}
+
+	/// Fixed-function state draws are currently processed with
+	pub fn render(&self) -> &RenderState {
+		&self.render
+	}
+
+	fn capability(
+		gl_version: &GlVersion,
+		error: &mut GLenum,
+		func: &str,
+		cap: GLenum,
+	) -> Option<Capability> {
+		match Capability::from_gl(cap) {
+			None => {
+				*error = gl::INVALID_ENUM;
+				error!("mock-gl does not support {} capability {}", func, cap);
+				None
+			},
+			Some(cap) if !cap.supported(gl_version) => {
+				*error = gl::INVALID_ENUM;
+				cap.check_version(gl_version);
+				None
+			},
+			Some(cap) => Some(cap),
+		}
+	}
+
+	/// Draw buffers set by a function, all of them unless `index` is given
+	fn draw_buffers(
+		limits: &Limits,
+		error: &mut GLenum,
+		func: &str,
+		index: Option<GLuint>,
+	) -> Option<Range<usize>> {
+		match index {
+			None => Some(0..MAX_DRAW_BUFFERS),
+			Some(index) if index >= limits.max_draw_buffers => {
+				*error = gl::INVALID_VALUE;
+				error!(
+					"{} called with draw buffer {}, but GL_MAX_DRAW_BUFFERS is {}",
+					func, index, limits.max_draw_buffers
+				);
+				None
+			},
+			Some(index) => Some(index as usize..index as usize + 1),
+		}
+	}
+
+	/// Viewports set by a function, all of them unless `first` is given
+	fn viewport_range(
+		limits: &Limits,
+		error: &mut GLenum,
+		func: &str,
+		first: Option<GLuint>,
+		count: GLsizei,
+	) -> Option<Range<usize>> {
+		let Some(first) = first else {
+			return Some(0..MAX_VIEWPORTS)
+		};
+
+		let max = limits.max_viewports;
+		if count < 0 || first as u64 + count as u64 > max as u64 {
+			*error = gl::INVALID_VALUE;
+			error!(
+				"{} called with {} viewports starting at {}, but GL_MAX_VIEWPORTS is {}",
+				func, count, first, max
+			);
+			return None
+		}
+
+		Some(first as usize..first as usize + count as usize)
+	}
+
+	/// Faces set by a function, given `GL_FRONT`, `GL_BACK` or `GL_FRONT_AND_BACK`
+	fn faces(
+		gl_version: &GlVersion,
+		error: &mut GLenum,
+		func: &str,
+		face: GLenum,
+	) -> Option<Range<usize>> {
+		match Face::from_gl(face).filter(|face| face.supported(gl_version)) {
+			None => {
+				*error = gl::INVALID_ENUM;
+				error!("{} called with invalid face {}", func, face);
+				None
+			},
+			Some(Face::FRONT) => Some(0..1),
+			Some(Face::BACK) => Some(1..2),
+			Some(Face::FRONT_AND_BACK) => Some(0..2),
+		}
+	}
+
+	pub fn set_enabled(
+		&mut self,
+		gl_version: &GlVersion,
+		error: &mut GLenum,
+		func: &str,
+		cap: GLenum,
+		enabled: bool,
+	) {
+		if let Some(cap) = Self::capability(gl_version, error, func, cap) {
+			self.render.set_enabled(cap, enabled);
+			debug!("{} {}", if enabled { "enabled" } else { "disabled" }, cap);
+		}
+	}
+
+	pub fn is_enabled(&self, gl_version: &GlVersion, error: &mut GLenum, cap: GLenum) -> GLboolean {
+		match Self::capability(gl_version, error, "glIsEnabled", cap) {
+			Some(cap) => self.render.enabled(cap) as GLboolean,
+			None => gl::FALSE,
+		}
+	}
+
+	/// Enable or disable an indexed capability, `GL_BLEND` of one draw buffer
+	/// or `GL_SCISSOR_TEST` of one viewport
+	pub fn set_enabled_indexed(
+		&mut self,
+		limits: &Limits,
+		error: &mut GLenum,
+		func: &str,
+		cap: GLenum,
+		index: GLuint,
+		enabled: bool,
+	) {
+		let verb = if enabled { "enabled" } else { "disabled" };
+		match cap {
+			gl::BLEND => {
+				if let Some(buffers) = Self::draw_buffers(limits, error, func, Some(index)) {
+					self.render.draw_buffers[buffers].iter_mut().for_each(|b| b.blend = enabled);
+					debug!("{} GL_BLEND of draw buffer {}", verb, index);
+				}
+			},
+			gl::SCISSOR_TEST => {
+				if let Some(viewports) = Self::viewport_range(limits, error, func, Some(index), 1) {
+					self.render.scissors[viewports].iter_mut().for_each(|s| s.enabled = enabled);
+					debug!("{} GL_SCISSOR_TEST of viewport {}", verb, index);
+				}
+			},
+			_ => {
+				*error = gl::INVALID_ENUM;
+				error!("{} called with {}, which is not an indexed capability", func, cap);
+			},
+		}
+	}
+
+	pub fn is_enabled_indexed(
+		&self,
+		limits: &Limits,
+		error: &mut GLenum,
+		cap: GLenum,
+		index: GLuint,
+	) -> GLboolean {
+		let func = "glIsEnabledi";
+		let enabled = match cap {
+			gl::BLEND => Self::draw_buffers(limits, error, func, Some(index))
+				.map(|buffers| self.render.draw_buffers[buffers.start].blend),
+			gl::SCISSOR_TEST => Self::viewport_range(limits, error, func, Some(index), 1)
+				.map(|viewports| self.render.scissors[viewports.start].enabled),
+			_ => {
+				*error = gl::INVALID_ENUM;
+				error!("{} called with {}, which is not an indexed capability", func, cap);
+				None
+			},
+		};
+
+		enabled.unwrap_or(false) as GLboolean
+	}
+
+	pub fn enabled(&self, cap: Capability) -> bool {
+		self.render.enabled(cap)
+	}
+
+	pub fn set_primitive_restart_index(&mut self, index: GLuint) {
+		self.primitive_restart_index = index;
+	}
+
+	/// Viewport 0, the one used without geometry shaders
+	pub fn viewport(&self) -> Viewport {
+		self.render.viewports[0]
+	}
+
+	/// Scissor box of viewport 0
+	pub fn scissor(&self) -> Rect {
+		self.render.scissors[0].rect
+	}
+
+	/// Set viewports `first..` to `viewports`, or all of them to the single viewport given
+	/// if `first` is `None`
+	///
+	/// Negative sizes are `GL_INVALID_VALUE`. Sizes are clamped to `GL_MAX_VIEWPORT_DIMS` and
+	/// origins to `GL_VIEWPORT_BOUNDS_RANGE`.
+	pub fn set_viewports(
+		&mut self,
+		limits: &Limits,
+		error: &mut GLenum,
+		func: &str,
+		first: Option<GLuint>,
+		viewports: &[[f32; 4]],
+	) {
+		let count = viewports.len() as GLsizei;
+		let Some(range) = Self::viewport_range(limits, error, func, first, count) else {
+			return
+		};
+
+		if let Some([_, _, width, height]) = viewports.iter().find(|v| v[2] < 0.0 || v[3] < 0.0) {
+			*error = gl::INVALID_VALUE;
+			error!("{} called with negative size {}x{}", func, width, height);
+			return
+		}
+
+		let [min, max] = limits.viewport_bounds();
+		for (i, viewport) in self.render.viewports[range].iter_mut().enumerate() {
+			let [x, y, width, height] = viewports[i.min(viewports.len() - 1)];
+			viewport.x = x.clamp(min, max);
+			viewport.y = y.clamp(min, max);
+			viewport.width = width.min(limits.max_viewport_dims[0] as f32);
+			viewport.height = height.min(limits.max_viewport_dims[1] as f32);
+		}
+	}
+
+	/// Set the scissor boxes of viewports `first..` to `rects`, or all of them to the single box
+	/// given if `first` is `None`, negative sizes are `GL_INVALID_VALUE`
+	pub fn set_scissors(
+		&mut self,
+		limits: &Limits,
+		error: &mut GLenum,
+		func: &str,
+		first: Option<GLuint>,
+		rects: &[Rect],
+	) {
+		let count = rects.len() as GLsizei;
+		let Some(range) = Self::viewport_range(limits, error, func, first, count) else {
+			return
+		};
+
+		if let Some(rect) = rects.iter().find(|rect| rect.width < 0 || rect.height < 0) {
+			*error = gl::INVALID_VALUE;
+			error!("{} called with negative size {}x{}", func, rect.width, rect.height);
+			return
+		}
+
+		for (i, scissor) in self.render.scissors[range].iter_mut().enumerate() {
+			scissor.rect = rects[i.min(rects.len() - 1)];
+		}
+	}
+
+	/// Set the depth ranges of viewports `first..` to `ranges`, or all of them to the single
+	/// range given if `first` is `None`, clamping them to `[0, 1]`
+	pub fn set_depth_ranges(
+		&mut self,
+		limits: &Limits,
+		error: &mut GLenum,
+		func: &str,
+		first: Option<GLuint>,
+		ranges: &[[f64; 2]],
+	) {
+		let count = ranges.len() as GLsizei;
+		let Some(range) = Self::viewport_range(limits, error, func, first, count) else {
+			return
+		};
+
+		for (i, viewport) in self.render.viewports[range].iter_mut().enumerate() {
+			viewport.depth_range =
+				ranges[i.min(ranges.len() - 1)].map(|d| d.clamp(0.0, 1.0) as f32);
+		}
+	}
+
+	pub fn set_clip_control(&mut self, error: &mut GLenum, origin: GLenum, depth: GLenum) {
+		let Some(origin) = ClipOrigin::from_gl(origin) else {
+			*error = gl::INVALID_ENUM;
+			error!("glClipControl called with invalid origin {}", origin);
+			return
+		};
+		let Some(depth) = ClipDepthMode::from_gl(depth) else {
+			*error = gl::INVALID_ENUM;
+			error!("glClipControl called with invalid depth mode {}", depth);
+			return
+		};
+
+		self.render.clip_origin = origin;
+		self.render.clip_depth_mode = depth;
+	}
+
+	/// Initialize the viewport and scissor box to the size of the surface
+	/// the context is first used with
+	pub(crate) fn init_surface_size(&mut self, width: GLsizei, height: GLsizei) {
+		for viewport in &mut self.render.viewports {
+			viewport.width = width as f32;
+			viewport.height = height as f32;
+		}
+		for scissor in &mut self.render.scissors {
+			scissor.rect = Rect::new(0, 0, width, height);
+		}
+	}
+
+	pub fn set_depth_func(&mut self, gl_version: &GlVersion, error: &mut GLenum, func: GLenum) {
+		match CompareFunc::from_gl(func).filter(|func| func.supported(gl_version)) {
+			None => {
+				*error = gl::INVALID_ENUM;
+				error!("glDepthFunc called with invalid function {}", func);
+			},
+			Some(func) => self.render.depth_func = func,
+		}
+	}
+
+	pub fn set_depth_mask(&mut self, mask: bool) {
+		self.render.depth_mask = mask;
+	}
+
+	/// Set the color and alpha blend factors of all draw buffers, or only draw buffer `index`
+	pub fn set_blend_func(
+		&mut self,
+		gl_version: &GlVersion,
+		limits: &Limits,
+		error: &mut GLenum,
+		func: &str,
+		index: Option<GLuint>,
+		[src_rgb, dst_rgb, src_alpha, dst_alpha]: [GLenum; 4],
+	) {
+		let factor = |error: &mut GLenum, factor: GLenum| match BlendFactor::from_gl(factor)
+			.filter(|factor| factor.supported(gl_version))
+		{
+			None => {
+				*error = gl::INVALID_ENUM;
+				error!("{} called with invalid blend factor {}", func, factor);
+				None
+			},
+			factor => factor,
+		};
+
+		let factors = [src_rgb, dst_rgb, src_alpha, dst_alpha].map(|f| factor(error, f));
+		let [Some(src_rgb), Some(dst_rgb), Some(src_alpha), Some(dst_alpha)] = factors else {
+			return
+		};
+
+		if let Some(buffers) = Self::draw_buffers(limits, error, func, index) {
+			for buffer in &mut self.render.draw_buffers[buffers] {
+				buffer.blend_func = BlendFunc {
+					src_rgb,
+					dst_rgb,
+					src_alpha,
+					dst_alpha,
+				};
+			}
+		}
+	}
+
+	/// Set the color and alpha blend equations of all draw buffers, or only draw buffer `index`
+	pub fn set_blend_equation(
+		&mut self,
+		gl_version: &GlVersion,
+		limits: &Limits,
+		error: &mut GLenum,
+		func: &str,
+		index: Option<GLuint>,
+		[rgb, alpha]: [GLenum; 2],
+	) {
+		let equation = |error: &mut GLenum, mode: GLenum| {
+			let supported = |equation: &BlendEquation| {
+				equation.supported(gl_version)
+					|| (matches!(equation, BlendEquation::MIN | BlendEquation::MAX)
+						&& gl_version.has_extension(&ext::EXT_blend_minmax))
+			};
+
+			match BlendEquation::from_gl(mode).filter(supported) {
+				None => {
+					*error = gl::INVALID_ENUM;
+					error!("{} called with invalid blend equation {}", func, mode);
+					None
+				},
+				equation => equation,
+			}
+		};
+
+		let (Some(rgb), Some(alpha)) = (equation(error, rgb), equation(error, alpha)) else {
+			return
+		};
+
+		if let Some(buffers) = Self::draw_buffers(limits, error, func, index) {
+			for buffer in &mut self.render.draw_buffers[buffers] {
+				buffer.blend_equation = (rgb, alpha);
+			}
+		}
+	}
+
+	pub fn set_blend_color(&mut self, color: [f32; 4]) {
+		self.render.blend_color = color;
+	}
+
+	/// Set the color write masks of all draw buffers, or only draw buffer `index`
+	pub fn set_color_mask(
+		&mut self,
+		limits: &Limits,
+		error: &mut GLenum,
+		func: &str,
+		index: Option<GLuint>,
+		mask: [bool; 4],
+	) {
+		if let Some(buffers) = Self::draw_buffers(limits, error, func, index) {
+			for buffer in &mut self.render.draw_buffers[buffers] {
+				buffer.color_mask = mask;
+			}
+		}
+	}
+
+	#[allow(clippy::too_many_arguments)]
+	pub fn set_stencil_func(
+		&mut self,
+		gl_version: &GlVersion,
+		error: &mut GLenum,
+		func_name: &str,
+		face: GLenum,
+		func: GLenum,
+		reference: GLint,
+		mask: GLuint,
+	) {
+		let Some(faces) = Self::faces(gl_version, error, func_name, face) else {
+			return
+		};
+		let Some(func) = CompareFunc::from_gl(func).filter(|func| func.supported(gl_version))
+		else {
+			*error = gl::INVALID_ENUM;
+			error!("{} called with invalid function {}", func_name, func);
+			return
+		};
+
+		for stencil in &mut self.render.stencil[faces] {
+			stencil.func = func;
+			stencil.reference = reference;
+			stencil.value_mask = mask;
+		}
+	}
+
+	/// Set the operations when the stencil test fails, the depth test fails, or both pass
+	pub fn set_stencil_op(
+		&mut self,
+		gl_version: &GlVersion,
+		error: &mut GLenum,
+		func: &str,
+		face: GLenum,
+		ops: [GLenum; 3],
+	) {
+		let Some(faces) = Self::faces(gl_version, error, func, face) else {
+			return
+		};
+
+		let op = |error: &mut GLenum, op: GLenum| match StencilOp::from_gl(op)
+			.filter(|op| op.supported(gl_version))
+		{
+			None => {
+				*error = gl::INVALID_ENUM;
+				error!("{} called with invalid stencil operation {}", func, op);
+				None
+			},
+			op => op,
+		};
+		let [Some(fail), Some(depth_fail), Some(depth_pass)] = ops.map(|o| op(error, o)) else {
+			return
+		};
+
+		for stencil in &mut self.render.stencil[faces] {
+			stencil.fail = fail;
+			stencil.depth_fail = depth_fail;
+			stencil.depth_pass = depth_pass;
+		}
+	}
+
+	pub fn set_stencil_writemask(
+		&mut self,
+		gl_version: &GlVersion,
+		error: &mut GLenum,
+		func: &str,
+		face: GLenum,
+		mask: GLuint,
+	) {
+		if let Some(faces) = Self::faces(gl_version, error, func, face) {
+			for stencil in &mut self.render.stencil[faces] {
+				stencil.writemask = mask;
+			}
+		}
+	}
+
+	pub fn set_cull_face(&mut self, gl_version: &GlVersion, error: &mut GLenum, mode: GLenum) {
+		match Face::from_gl(mode).filter(|face| face.supported(gl_version)) {
+			None => {
+				*error = gl::INVALID_ENUM;
+				error!("glCullFace called with invalid face {}", mode);
+			},
+			Some(face) => self.render.cull_face = face,
+		}
+	}
+
+	pub fn set_front_face(&mut self, gl_version: &GlVersion, error: &mut GLenum, mode: GLenum) {
+		match FrontFace::from_gl(mode).filter(|face| face.supported(gl_version)) {
+			None => {
+				*error = gl::INVALID_ENUM;
+				error!("glFrontFace called with invalid orientation {}", mode);
+			},
+			Some(face) => self.render.front_face = face,
+		}
+	}
+
+	pub fn clear_color(&self) -> [f32; 4] {
+		self.clear_color
+	}
+
+	pub fn clear_depth(&self) -> f32 {
+		self.clear_depth
+	}
+
+	pub fn clear_stencil(&self) -> GLint {
+		self.clear_stencil
+	}
+
+	pub fn set_clear_color(&mut self, color: [f32; 4]) {
+		self.clear_color = color;
+	}
+
+	/// Set the depth clear value, clamped to `[0, 1]`
+	pub fn set_clear_depth(&mut self, depth: f32) {
+		self.clear_depth = depth.clamp(0.0, 1.0);
+	}
+
+	pub fn set_clear_stencil(&mut self, stencil: GLint) {
+		self.clear_stencil = stencil;
+	}
+
+	/// Pixel storage modes of reads into client memory or pixel pack buffers
+	pub fn pack(&self) -> PixelStore {
+		self.pack
+	}
+
+	/// Pixel storage modes of uploads from client memory or pixel unpack buffers
+	pub fn unpack(&self) -> PixelStore {
+		self.unpack
+	}
+
+	pub fn pixel_store(
+		&mut self,
+		gl_version: &GlVersion,
+		error: &mut GLenum,
+		pname: GLenum,
+		param: GLint,
+	) {
+		let store = match pname {
+			gl::PACK_ALIGNMENT
+			| gl::PACK_ROW_LENGTH
+			| gl::PACK_SKIP_PIXELS
+			| gl::PACK_SKIP_ROWS
+			| gl::PACK_IMAGE_HEIGHT
+			| gl::PACK_SKIP_IMAGES => &mut self.pack,
+			_ => &mut self.unpack,
+		};
+		store.set(gl_version, error, pname, param);
+	}
+
+	/// Index value which restarts primitives when drawing indices of type `ty`
+	///
+	/// The fixed index takes precedence if both kinds of restart are enabled.
+	pub fn restart_index(&self, ty: IndexType) -> Option<u32> {
+		if self.render.enabled(Capability::PRIMITIVE_RESTART_FIXED_INDEX) {
+			Some(u32::MAX >> (32 - 8 * ty.size()))
+		} else if self.render.enabled(Capability::PRIMITIVE_RESTART) {
+			Some(self.primitive_restart_index)
+		} else {
+			None
+		}
+	}
+
+	pub fn primitive_restart_index(&self) -> GLuint {
+		self.primitive_restart_index
+	}
+
+	/// State of draw buffer or viewport `index`, `None` if `pname` is not indexed state
+	pub fn indexed_state(&self, pname: GLenum, index: usize) -> Option<StateValue> {
+		let buffer = &self.render.draw_buffers[index.min(MAX_DRAW_BUFFERS - 1)];
+		let viewport = &self.render.viewports[index.min(MAX_VIEWPORTS - 1)];
+		let factor = |factor: BlendFactor| StateValue::int(factor.to_gl());
+
+		Some(match pname {
+			gl::BLEND => StateValue::boolean(buffer.blend),
+			gl::BLEND_SRC_RGB => factor(buffer.blend_func.src_rgb),
+			gl::BLEND_DST_RGB => factor(buffer.blend_func.dst_rgb),
+			gl::BLEND_SRC_ALPHA => factor(buffer.blend_func.src_alpha),
+			gl::BLEND_DST_ALPHA => factor(buffer.blend_func.dst_alpha),
+			gl::BLEND_EQUATION_RGB => StateValue::int(buffer.blend_equation.0.to_gl()),
+			gl::BLEND_EQUATION_ALPHA => StateValue::int(buffer.blend_equation.1.to_gl()),
+			gl::COLOR_WRITEMASK => StateValue::Boolean(buffer.color_mask.to_vec()),
+			gl::SCISSOR_TEST => StateValue::boolean(self.render.scissors[index].enabled),
+			gl::VIEWPORT => StateValue::Float(
+				[viewport.x, viewport.y, viewport.width, viewport.height]
+					.map(f64::from)
+					.to_vec(),
+			),
+			gl::SCISSOR_BOX => {
+				let rect = self.render.scissors[index].rect;
+				StateValue::Integer(
+					[rect.x, rect.y, rect.width, rect.height].map(i64::from).to_vec(),
+				)
+			},
+			gl::DEPTH_RANGE => StateValue::Normalized(viewport.depth_range.map(f64::from).to_vec()),
+			_ => return None,
+		})
+	}
+
+	/// Indexed state of a draw buffer or viewport, as queried by `glGetIntegeri_v` and friends
+	pub fn get_indexed(
+		&self,
+		limits: &Limits,
+		error: &mut GLenum,
+		func: &str,
+		pname: GLenum,
+		index: GLuint,
+	) -> Option<StateValue> {
+		let index = match pname {
+			gl::BLEND
+			| gl::BLEND_SRC_RGB
+			| gl::BLEND_DST_RGB
+			| gl::BLEND_SRC_ALPHA
+			| gl::BLEND_DST_ALPHA
+			| gl::BLEND_EQUATION_RGB
+			| gl::BLEND_EQUATION_ALPHA
+			| gl::COLOR_WRITEMASK => Self::draw_buffers(limits, error, func, Some(index))?.start,
+			gl::VIEWPORT | gl::SCISSOR_BOX | gl::DEPTH_RANGE | gl::SCISSOR_TEST =>
+				Self::viewport_range(limits, error, func, Some(index), 1)?.start,
+			_ => {
+				*error = gl::INVALID_ENUM;
+				error!("mock-gl does not support {} target {}", func, pname);
+				return None
+			},
+		};
+
+		self.indexed_state(pname, index)
+	}
+}
@@ -0,0 +1,519 @@
+use gl::types::{GLboolean, GLdouble, GLenum, GLfloat, GLint, GLsizei, GLuint};
+
+use crate::{
+	function_mapping::gl_functions,
+	state::{array_values, Rect},
+};
+
+gl_functions! {
+	fn glEnable(cap: GLenum);
+	require gl 2 . 1;
+	require es 2 . 0;
+	take [gl_version, error, state_manager]
+	{
+		state_manager.set_enabled(gl_version, error, "glEnable", cap, true);
+	}
+
+	fn glDisable(cap: GLenum);
+	require gl 2 . 1;
+	require es 2 . 0;
+	take [gl_version, error, state_manager]
+	{
+		state_manager.set_enabled(gl_version, error, "glDisable", cap, false);
+	}
+
+	fn glIsEnabled(cap: GLenum) -> GLboolean;
+	require gl 2 . 1;
+	require es 2 . 0;
+	take [gl_version, error, state_manager]
+	{
+		state_manager.is_enabled(gl_version, error, cap)
+	}
+
+	fn glEnablei(target: GLenum, index: GLuint);
+	require gl 3 . 0;
+	require es 3 . 2;
+	require ext OES_draw_buffers_indexed | EXT_draw_buffers_indexed;
+	take [limits, error, state_manager]
+	{
+		state_manager.set_enabled_indexed(limits, error, "glEnablei", target, index, true);
+	}
+
+	fn glDisablei(target: GLenum, index: GLuint);
+	require gl 3 . 0;
+	require es 3 . 2;
+	require ext OES_draw_buffers_indexed | EXT_draw_buffers_indexed;
+	take [limits, error, state_manager]
+	{
+		state_manager.set_enabled_indexed(limits, error, "glDisablei", target, index, false);
+	}
+
+	fn glIsEnabledi(target: GLenum, index: GLuint) -> GLboolean;
+	require gl 3 . 0;
+	require es 3 . 2;
+	require ext OES_draw_buffers_indexed | EXT_draw_buffers_indexed;
+	take [limits, error, state_manager]
+	{
+		state_manager.is_enabled_indexed(limits, error, target, index)
+	}
+
+	fn glPrimitiveRestartIndex(index: GLuint);
+	require gl 3 . 1;
+	take [state_manager]
+	{
+		state_manager.set_primitive_restart_index(index);
+	}
+
+	fn glViewport(x: GLint, y: GLint, width: GLsizei, height: GLsizei);
+	require gl 2 . 1;
+	require es 2 . 0;
+	take [limits, error, state_manager]
+	{
+		let viewport = [x as f32, y as f32, width as f32, height as f32];
+		state_manager.set_viewports(limits, error, "glViewport", None, &[viewport]);
+	}
+
+	fn glViewportIndexedf(index: GLuint, x: GLfloat, y: GLfloat, w: GLfloat, h: GLfloat);
+	require gl 4 . 1;
+	require ext ARB_viewport_array | OES_viewport_array;
+	take [limits, error, state_manager]
+	{
+		state_manager.set_viewports(
+			limits,
+			error,
+			"glViewportIndexedf",
+			Some(index),
+			&[[x, y, w, h]],
+		);
+	}
+
+	fn glViewportIndexedfv(index: GLuint, v: *const GLfloat);
+	require gl 4 . 1;
+	require ext ARB_viewport_array | OES_viewport_array;
+	take [limits, error, state_manager]
+	{
+		let viewport = (v as *const [GLfloat; 4]).read();
+		state_manager.set_viewports(limits, error, "glViewportIndexedfv", Some(index), &[viewport]);
+	}
+
+	fn glViewportArrayv(first: GLuint, count: GLsizei, v: *const GLfloat);
+	require gl 4 . 1;
+	require ext ARB_viewport_array | OES_viewport_array;
+	take [limits, error, state_manager]
+	{
+		let func = "glViewportArrayv";
+		if let Some(viewports) = array_values(error, func, v as *const [GLfloat; 4], count) {
+			state_manager.set_viewports(limits, error, func, Some(first), viewports);
+		}
+	}
+
+	fn glScissor(x: GLint, y: GLint, width: GLsizei, height: GLsizei);
+	require gl 2 . 1;
+	require es 2 . 0;
+	take [limits, error, state_manager]
+	{
+		let rect = Rect::new(x, y, width, height);
+		state_manager.set_scissors(limits, error, "glScissor", None, &[rect]);
+	}
+
+	fn glScissorIndexed(index: GLuint, left: GLint, bottom: GLint, width: GLsizei, height: GLsizei);
+	require gl 4 . 1;
+	require ext ARB_viewport_array | OES_viewport_array;
+	take [limits, error, state_manager]
+	{
+		let rect = Rect::new(left, bottom, width, height);
+		state_manager.set_scissors(limits, error, "glScissorIndexed", Some(index), &[rect]);
+	}
+
+	fn glScissorIndexedv(index: GLuint, v: *const GLint);
+	require gl 4 . 1;
+	require ext ARB_viewport_array | OES_viewport_array;
+	take [limits, error, state_manager]
+	{
+		let [x, y, width, height] = (v as *const [GLint; 4]).read();
+		let rect = Rect::new(x, y, width, height);
+		state_manager.set_scissors(limits, error, "glScissorIndexedv", Some(index), &[rect]);
+	}
+
+	fn glScissorArrayv(first: GLuint, count: GLsizei, v: *const GLint);
+	require gl 4 . 1;
+	require ext ARB_viewport_array | OES_viewport_array;
+	take [limits, error, state_manager]
+	{
+		let func = "glScissorArrayv";
+		if let Some(values) = array_values(error, func, v as *const [GLint; 4], count) {
+			let rects: Vec<_> =
+				values.iter().map(|[x, y, w, h]| Rect::new(*x, *y, *w, *h)).collect();
+			state_manager.set_scissors(limits, error, func, Some(first), &rects);
+		}
+	}
+
+	fn glDepthRange(near: GLdouble, far: GLdouble);
+	require gl 2 . 1;
+	take [limits, error, state_manager]
+	{
+		state_manager.set_depth_ranges(limits, error, "glDepthRange", None, &[[near, far]]);
+	}
+
+	fn glDepthRangef(near: GLfloat, far: GLfloat);
+	require gl 4 . 1;
+	require es 2 . 0;
+	require ext ARB_ES2_compatibility;
+	take [limits, error, state_manager]
+	{
+		let range = [near as f64, far as f64];
+		state_manager.set_depth_ranges(limits, error, "glDepthRangef", None, &[range]);
+	}
+
+	fn glDepthRangeIndexed(index: GLuint, near: GLdouble, far: GLdouble);
+	require gl 4 . 1;
+	require ext ARB_viewport_array;
+	take [limits, error, state_manager]
+	{
+		let func = "glDepthRangeIndexed";
+		state_manager.set_depth_ranges(limits, error, func, Some(index), &[[near, far]]);
+	}
+
+	fn glDepthRangeIndexedfOES(index: GLuint, near: GLfloat, far: GLfloat);
+	require ext OES_viewport_array;
+	take [limits, error, state_manager]
+	{
+		let (func, range) = ("glDepthRangeIndexedfOES", [near as f64, far as f64]);
+		state_manager.set_depth_ranges(limits, error, func, Some(index), &[range]);
+	}
+
+	fn glDepthRangeArrayv(first: GLuint, count: GLsizei, v: *const GLdouble);
+	require gl 4 . 1;
+	require ext ARB_viewport_array;
+	take [limits, error, state_manager]
+	{
+		let func = "glDepthRangeArrayv";
+		if let Some(ranges) = array_values(error, func, v as *const [GLdouble; 2], count) {
+			state_manager.set_depth_ranges(limits, error, func, Some(first), ranges);
+		}
+	}
+
+	fn glDepthRangeArrayfvOES(first: GLuint, count: GLsizei, v: *const GLfloat);
+	require ext OES_viewport_array;
+	take [limits, error, state_manager]
+	{
+		let func = "glDepthRangeArrayfvOES";
+		if let Some(ranges) = array_values(error, func, v as *const [GLfloat; 2], count) {
+			let ranges: Vec<_> = ranges.iter().map(|range| range.map(|d| d as f64)).collect();
+			state_manager.set_depth_ranges(limits, error, func, Some(first), &ranges);
+		}
+	}
+
+	fn glClipControl(origin: GLenum, depth: GLenum);
+	require gl 4 . 5;
+	require ext ARB_clip_control | EXT_clip_control;
+	take [error, state_manager]
+	{
+		state_manager.set_clip_control(error, origin, depth);
+	}
+
+	fn glDepthFunc(func: GLenum);
+	require gl 2 . 1;
+	require es 2 . 0;
+	take [gl_version, error, state_manager]
+	{
+		state_manager.set_depth_func(gl_version, error, func);
+	}
+
+	fn glDepthMask(flag: GLboolean);
+	require gl 2 . 1;
+	require es 2 . 0;
+	take [state_manager]
+	{
+		state_manager.set_depth_mask(flag != gl::FALSE);
+	}
+
+	fn glBlendFunc(src: GLenum, dst: GLenum);
+	require gl 2 . 1;
+	require es 2 . 0;
+	take [gl_version, limits, error, state_manager]
+	{
+		state_manager.set_blend_func(
+			gl_version,
+			limits,
+			error,
+			"glBlendFunc",
+			None,
+			[src, dst, src, dst],
+		);
+	}
+
+	fn glBlendFuncSeparate(src_rgb: GLenum, dst_rgb: GLenum, src_alpha: GLenum, dst_alpha: GLenum);
+	require gl 2 . 1;
+	require es 2 . 0;
+	take [gl_version, limits, error, state_manager]
+	{
+		let factors = [src_rgb, dst_rgb, src_alpha, dst_alpha];
+		state_manager.set_blend_func(
+			gl_version,
+			limits,
+			error,
+			"glBlendFuncSeparate",
+			None,
+			factors,
+		);
+	}
+
+	fn glBlendFunci(buf: GLuint, src: GLenum, dst: GLenum);
+	require gl 4 . 0;
+	require es 3 . 2;
+	require ext ARB_draw_buffers_blend | OES_draw_buffers_indexed | EXT_draw_buffers_indexed;
+	take [gl_version, limits, error, state_manager]
+	{
+		state_manager.set_blend_func(
+			gl_version,
+			limits,
+			error,
+			"glBlendFunci",
+			Some(buf),
+			[src, dst, src, dst],
+		);
+	}
+
+	fn glBlendFuncSeparatei(
+		buf: GLuint,
+		src_rgb: GLenum,
+		dst_rgb: GLenum,
+		src_alpha: GLenum,
+		dst_alpha: GLenum,
+	);
+	require gl 4 . 0;
+	require es 3 . 2;
+	require ext ARB_draw_buffers_blend | OES_draw_buffers_indexed | EXT_draw_buffers_indexed;
+	take [gl_version, limits, error, state_manager]
+	{
+		let factors = [src_rgb, dst_rgb, src_alpha, dst_alpha];
+		state_manager.set_blend_func(
+			gl_version,
+			limits,
+			error,
+			"glBlendFuncSeparatei",
+			Some(buf),
+			factors,
+		);
+	}
+
+	fn glBlendEquation(mode: GLenum);
+	require gl 2 . 1;
+	require es 2 . 0;
+	take [gl_version, limits, error, state_manager]
+	{
+		state_manager.set_blend_equation(
+			gl_version,
+			limits,
+			error,
+			"glBlendEquation",
+			None,
+			[mode, mode],
+		);
+	}
+
+	fn glBlendEquationSeparate(mode_rgb: GLenum, mode_alpha: GLenum);
+	require gl 2 . 1;
+	require es 2 . 0;
+	take [gl_version, limits, error, state_manager]
+	{
+		let modes = [mode_rgb, mode_alpha];
+		state_manager.set_blend_equation(
+			gl_version,
+			limits,
+			error,
+			"glBlendEquationSeparate",
+			None,
+			modes,
+		);
+	}
+
+	fn glBlendEquationi(buf: GLuint, mode: GLenum);
+	require gl 4 . 0;
+	require es 3 . 2;
+	require ext ARB_draw_buffers_blend | OES_draw_buffers_indexed | EXT_draw_buffers_indexed;
+	take [gl_version, limits, error, state_manager]
+	{
+		state_manager.set_blend_equation(
+			gl_version,
+			limits,
+			error,
+			"glBlendEquationi",
+			Some(buf),
+			[mode, mode],
+		);
+	}
+
+	fn glBlendEquationSeparatei(buf: GLuint, mode_rgb: GLenum, mode_alpha: GLenum);
+	require gl 4 . 0;
+	require es 3 . 2;
+	require ext ARB_draw_buffers_blend | OES_draw_buffers_indexed | EXT_draw_buffers_indexed;
+	take [gl_version, limits, error, state_manager]
+	{
+		let modes = [mode_rgb, mode_alpha];
+		state_manager.set_blend_equation(
+			gl_version,
+			limits,
+			error,
+			"glBlendEquationSeparatei",
+			Some(buf),
+			modes,
+		);
+	}
+
+	fn glBlendColor(red: GLfloat, green: GLfloat, blue: GLfloat, alpha: GLfloat);
+	require gl 2 . 1;
+	require es 2 . 0;
+	take [state_manager]
+	{
+		state_manager.set_blend_color([red, green, blue, alpha]);
+	}
+
+	fn glStencilFunc(func: GLenum, reference: GLint, mask: GLuint);
+	require gl 2 . 1;
+	require es 2 . 0;
+	take [gl_version, error, state_manager]
+	{
+		let face = gl::FRONT_AND_BACK;
+		state_manager.set_stencil_func(
+			gl_version,
+			error,
+			"glStencilFunc",
+			face,
+			func,
+			reference,
+			mask,
+		);
+	}
+
+	fn glStencilFuncSeparate(face: GLenum, func: GLenum, reference: GLint, mask: GLuint);
+	require gl 2 . 1;
+	require es 2 . 0;
+	take [gl_version, error, state_manager]
+	{
+		let func_name = "glStencilFuncSeparate";
+		state_manager.set_stencil_func(gl_version, error, func_name, face, func, reference, mask);
+	}
+
+	fn glStencilOp(sfail: GLenum, dpfail: GLenum, dppass: GLenum);
+	require gl 2 . 1;
+	require es 2 . 0;
+	take [gl_version, error, state_manager]
+	{
+		let ops = [sfail, dpfail, dppass];
+		state_manager.set_stencil_op(gl_version, error, "glStencilOp", gl::FRONT_AND_BACK, ops);
+	}
+
+	fn glStencilOpSeparate(face: GLenum, sfail: GLenum, dpfail: GLenum, dppass: GLenum);
+	require gl 2 . 1;
+	require es 2 . 0;
+	take [gl_version, error, state_manager]
+	{
+		let ops = [sfail, dpfail, dppass];
+		state_manager.set_stencil_op(gl_version, error, "glStencilOpSeparate", face, ops);
+	}
+
+	fn glStencilMask(mask: GLuint);
+	require gl 2 . 1;
+	require es 2 . 0;
+	take [gl_version, error, state_manager]
+	{
+		state_manager.set_stencil_writemask(
+			gl_version,
+			error,
+			"glStencilMask",
+			gl::FRONT_AND_BACK,
+			mask,
+		);
+	}
+
+	fn glStencilMaskSeparate(face: GLenum, mask: GLuint);
+	require gl 2 . 1;
+	require es 2 . 0;
+	take [gl_version, error, state_manager]
+	{
+		state_manager.set_stencil_writemask(gl_version, error, "glStencilMaskSeparate", face, mask);
+	}
+
+	fn glCullFace(mode: GLenum);
+	require gl 2 . 1;
+	require es 2 . 0;
+	take [gl_version, error, state_manager]
+	{
+		state_manager.set_cull_face(gl_version, error, mode);
+	}
+
+	fn glFrontFace(mode: GLenum);
+	require gl 2 . 1;
+	require es 2 . 0;
+	take [gl_version, error, state_manager]
+	{
+		state_manager.set_front_face(gl_version, error, mode);
+	}
+
+	fn glClearColor(red: GLfloat, green: GLfloat, blue: GLfloat, alpha: GLfloat);
+	require gl 2 . 1;
+	require es 2 . 0;
+	take [state_manager]
+	{
+		state_manager.set_clear_color([red, green, blue, alpha]);
+	}
+
+	fn glClearDepth(depth: GLdouble);
+	require gl 2 . 1;
+	take [state_manager]
+	{
+		state_manager.set_clear_depth(depth as f32);
+	}
+
+	fn glClearDepthf(depth: GLfloat);
+	require gl 4 . 1;
+	require es 2 . 0;
+	require ext ARB_ES2_compatibility;
+	take [state_manager]
+	{
+		state_manager.set_clear_depth(depth);
+	}
+
+	fn glClearStencil(stencil: GLint);
+	require gl 2 . 1;
+	require es 2 . 0;
+	take [state_manager]
+	{
+		state_manager.set_clear_stencil(stencil);
+	}
+
+	fn glColorMask(red: GLboolean, green: GLboolean, blue: GLboolean, alpha: GLboolean);
+	require gl 2 . 1;
+	require es 2 . 0;
+	take [limits, error, state_manager]
+	{
+		let mask = [red, green, blue, alpha].map(|mask| mask != gl::FALSE);
+		state_manager.set_color_mask(limits, error, "glColorMask", None, mask);
+	}
+
+	fn glColorMaski(
+		buf: GLuint,
+		red: GLboolean,
+		green: GLboolean,
+		blue: GLboolean,
+		alpha: GLboolean,
+	);
+	require gl 3 . 0;
+	require es 3 . 2;
+	require ext OES_draw_buffers_indexed | EXT_draw_buffers_indexed;
+	take [limits, error, state_manager]
+	{
+		let mask = [red, green, blue, alpha].map(|mask| mask != gl::FALSE);
+		state_manager.set_color_mask(limits, error, "glColorMaski", Some(buf), mask);
+	}
+
+	fn glPixelStorei(pname: GLenum, param: GLint);
+	require gl 2 . 1;
+	require es 2 . 0;
+	take [gl_version, error, state_manager]
+	{
+		state_manager.pixel_store(gl_version, error, pname, param);
+	}
+}
@@ -0,0 +1,405 @@
+use enum_map::EnumMap;
+use gl::types::{GLint, GLsizei, GLuint};
+
+use crate::{
+	state::{Capability, Rect},
+	version::gl_enum,
+};
+
+/// Number of draw buffers with their own blending and color mask state
+pub const MAX_DRAW_BUFFERS: usize = 8;
+/// Number of viewports with their own scissor box and depth range
+pub const MAX_VIEWPORTS: usize = 16;
+
+gl_enum! {
+	CompareFunc {
+		NEVER(gl: 2 . 1, es: 2 . 0);
+		LESS(gl: 2 . 1, es: 2 . 0);
+		EQUAL(gl: 2 . 1, es: 2 . 0);
+		LEQUAL(gl: 2 . 1, es: 2 . 0);
+		GREATER(gl: 2 . 1, es: 2 . 0);
+		NOTEQUAL(gl: 2 . 1, es: 2 . 0);
+		GEQUAL(gl: 2 . 1, es: 2 . 0);
+		ALWAYS(gl: 2 . 1, es: 2 . 0);
+	}
+}
+
+impl CompareFunc {
+	/// True if an `incoming` value passes against the `stored` one
+	pub fn test<T: PartialOrd>(&self, incoming: T, stored: T) -> bool {
+		match self {
+			Self::NEVER => false,
+			Self::LESS => incoming < stored,
+			Self::EQUAL => incoming == stored,
+			Self::LEQUAL => incoming <= stored,
+			Self::GREATER => incoming > stored,
+			Self::NOTEQUAL => incoming != stored,
+			Self::GEQUAL => incoming >= stored,
+			Self::ALWAYS => true,
+		}
+	}
+}
+
+gl_enum! {
+	BlendFactor {
+		ZERO(gl: 2 . 1, es: 2 . 0);
+		ONE(gl: 2 . 1, es: 2 . 0);
+		SRC_COLOR(gl: 2 . 1, es: 2 . 0);
+		ONE_MINUS_SRC_COLOR(gl: 2 . 1, es: 2 . 0);
+		DST_COLOR(gl: 2 . 1, es: 2 . 0);
+		ONE_MINUS_DST_COLOR(gl: 2 . 1, es: 2 . 0);
+		SRC_ALPHA(gl: 2 . 1, es: 2 . 0);
+		ONE_MINUS_SRC_ALPHA(gl: 2 . 1, es: 2 . 0);
+		DST_ALPHA(gl: 2 . 1, es: 2 . 0);
+		ONE_MINUS_DST_ALPHA(gl: 2 . 1, es: 2 . 0);
+		CONSTANT_COLOR(gl: 2 . 1, es: 2 . 0);
+		ONE_MINUS_CONSTANT_COLOR(gl: 2 . 1, es: 2 . 0);
+		CONSTANT_ALPHA(gl: 2 . 1, es: 2 . 0);
+		ONE_MINUS_CONSTANT_ALPHA(gl: 2 . 1, es: 2 . 0);
+		SRC_ALPHA_SATURATE(gl: 2 . 1, es: 2 . 0);
+	}
+}
+
+impl BlendFactor {
+	/// Per-component factor for source color `src`, destination color `dst` and blend color `constant`
+	pub fn factor(&self, src: [f32; 4], dst: [f32; 4], constant: [f32; 4]) -> [f32; 4] {
+		match self {
+			Self::ZERO => [0.0; 4],
+			Self::ONE => [1.0; 4],
+			Self::SRC_COLOR => src,
+			Self::ONE_MINUS_SRC_COLOR => src.map(|c| 1.0 - c),
+			Self::DST_COLOR => dst,
+			Self::ONE_MINUS_DST_COLOR => dst.map(|c| 1.0 - c),
+			Self::SRC_ALPHA => [src[3]; 4],
+			Self::ONE_MINUS_SRC_ALPHA => [1.0 - src[3]; 4],
+			Self::DST_ALPHA => [dst[3]; 4],
+			Self::ONE_MINUS_DST_ALPHA => [1.0 - dst[3]; 4],
+			Self::CONSTANT_COLOR => constant,
+			Self::ONE_MINUS_CONSTANT_COLOR => constant.map(|c| 1.0 - c),
+			Self::CONSTANT_ALPHA => [constant[3]; 4],
+			Self::ONE_MINUS_CONSTANT_ALPHA => [1.0 - constant[3]; 4],
+			Self::SRC_ALPHA_SATURATE => {
+				let f = src[3].min(1.0 - dst[3]);
+				[f, f, f, 1.0]
+			},
+		}
+	}
+}
+
+gl_enum! {
+	BlendEquation {
+		FUNC_ADD(gl: 2 . 1, es: 2 . 0);
+		FUNC_SUBTRACT(gl: 2 . 1, es: 2 . 0);
+		FUNC_REVERSE_SUBTRACT(gl: 2 . 1, es: 2 . 0);
+		MIN(gl: 2 . 1, es: 3 . 0);
+		MAX(gl: 2 . 1, es: 3 . 0);
+	}
+}
+
+impl BlendEquation {
+	/// Combine source and destination components, scaled by their factors except for min and max
+	pub fn apply(&self, src: f32, dst: f32, src_factor: f32, dst_factor: f32) -> f32 {
+		match self {
+			Self::FUNC_ADD => src * src_factor + dst * dst_factor,
+			Self::FUNC_SUBTRACT => src * src_factor - dst * dst_factor,
+			Self::FUNC_REVERSE_SUBTRACT => dst * dst_factor - src * src_factor,
+			Self::MIN => src.min(dst),
+			Self::MAX => src.max(dst),
+		}
+	}
+}
+
+gl_enum! {
+	StencilOp {
+		KEEP(gl: 2 . 1, es: 2 . 0);
+		ZERO(gl: 2 . 1, es: 2 . 0);
+		REPLACE(gl: 2 . 1, es: 2 . 0);
+		INCR(gl: 2 . 1, es: 2 . 0);
+		INCR_WRAP(gl: 2 . 1, es: 2 . 0);
+		DECR(gl: 2 . 1, es: 2 . 0);
+		DECR_WRAP(gl: 2 . 1, es: 2 . 0);
+		INVERT(gl: 2 . 1, es: 2 . 0);
+	}
+}
+
+impl StencilOp {
+	/// New stencil value replacing `value`, before the write mask is applied
+	pub fn apply(&self, value: u8, reference: u8) -> u8 {
+		match self {
+			Self::KEEP => value,
+			Self::ZERO => 0,
+			Self::REPLACE => reference,
+			Self::INCR => value.saturating_add(1),
+			Self::INCR_WRAP => value.wrapping_add(1),
+			Self::DECR => value.saturating_sub(1),
+			Self::DECR_WRAP => value.wrapping_sub(1),
+			Self::INVERT => !value,
+		}
+	}
+}
+
+gl_enum! {
+	Face {
+		FRONT(gl: 2 . 1, es: 2 . 0);
+		BACK(gl: 2 . 1, es: 2 . 0);
+		FRONT_AND_BACK(gl: 2 . 1, es: 2 . 0);
+	}
+}
+
+impl Face {
+	/// True if the face includes front or back facing primitives
+	pub fn includes(&self, front_facing: bool) -> bool {
+		match self {
+			Self::FRONT => front_facing,
+			Self::BACK => !front_facing,
+			Self::FRONT_AND_BACK => true,
+		}
+	}
+}
+
+gl_enum! {
+	FrontFace {
+		CW(gl: 2 . 1, es: 2 . 0);
+		CCW(gl: 2 . 1, es: 2 . 0);
+	}
+}
+
+gl_enum! {
+	ClipOrigin {
+		LOWER_LEFT(gl: 4 . 5);
+		UPPER_LEFT(gl: 4 . 5);
+	}
+}
+
+gl_enum! {
+	ClipDepthMode {
+		NEGATIVE_ONE_TO_ONE(gl: 4 . 5);
+		ZERO_TO_ONE(gl: 4 . 5);
+	}
+}
+
+/// Source and destination factors of color and alpha
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct BlendFunc {
+	pub src_rgb: BlendFactor,
+	pub dst_rgb: BlendFactor,
+	pub src_alpha: BlendFactor,
+	pub dst_alpha: BlendFactor,
+}
+
+/// Blending and color mask of one draw buffer
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct DrawBufferState {
+	pub blend: bool,
+	pub blend_func: BlendFunc,
+	/// Equations of color and alpha
+	pub blend_equation: (BlendEquation, BlendEquation),
+	/// Red, green, blue and alpha write masks
+	pub color_mask: [bool; 4],
+}
+
+impl Default for DrawBufferState {
+	fn default() -> Self {
+		Self {
+			blend: false,
+			blend_func: BlendFunc {
+				src_rgb: BlendFactor::ONE,
+				dst_rgb: BlendFactor::ZERO,
+				src_alpha: BlendFactor::ONE,
+				dst_alpha: BlendFactor::ZERO,
+			},
+			blend_equation: (BlendEquation::FUNC_ADD, BlendEquation::FUNC_ADD),
+			color_mask: [true; 4],
+		}
+	}
+}
+
+impl DrawBufferState {
+	/// Blend the fragment color `src` with the stored color `dst`,
+	/// or take `src` as it is if blending is disabled
+	pub fn blend(&self, src: [f32; 4], dst: [f32; 4], constant: [f32; 4]) -> [f32; 4] {
+		if !self.blend {
+			return src
+		}
+
+		let src = src.map(|c| c.clamp(0.0, 1.0));
+		let func = &self.blend_func;
+		let src_rgb = func.src_rgb.factor(src, dst, constant);
+		let dst_rgb = func.dst_rgb.factor(src, dst, constant);
+		let src_alpha = func.src_alpha.factor(src, dst, constant)[3];
+		let dst_alpha = func.dst_alpha.factor(src, dst, constant)[3];
+
+		let (rgb, alpha) = self.blend_equation;
+		[
+			rgb.apply(src[0], dst[0], src_rgb[0], dst_rgb[0]),
+			rgb.apply(src[1], dst[1], src_rgb[1], dst_rgb[1]),
+			rgb.apply(src[2], dst[2], src_rgb[2], dst_rgb[2]),
+			alpha.apply(src[3], dst[3], src_alpha, dst_alpha),
+		]
+	}
+}
+
+/// Stencil test of one face
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct StencilState {
+	pub func: CompareFunc,
+	/// Reference value as set, clamped to the stencil buffer's range when tested
+	pub reference: GLint,
+	pub value_mask: GLuint,
+	/// Operation when the stencil test fails
+	pub fail: StencilOp,
+	/// Operation when the stencil test passes but the depth test fails
+	pub depth_fail: StencilOp,
+	/// Operation when both tests pass, or the depth test is disabled
+	pub depth_pass: StencilOp,
+	pub writemask: GLuint,
+}
+
+impl Default for StencilState {
+	fn default() -> Self {
+		Self {
+			func: CompareFunc::ALWAYS,
+			reference: 0,
+			value_mask: GLuint::MAX,
+			fail: StencilOp::KEEP,
+			depth_fail: StencilOp::KEEP,
+			depth_pass: StencilOp::KEEP,
+			writemask: GLuint::MAX,
+		}
+	}
+}
+
+impl StencilState {
+	/// Reference value of an 8 bit stencil buffer
+	pub fn reference(&self) -> u8 {
+		self.reference.clamp(0, u8::MAX as GLint) as u8
+	}
+
+	/// True if a fragment passes against the `stored` stencil value
+	pub fn test(&self, stored: u8) -> bool {
+		let mask = self.value_mask as u8;
+		self.func.test(self.reference() & mask, stored & mask)
+	}
+
+	/// Apply `op` to the `stored` value, honoring the write mask
+	pub fn update(&self, op: StencilOp, stored: u8) -> u8 {
+		let mask = self.writemask as u8;
+		(stored & !mask) | (op.apply(stored, self.reference()) & mask)
+	}
+}
+
+/// Viewport transform of one viewport
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Viewport {
+	pub x: f32,
+	pub y: f32,
+	pub width: f32,
+	pub height: f32,
+	/// Window depths of the near and far clip planes
+	pub depth_range: [f32; 2],
+}
+
+impl Default for Viewport {
+	fn default() -> Self {
+		Self {
+			x: 0.0,
+			y: 0.0,
+			width: 0.0,
+			height: 0.0,
+			depth_range: [0.0, 1.0],
+		}
+	}
+}
+
+impl Viewport {
+	/// The viewport rounded to whole pixels, as `glGetIntegerv` reports it
+	pub fn rect(&self) -> Rect {
+		Rect::new(
+			self.x.round() as GLint,
+			self.y.round() as GLint,
+			self.width.round() as GLsizei,
+			self.height.round() as GLsizei,
+		)
+	}
+}
+
+/// Scissor test of one viewport
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Scissor {
+	pub enabled: bool,
+	pub rect: Rect,
+}
+
+/// Fixed-function state a draw is processed with, recorded with every draw call
+#[derive(Clone, Debug, PartialEq)]
+pub struct RenderState {
+	/// Capabilities besides `GL_BLEND` and `GL_SCISSOR_TEST`,
+	/// which each draw buffer and viewport enable separately
+	enabled: EnumMap<Capability, bool>,
+	pub draw_buffers: [DrawBufferState; MAX_DRAW_BUFFERS],
+	pub blend_color: [f32; 4],
+	pub depth_func: CompareFunc,
+	pub depth_mask: bool,
+	/// Stencil test of front and back facing primitives
+	pub stencil: [StencilState; 2],
+	pub cull_face: Face,
+	pub front_face: FrontFace,
+	pub viewports: [Viewport; MAX_VIEWPORTS],
+	pub scissors: [Scissor; MAX_VIEWPORTS],
+	pub clip_origin: ClipOrigin,
+	pub clip_depth_mode: ClipDepthMode,
+}
+
+impl Default for RenderState {
+	fn default() -> Self {
+		let mut enabled = EnumMap::default();
+		enabled[Capability::DITHER] = true;
+		enabled[Capability::MULTISAMPLE] = true;
+
+		Self {
+			enabled,
+			draw_buffers: [DrawBufferState::default(); MAX_DRAW_BUFFERS],
+			blend_color: [0.0; 4],
+			depth_func: CompareFunc::LESS,
+			depth_mask: true,
+			stencil: [StencilState::default(); 2],
+			cull_face: Face::BACK,
+			front_face: FrontFace::CCW,
+			viewports: [Viewport::default(); MAX_VIEWPORTS],
+			scissors: [Scissor::default(); MAX_VIEWPORTS],
+			clip_origin: ClipOrigin::LOWER_LEFT,
+			clip_depth_mode: ClipDepthMode::NEGATIVE_ONE_TO_ONE,
+		}
+	}
+}
+
+impl RenderState {
+	/// True if `cap` is enabled, for draw buffer or viewport 0 if it is indexed
+	pub fn enabled(&self, cap: Capability) -> bool {
+		match cap {
+			Capability::BLEND => self.draw_buffers[0].blend,
+			Capability::SCISSOR_TEST => self.scissors[0].enabled,
+			cap => self.enabled[cap],
+		}
+	}
+
+	pub(crate) fn set_enabled(&mut self, cap: Capability, enabled: bool) {
+		match cap {
+			Capability::BLEND =>
+				self.draw_buffers.iter_mut().for_each(|buffer| buffer.blend = enabled),
+			Capability::SCISSOR_TEST =>
+				self.scissors.iter_mut().for_each(|scissor| scissor.enabled = enabled),
+			cap => self.enabled[cap] = enabled,
+		}
+	}
+
+	/// Stencil test of front or back facing primitives
+	pub fn stencil(&self, front_facing: bool) -> &StencilState {
+		&self.stencil[!front_facing as usize]
+	}
+
+	/// True if primitives of this facing are discarded by face culling
+	pub fn culled(&self, front_facing: bool) -> bool {
+		self.enabled(Capability::CULL_FACE) && self.cull_face.includes(front_facing)
+	}
+}
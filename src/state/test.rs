@@ -0,0 +1,306 @@
+use std::ptr;
+
+use gl::types::{GLboolean, GLint};
+
+use crate::{
+	limits::Limits,
+	test::{gl43_core, test_harness, test_harness_context, test_harness_handling},
+	version::{ext, Profile, VersionType},
+	GlVersion,
+};
+
+#[test]
+fn enable_disable() {
+	test_harness(gl43_core(), || unsafe {
+		assert_eq!(gl::IsEnabled(gl::PRIMITIVE_RESTART), gl::FALSE);
+
+		gl::Enable(gl::PRIMITIVE_RESTART);
+		gl::Enable(gl::PRIMITIVE_RESTART_FIXED_INDEX);
+		assert_eq!(gl::IsEnabled(gl::PRIMITIVE_RESTART), gl::TRUE);
+		assert_eq!(gl::IsEnabled(gl::PRIMITIVE_RESTART_FIXED_INDEX), gl::TRUE);
+
+		gl::Disable(gl::PRIMITIVE_RESTART);
+		assert_eq!(gl::IsEnabled(gl::PRIMITIVE_RESTART), gl::FALSE);
+		assert_eq!(gl::IsEnabled(gl::PRIMITIVE_RESTART_FIXED_INDEX), gl::TRUE);
+	})
+}
+
+#[test]
+fn primitive_restart_index() {
+	test_harness(gl43_core(), || unsafe {
+		let mut index: GLint = -1;
+		gl::GetIntegerv(gl::PRIMITIVE_RESTART_INDEX, &mut index);
+		assert_eq!(index, 0);
+
+		gl::PrimitiveRestartIndex(7);
+		gl::GetIntegerv(gl::PRIMITIVE_RESTART_INDEX, &mut index);
+		assert_eq!(index, 7);
+	})
+}
+
+#[test]
+#[should_panic]
+fn primitive_restart_on_es() {
+	test_harness(GlVersion::from_version(VersionType::ES, 3, 0), || unsafe {
+		gl::Enable(gl::PRIMITIVE_RESTART);
+	})
+}
+
+#[test]
+fn gl_errors() {
+	test_harness_handling(gl43_core(), crate::ErrorHandling::DoNotPanic, || unsafe {
+		gl::Enable(gl::TEXTURE_BINDING_2D);
+		assert_eq!(gl::GetError(), gl::INVALID_ENUM);
+
+		assert_eq!(gl::IsEnabled(gl::TEXTURE_BINDING_2D), gl::FALSE);
+		assert_eq!(gl::GetError(), gl::INVALID_ENUM);
+	})
+}
+
+#[test]
+fn fragment_state_queries() {
+	test_harness(gl43_core(), || unsafe {
+		let get = |pname| {
+			let mut value: GLint = -1;
+			gl::GetIntegerv(pname, &mut value);
+			value as u32
+		};
+
+		assert_eq!(get(gl::BLEND), gl::FALSE as u32);
+		assert_eq!(get(gl::DITHER), gl::TRUE as u32);
+		assert_eq!(get(gl::BLEND_SRC_RGB), gl::ONE);
+		assert_eq!(get(gl::DEPTH_FUNC), gl::LESS);
+		assert_eq!(get(gl::STENCIL_BACK_FUNC), gl::ALWAYS);
+		assert_eq!(get(gl::CULL_FACE_MODE), gl::BACK);
+		assert_eq!(get(gl::FRONT_FACE), gl::CCW);
+
+		gl::Enable(gl::CULL_FACE);
+		gl::BlendFuncSeparate(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA, gl::ONE, gl::ZERO);
+		gl::BlendEquationSeparate(gl::FUNC_SUBTRACT, gl::MAX);
+		gl::DepthFunc(gl::GEQUAL);
+		gl::StencilFuncSeparate(gl::BACK, gl::EQUAL, 3, 0xf);
+		gl::StencilOpSeparate(gl::FRONT, gl::ZERO, gl::INCR_WRAP, gl::INVERT);
+		gl::StencilMaskSeparate(gl::BACK, 0x7);
+		gl::CullFace(gl::FRONT_AND_BACK);
+		gl::FrontFace(gl::CW);
+
+		assert_eq!(gl::IsEnabled(gl::CULL_FACE), gl::TRUE);
+		assert_eq!(get(gl::BLEND_SRC_RGB), gl::SRC_ALPHA);
+		assert_eq!(get(gl::BLEND_DST_RGB), gl::ONE_MINUS_SRC_ALPHA);
+		assert_eq!(get(gl::BLEND_DST_ALPHA), gl::ZERO);
+		assert_eq!(get(gl::BLEND_EQUATION_RGB), gl::FUNC_SUBTRACT);
+		assert_eq!(get(gl::BLEND_EQUATION_ALPHA), gl::MAX);
+		assert_eq!(get(gl::DEPTH_FUNC), gl::GEQUAL);
+		assert_eq!(get(gl::STENCIL_FUNC), gl::ALWAYS);
+		assert_eq!(get(gl::STENCIL_BACK_FUNC), gl::EQUAL);
+		assert_eq!(get(gl::STENCIL_BACK_REF), 3);
+		assert_eq!(get(gl::STENCIL_BACK_VALUE_MASK), 0xf);
+		assert_eq!(get(gl::STENCIL_FAIL), gl::ZERO);
+		assert_eq!(get(gl::STENCIL_PASS_DEPTH_PASS), gl::INVERT);
+		assert_eq!(get(gl::STENCIL_BACK_FAIL), gl::KEEP);
+		assert_eq!(get(gl::STENCIL_WRITEMASK), u32::MAX);
+		assert_eq!(get(gl::STENCIL_BACK_WRITEMASK), 0x7);
+		assert_eq!(get(gl::CULL_FACE_MODE), gl::FRONT_AND_BACK);
+		assert_eq!(get(gl::FRONT_FACE), gl::CW);
+
+		let mut mask = [-1; 4];
+		gl::ColorMask(gl::TRUE, gl::FALSE, gl::TRUE, gl::FALSE);
+		gl::GetIntegerv(gl::COLOR_WRITEMASK, mask.as_mut_ptr());
+		assert_eq!(mask, [1, 0, 1, 0]);
+
+		let mut color = [0; 4];
+		gl::BlendColor(1.0, 0.0, 0.5, 0.0);
+		gl::GetIntegerv(gl::BLEND_COLOR, color.as_mut_ptr());
+		assert_eq!([color[0], color[1], color[3]], [GLint::MAX, 0, 0]);
+		assert!((color[2] - GLint::MAX / 2).abs() < 256);
+	})
+}
+
+#[test]
+fn indexed_draw_buffer_state() {
+	test_harness(gl43_core(), || unsafe {
+		gl::Enablei(gl::BLEND, 2);
+		assert_eq!(gl::IsEnabledi(gl::BLEND, 2), gl::TRUE);
+		assert_eq!(gl::IsEnabledi(gl::BLEND, 0), gl::FALSE);
+		assert_eq!(gl::IsEnabled(gl::BLEND), gl::FALSE);
+
+		gl::BlendFunci(1, gl::DST_COLOR, gl::ZERO);
+		gl::BlendEquationi(1, gl::MIN);
+		gl::ColorMaski(1, gl::FALSE, gl::TRUE, gl::FALSE, gl::TRUE);
+
+		let mut value: GLint = -1;
+		gl::GetIntegeri_v(gl::BLEND_SRC_ALPHA, 1, &mut value);
+		assert_eq!(value as u32, gl::DST_COLOR);
+		gl::GetIntegeri_v(gl::BLEND_EQUATION_RGB, 1, &mut value);
+		assert_eq!(value as u32, gl::MIN);
+		gl::GetIntegeri_v(gl::BLEND_SRC_ALPHA, 0, &mut value);
+		assert_eq!(value as u32, gl::ONE);
+
+		let mut mask: [GLboolean; 4] = [2; 4];
+		gl::GetBooleani_v(gl::COLOR_WRITEMASK, 1, mask.as_mut_ptr());
+		assert_eq!(mask, [gl::FALSE, gl::TRUE, gl::FALSE, gl::TRUE]);
+
+		// the non-indexed functions set every draw buffer
+		gl::Enable(gl::BLEND);
+		gl::BlendFunc(gl::ONE, gl::ONE);
+		assert_eq!(gl::IsEnabledi(gl::BLEND, 7), gl::TRUE);
+		gl::GetIntegeri_v(gl::BLEND_DST_RGB, 1, &mut value);
+		assert_eq!(value as u32, gl::ONE);
+	})
+}
+
+#[test]
+fn fragment_state_errors() {
+	test_harness_handling(gl43_core(), crate::ErrorHandling::DoNotPanic, || unsafe {
+		gl::BlendFunc(gl::ONE, gl::LESS);
+		assert_eq!(gl::GetError(), gl::INVALID_ENUM);
+		gl::BlendEquation(gl::ONE);
+		assert_eq!(gl::GetError(), gl::INVALID_ENUM);
+		gl::StencilFuncSeparate(gl::LEFT, gl::LESS, 0, 0);
+		assert_eq!(gl::GetError(), gl::INVALID_ENUM);
+		gl::StencilOp(gl::KEEP, gl::KEEP, gl::LESS);
+		assert_eq!(gl::GetError(), gl::INVALID_ENUM);
+		gl::CullFace(gl::CW);
+		assert_eq!(gl::GetError(), gl::INVALID_ENUM);
+		gl::FrontFace(gl::BACK);
+		assert_eq!(gl::GetError(), gl::INVALID_ENUM);
+
+		gl::Enablei(gl::BLEND, 8);
+		assert_eq!(gl::GetError(), gl::INVALID_VALUE);
+		gl::Enablei(gl::DEPTH_TEST, 0);
+		assert_eq!(gl::GetError(), gl::INVALID_ENUM);
+		gl::ColorMaski(8, gl::TRUE, gl::TRUE, gl::TRUE, gl::TRUE);
+		assert_eq!(gl::GetError(), gl::INVALID_VALUE);
+
+		let mut value: GLint = -1;
+		gl::GetIntegeri_v(gl::DEPTH_FUNC, 0, &mut value);
+		assert_eq!(gl::GetError(), gl::INVALID_ENUM);
+		assert_eq!(value, -1);
+	})
+}
+
+#[test]
+fn capabilities_per_version() {
+	let es2 = GlVersion::from_version(VersionType::ES, 2, 0);
+	test_harness_handling(es2, crate::ErrorHandling::DoNotPanic, || unsafe {
+		gl::Enable(gl::STENCIL_TEST);
+		gl::Enable(gl::RASTERIZER_DISCARD);
+		assert_eq!(gl::GetError(), gl::INVALID_ENUM);
+		gl::Enable(gl::DEPTH_CLAMP);
+		assert_eq!(gl::GetError(), gl::INVALID_ENUM);
+		assert_eq!(gl::IsEnabled(gl::STENCIL_TEST), gl::TRUE);
+
+		// min and max need OpenGL ES 3.0 or EXT_blend_minmax
+		gl::BlendEquation(gl::MAX);
+		assert_eq!(gl::GetError(), gl::INVALID_ENUM);
+	});
+
+	let es2_minmax = GlVersion::new(VersionType::ES, 2, 0, &[&ext::EXT_blend_minmax]);
+	test_harness(es2_minmax, || unsafe {
+		gl::BlendEquation(gl::MAX);
+	});
+}
+
+#[test]
+fn viewport_state_queries() {
+	let version = GlVersion::from_version(VersionType::GL, 4, 5).with_profile(Profile::Core);
+	test_harness_context(version, |ctx| unsafe {
+		gl::Viewport(1, 2, 30, 40);
+		gl::Scissor(3, 4, 5, 6);
+		gl::DepthRange(0.25, 2.0);
+
+		let mut values: [GLint; 4] = [0; 4];
+		gl::GetIntegerv(gl::VIEWPORT, values.as_mut_ptr());
+		assert_eq!(values, [1, 2, 30, 40]);
+		gl::GetIntegerv(gl::SCISSOR_BOX, values.as_mut_ptr());
+		assert_eq!(values, [3, 4, 5, 6]);
+
+		// depth ranges are clamped to [0, 1]
+		let mut range = [0.0; 2];
+		gl::GetFloati_v(gl::DEPTH_RANGE, 0, range.as_mut_ptr());
+		assert_eq!(range, [0.25, 1.0]);
+
+		// sizes are clamped to GL_MAX_VIEWPORT_DIMS
+		ctx.set_limits(Limits {
+			max_viewport_dims: [64, 32],
+			..ctx.limits()
+		});
+		gl::GetIntegerv(gl::MAX_VIEWPORT_DIMS, values.as_mut_ptr());
+		assert_eq!(values[..2], [64, 32]);
+		gl::Viewport(-1000, 0, 100, 100);
+		gl::GetIntegerv(gl::VIEWPORT, values.as_mut_ptr());
+		assert_eq!(values, [-128, 0, 64, 32]);
+
+		let mut origin = 0;
+		gl::ClipControl(gl::UPPER_LEFT, gl::ZERO_TO_ONE);
+		gl::GetIntegerv(gl::CLIP_ORIGIN, &mut origin);
+		assert_eq!(origin, gl::UPPER_LEFT as GLint);
+		gl::GetIntegerv(gl::CLIP_DEPTH_MODE, &mut origin);
+		assert_eq!(origin, gl::ZERO_TO_ONE as GLint);
+	})
+}
+
+#[test]
+fn indexed_viewports() {
+	test_harness(gl43_core(), || unsafe {
+		let mut max_viewports = 0;
+		gl::GetIntegerv(gl::MAX_VIEWPORTS, &mut max_viewports);
+		assert_eq!(max_viewports, 16);
+
+		gl::Viewport(0, 0, 8, 8);
+		gl::ViewportIndexedf(1, 0.5, 1.5, 2.0, 4.0);
+		gl::ViewportArrayv(2, 2, [1.0, 1.0, 2.0, 2.0, 3.0, 3.0, 4.0, 4.0].as_ptr());
+		gl::ScissorIndexed(3, 1, 2, 3, 4);
+		gl::DepthRangeArrayv(4, 1, [0.5, 0.75].as_ptr());
+
+		let mut viewport = [0.0; 4];
+		gl::GetFloati_v(gl::VIEWPORT, 1, viewport.as_mut_ptr());
+		assert_eq!(viewport, [0.5, 1.5, 2.0, 4.0]);
+		gl::GetFloati_v(gl::VIEWPORT, 3, viewport.as_mut_ptr());
+		assert_eq!(viewport, [3.0, 3.0, 4.0, 4.0]);
+
+		let mut values: [GLint; 4] = [0; 4];
+		gl::GetIntegeri_v(gl::VIEWPORT, 0, values.as_mut_ptr());
+		assert_eq!(values, [0, 0, 8, 8]);
+		gl::GetIntegeri_v(gl::SCISSOR_BOX, 3, values.as_mut_ptr());
+		assert_eq!(values, [1, 2, 3, 4]);
+
+		let mut range = [0.0; 2];
+		gl::GetDoublei_v(gl::DEPTH_RANGE, 4, range.as_mut_ptr());
+		assert_eq!(range, [0.5, 0.75]);
+
+		gl::Enablei(gl::SCISSOR_TEST, 5);
+		assert_eq!(gl::IsEnabledi(gl::SCISSOR_TEST, 5), gl::TRUE);
+		assert_eq!(gl::IsEnabled(gl::SCISSOR_TEST), gl::FALSE);
+	})
+}
+
+#[test]
+fn viewport_errors() {
+	let version = GlVersion::from_version(VersionType::GL, 4, 5).with_profile(Profile::Core);
+	test_harness_handling(version, crate::ErrorHandling::DoNotPanic, || unsafe {
+		gl::Viewport(0, 0, -1, 4);
+		assert_eq!(gl::GetError(), gl::INVALID_VALUE);
+		gl::Scissor(0, 0, 4, -1);
+		assert_eq!(gl::GetError(), gl::INVALID_VALUE);
+		gl::ViewportArrayv(0, -1, ptr::null());
+		assert_eq!(gl::GetError(), gl::INVALID_VALUE);
+
+		// first + count must not exceed GL_MAX_VIEWPORTS
+		gl::ScissorArrayv(15, 2, [0; 8].as_ptr());
+		assert_eq!(gl::GetError(), gl::INVALID_VALUE);
+		gl::ViewportIndexedf(16, 0.0, 0.0, 1.0, 1.0);
+		assert_eq!(gl::GetError(), gl::INVALID_VALUE);
+
+		gl::ClipControl(gl::LOWER_LEFT, gl::LOWER_LEFT);
+		assert_eq!(gl::GetError(), gl::INVALID_ENUM);
+	});
+
+	// ES has no GL_MAX_VIEWPORTS without OES_viewport_array
+	let es3 = GlVersion::from_version(VersionType::ES, 3, 2);
+	test_harness_handling(es3, crate::ErrorHandling::DoNotPanic, || unsafe {
+		let mut max_viewports = 0;
+		gl::GetIntegerv(gl::MAX_VIEWPORTS, &mut max_viewports);
+		assert_eq!(gl::GetError(), gl::INVALID_ENUM);
+	});
+}
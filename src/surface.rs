@@ -0,0 +1,189 @@
+use gl::types::GLsizei;
+
+use crate::{debug, format::InternalFormat, framebuffer::Framebuffer, MockContextRef};
+
+#[cfg(test)]
+mod test;
+
+/// Description of the default framebuffer, like the config a window surface is created with
+///
+/// The default surface starts out empty until `MockContextRef::resize` is called, keeps colors
+/// unrounded, and has 24 depth bits and 8 stencil bits.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Surface {
+	pub width: GLsizei,
+	pub height: GLsizei,
+	/// Format colors are rounded to, `None` only clamps them to `[0, 1]`
+	pub color_format: Option<InternalFormat>,
+	pub depth_bits: u8,
+	pub stencil_bits: u8,
+	pub samples: GLsizei,
+	pub double_buffered: bool,
+}
+
+impl Default for Surface {
+	fn default() -> Self {
+		Self {
+			width: 0,
+			height: 0,
+			color_format: None,
+			depth_bits: 24,
+			stencil_bits: 8,
+			samples: 0,
+			double_buffered: true,
+		}
+	}
+}
+
+impl Surface {
+	pub fn new(width: GLsizei, height: GLsizei) -> Self {
+		Self {
+			width,
+			height,
+			..Self::default()
+		}
+	}
+
+	/// Round colors to the normalized format with these red, green, blue and alpha bits
+	///
+	/// # Panics
+	/// If no such format exists, the supported ones being 8888, 888, 4444, 5551, 565 and 10 10 10 2.
+	pub fn with_color_bits(self, bits: [u8; 4]) -> Self {
+		let formats = [
+			InternalFormat::RGBA8,
+			InternalFormat::RGB8,
+			InternalFormat::RGBA4,
+			InternalFormat::RGB5_A1,
+			InternalFormat::RGB565,
+			InternalFormat::RGB10_A2,
+		];
+		let Some(format) = formats.into_iter().find(|format| format.color_bits() == bits) else {
+			panic!("mock-gl has no default framebuffer format with color bits {bits:?}");
+		};
+
+		Self {
+			color_format: Some(format),
+			..self
+		}
+	}
+
+	/// Use an sRGB color format, which requires 8 bits per component
+	///
+	/// # Panics
+	/// If the color bits aren't 8888
+	pub fn with_srgb(self) -> Self {
+		let format = self.color_format.unwrap_or(InternalFormat::RGBA8);
+		if format.color_bits() != [8; 4] {
+			panic!("mock-gl only supports sRGB default framebuffers with 8888 color bits");
+		}
+
+		Self {
+			color_format: Some(InternalFormat::SRGB8_ALPHA8),
+			..self
+		}
+	}
+
+	pub fn with_depth_bits(self, depth_bits: u8) -> Self {
+		Self { depth_bits, ..self }
+	}
+
+	pub fn with_stencil_bits(self, stencil_bits: u8) -> Self {
+		Self {
+			stencil_bits,
+			..self
+		}
+	}
+
+	pub fn with_samples(self, samples: GLsizei) -> Self {
+		Self { samples, ..self }
+	}
+
+	/// Draw straight to the front buffer, swaps then only mark frame boundaries
+	pub fn single_buffered(self) -> Self {
+		Self {
+			double_buffered: false,
+			..self
+		}
+	}
+
+	pub fn is_srgb(&self) -> bool {
+		self.color_format == Some(InternalFormat::SRGB8_ALPHA8)
+	}
+}
+
+/// Work done between two buffer swaps
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct FrameStats {
+	/// Index of the frame, counting from 0
+	pub frame: u64,
+	pub draw_calls: usize,
+	/// Vertices fetched by the frame's draw calls, counting every instance
+	pub vertices: usize,
+	/// `glClear` and `glClearBuffer*` calls
+	pub clears: usize,
+}
+
+/// Frame boundaries marked by `MockContextRef::swap_buffers`
+#[derive(Default)]
+pub struct FrameManager {
+	frames: Vec<FrameStats>,
+	/// Running totals when the current frame began
+	frame_start: FrameStats,
+}
+
+impl FrameManager {
+	/// Index of the frame being drawn, the number of swaps so far
+	pub fn frame(&self) -> u64 {
+		self.frames.len() as u64
+	}
+
+	/// End the current frame, given running totals of the work done so far
+	fn end_frame(&mut self, totals: FrameStats) {
+		let stats = FrameStats {
+			frame: self.frame(),
+			draw_calls: totals.draw_calls - self.frame_start.draw_calls,
+			vertices: totals.vertices - self.frame_start.vertices,
+			clears: totals.clears - self.frame_start.clears,
+		};
+		debug!("finished frame {}: {:?}", stats.frame, stats);
+
+		self.frames.push(stats);
+		self.frame_start = totals;
+	}
+}
+
+impl MockContextRef {
+	/// Present the back buffer as `eglSwapBuffers` would, marking the end of a frame
+	///
+	/// The back buffer keeps its contents, as if swaps preserved them.
+	pub fn swap_buffers(&self) {
+		let mut context = crate::context();
+		let context = &mut *context;
+
+		let (draw_calls, vertices) = context.draw_manager.totals();
+		context.framebuffer_manager.swap_buffers();
+		context.frame_manager.end_frame(FrameStats {
+			frame: 0,
+			draw_calls,
+			vertices,
+			clears: context.framebuffer_manager.clears(),
+		});
+	}
+
+	/// Number of buffer swaps so far
+	pub fn frame(&self) -> u64 {
+		crate::context().frame_manager.frame()
+	}
+
+	/// Statistics of every finished frame
+	pub fn frame_stats(&self) -> Vec<FrameStats> {
+		crate::context().frame_manager.frames.clone()
+	}
+
+	/// Copy of the front buffer's contents, what was presented by the last swap
+	///
+	/// Single buffered surfaces draw to the front buffer directly.
+	pub fn front_buffer(&self) -> Framebuffer {
+		crate::context().framebuffer_manager.front_buffer().clone()
+	}
+}
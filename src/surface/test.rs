@@ -0,0 +1,108 @@
+use crate::{
+	format::InternalFormat,
+	state::Rect,
+	surface::{FrameStats, Surface},
+	test::{gl33_core, test_harness_context, test_harness_surface},
+};
+
+#[test]
+fn configuration() {
+	let surface = Surface::new(4, 2)
+		.with_color_bits([5, 6, 5, 0])
+		.with_depth_bits(0)
+		.with_samples(4);
+	test_harness_surface(gl33_core(), surface, |ctx| unsafe {
+		let framebuffer = ctx.default_framebuffer();
+		assert_eq!((framebuffer.width(), framebuffer.height()), (4, 2));
+		assert_eq!(framebuffer.color_format(), Some(InternalFormat::RGB565));
+		assert_eq!(framebuffer.depth(0, 0), None);
+		assert_eq!(framebuffer.stencil(0, 0), Some(0));
+
+		// the viewport starts out covering the surface
+		assert_eq!(crate::context().state_manager.viewport().rect(), Rect::new(0, 0, 4, 2));
+
+		let (mut samples, mut sample_buffers, mut double_buffer) = (0, 0, 0);
+		gl::GetIntegerv(gl::SAMPLES, &mut samples);
+		gl::GetIntegerv(gl::SAMPLE_BUFFERS, &mut sample_buffers);
+		gl::GetIntegerv(gl::DOUBLEBUFFER, &mut double_buffer);
+		assert_eq!((samples, sample_buffers, double_buffer), (4, 1, 1));
+
+		gl::ClearColor(0.5, 0.5, 0.5, 0.5);
+		gl::Clear(gl::COLOR_BUFFER_BIT);
+		assert_eq!(
+			ctx.default_framebuffer().color(0, 0),
+			Some([16.0 / 31.0, 32.0 / 63.0, 16.0 / 31.0, 1.0])
+		);
+
+		// resizing keeps the configuration
+		ctx.resize(1, 1);
+		assert_eq!(ctx.default_framebuffer().color_format(), Some(InternalFormat::RGB565));
+		assert_eq!(ctx.default_framebuffer().depth(0, 0), None);
+	});
+
+	assert!(Surface::default().with_srgb().is_srgb());
+}
+
+#[test]
+#[should_panic]
+fn unsupported_color_bits() {
+	let _ = Surface::default().with_color_bits([3, 3, 2, 0]);
+}
+
+#[test]
+fn swap_buffers() {
+	test_harness_context(gl33_core(), |ctx| unsafe {
+		ctx.resize(2, 2);
+		assert_eq!(ctx.frame(), 0);
+
+		gl::ClearColor(1.0, 0.0, 0.0, 1.0);
+		gl::Clear(gl::COLOR_BUFFER_BIT);
+		// nothing is presented until the buffers are swapped
+		assert_eq!(ctx.front_buffer().color(0, 0), Some([0.0; 4]));
+
+		ctx.swap_buffers();
+		assert_eq!(ctx.frame(), 1);
+		assert_eq!(ctx.front_buffer().color(0, 0), Some([1.0, 0.0, 0.0, 1.0]));
+
+		let mut vertex_array = 0;
+		gl::GenVertexArrays(1, &mut vertex_array);
+		gl::BindVertexArray(vertex_array);
+		gl::DrawArraysInstanced(gl::TRIANGLES, 0, 3, 2);
+		gl::DrawArrays(gl::POINTS, 0, 1);
+		ctx.swap_buffers();
+
+		assert_eq!(ctx.frame_stats(), [
+			FrameStats {
+				frame: 0,
+				draw_calls: 0,
+				vertices: 0,
+				clears: 1,
+			},
+			FrameStats {
+				frame: 1,
+				draw_calls: 2,
+				vertices: 7,
+				clears: 0,
+			},
+		]);
+
+		gl::BindVertexArray(0);
+		gl::DeleteVertexArrays(1, &vertex_array);
+	})
+}
+
+#[test]
+fn single_buffered() {
+	test_harness_surface(gl33_core(), Surface::new(1, 1).single_buffered(), |ctx| unsafe {
+		gl::ClearColor(0.0, 1.0, 0.0, 1.0);
+		gl::Clear(gl::COLOR_BUFFER_BIT);
+		assert_eq!(ctx.front_buffer().color(0, 0), Some([0.0, 1.0, 0.0, 1.0]));
+
+		let mut double_buffer = 1;
+		gl::GetIntegerv(gl::DOUBLEBUFFER, &mut double_buffer);
+		assert_eq!(double_buffer, 0);
+
+		ctx.swap_buffers();
+		assert_eq!(ctx.frame(), 1);
+	})
+}
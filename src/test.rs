@@ -1,6 +1,19 @@
-use std::sync::Mutex;
+use std::{
+	ffi::{c_char, CStr},
+	sync::Mutex,
+};
 
-use crate::{ErrorHandling, GlVersion};
+use gl::types::{GLenum, GLint, GLuint};
+
+use crate::{
+	limits::Limits,
+	preset::Preset,
+	surface::Surface,
+	version::{Profile, VersionType},
+	ErrorHandling,
+	GlVersion,
+	MockContextRef,
+};
 
 // tests can't run in parallel  when they depend on a global variable
 static TEST_LOCK: Mutex<()> = Mutex::new(());
@@ -14,7 +27,7 @@ pub fn init_logger() {
 	static INIT: Mutex<bool> = Mutex::new(false);
 	let mut lock = INIT.lock().unwrap();
 	if !*lock {
-		let _ = env_logger::builder().filter_level(log::LevelFilter::Trace).is_test(true).init();
+		env_logger::builder().filter_level(log::LevelFilter::Trace).is_test(true).init();
 		*lock = true;
 	}
 }
@@ -23,24 +36,99 @@ fn mk_context() -> crate::MockContextRef {
 	crate::new(GlVersion::clear(), ErrorHandling::PanicEarly { warn: true })
 }
 
-pub fn test_harness_handling(version: GlVersion, error_handling: ErrorHandling, f: impl FnOnce()) {
+/// Run `f` against the context `new` creates, with the mocked functions loaded
+pub fn test_harness_with(new: impl FnOnce() -> MockContextRef, f: impl FnOnce(&MockContextRef)) {
 	test_lock(|| {
 		init_logger();
 
-		let context = crate::new(version, error_handling);
+		let context = new();
 
 		gl::load_with(|s| context.get_proc_address(s));
 
-		f();
+		f(&context);
 
 		context.finalize();
 	});
 }
 
+pub fn test_harness_handling(version: GlVersion, error_handling: ErrorHandling, f: impl FnOnce()) {
+	test_harness_with(|| crate::new(version, error_handling), |_| f());
+}
+
+/// OpenGL 3.3 core profile
+pub fn gl33_core() -> GlVersion {
+	GlVersion::from_version(VersionType::GL, 3, 3).with_profile(Profile::Core)
+}
+
+/// OpenGL 4.3 core profile
+pub fn gl43_core() -> GlVersion {
+	GlVersion::from_version(VersionType::GL, 4, 3).with_profile(Profile::Core)
+}
+
+/// OpenGL 4.5 core profile
+pub fn gl45_core() -> GlVersion {
+	GlVersion::from_version(VersionType::GL, 4, 5).with_profile(Profile::Core)
+}
+
+/// OpenGL 4.6 core profile
+pub fn gl46_core() -> GlVersion {
+	GlVersion::from_version(VersionType::GL, 4, 6).with_profile(Profile::Core)
+}
+
+/// Single value of `pname` as returned by `glGetIntegerv`
+pub unsafe fn get_integer(pname: GLenum) -> GLint {
+	let mut value = -1;
+	gl::GetIntegerv(pname, &mut value);
+	value
+}
+
+/// String `name` as returned by `glGetString`
+pub unsafe fn get_string(name: GLenum) -> String {
+	CStr::from_ptr(gl::GetString(name) as *const c_char)
+		.to_string_lossy()
+		.into_owned()
+}
+
+/// Extensions listed by `glGetStringi`
+pub unsafe fn get_extensions() -> Vec<String> {
+	(0..get_integer(gl::NUM_EXTENSIONS) as GLuint)
+		.map(|i| CStr::from_ptr(gl::GetStringi(gl::EXTENSIONS, i) as *const c_char))
+		.map(|name| name.to_string_lossy().into_owned())
+		.collect()
+}
+
 pub fn test_harness(version: GlVersion, f: impl FnOnce()) {
 	test_harness_handling(version, ErrorHandling::PanicEarly { warn: true }, f);
 }
 
+/// Like `test_harness`, giving `f` access to the context for inspection
+pub fn test_harness_context(version: GlVersion, f: impl FnOnce(&MockContextRef)) {
+	test_harness_surface(version, Surface::default(), f);
+}
+
+/// Like `test_harness_context`, with a default framebuffer described by `surface`
+pub fn test_harness_surface(version: GlVersion, surface: Surface, f: impl FnOnce(&MockContextRef)) {
+	let error_handling = ErrorHandling::PanicEarly { warn: true };
+	test_harness_with(|| crate::new_with_surface(version, error_handling, surface), f);
+}
+
+/// Like `test_harness_handling`, for a context enforcing `limits`
+pub fn test_harness_limits(
+	version: GlVersion,
+	limits: Limits,
+	error_handling: ErrorHandling,
+	f: impl FnOnce(),
+) {
+	let new = || crate::new_with_limits(version, error_handling, Surface::default(), limits);
+	test_harness_with(new, |_| f());
+}
+
+/// Like `test_harness_limits`, for a context created from `preset`
+pub fn test_harness_preset(preset: Preset, error_handling: ErrorHandling, f: impl FnOnce()) {
+	let new = || crate::new_with_preset(preset, error_handling, Surface::default());
+	test_harness_with(new, |_| f());
+}
+
 #[test]
 #[should_panic]
 fn max_one_context() {
@@ -75,7 +163,7 @@ fn panic_on_finalize() {
 			ctx.finalize();
 		})
 		.is_err();
-		assert_eq!(instant_panic, false, "paniced on error");
-		assert_eq!(late_panic, true, "did not panic with logged error");
+		assert!(!instant_panic, "paniced on error");
+		assert!(late_panic, "did not panic with logged error");
 	})
 }
@@ -5,10 +5,22 @@ pub struct GlVersion {
 	pub ty: VersionType,
 	pub major: u8,
 	pub minor: u8,
+	pub profile: Profile,
 	pub extensions: Vec<&'static GlExtension>,
 }
 
 impl GlVersion {
+	/// True for desktop core profile contexts, which drop the default
+	/// vertex array object and client side arrays
+	pub fn is_core(&self) -> bool {
+		matches!(self.ty, VersionType::GL) && self.profile == Profile::Core
+	}
+
+	/// True if the extension is present, either explicitly or unlocked by the version
+	pub fn has_extension(&self, extension: &GlExtension) -> bool {
+		self.extensions.contains(&extension)
+	}
+
 	pub fn at_least(&self, gl: Option<(u8, u8)>, es: Option<(u8, u8)>) -> bool {
 		match (self.ty, (self.major, self.minor), gl, es) {
 			(VersionType::GL, (ma, mi), Some((rma, rmi)), _)
@@ -23,7 +35,7 @@ impl GlVersion {
 }
 
 macro_rules! at_least {
-	($v:expr, $(gl: $gl_major:literal . $gl_minor:literal)? $(, es: $es_major:literal . $es_minor:literal)?) => {
+	($v:expr, $(gl: $gl_major:literal . $gl_minor:literal)? $(,)? $(es: $es_major:literal . $es_minor:literal)?) => {
 		$v.at_least(
 			$crate::version::at_least!(opt $(($gl_major, $gl_minor))?),
 			$crate::version::at_least!(opt $(($es_major, $es_minor))?),
@@ -35,12 +47,87 @@ macro_rules! at_least {
 
 pub(crate) use at_least;
 
+macro_rules! gl_enum {
+	($ename:ident { $($name:ident($(gl: $gl_major:literal . $gl_minor:literal)? $(,)? $(es: $es_major:literal . $es_minor:literal)?);)* }) => {
+		#[derive(Copy, Clone, PartialEq, Eq, Debug, ::enum_map::Enum)]
+		#[allow(non_camel_case_types)]
+		pub enum $ename {
+			$($name,)*
+		}
+
+		impl $ename {
+			pub fn from_gl(gl: ::gl::types::GLenum) -> Option<Self> {
+				match gl {
+					$($crate::version::enums::$name => Some(Self::$name),)*
+					_ => None,
+				}
+			}
+
+			/// True if the version provides the enum
+			pub fn supported(&self, version: &$crate::version::GlVersion) -> bool {
+				match self {
+					$(Self::$name => $crate::version::at_least!(version, $(gl: $gl_major . $gl_minor)? $(, es: $es_major . $es_minor)?),)*
+				}
+			}
+
+			pub fn check_version(&self, version: &$crate::version::GlVersion) {
+				match self {
+					$(Self::$name if !$crate::version::at_least!(version, $(gl: $gl_major . $gl_minor)? $(, es: $es_major . $es_minor)?) => {
+						$crate::error!("{}", concat!(
+							"GL_",
+							stringify!($name),
+							" requires",
+							$(concat!(" OpenGL ", $gl_major, ".", $gl_minor),)?
+							$(concat!(" or OpenGL ES ", $es_major, ".", $es_minor),)?
+						));
+					},)*
+					_ => {},
+				}
+			}
+
+			pub fn to_gl(&self) -> ::gl::types::GLenum {
+				match self {
+					$(Self::$name => $crate::version::enums::$name,)*
+				}
+			}
+		}
+
+		impl ::std::fmt::Display for $ename {
+			fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+				match self {
+					$(Self::$name => write!(f, concat!("GL_", stringify!($name))),)*
+				}
+			}
+		}
+	}
+}
+
+pub(crate) use gl_enum;
+
+/// Enums of the `gl` crate, plus OpenGL 4.6 and OpenGL ES enums it lacks
+pub mod enums {
+	use gl::types::GLenum;
+	pub use gl::*;
+
+	pub const PARAMETER_BUFFER: GLenum = 0x80ee;
+	pub const PARAMETER_BUFFER_BINDING: GLenum = 0x80ef;
+	/// OpenGL ES 2.0 only
+	pub const FRAMEBUFFER_INCOMPLETE_DIMENSIONS: GLenum = 0x8cd9;
+}
+
 #[derive(Copy, Clone)]
 pub enum VersionType {
 	GL,
 	ES,
 }
 
+/// Desktop OpenGL context profile, ignored for ES contexts
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum Profile {
+	Core,
+	Compatibility,
+}
+
 pub struct GlExtension {
 	pub name: &'static str,
 	pub unlock_gl: Option<(u8, u8)>,
@@ -60,6 +147,7 @@ impl GlVersion {
 			ty,
 			major,
 			minor,
+			profile: Profile::Compatibility,
 			extensions: {
 				let mut ext = ext::version_extensions(ty, major, minor);
 				ext.extend(extensions);
@@ -79,13 +167,18 @@ impl GlVersion {
 	pub fn clear() -> Self {
 		Self::new(VersionType::GL, 0, 0, &[])
 	}
+
+	pub fn with_profile(mut self, profile: Profile) -> Self {
+		self.profile = profile;
+		self
+	}
 }
 
 pub mod ext {
 	use super::{GlExtension, VersionType};
 
 	macro_rules! extensions {
-		($($name:ident($(gl: $gl_major:literal . $gl_minor:literal)? $(, es: $es_major:literal . $es_minor:literal)?);)*) => {
+		($($name:ident($(gl: $gl_major:literal . $gl_minor:literal)? $(,)? $(es: $es_major:literal . $es_minor:literal)?);)*) => {
 			$(
 				#[allow(unused, non_upper_case_globals)]
 				pub const $name: GlExtension = GlExtension {
@@ -100,6 +193,7 @@ pub mod ext {
 				};
 			)*
 
+			#[allow(unused_comparisons)]
 			pub(crate) fn version_extensions(ty: VersionType, major: u8, minor: u8) -> Vec<&'static GlExtension> {
 				let mut extensions = Vec::new();
 				$(
@@ -114,6 +208,11 @@ pub mod ext {
 
 				extensions
 			}
+
+			/// Extension called `name`, without the `GL_` prefix, if mock-gl knows it
+			pub fn from_name(name: &str) -> Option<&'static GlExtension> {
+				[$(&$name),*].into_iter().find(|extension| extension.name == name)
+			}
 		};
 		(opt| ) => { None };
 		(opt| $($expr:tt)+) => { Some($($expr)+) };
@@ -121,5 +220,43 @@ pub mod ext {
 
 	extensions! {
 		ARB_buffer_storage(gl: 4 . 6);
+		ARB_vertex_array_object(gl: 3 . 0);
+		OES_vertex_array_object(es: 3 . 0);
+		ARB_vertex_attrib_binding(gl: 4 . 3);
+		ARB_multi_bind(gl: 4 . 4);
+		ARB_direct_state_access(gl: 4 . 5);
+		ARB_instanced_arrays(gl: 3 . 3);
+		EXT_instanced_arrays(es: 3 . 0);
+		ANGLE_instanced_arrays(es: 3 . 0);
+		ARB_draw_instanced(gl: 3 . 1);
+		EXT_draw_instanced(es: 3 . 0);
+		ARB_base_instance(gl: 4 . 2);
+		EXT_base_instance();
+		ARB_draw_elements_base_vertex(gl: 3 . 2);
+		OES_draw_elements_base_vertex(es: 3 . 2);
+		EXT_draw_elements_base_vertex(es: 3 . 2);
+		EXT_multi_draw_arrays();
+		ARB_draw_indirect(gl: 4 . 0);
+		ARB_multi_draw_indirect(gl: 4 . 3);
+		EXT_multi_draw_indirect();
+		ARB_indirect_parameters(gl: 4 . 6);
+		ARB_compute_shader(gl: 4 . 3);
+		OES_element_index_uint(es: 3 . 0);
+		ARB_framebuffer_object(gl: 3 . 0);
+		ARB_internalformat_query(gl: 4 . 2);
+		ARB_ES2_compatibility(gl: 4 . 1);
+		OES_rgb8_rgba8(es: 3 . 0);
+		OES_depth24(es: 3 . 0);
+		OES_packed_depth_stencil(es: 3 . 0);
+		EXT_color_buffer_half_float();
+		EXT_color_buffer_float(es: 3 . 2);
+		EXT_blend_minmax(es: 3 . 0);
+		ARB_draw_buffers_blend(gl: 4 . 0);
+		OES_draw_buffers_indexed(es: 3 . 2);
+		EXT_draw_buffers_indexed(es: 3 . 2);
+		ARB_viewport_array(gl: 4 . 1);
+		OES_viewport_array();
+		ARB_clip_control(gl: 4 . 5);
+		EXT_clip_control();
 	}
 }
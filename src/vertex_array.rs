@@ -0,0 +1,1061 @@
+use std::{collections::HashMap, slice};
+
+use gl::types::{GLboolean, GLenum, GLint, GLintptr, GLsizei, GLuint, GLvoid};
+
+use crate::{
+	buffer::BufferManager,
+	debug,
+	error,
+	limits::Limits,
+	version::gl_enum,
+	warning,
+	GlVersion,
+};
+
+pub mod gl_functions;
+
+#[cfg(test)]
+mod test;
+
+/// Attributes and bindings stored per vertex array, the most `GL_MAX_VERTEX_ATTRIBS` and
+/// `GL_MAX_VERTEX_ATTRIB_BINDINGS` can be raised to
+pub const MAX_VERTEX_ATTRIBS: GLuint = 32;
+pub const MAX_VERTEX_ATTRIB_BINDINGS: GLuint = 32;
+
+pub struct VertexArrayManager {
+	vertex_array_index: GLuint,
+	/// Includes the default vertex array 0
+	active_vertex_arrays: HashMap<GLuint, VertexArray>,
+	deleted_vertex_arrays: Vec<GLuint>,
+	bound_vertex_array: GLuint,
+}
+
+gl_enum! {
+	AttribType {
+		BYTE(gl: 2 . 1, es: 2 . 0);
+		UNSIGNED_BYTE(gl: 2 . 1, es: 2 . 0);
+		SHORT(gl: 2 . 1, es: 2 . 0);
+		UNSIGNED_SHORT(gl: 2 . 1, es: 2 . 0);
+		INT(gl: 2 . 1, es: 3 . 0);
+		UNSIGNED_INT(gl: 2 . 1, es: 3 . 0);
+		HALF_FLOAT(gl: 3 . 0, es: 3 . 0);
+		FLOAT(gl: 2 . 1, es: 2 . 0);
+		DOUBLE(gl: 2 . 1);
+		FIXED(gl: 4 . 1, es: 2 . 0);
+		INT_2_10_10_10_REV(gl: 3 . 3, es: 3 . 0);
+		UNSIGNED_INT_2_10_10_10_REV(gl: 3 . 3, es: 3 . 0);
+		UNSIGNED_INT_10F_11F_11F_REV(gl: 4 . 4);
+	}
+}
+
+impl AttribType {
+	/// Size of a single component, or of the whole attribute for packed types
+	pub fn component_size(&self) -> usize {
+		match self {
+			Self::BYTE | Self::UNSIGNED_BYTE => 1,
+			Self::SHORT | Self::UNSIGNED_SHORT | Self::HALF_FLOAT => 2,
+			Self::INT | Self::UNSIGNED_INT | Self::FLOAT | Self::FIXED => 4,
+			Self::DOUBLE => 8,
+			Self::INT_2_10_10_10_REV
+			| Self::UNSIGNED_INT_2_10_10_10_REV
+			| Self::UNSIGNED_INT_10F_11F_11F_REV => 4,
+		}
+	}
+
+	/// Packed types store every component in a single 32 bit word
+	pub fn is_packed(&self) -> bool {
+		matches!(
+			self,
+			Self::INT_2_10_10_10_REV
+				| Self::UNSIGNED_INT_2_10_10_10_REV
+				| Self::UNSIGNED_INT_10F_11F_11F_REV
+		)
+	}
+}
+
+/// How attribute data is converted before reaching the shader
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum AttribKind {
+	/// `glVertexAttribPointer` / `glVertexAttribFormat`, converted to floating point
+	Float,
+	/// `glVertexAttribIPointer` / `glVertexAttribIFormat`
+	Integer,
+	/// `glVertexAttribLPointer` / `glVertexAttribLFormat`
+	Double,
+}
+
+/// Format half of a vertex attribute, sourcing its data from `binding`
+#[derive(Clone)]
+pub struct VertexAttribute {
+	pub enabled: bool,
+	/// Component count, `GL_BGRA` is stored as 4 with `bgra` set
+	pub size: GLint,
+	pub bgra: bool,
+	pub ty: AttribType,
+	pub normalized: bool,
+	pub kind: AttribKind,
+	pub relative_offset: GLuint,
+	pub binding: GLuint,
+}
+
+impl VertexAttribute {
+	fn new(index: GLuint) -> Self {
+		Self {
+			enabled: false,
+			size: 4,
+			bgra: false,
+			ty: AttribType::FLOAT,
+			normalized: false,
+			kind: AttribKind::Float,
+			relative_offset: 0,
+			binding: index,
+		}
+	}
+
+	/// Size in bytes of a single vertex's worth of this attribute
+	pub fn element_size(&self) -> usize {
+		match self.ty.is_packed() {
+			true => self.ty.component_size(),
+			false => self.size as usize * self.ty.component_size(),
+		}
+	}
+
+	/// Decode a single vertex's worth of this attribute, `bytes` must hold `element_size` bytes
+	pub fn decode(&self, bytes: &[u8]) -> AttribValue {
+		let size = self.size as usize;
+
+		match self.kind {
+			AttribKind::Double => {
+				let mut value = [0.0, 0.0, 0.0, 1.0];
+				for (i, component) in value.iter_mut().enumerate().take(size) {
+					*component = f64::from_ne_bytes(bytes[i * 8..i * 8 + 8].try_into().unwrap());
+				}
+				AttribValue::Double(value)
+			},
+			AttribKind::Integer => {
+				let mut value = [0, 0, 0, 1];
+				for (i, component) in value.iter_mut().enumerate().take(size) {
+					*component = self.integer_component(bytes, i);
+				}
+
+				match self.ty {
+					AttribType::BYTE | AttribType::SHORT | AttribType::INT =>
+						AttribValue::Int(value.map(|c| c as i32)),
+					_ => AttribValue::UInt(value.map(|c| c as u32)),
+				}
+			},
+			AttribKind::Float => {
+				let mut value = match self.ty.is_packed() {
+					true =>
+						self.packed_components(u32::from_ne_bytes(bytes[..4].try_into().unwrap())),
+					false => {
+						let mut value = [0.0, 0.0, 0.0, 1.0];
+						for (i, component) in value.iter_mut().enumerate().take(size) {
+							*component = self.float_component(bytes, i);
+						}
+						value
+					},
+				};
+
+				if self.bgra {
+					value.swap(0, 2);
+				}
+
+				AttribValue::Float(value)
+			},
+		}
+	}
+
+	fn integer_component(&self, bytes: &[u8], i: usize) -> i64 {
+		let n = self.ty.component_size();
+		let bytes = &bytes[i * n..i * n + n];
+
+		match self.ty {
+			AttribType::BYTE => bytes[0] as i8 as i64,
+			AttribType::UNSIGNED_BYTE => bytes[0] as i64,
+			AttribType::SHORT => i16::from_ne_bytes(bytes.try_into().unwrap()) as i64,
+			AttribType::UNSIGNED_SHORT => u16::from_ne_bytes(bytes.try_into().unwrap()) as i64,
+			AttribType::INT | AttribType::FIXED =>
+				i32::from_ne_bytes(bytes.try_into().unwrap()) as i64,
+			AttribType::UNSIGNED_INT => u32::from_ne_bytes(bytes.try_into().unwrap()) as i64,
+			// formats are validated to only pair integer attributes with integer types
+			_ => unreachable!(),
+		}
+	}
+
+	fn float_component(&self, bytes: &[u8], i: usize) -> f32 {
+		let n = self.ty.component_size();
+		let component = &bytes[i * n..i * n + n];
+
+		// signed normalization clamps to -1, see "Conversion from Integer to Floating-Point"
+		let signed = |value: i64, bits: u32| match self.normalized {
+			true => (value as f64 / ((1i64 << (bits - 1)) - 1) as f64).max(-1.0) as f32,
+			false => value as f32,
+		};
+		let unsigned = |value: i64, bits: u32| match self.normalized {
+			true => (value as f64 / ((1i64 << bits) - 1) as f64) as f32,
+			false => value as f32,
+		};
+
+		match self.ty {
+			AttribType::BYTE => signed(self.integer_component(bytes, i), 8),
+			AttribType::UNSIGNED_BYTE => unsigned(self.integer_component(bytes, i), 8),
+			AttribType::SHORT => signed(self.integer_component(bytes, i), 16),
+			AttribType::UNSIGNED_SHORT => unsigned(self.integer_component(bytes, i), 16),
+			AttribType::INT => signed(self.integer_component(bytes, i), 32),
+			AttribType::UNSIGNED_INT => unsigned(self.integer_component(bytes, i), 32),
+			AttribType::FIXED => (self.integer_component(bytes, i) as f64 / 65536.0) as f32,
+			AttribType::HALF_FLOAT =>
+				half_to_f32(u16::from_ne_bytes(component.try_into().unwrap())),
+			AttribType::FLOAT => f32::from_ne_bytes(component.try_into().unwrap()),
+			AttribType::DOUBLE => f64::from_ne_bytes(component.try_into().unwrap()) as f32,
+			// packed types are decoded by `packed_components`
+			_ => unreachable!(),
+		}
+	}
+
+	fn packed_components(&self, word: u32) -> [f32; 4] {
+		let field = |shift: u32, bits: u32| (word >> shift) & ((1 << bits) - 1);
+		let signed_field = |shift: u32, bits: u32| {
+			let value = field(shift, bits) as i32;
+			(value << (32 - bits)) >> (32 - bits)
+		};
+
+		let normalize_signed = |value: i32, bits: u32| match self.normalized {
+			true => (value as f32 / ((1 << (bits - 1)) - 1) as f32).max(-1.0),
+			false => value as f32,
+		};
+		let normalize_unsigned = |value: u32, bits: u32| match self.normalized {
+			true => value as f32 / ((1 << bits) - 1) as f32,
+			false => value as f32,
+		};
+
+		match self.ty {
+			AttribType::INT_2_10_10_10_REV => [
+				normalize_signed(signed_field(0, 10), 10),
+				normalize_signed(signed_field(10, 10), 10),
+				normalize_signed(signed_field(20, 10), 10),
+				normalize_signed(signed_field(30, 2), 2),
+			],
+			AttribType::UNSIGNED_INT_2_10_10_10_REV => [
+				normalize_unsigned(field(0, 10), 10),
+				normalize_unsigned(field(10, 10), 10),
+				normalize_unsigned(field(20, 10), 10),
+				normalize_unsigned(field(30, 2), 2),
+			],
+			AttribType::UNSIGNED_INT_10F_11F_11F_REV => [
+				small_float_to_f32(field(0, 11), 6),
+				small_float_to_f32(field(11, 11), 6),
+				small_float_to_f32(field(22, 10), 5),
+				1.0,
+			],
+			_ => unreachable!(),
+		}
+	}
+}
+
+/// Attribute value as seen by the shader, missing components are taken from (0, 0, 0, 1)
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum AttribValue {
+	Float([f32; 4]),
+	Int([i32; 4]),
+	UInt([u32; 4]),
+	Double([f64; 4]),
+}
+
+impl AttribValue {
+	/// Components converted to `f32` regardless of how the attribute is read
+	pub fn to_f32(&self) -> [f32; 4] {
+		match self {
+			Self::Float(value) => *value,
+			Self::Int(value) => value.map(|c| c as f32),
+			Self::UInt(value) => value.map(|c| c as f32),
+			Self::Double(value) => value.map(|c| c as f32),
+		}
+	}
+}
+
+/// Unsigned float with a 5 bit exponent, as used by half floats and packed 10/11 bit floats
+fn small_float_to_f32(bits: u32, mantissa_bits: u32) -> f32 {
+	let exponent = (bits >> mantissa_bits) as i32;
+	let mantissa = (bits & ((1 << mantissa_bits) - 1)) as f32 / (1 << mantissa_bits) as f32;
+
+	match exponent {
+		0 => mantissa * 2f32.powi(-14),
+		31 if mantissa == 0.0 => f32::INFINITY,
+		31 => f32::NAN,
+		exponent => (1.0 + mantissa) * 2f32.powi(exponent - 15),
+	}
+}
+
+fn half_to_f32(bits: u16) -> f32 {
+	let magnitude = small_float_to_f32((bits & 0x7fff) as u32, 10);
+	match bits & 0x8000 {
+		0 => magnitude,
+		_ => -magnitude,
+	}
+}
+
+/// Buffer half of a vertex attribute, shared by every attribute pointing at it
+#[derive(Clone, Default)]
+pub struct VertexBinding {
+	pub buffer: GLuint,
+	/// Offset into `buffer`, or a client memory address if `buffer` is 0
+	pub offset: usize,
+	/// Distance in bytes between vertices, a stride of 0 reads the same vertex repeatedly
+	pub stride: usize,
+	pub divisor: GLuint,
+}
+
+pub struct VertexArray {
+	attributes: Vec<VertexAttribute>,
+	bindings: Vec<VertexBinding>,
+	element_array_buffer: GLuint,
+}
+
+impl VertexArray {
+	fn new() -> Self {
+		Self {
+			attributes: (0..MAX_VERTEX_ATTRIBS).map(VertexAttribute::new).collect(),
+			bindings: vec![VertexBinding::default(); MAX_VERTEX_ATTRIB_BINDINGS as usize],
+			element_array_buffer: 0,
+		}
+	}
+
+	pub fn attributes(&self) -> &[VertexAttribute] {
+		&self.attributes
+	}
+
+	pub fn bindings(&self) -> &[VertexBinding] {
+		&self.bindings
+	}
+
+	/// Binding an attribute sources its data from
+	pub fn attribute_binding(&self, index: usize) -> &VertexBinding {
+		&self.bindings[self.attributes[index].binding as usize]
+	}
+
+	pub fn element_array_buffer(&self) -> GLuint {
+		self.element_array_buffer
+	}
+
+	fn references_buffer(&self, buffer_id: GLuint) -> bool {
+		self.element_array_buffer == buffer_id
+			|| self.bindings.iter().any(|binding| binding.buffer == buffer_id)
+	}
+}
+
+impl Default for VertexArrayManager {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl VertexArrayManager {
+	pub fn new() -> Self {
+		Self {
+			vertex_array_index: 1,
+			active_vertex_arrays: HashMap::from([(0, VertexArray::new())]),
+			deleted_vertex_arrays: Vec::new(),
+			bound_vertex_array: 0,
+		}
+	}
+
+	/// # Safety
+	/// `arrays` must be valid for `count` writes
+	pub unsafe fn gen_vertex_arrays(
+		&mut self,
+		error: &mut GLenum,
+		count: GLsizei,
+		arrays: *mut GLuint,
+	) {
+		if count < 0 {
+			*error = gl::INVALID_VALUE;
+			error!("glGenVertexArrays called with invalid vertex array count {}", count);
+		} else {
+			let arrays = slice::from_raw_parts_mut(arrays, count as usize);
+
+			for array_id in arrays.iter_mut() {
+				*array_id = self.vertex_array_index;
+				self.vertex_array_index += 1;
+
+				self.active_vertex_arrays.insert(*array_id, VertexArray::new());
+			}
+
+			debug!("created {} vertex array(s) {:?}", arrays.len(), arrays);
+		}
+	}
+
+	/// # Safety
+	/// `arrays` must be valid for `count` reads
+	pub unsafe fn free_vertex_arrays(
+		&mut self,
+		error: &mut GLenum,
+		count: GLsizei,
+		arrays: *const GLuint,
+	) {
+		if count < 0 {
+			*error = gl::INVALID_VALUE;
+			error!("glDeleteVertexArrays called with invalid vertex array count {}", count);
+		} else {
+			let arrays = slice::from_raw_parts(arrays, count as usize);
+
+			for array_id in arrays.iter() {
+				if *array_id == 0 {
+					// silently ignored per spec
+				} else if self.deleted_vertex_arrays.contains(array_id) {
+					warning!("double freed vertex array {}", array_id);
+				} else if self.active_vertex_arrays.remove(array_id).is_none() {
+					warning!("attempted to free unallocated vertex array {}", array_id);
+				} else {
+					self.deleted_vertex_arrays.push(*array_id);
+					if self.bound_vertex_array == *array_id {
+						self.bound_vertex_array = 0;
+					}
+				}
+			}
+
+			debug!("freed {} vertex array(s) {:?}", arrays.len(), arrays);
+		}
+	}
+
+	pub fn is_vertex_array(&self, array: GLuint) -> GLboolean {
+		(array != 0 && self.active_vertex_arrays.contains_key(&array)) as u8
+	}
+
+	pub fn bind_vertex_array(&mut self, error: &mut GLenum, array_id: GLuint) {
+		if self.active_vertex_arrays.contains_key(&array_id) {
+			self.bound_vertex_array = array_id;
+			debug!("bound vertex array {}", array_id);
+		} else {
+			*error = gl::INVALID_OPERATION;
+			if self.deleted_vertex_arrays.contains(&array_id) {
+				error!("attempted to bind vertex array that has already been freed");
+			} else {
+				error!("attempted to bind an unallocated vertex array");
+			}
+		}
+	}
+
+	pub fn bound_id(&self) -> GLuint {
+		self.bound_vertex_array
+	}
+
+	pub fn bound(&self) -> &VertexArray {
+		// the bound vertex array is reset to 0 when deleted
+		&self.active_vertex_arrays[&self.bound_vertex_array]
+	}
+
+	fn bound_mut(&mut self) -> &mut VertexArray {
+		self.active_vertex_arrays.get_mut(&self.bound_vertex_array).unwrap()
+	}
+
+	pub(crate) fn element_array_buffer_mut(&mut self) -> &mut GLuint {
+		&mut self.bound_mut().element_array_buffer
+	}
+
+	/// Resolve the vertex array modified by a call, `vaobj` is only given for DSA calls
+	fn target_array(
+		&self,
+		gl_version: &GlVersion,
+		error: &mut GLenum,
+		func: &str,
+		vaobj: Option<GLuint>,
+	) -> Option<GLuint> {
+		match vaobj {
+			// core profile contexts have no default vertex array to modify
+			None if gl_version.is_core() && self.bound_vertex_array == 0 => {
+				*error = gl::INVALID_OPERATION;
+				error!("{} called without a bound vertex array in a core profile context", func);
+				None
+			},
+			None => Some(self.bound_vertex_array),
+			Some(vaobj) if vaobj != 0 && self.active_vertex_arrays.contains_key(&vaobj) =>
+				Some(vaobj),
+			Some(vaobj) => {
+				*error = gl::INVALID_OPERATION;
+				error!("{} called with invalid vertex array {}", func, vaobj);
+				None
+			},
+		}
+	}
+
+	fn array_mut(&mut self, array_id: GLuint) -> &mut VertexArray {
+		// ids are validated by `target_array`
+		self.active_vertex_arrays.get_mut(&array_id).unwrap()
+	}
+
+	fn check_index(limits: &Limits, error: &mut GLenum, func: &str, index: GLuint) -> bool {
+		if index >= limits.max_vertex_attribs {
+			*error = gl::INVALID_VALUE;
+			error!(
+				"{} called with attribute index {} exceeding GL_MAX_VERTEX_ATTRIBS ({})",
+				func, index, limits.max_vertex_attribs
+			);
+			false
+		} else {
+			true
+		}
+	}
+
+	fn check_binding_index(
+		limits: &Limits,
+		error: &mut GLenum,
+		func: &str,
+		binding: GLuint,
+	) -> bool {
+		if binding >= limits.max_vertex_attrib_bindings {
+			*error = gl::INVALID_VALUE;
+			error!(
+				"{} called with binding index {} exceeding GL_MAX_VERTEX_ATTRIB_BINDINGS ({})",
+				func, binding, limits.max_vertex_attrib_bindings
+			);
+			false
+		} else {
+			true
+		}
+	}
+
+	fn check_stride(
+		gl_version: &GlVersion,
+		limits: &Limits,
+		error: &mut GLenum,
+		func: &str,
+		stride: GLsizei,
+	) -> bool {
+		if stride < 0 {
+			*error = gl::INVALID_VALUE;
+			error!("{} called with negative stride {}", func, stride);
+			false
+		} else if stride > limits.max_vertex_attrib_stride
+			// the limit was introduced by OpenGL 4.4 and OpenGL ES 3.1
+			&& crate::version::at_least!(gl_version, gl: 4 . 4, es: 3 . 1)
+		{
+			*error = gl::INVALID_VALUE;
+			error!(
+				"{} called with stride {} exceeding GL_MAX_VERTEX_ATTRIB_STRIDE ({})",
+				func, stride, limits.max_vertex_attrib_stride
+			);
+			false
+		} else {
+			true
+		}
+	}
+
+	/// Validate an attribute format, returning its component count, BGRA flag and type
+	#[allow(clippy::too_many_arguments)]
+	fn check_format(
+		gl_version: &GlVersion,
+		error: &mut GLenum,
+		func: &str,
+		kind: AttribKind,
+		size: GLint,
+		ty: GLenum,
+		normalized: GLboolean,
+	) -> Option<(GLint, bool, AttribType)> {
+		let bgra = size == gl::BGRA as GLint;
+		if bgra && kind != AttribKind::Float {
+			*error = gl::INVALID_VALUE;
+			error!("{} does not accept size GL_BGRA", func);
+			return None
+		} else if bgra && !crate::version::at_least!(gl_version, gl: 3 . 2) {
+			*error = gl::INVALID_VALUE;
+			error!("{} size GL_BGRA requires OpenGL 3.2", func);
+			return None
+		} else if !bgra && !(1..=4).contains(&size) {
+			*error = gl::INVALID_VALUE;
+			error!("{} called with invalid size {}", func, size);
+			return None
+		}
+
+		let ty = match AttribType::from_gl(ty) {
+			None => {
+				*error = gl::INVALID_ENUM;
+				error!("{} called with invalid type {}", func, ty);
+				return None
+			},
+			Some(ty) => {
+				ty.check_version(gl_version);
+				ty
+			},
+		};
+
+		let kind_matches = match kind {
+			AttribKind::Float => true,
+			AttribKind::Integer => matches!(
+				ty,
+				AttribType::BYTE
+					| AttribType::UNSIGNED_BYTE
+					| AttribType::SHORT
+					| AttribType::UNSIGNED_SHORT
+					| AttribType::INT
+					| AttribType::UNSIGNED_INT
+			),
+			AttribKind::Double => ty == AttribType::DOUBLE,
+		};
+
+		if !kind_matches {
+			*error = gl::INVALID_ENUM;
+			error!("{} does not accept type {}", func, ty);
+			return None
+		}
+
+		match ty {
+			AttribType::UNSIGNED_INT_10F_11F_11F_REV if size != 3 => {
+				*error = gl::INVALID_OPERATION;
+				error!("{} attributes must have a size of 3", ty);
+				return None
+			},
+			AttribType::INT_2_10_10_10_REV | AttribType::UNSIGNED_INT_2_10_10_10_REV
+				if size != 4 && !bgra =>
+			{
+				*error = gl::INVALID_OPERATION;
+				error!("{} attributes must have a size of 4 or GL_BGRA", ty);
+				return None
+			},
+			_ => {},
+		}
+
+		if bgra {
+			if !matches!(
+				ty,
+				AttribType::UNSIGNED_BYTE
+					| AttribType::INT_2_10_10_10_REV
+					| AttribType::UNSIGNED_INT_2_10_10_10_REV
+			) {
+				*error = gl::INVALID_OPERATION;
+				error!("GL_BGRA attributes cannot have type {}", ty);
+				return None
+			} else if normalized == gl::FALSE {
+				*error = gl::INVALID_OPERATION;
+				error!("GL_BGRA attributes must be normalized");
+				return None
+			}
+		}
+
+		Some((if bgra { 4 } else { size }, bgra, ty))
+	}
+
+	/// `glVertexAttrib*Pointer`, setting both the format and the binding of `index`
+	#[allow(clippy::too_many_arguments)]
+	pub fn vertex_attrib_pointer(
+		&mut self,
+		gl_version: &GlVersion,
+		limits: &Limits,
+		error: &mut GLenum,
+		kind: AttribKind,
+		array_buffer: GLuint,
+		index: GLuint,
+		size: GLint,
+		ty: GLenum,
+		normalized: GLboolean,
+		stride: GLsizei,
+		pointer: *const GLvoid,
+	) {
+		let func = match kind {
+			AttribKind::Float => "glVertexAttribPointer",
+			AttribKind::Integer => "glVertexAttribIPointer",
+			AttribKind::Double => "glVertexAttribLPointer",
+		};
+
+		let Some(array_id) = self.target_array(gl_version, error, func, None) else {
+			return
+		};
+		if !Self::check_index(limits, error, func, index) {
+			return
+		}
+
+		let Some((size, bgra, ty)) =
+			Self::check_format(gl_version, error, func, kind, size, ty, normalized)
+		else {
+			return
+		};
+
+		if !Self::check_stride(gl_version, limits, error, func, stride) {
+			return
+		}
+
+		if array_buffer == 0 && array_id != 0 && !pointer.is_null() {
+			*error = gl::INVALID_OPERATION;
+			error!(
+				"{} called with a client side array while vertex array {} is bound",
+				func, array_id
+			);
+			return
+		}
+
+		let array = self.array_mut(array_id);
+		let attrib = &mut array.attributes[index as usize];
+		*attrib = VertexAttribute {
+			enabled: attrib.enabled,
+			size,
+			bgra,
+			ty,
+			normalized: normalized != gl::FALSE,
+			kind,
+			relative_offset: 0,
+			binding: index,
+		};
+
+		let binding = &mut array.bindings[index as usize];
+		binding.buffer = array_buffer;
+		binding.offset = pointer as usize;
+		binding.stride = match stride {
+			0 => attrib.element_size(),
+			stride => stride as usize,
+		};
+
+		debug!(
+			"set vertex attribute {} of vertex array {} to {} x {} at {:?} in buffer {}",
+			index,
+			array_id,
+			if bgra {
+				"GL_BGRA".to_string()
+			} else {
+				size.to_string()
+			},
+			ty,
+			pointer,
+			array_buffer
+		);
+	}
+
+	/// `glVertexAttrib*Format` and `glVertexArrayAttrib*Format`
+	#[allow(clippy::too_many_arguments)]
+	pub fn vertex_attrib_format(
+		&mut self,
+		gl_version: &GlVersion,
+		limits: &Limits,
+		error: &mut GLenum,
+		func: &str,
+		vaobj: Option<GLuint>,
+		kind: AttribKind,
+		index: GLuint,
+		size: GLint,
+		ty: GLenum,
+		normalized: GLboolean,
+		relative_offset: GLuint,
+	) {
+		let Some(array_id) = self.target_array(gl_version, error, func, vaobj) else {
+			return
+		};
+		if !Self::check_index(limits, error, func, index) {
+			return
+		}
+
+		let Some((size, bgra, ty)) =
+			Self::check_format(gl_version, error, func, kind, size, ty, normalized)
+		else {
+			return
+		};
+
+		if relative_offset > limits.max_vertex_attrib_relative_offset {
+			*error = gl::INVALID_VALUE;
+			error!(
+				"{} called with relative offset {} exceeding GL_MAX_VERTEX_ATTRIB_RELATIVE_OFFSET ({})",
+				func, relative_offset, limits.max_vertex_attrib_relative_offset
+			);
+			return
+		}
+
+		let attrib = &mut self.array_mut(array_id).attributes[index as usize];
+		attrib.size = size;
+		attrib.bgra = bgra;
+		attrib.ty = ty;
+		attrib.normalized = normalized != gl::FALSE;
+		attrib.kind = kind;
+		attrib.relative_offset = relative_offset;
+
+		debug!(
+			"set format of vertex attribute {} of vertex array {} to {} x {} at relative offset {}",
+			index, array_id, size, ty, relative_offset
+		);
+	}
+
+	/// `glVertexAttribBinding` and `glVertexArrayAttribBinding`
+	#[allow(clippy::too_many_arguments)]
+	pub fn vertex_attrib_binding(
+		&mut self,
+		gl_version: &GlVersion,
+		limits: &Limits,
+		error: &mut GLenum,
+		func: &str,
+		vaobj: Option<GLuint>,
+		index: GLuint,
+		binding: GLuint,
+	) {
+		let Some(array_id) = self.target_array(gl_version, error, func, vaobj) else {
+			return
+		};
+		if !Self::check_index(limits, error, func, index)
+			|| !Self::check_binding_index(limits, error, func, binding)
+		{
+			return
+		}
+
+		self.array_mut(array_id).attributes[index as usize].binding = binding;
+		debug!(
+			"pointed vertex attribute {} of vertex array {} at binding {}",
+			index, array_id, binding
+		);
+	}
+
+	/// `glBindVertexBuffer` and `glVertexArrayVertexBuffer`
+	#[allow(clippy::too_many_arguments)]
+	pub fn bind_vertex_buffer(
+		&mut self,
+		gl_version: &GlVersion,
+		limits: &Limits,
+		buffers: &BufferManager,
+		error: &mut GLenum,
+		func: &str,
+		vaobj: Option<GLuint>,
+		binding: GLuint,
+		buffer: GLuint,
+		offset: GLintptr,
+		stride: GLsizei,
+	) {
+		let Some(array_id) = self.target_array(gl_version, error, func, vaobj) else {
+			return
+		};
+		if !Self::check_binding_index(limits, error, func, binding) {
+			return
+		}
+
+		self.set_vertex_buffer(
+			gl_version, limits, buffers, error, func, array_id, binding, buffer, offset, stride,
+		);
+	}
+
+	/// `glBindVertexBuffers` and `glVertexArrayVertexBuffers`
+	///
+	/// # Safety
+	/// `buffer_ids`, `offsets` and `strides` must be valid for `count` reads, or `buffer_ids`
+	/// must be null
+	#[allow(clippy::too_many_arguments)]
+	pub unsafe fn bind_vertex_buffers(
+		&mut self,
+		gl_version: &GlVersion,
+		limits: &Limits,
+		buffers: &BufferManager,
+		error: &mut GLenum,
+		func: &str,
+		vaobj: Option<GLuint>,
+		first: GLuint,
+		count: GLsizei,
+		buffer_ids: *const GLuint,
+		offsets: *const GLintptr,
+		strides: *const GLsizei,
+	) {
+		let Some(array_id) = self.target_array(gl_version, error, func, vaobj) else {
+			return
+		};
+
+		if count < 0 {
+			*error = gl::INVALID_VALUE;
+			error!("{} called with negative count {}", func, count);
+			return
+		} else if first as u64 + count as u64 > limits.max_vertex_attrib_bindings as u64 {
+			*error = gl::INVALID_OPERATION;
+			error!(
+				"{} called with bindings {}..{} exceeding GL_MAX_VERTEX_ATTRIB_BINDINGS ({})",
+				func,
+				first,
+				first as u64 + count as u64,
+				limits.max_vertex_attrib_bindings
+			);
+			return
+		}
+
+		for i in 0..count as usize {
+			let binding = first + i as GLuint;
+
+			// a null buffer array resets every binding in the range
+			if buffer_ids.is_null() {
+				self.set_vertex_buffer(
+					gl_version, limits, buffers, error, func, array_id, binding, 0, 0, 0,
+				);
+			} else {
+				self.set_vertex_buffer(
+					gl_version,
+					limits,
+					buffers,
+					error,
+					func,
+					array_id,
+					binding,
+					*buffer_ids.add(i),
+					*offsets.add(i),
+					*strides.add(i),
+				);
+			}
+		}
+	}
+
+	#[allow(clippy::too_many_arguments)]
+	fn set_vertex_buffer(
+		&mut self,
+		gl_version: &GlVersion,
+		limits: &Limits,
+		buffers: &BufferManager,
+		error: &mut GLenum,
+		func: &str,
+		array_id: GLuint,
+		binding: GLuint,
+		buffer: GLuint,
+		offset: GLintptr,
+		stride: GLsizei,
+	) {
+		if buffer != 0 && buffers.is_buffer(buffer) == gl::FALSE {
+			*error = gl::INVALID_OPERATION;
+			error!("{} called with invalid buffer {}", func, buffer);
+			return
+		} else if offset < 0 {
+			*error = gl::INVALID_VALUE;
+			error!("{} called with negative offset {}", func, offset);
+			return
+		} else if !Self::check_stride(gl_version, limits, error, func, stride) {
+			return
+		}
+
+		let vertex_binding = &mut self.array_mut(array_id).bindings[binding as usize];
+		vertex_binding.buffer = buffer;
+		vertex_binding.offset = offset as usize;
+		vertex_binding.stride = stride as usize;
+
+		debug!(
+			"bound buffer {} at offset {} with stride {} to binding {} of vertex array {}",
+			buffer, offset, stride, binding, array_id
+		);
+	}
+
+	/// `glVertexBindingDivisor` and `glVertexArrayBindingDivisor`
+	#[allow(clippy::too_many_arguments)]
+	pub fn binding_divisor(
+		&mut self,
+		gl_version: &GlVersion,
+		limits: &Limits,
+		error: &mut GLenum,
+		func: &str,
+		vaobj: Option<GLuint>,
+		binding: GLuint,
+		divisor: GLuint,
+	) {
+		let Some(array_id) = self.target_array(gl_version, error, func, vaobj) else {
+			return
+		};
+		if !Self::check_binding_index(limits, error, func, binding) {
+			return
+		}
+
+		self.array_mut(array_id).bindings[binding as usize].divisor = divisor;
+		debug!("set divisor of binding {} of vertex array {} to {}", binding, array_id, divisor);
+	}
+
+	/// `glVertexAttribDivisor`, pointing `index` at the binding of the same index
+	pub fn attrib_divisor(
+		&mut self,
+		gl_version: &GlVersion,
+		limits: &Limits,
+		error: &mut GLenum,
+		index: GLuint,
+		divisor: GLuint,
+	) {
+		let func = "glVertexAttribDivisor";
+		let Some(array_id) = self.target_array(gl_version, error, func, None) else {
+			return
+		};
+		if !Self::check_index(limits, error, func, index) {
+			return
+		}
+
+		let array = self.array_mut(array_id);
+		array.attributes[index as usize].binding = index;
+		array.bindings[index as usize].divisor = divisor;
+		debug!(
+			"set divisor of vertex attribute {} of vertex array {} to {}",
+			index, array_id, divisor
+		);
+	}
+
+	/// `gl(Enable|Disable)VertexAttribArray` and `gl(Enable|Disable)VertexArrayAttrib`
+	#[allow(clippy::too_many_arguments)]
+	pub fn set_attrib_enabled(
+		&mut self,
+		gl_version: &GlVersion,
+		limits: &Limits,
+		error: &mut GLenum,
+		func: &str,
+		vaobj: Option<GLuint>,
+		index: GLuint,
+		enabled: bool,
+	) {
+		let Some(array_id) = self.target_array(gl_version, error, func, vaobj) else {
+			return
+		};
+		if !Self::check_index(limits, error, func, index) {
+			return
+		}
+
+		self.array_mut(array_id).attributes[index as usize].enabled = enabled;
+		debug!(
+			"{} vertex attribute {} of vertex array {}",
+			if enabled { "enabled" } else { "disabled" },
+			index,
+			array_id
+		);
+	}
+
+	/// `glVertexArrayElementBuffer`
+	pub fn element_buffer(
+		&mut self,
+		gl_version: &GlVersion,
+		buffers: &BufferManager,
+		error: &mut GLenum,
+		vaobj: GLuint,
+		buffer: GLuint,
+	) {
+		let func = "glVertexArrayElementBuffer";
+		let Some(array_id) = self.target_array(gl_version, error, func, Some(vaobj)) else {
+			return
+		};
+
+		if buffer != 0 && buffers.is_buffer(buffer) == gl::FALSE {
+			*error = gl::INVALID_OPERATION;
+			error!("{} called with invalid buffer {}", func, buffer);
+			return
+		}
+
+		self.array_mut(array_id).element_array_buffer = buffer;
+		debug!("bound element array buffer {} to vertex array {}", buffer, array_id);
+	}
+
+	/// Detach a deleted buffer from the bound vertex array
+	pub fn detach_buffer(&mut self, buffer_id: GLuint) {
+		let array = self.bound_mut();
+
+		if array.element_array_buffer == buffer_id {
+			array.element_array_buffer = 0;
+		}
+
+		for binding in array.bindings.iter_mut() {
+			if binding.buffer == buffer_id {
+				binding.buffer = 0;
+			}
+		}
+	}
+
+	pub fn references_buffer(&self, buffer_id: GLuint) -> bool {
+		self.active_vertex_arrays
+			.values()
+			.any(|array| array.references_buffer(buffer_id))
+	}
+
+	pub fn finalize(self) {
+		let dangling = self.active_vertex_arrays.keys().filter(|id| **id != 0).collect::<Vec<_>>();
+
+		if !dangling.is_empty() {
+			error!("mock-gl context was dropped with dangling vertex arrays {:?}", dangling);
+		}
+	}
+}
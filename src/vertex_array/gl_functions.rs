@@ -0,0 +1,532 @@
+use gl::types::{GLboolean, GLenum, GLint, GLintptr, GLsizei, GLuint, GLvoid};
+
+use super::AttribKind;
+use crate::{buffer::BufferBinding, function_mapping::gl_functions};
+
+gl_functions! {
+	fn glGenVertexArrays(count: GLsizei, arrays: *mut GLuint);
+	require gl 3 . 0;
+	require es 3 . 0;
+	require ext ARB_vertex_array_object | OES_vertex_array_object;
+	take [error, vertex_array_manager]
+	{
+		vertex_array_manager.gen_vertex_arrays(error, count, arrays);
+	}
+
+	fn glDeleteVertexArrays(count: GLsizei, arrays: *const GLuint);
+	require gl 3 . 0;
+	require es 3 . 0;
+	require ext ARB_vertex_array_object | OES_vertex_array_object;
+	take [error, buffer_manager, vertex_array_manager]
+	{
+		vertex_array_manager.free_vertex_arrays(error, count, arrays);
+		buffer_manager.collect_orphans(vertex_array_manager);
+	}
+
+	fn glIsVertexArray(array: GLuint) -> GLboolean;
+	require gl 3 . 0;
+	require es 3 . 0;
+	require ext ARB_vertex_array_object | OES_vertex_array_object;
+	take [vertex_array_manager]
+	{
+		vertex_array_manager.is_vertex_array(array)
+	}
+
+	fn glBindVertexArray(array: GLuint);
+	require gl 3 . 0;
+	require es 3 . 0;
+	require ext ARB_vertex_array_object | OES_vertex_array_object;
+	take [error, vertex_array_manager]
+	{
+		vertex_array_manager.bind_vertex_array(error, array);
+	}
+
+	fn glVertexAttribPointer(
+		index: GLuint,
+		size: GLint,
+		ty: GLenum,
+		normalized: GLboolean,
+		stride: GLsizei,
+		pointer: *const GLvoid,
+	);
+	require gl 2 . 1;
+	require es 2 . 0;
+	take [gl_version, limits, error, buffer_manager, vertex_array_manager]
+	{
+		let array_buffer = buffer_manager.bound(vertex_array_manager, BufferBinding::ARRAY_BUFFER);
+		vertex_array_manager.vertex_attrib_pointer(
+			gl_version,
+			limits,
+			error,
+			AttribKind::Float,
+			array_buffer,
+			index,
+			size,
+			ty,
+			normalized,
+			stride,
+			pointer,
+		);
+		buffer_manager.collect_orphans(vertex_array_manager);
+	}
+
+	fn glVertexAttribIPointer(
+		index: GLuint,
+		size: GLint,
+		ty: GLenum,
+		stride: GLsizei,
+		pointer: *const GLvoid,
+	);
+	require gl 3 . 0;
+	require es 3 . 0;
+	take [gl_version, limits, error, buffer_manager, vertex_array_manager]
+	{
+		let array_buffer = buffer_manager.bound(vertex_array_manager, BufferBinding::ARRAY_BUFFER);
+		vertex_array_manager.vertex_attrib_pointer(
+			gl_version,
+			limits,
+			error,
+			AttribKind::Integer,
+			array_buffer,
+			index,
+			size,
+			ty,
+			gl::FALSE,
+			stride,
+			pointer,
+		);
+		buffer_manager.collect_orphans(vertex_array_manager);
+	}
+
+	fn glVertexAttribLPointer(
+		index: GLuint,
+		size: GLint,
+		ty: GLenum,
+		stride: GLsizei,
+		pointer: *const GLvoid,
+	);
+	require gl 4 . 1;
+	take [gl_version, limits, error, buffer_manager, vertex_array_manager]
+	{
+		let array_buffer = buffer_manager.bound(vertex_array_manager, BufferBinding::ARRAY_BUFFER);
+		vertex_array_manager.vertex_attrib_pointer(
+			gl_version,
+			limits,
+			error,
+			AttribKind::Double,
+			array_buffer,
+			index,
+			size,
+			ty,
+			gl::FALSE,
+			stride,
+			pointer,
+		);
+		buffer_manager.collect_orphans(vertex_array_manager);
+	}
+
+	fn glEnableVertexAttribArray(index: GLuint);
+	require gl 2 . 1;
+	require es 2 . 0;
+	take [gl_version, limits, error, vertex_array_manager]
+	{
+		vertex_array_manager.set_attrib_enabled(
+			gl_version,
+			limits,
+			error,
+			"glEnableVertexAttribArray",
+			None,
+			index,
+			true,
+		);
+	}
+
+	fn glDisableVertexAttribArray(index: GLuint);
+	require gl 2 . 1;
+	require es 2 . 0;
+	take [gl_version, limits, error, vertex_array_manager]
+	{
+		vertex_array_manager.set_attrib_enabled(
+			gl_version,
+			limits,
+			error,
+			"glDisableVertexAttribArray",
+			None,
+			index,
+			false,
+		);
+	}
+
+	fn glVertexAttribDivisor(index: GLuint, divisor: GLuint);
+	require gl 3 . 3;
+	require es 3 . 0;
+	require ext ARB_instanced_arrays | EXT_instanced_arrays | ANGLE_instanced_arrays;
+	take [gl_version, limits, error, vertex_array_manager]
+	{
+		vertex_array_manager.attrib_divisor(gl_version, limits, error, index, divisor);
+	}
+
+	fn glVertexAttribFormat(
+		index: GLuint,
+		size: GLint,
+		ty: GLenum,
+		normalized: GLboolean,
+		relative_offset: GLuint,
+	);
+	require gl 4 . 3;
+	require es 3 . 1;
+	require ext ARB_vertex_attrib_binding;
+	take [gl_version, limits, error, vertex_array_manager]
+	{
+		vertex_array_manager.vertex_attrib_format(
+			gl_version,
+			limits,
+			error,
+			"glVertexAttribFormat",
+			None,
+			AttribKind::Float,
+			index,
+			size,
+			ty,
+			normalized,
+			relative_offset,
+		);
+	}
+
+	fn glVertexAttribIFormat(index: GLuint, size: GLint, ty: GLenum, relative_offset: GLuint);
+	require gl 4 . 3;
+	require es 3 . 1;
+	require ext ARB_vertex_attrib_binding;
+	take [gl_version, limits, error, vertex_array_manager]
+	{
+		vertex_array_manager.vertex_attrib_format(
+			gl_version,
+			limits,
+			error,
+			"glVertexAttribIFormat",
+			None,
+			AttribKind::Integer,
+			index,
+			size,
+			ty,
+			gl::FALSE,
+			relative_offset,
+		);
+	}
+
+	fn glVertexAttribLFormat(index: GLuint, size: GLint, ty: GLenum, relative_offset: GLuint);
+	require gl 4 . 3;
+	require ext ARB_vertex_attrib_binding;
+	take [gl_version, limits, error, vertex_array_manager]
+	{
+		vertex_array_manager.vertex_attrib_format(
+			gl_version,
+			limits,
+			error,
+			"glVertexAttribLFormat",
+			None,
+			AttribKind::Double,
+			index,
+			size,
+			ty,
+			gl::FALSE,
+			relative_offset,
+		);
+	}
+
+	fn glVertexAttribBinding(index: GLuint, binding: GLuint);
+	require gl 4 . 3;
+	require es 3 . 1;
+	require ext ARB_vertex_attrib_binding;
+	take [gl_version, limits, error, vertex_array_manager]
+	{
+		vertex_array_manager.vertex_attrib_binding(
+			gl_version,
+			limits,
+			error,
+			"glVertexAttribBinding",
+			None,
+			index,
+			binding,
+		);
+	}
+
+	fn glBindVertexBuffer(binding: GLuint, buffer: GLuint, offset: GLintptr, stride: GLsizei);
+	require gl 4 . 3;
+	require es 3 . 1;
+	require ext ARB_vertex_attrib_binding;
+	take [gl_version, limits, error, buffer_manager, vertex_array_manager]
+	{
+		vertex_array_manager.bind_vertex_buffer(
+			gl_version,
+			limits,
+			buffer_manager,
+			error,
+			"glBindVertexBuffer",
+			None,
+			binding,
+			buffer,
+			offset,
+			stride,
+		);
+		buffer_manager.collect_orphans(vertex_array_manager);
+	}
+
+	fn glBindVertexBuffers(
+		first: GLuint,
+		count: GLsizei,
+		buffers: *const GLuint,
+		offsets: *const GLintptr,
+		strides: *const GLsizei,
+	);
+	require gl 4 . 4;
+	require ext ARB_multi_bind;
+	take [gl_version, limits, error, buffer_manager, vertex_array_manager]
+	{
+		vertex_array_manager.bind_vertex_buffers(
+			gl_version,
+			limits,
+			buffer_manager,
+			error,
+			"glBindVertexBuffers",
+			None,
+			first,
+			count,
+			buffers,
+			offsets,
+			strides,
+		);
+		buffer_manager.collect_orphans(vertex_array_manager);
+	}
+
+	fn glVertexBindingDivisor(binding: GLuint, divisor: GLuint);
+	require gl 4 . 3;
+	require es 3 . 1;
+	require ext ARB_vertex_attrib_binding;
+	take [gl_version, limits, error, vertex_array_manager]
+	{
+		vertex_array_manager.binding_divisor(
+			gl_version,
+			limits,
+			error,
+			"glVertexBindingDivisor",
+			None,
+			binding,
+			divisor,
+		);
+	}
+
+	fn glCreateVertexArrays(count: GLsizei, arrays: *mut GLuint);
+	require gl 4 . 5;
+	require ext ARB_direct_state_access;
+	take [error, vertex_array_manager]
+	{
+		vertex_array_manager.gen_vertex_arrays(error, count, arrays);
+	}
+
+	fn glVertexArrayElementBuffer(vaobj: GLuint, buffer: GLuint);
+	require gl 4 . 5;
+	require ext ARB_direct_state_access;
+	take [gl_version, error, buffer_manager, vertex_array_manager]
+	{
+		vertex_array_manager.element_buffer(gl_version, buffer_manager, error, vaobj, buffer);
+		buffer_manager.collect_orphans(vertex_array_manager);
+	}
+
+	fn glVertexArrayAttribFormat(
+		vaobj: GLuint,
+		index: GLuint,
+		size: GLint,
+		ty: GLenum,
+		normalized: GLboolean,
+		relative_offset: GLuint,
+	);
+	require gl 4 . 5;
+	require ext ARB_direct_state_access;
+	take [gl_version, limits, error, vertex_array_manager]
+	{
+		vertex_array_manager.vertex_attrib_format(
+			gl_version,
+			limits,
+			error,
+			"glVertexArrayAttribFormat",
+			Some(vaobj),
+			AttribKind::Float,
+			index,
+			size,
+			ty,
+			normalized,
+			relative_offset,
+		);
+	}
+
+	fn glVertexArrayAttribIFormat(
+		vaobj: GLuint,
+		index: GLuint,
+		size: GLint,
+		ty: GLenum,
+		relative_offset: GLuint,
+	);
+	require gl 4 . 5;
+	require ext ARB_direct_state_access;
+	take [gl_version, limits, error, vertex_array_manager]
+	{
+		vertex_array_manager.vertex_attrib_format(
+			gl_version,
+			limits,
+			error,
+			"glVertexArrayAttribIFormat",
+			Some(vaobj),
+			AttribKind::Integer,
+			index,
+			size,
+			ty,
+			gl::FALSE,
+			relative_offset,
+		);
+	}
+
+	fn glVertexArrayAttribLFormat(
+		vaobj: GLuint,
+		index: GLuint,
+		size: GLint,
+		ty: GLenum,
+		relative_offset: GLuint,
+	);
+	require gl 4 . 5;
+	require ext ARB_direct_state_access;
+	take [gl_version, limits, error, vertex_array_manager]
+	{
+		vertex_array_manager.vertex_attrib_format(
+			gl_version,
+			limits,
+			error,
+			"glVertexArrayAttribLFormat",
+			Some(vaobj),
+			AttribKind::Double,
+			index,
+			size,
+			ty,
+			gl::FALSE,
+			relative_offset,
+		);
+	}
+
+	fn glVertexArrayAttribBinding(vaobj: GLuint, index: GLuint, binding: GLuint);
+	require gl 4 . 5;
+	require ext ARB_direct_state_access;
+	take [gl_version, limits, error, vertex_array_manager]
+	{
+		vertex_array_manager.vertex_attrib_binding(
+			gl_version,
+			limits,
+			error,
+			"glVertexArrayAttribBinding",
+			Some(vaobj),
+			index,
+			binding,
+		);
+	}
+
+	fn glVertexArrayVertexBuffer(
+		vaobj: GLuint,
+		binding: GLuint,
+		buffer: GLuint,
+		offset: GLintptr,
+		stride: GLsizei,
+	);
+	require gl 4 . 5;
+	require ext ARB_direct_state_access;
+	take [gl_version, limits, error, buffer_manager, vertex_array_manager]
+	{
+		vertex_array_manager.bind_vertex_buffer(
+			gl_version,
+			limits,
+			buffer_manager,
+			error,
+			"glVertexArrayVertexBuffer",
+			Some(vaobj),
+			binding,
+			buffer,
+			offset,
+			stride,
+		);
+		buffer_manager.collect_orphans(vertex_array_manager);
+	}
+
+	fn glVertexArrayVertexBuffers(
+		vaobj: GLuint,
+		first: GLuint,
+		count: GLsizei,
+		buffers: *const GLuint,
+		offsets: *const GLintptr,
+		strides: *const GLsizei,
+	);
+	require gl 4 . 5;
+	require ext ARB_direct_state_access;
+	take [gl_version, limits, error, buffer_manager, vertex_array_manager]
+	{
+		vertex_array_manager.bind_vertex_buffers(
+			gl_version,
+			limits,
+			buffer_manager,
+			error,
+			"glVertexArrayVertexBuffers",
+			Some(vaobj),
+			first,
+			count,
+			buffers,
+			offsets,
+			strides,
+		);
+		buffer_manager.collect_orphans(vertex_array_manager);
+	}
+
+	fn glVertexArrayBindingDivisor(vaobj: GLuint, binding: GLuint, divisor: GLuint);
+	require gl 4 . 5;
+	require ext ARB_direct_state_access;
+	take [gl_version, limits, error, vertex_array_manager]
+	{
+		vertex_array_manager.binding_divisor(
+			gl_version,
+			limits,
+			error,
+			"glVertexArrayBindingDivisor",
+			Some(vaobj),
+			binding,
+			divisor,
+		);
+	}
+
+	fn glEnableVertexArrayAttrib(vaobj: GLuint, index: GLuint);
+	require gl 4 . 5;
+	require ext ARB_direct_state_access;
+	take [gl_version, limits, error, vertex_array_manager]
+	{
+		vertex_array_manager.set_attrib_enabled(
+			gl_version,
+			limits,
+			error,
+			"glEnableVertexArrayAttrib",
+			Some(vaobj),
+			index,
+			true,
+		);
+	}
+
+	fn glDisableVertexArrayAttrib(vaobj: GLuint, index: GLuint);
+	require gl 4 . 5;
+	require ext ARB_direct_state_access;
+	take [gl_version, limits, error, vertex_array_manager]
+	{
+		vertex_array_manager.set_attrib_enabled(
+			gl_version,
+			limits,
+			error,
+			"glDisableVertexArrayAttrib",
+			Some(vaobj),
+			index,
+			false,
+		);
+	}
+}
@@ -0,0 +1,403 @@
+use gl::types::GLint;
+
+use crate::{
+	test::{test_harness, test_harness_handling},
+	version::{ext::OES_vertex_array_object, Profile, VersionType},
+	vertex_array::{AttribKind, AttribType, AttribValue, VertexAttribute},
+	GlVersion,
+};
+
+#[test]
+fn create_destroy() {
+	test_harness(GlVersion::from_version(VersionType::GL, 3, 0), || unsafe {
+		let mut array = 0;
+		gl::GenVertexArrays(1, &mut array);
+		assert_eq!(gl::IsVertexArray(array), gl::TRUE);
+		gl::DeleteVertexArrays(1, &array);
+		assert_eq!(gl::IsVertexArray(array), gl::FALSE);
+	})
+}
+
+#[test]
+#[should_panic]
+fn dangling() {
+	test_harness(GlVersion::from_version(VersionType::GL, 3, 0), || unsafe {
+		let mut array = 0;
+		gl::GenVertexArrays(1, &mut array);
+	})
+}
+
+#[test]
+#[should_panic]
+fn missing_extension() {
+	test_harness(GlVersion::from_version(VersionType::ES, 2, 0), || unsafe {
+		let mut array = 0;
+		gl::GenVertexArrays(1, &mut array);
+		gl::DeleteVertexArrays(1, &array);
+	})
+}
+
+#[test]
+fn oes_extension() {
+	test_harness(GlVersion::new(VersionType::ES, 2, 0, &[&OES_vertex_array_object]), || unsafe {
+		let mut array = 0;
+		gl::GenVertexArrays(1, &mut array);
+		gl::BindVertexArray(array);
+		gl::BindVertexArray(0);
+		gl::DeleteVertexArrays(1, &array);
+	})
+}
+
+#[test]
+fn bind_vertex_array() {
+	test_harness(GlVersion::from_version(VersionType::GL, 3, 0), || unsafe {
+		let mut get_val: GLint = -1;
+		let mut array = 0;
+		gl::GenVertexArrays(1, &mut array);
+		gl::BindVertexArray(array);
+		gl::GetIntegerv(gl::VERTEX_ARRAY_BINDING, &mut get_val);
+		assert_eq!(get_val, array as GLint);
+
+		// deleting the bound vertex array reverts to the default one
+		gl::DeleteVertexArrays(1, &array);
+		gl::GetIntegerv(gl::VERTEX_ARRAY_BINDING, &mut get_val);
+		assert_eq!(get_val, 0);
+	})
+}
+
+#[test]
+fn element_array_binding_per_vertex_array() {
+	test_harness(GlVersion::from_version(VersionType::GL, 3, 0), || unsafe {
+		let mut get_val: GLint = -1;
+		let mut arrays = [0; 2];
+		let mut buffer = 0;
+		gl::GenVertexArrays(2, arrays.as_mut_ptr());
+		gl::GenBuffers(1, &mut buffer);
+
+		gl::BindVertexArray(arrays[0]);
+		gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, buffer);
+		gl::GetIntegerv(gl::ELEMENT_ARRAY_BUFFER_BINDING, &mut get_val);
+		assert_eq!(get_val, buffer as GLint);
+
+		gl::BindVertexArray(arrays[1]);
+		gl::GetIntegerv(gl::ELEMENT_ARRAY_BUFFER_BINDING, &mut get_val);
+		assert_eq!(get_val, 0);
+
+		gl::BindVertexArray(arrays[0]);
+		gl::GetIntegerv(gl::ELEMENT_ARRAY_BUFFER_BINDING, &mut get_val);
+		assert_eq!(get_val, buffer as GLint);
+
+		gl::DeleteBuffers(1, &buffer);
+		gl::DeleteVertexArrays(2, arrays.as_ptr());
+	})
+}
+
+#[test]
+fn attrib_pointer_captures_array_buffer() {
+	test_harness(GlVersion::from_version(VersionType::GL, 3, 0), || unsafe {
+		let mut array = 0;
+		let mut buffer = 0;
+		gl::GenVertexArrays(1, &mut array);
+		gl::GenBuffers(1, &mut buffer);
+
+		gl::BindVertexArray(array);
+		gl::BindBuffer(gl::ARRAY_BUFFER, buffer);
+		gl::VertexAttribPointer(1, 3, gl::FLOAT, gl::FALSE, 12, std::ptr::null());
+		gl::EnableVertexAttribArray(1);
+		gl::BindBuffer(gl::ARRAY_BUFFER, 0);
+
+		{
+			let context = crate::context();
+			let array = context.vertex_array_manager.bound();
+			assert!(array.attributes()[1].enabled);
+			assert_eq!(array.attribute_binding(1).buffer, buffer);
+			assert_eq!(array.attribute_binding(1).stride, 12);
+		}
+
+		gl::DeleteBuffers(1, &buffer);
+		gl::DeleteVertexArrays(1, &array);
+	})
+}
+
+#[test]
+fn delete_attached_buffer() {
+	test_harness(GlVersion::from_version(VersionType::GL, 3, 0), || unsafe {
+		let mut arrays = [0; 2];
+		let mut buffer = 0;
+		gl::GenVertexArrays(2, arrays.as_mut_ptr());
+		gl::GenBuffers(1, &mut buffer);
+		gl::BindBuffer(gl::ARRAY_BUFFER, buffer);
+		gl::BufferData(gl::ARRAY_BUFFER, 16, std::ptr::null(), gl::STATIC_DRAW);
+
+		for array in arrays {
+			gl::BindVertexArray(array);
+			gl::VertexAttribPointer(0, 4, gl::FLOAT, gl::FALSE, 0, std::ptr::null());
+		}
+
+		// only the bound vertex array is detached, the other keeps the storage alive
+		gl::DeleteBuffers(1, &buffer);
+		assert_eq!(gl::IsBuffer(buffer), gl::FALSE);
+		{
+			let context = crate::context();
+			assert_eq!(context.vertex_array_manager.bound().attribute_binding(0).buffer, 0);
+			assert!(context.buffer_manager.buffer(buffer).is_some());
+		}
+
+		gl::BindVertexArray(arrays[0]);
+		{
+			let context = crate::context();
+			assert_eq!(context.vertex_array_manager.bound().attribute_binding(0).buffer, buffer);
+		}
+
+		// storage is released with the last attachment
+		gl::DeleteVertexArrays(2, arrays.as_ptr());
+		assert!(crate::context().buffer_manager.buffer(buffer).is_none());
+	})
+}
+
+#[test]
+fn attrib_binding() {
+	test_harness(GlVersion::from_version(VersionType::GL, 4, 4), || unsafe {
+		let mut array = 0;
+		let mut buffers = [0; 2];
+		gl::GenVertexArrays(1, &mut array);
+		gl::GenBuffers(2, buffers.as_mut_ptr());
+
+		gl::BindVertexArray(array);
+		gl::VertexAttribFormat(0, 3, gl::FLOAT, gl::FALSE, 0);
+		gl::VertexAttribIFormat(1, 1, gl::UNSIGNED_INT, 12);
+		gl::VertexAttribBinding(0, 2);
+		gl::VertexAttribBinding(1, 2);
+		gl::BindVertexBuffer(2, buffers[0], 64, 16);
+		gl::VertexBindingDivisor(2, 1);
+		gl::BindVertexBuffers(0, 2, buffers.as_ptr(), [0, 8].as_ptr(), [4, 4].as_ptr());
+
+		{
+			let context = crate::context();
+			let array = context.vertex_array_manager.bound();
+			let attrib = &array.attributes()[1];
+			assert_eq!(attrib.kind, AttribKind::Integer);
+			assert_eq!(attrib.relative_offset, 12);
+			assert_eq!(array.attribute_binding(1).buffer, buffers[0]);
+			assert_eq!(array.attribute_binding(1).offset, 64);
+			assert_eq!(array.attribute_binding(1).divisor, 1);
+			assert_eq!(array.bindings()[1].buffer, buffers[1]);
+		}
+
+		// glVertexAttribPointer points the attribute back at its own binding
+		gl::BindBuffer(gl::ARRAY_BUFFER, buffers[1]);
+		gl::VertexAttribPointer(1, 2, gl::SHORT, gl::TRUE, 0, 32 as *const _);
+		{
+			let context = crate::context();
+			let array = context.vertex_array_manager.bound();
+			assert_eq!(array.attributes()[1].binding, 1);
+			assert_eq!(array.attribute_binding(1).stride, 4);
+			assert_eq!(array.attribute_binding(1).offset, 32);
+		}
+
+		gl::DeleteBuffers(2, buffers.as_ptr());
+		gl::DeleteVertexArrays(1, &array);
+	})
+}
+
+#[test]
+fn direct_state_access() {
+	test_harness(GlVersion::from_version(VersionType::GL, 4, 5), || unsafe {
+		let mut array = 0;
+		let mut buffer = 0;
+		gl::CreateVertexArrays(1, &mut array);
+		gl::GenBuffers(1, &mut buffer);
+
+		gl::VertexArrayElementBuffer(array, buffer);
+		gl::VertexArrayVertexBuffer(array, 0, buffer, 0, 8);
+		gl::VertexArrayAttribFormat(array, 3, 2, gl::FLOAT, gl::FALSE, 0);
+		gl::VertexArrayAttribBinding(array, 3, 0);
+		gl::EnableVertexArrayAttrib(array, 3);
+
+		// the bound vertex array is left alone
+		{
+			let context = crate::context();
+			assert_eq!(context.vertex_array_manager.bound().element_array_buffer(), 0);
+			assert!(!context.vertex_array_manager.bound().attributes()[3].enabled);
+		}
+
+		gl::BindVertexArray(array);
+		{
+			let context = crate::context();
+			let array = context.vertex_array_manager.bound();
+			assert_eq!(array.element_array_buffer(), buffer);
+			assert!(array.attributes()[3].enabled);
+			assert_eq!(array.attribute_binding(3).buffer, buffer);
+		}
+
+		gl::DeleteBuffers(1, &buffer);
+		gl::DeleteVertexArrays(1, &array);
+	})
+}
+
+#[test]
+fn attrib_binding_errors() {
+	test_harness_handling(
+		GlVersion::from_version(VersionType::GL, 4, 5).with_profile(Profile::Core),
+		crate::ErrorHandling::DoNotPanic,
+		|| unsafe {
+			gl::VertexAttribFormat(0, 4, gl::FLOAT, gl::FALSE, 0);
+			assert_eq!(gl::GetError(), gl::INVALID_OPERATION);
+
+			let mut array = 0;
+			gl::GenVertexArrays(1, &mut array);
+			gl::BindVertexArray(array);
+
+			gl::VertexAttribFormat(0, 4, gl::FLOAT, gl::FALSE, 2048);
+			assert_eq!(gl::GetError(), gl::INVALID_VALUE);
+
+			gl::VertexAttribIFormat(0, 4, gl::FLOAT, 0);
+			assert_eq!(gl::GetError(), gl::INVALID_ENUM);
+
+			gl::VertexAttribBinding(0, 16);
+			assert_eq!(gl::GetError(), gl::INVALID_VALUE);
+
+			gl::BindVertexBuffer(0, 0, 0, 2049);
+			assert_eq!(gl::GetError(), gl::INVALID_VALUE);
+
+			gl::BindVertexBuffer(0, 0, -1, 0);
+			assert_eq!(gl::GetError(), gl::INVALID_VALUE);
+
+			gl::BindVertexBuffer(0, 42, 0, 0);
+			assert_eq!(gl::GetError(), gl::INVALID_OPERATION);
+
+			gl::BindVertexBuffers(15, 2, std::ptr::null(), std::ptr::null(), std::ptr::null());
+			assert_eq!(gl::GetError(), gl::INVALID_OPERATION);
+
+			gl::VertexAttribPointer(0, 4, gl::FLOAT, gl::FALSE, 4096, std::ptr::null());
+			assert_eq!(gl::GetError(), gl::INVALID_VALUE);
+
+			gl::VertexArrayAttribFormat(42, 0, 4, gl::FLOAT, gl::FALSE, 0);
+			assert_eq!(gl::GetError(), gl::INVALID_OPERATION);
+
+			gl::DeleteVertexArrays(1, &array);
+		},
+	)
+}
+
+#[test]
+#[should_panic]
+fn core_default_vertex_array() {
+	test_harness(
+		GlVersion::from_version(VersionType::GL, 3, 3).with_profile(Profile::Core),
+		|| unsafe {
+			gl::EnableVertexAttribArray(0);
+		},
+	)
+}
+
+#[test]
+fn gl_errors() {
+	test_harness_handling(
+		GlVersion::from_version(VersionType::GL, 3, 3).with_profile(Profile::Core),
+		crate::ErrorHandling::DoNotPanic,
+		|| unsafe {
+			gl::GenVertexArrays(-1, std::ptr::null_mut());
+			assert_eq!(gl::GetError(), gl::INVALID_VALUE);
+
+			gl::BindVertexArray(1);
+			assert_eq!(gl::GetError(), gl::INVALID_OPERATION);
+
+			gl::VertexAttribPointer(0, 4, gl::FLOAT, gl::FALSE, 0, std::ptr::null());
+			assert_eq!(gl::GetError(), gl::INVALID_OPERATION);
+
+			let mut array = 0;
+			gl::GenVertexArrays(1, &mut array);
+			gl::BindVertexArray(array);
+
+			gl::VertexAttribPointer(16, 4, gl::FLOAT, gl::FALSE, 0, std::ptr::null());
+			assert_eq!(gl::GetError(), gl::INVALID_VALUE);
+
+			gl::VertexAttribPointer(0, 5, gl::FLOAT, gl::FALSE, 0, std::ptr::null());
+			assert_eq!(gl::GetError(), gl::INVALID_VALUE);
+
+			gl::VertexAttribPointer(0, 4, gl::TEXTURE, gl::FALSE, 0, std::ptr::null());
+			assert_eq!(gl::GetError(), gl::INVALID_ENUM);
+
+			gl::VertexAttribPointer(0, 4, gl::FLOAT, gl::FALSE, -4, std::ptr::null());
+			assert_eq!(gl::GetError(), gl::INVALID_VALUE);
+
+			gl::VertexAttribPointer(0, 3, gl::INT_2_10_10_10_REV, gl::TRUE, 0, std::ptr::null());
+			assert_eq!(gl::GetError(), gl::INVALID_OPERATION);
+
+			gl::VertexAttribPointer(
+				0,
+				gl::BGRA as GLint,
+				gl::UNSIGNED_BYTE,
+				gl::FALSE,
+				0,
+				std::ptr::null(),
+			);
+			assert_eq!(gl::GetError(), gl::INVALID_OPERATION);
+
+			// client side arrays with a vertex array bound
+			gl::VertexAttribPointer(0, 4, gl::FLOAT, gl::FALSE, 0, 16 as *const _);
+			assert_eq!(gl::GetError(), gl::INVALID_OPERATION);
+
+			gl::DeleteVertexArrays(1, &array);
+		},
+	)
+}
+
+fn attribute(size: i32, ty: AttribType, normalized: bool, kind: AttribKind) -> VertexAttribute {
+	VertexAttribute {
+		size,
+		ty,
+		normalized,
+		kind,
+		..VertexAttribute::new(0)
+	}
+}
+
+#[test]
+fn decode_attributes() {
+	let bytes = [0x80u8, 0x7f, 0x00, 0x40];
+	assert_eq!(
+		attribute(4, AttribType::BYTE, true, AttribKind::Float).decode(&bytes),
+		AttribValue::Float([-1.0, 1.0, 0.0, 64.0 / 127.0])
+	);
+	assert_eq!(
+		attribute(2, AttribType::UNSIGNED_BYTE, false, AttribKind::Float).decode(&bytes),
+		AttribValue::Float([128.0, 127.0, 0.0, 1.0])
+	);
+	assert_eq!(
+		attribute(3, AttribType::UNSIGNED_BYTE, false, AttribKind::Integer).decode(&bytes),
+		AttribValue::UInt([128, 127, 0, 1])
+	);
+
+	let half = [0x00u8, 0x3c, 0x00, 0xc0];
+	assert_eq!(
+		attribute(2, AttribType::HALF_FLOAT, false, AttribKind::Float).decode(&half),
+		AttribValue::Float([1.0, -2.0, 0.0, 1.0])
+	);
+
+	let fixed = 0x18000i32.to_ne_bytes();
+	assert_eq!(
+		attribute(1, AttribType::FIXED, false, AttribKind::Float).decode(&fixed),
+		AttribValue::Float([1.5, 0.0, 0.0, 1.0])
+	);
+
+	// x = 511, y = -512, z = 0, w = 1
+	let packed = (0x1ffu32 | (0x200 << 10) | (1 << 30)).to_ne_bytes();
+	assert_eq!(
+		attribute(4, AttribType::INT_2_10_10_10_REV, true, AttribKind::Float).decode(&packed),
+		AttribValue::Float([1.0, -1.0, 0.0, 1.0])
+	);
+
+	let bgra = VertexAttribute {
+		bgra: true,
+		..attribute(4, AttribType::UNSIGNED_BYTE, true, AttribKind::Float)
+	};
+	assert_eq!(bgra.decode(&[0, 0, 255, 255]), AttribValue::Float([1.0, 0.0, 0.0, 1.0]));
+
+	let double = 0.5f64.to_ne_bytes();
+	assert_eq!(
+		attribute(1, AttribType::DOUBLE, false, AttribKind::Double).decode(&double),
+		AttribValue::Double([0.5, 0.0, 0.0, 1.0])
+	);
+}